@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use amqp_proto::FieldTable;
+use amqp_proto::codec::Decode;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = FieldTable::decode(data);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use bytes::BytesMut;
+use amqp_proto::codec::{Decode, Encode, Frame};
+
+fuzz_target!(|data: &[u8]| {
+    // a decoded frame must encode again
+    if let Ok((_, frame)) = Frame::decode(data) {
+        frame.encode(&mut BytesMut::new());
+    }
+});
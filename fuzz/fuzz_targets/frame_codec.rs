@@ -0,0 +1,26 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use bytes::BytesMut;
+use tokio_util::codec::Decoder;
+use amqp_proto::codec::FrameCodec;
+
+// The first byte is the chunk size, the rest is the stream which is fed to the codec chunk by chunk
+fuzz_target!(|data: &[u8]| {
+    let (chunk_size, stream) = match data.split_first() {
+        Some((chunk_size, stream)) => (*chunk_size as usize + 1, stream),
+        None => return
+    };
+    let mut codec = FrameCodec::default();
+    let mut buffer = BytesMut::new();
+    for chunk in stream.chunks(chunk_size) {
+        buffer.extend_from_slice(chunk);
+        loop {
+            match codec.decode(&mut buffer) {
+                Ok(Some(_)) => continue,
+                Ok(None) => break,
+                Err(_) => return
+            }
+        }
+    }
+    let _ = codec.decode_eof(&mut buffer);
+});
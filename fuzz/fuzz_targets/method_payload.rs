@@ -0,0 +1,7 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use amqp_proto::codec::{Decode, MethodPayload};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = MethodPayload::decode(data);
+});
@@ -0,0 +1,116 @@
+use std::io;
+use std::net::SocketAddr;
+use crate::latency::{Latency, Summary};
+use crate::output::Printer;
+use crate::stream::{Direction, Event, Stream};
+use crate::tcp::{Reassembly, Segment};
+
+/// An AMQP connection of a pcap capture, its events are printed to a buffer so connections don't interleave
+pub struct Conversation {
+    client: SocketAddr,
+    reassembly: [Reassembly; 2],
+    streams: [Stream; 2],
+    latency: Latency,
+    printer: Printer<Vec<u8>>,
+    received: bool,
+    failed: bool
+}
+
+impl Conversation {
+    pub fn new(client: SocketAddr, server: SocketAddr, json: bool, color: bool, limit: Option<usize>) -> io::Result<Self> {
+        let printer = Printer::connection(Vec::new(), json, color, limit, format!("{} -> {}", client, server))?;
+        Ok(Conversation {
+            client,
+            reassembly: [Reassembly::default(), Reassembly::default()],
+            streams: [Stream::new(Direction::Client), Stream::new(Direction::Server)],
+            latency: Latency::default(),
+            printer,
+            received: false,
+            failed: false
+        })
+    }
+
+    /// No AMQP bytes have been received yet
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !self.received
+    }
+
+    pub fn push(&mut self, time: u64, segment: &Segment<'_>) -> io::Result<()> {
+        let direction = if segment.source == self.client { Direction::Client } else { Direction::Server };
+        let mut data = Vec::new();
+        self.reassembly[direction as usize].push(segment, &mut data);
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.received = true;
+        let mut events = Vec::new();
+        self.streams[direction as usize].feed(&data, &mut events);
+        self.print(direction, Some(time), events)
+    }
+
+    /// Print what is left and the latencies, returns the output and if there were errors
+    pub fn finish(mut self) -> io::Result<(Vec<u8>, bool)> {
+        for direction in [Direction::Client, Direction::Server].iter().cloned() {
+            let mut events = Vec::new();
+            let missing = self.reassembly[direction as usize].missing();
+            if missing > 0 {
+                events.push((0, Event::Error(None, format!("{} bytes after a gap in the tcp stream were not decoded", missing))));
+            }
+            self.streams[direction as usize].finish(&mut events);
+            self.print(direction, None, events)?;
+        }
+        if let Some(summary) = Summary::of(&self.latency.publish_ack) {
+            self.printer.latency("publish->ack", &summary, Some(self.latency.nacks))?;
+        }
+        if let Some(summary) = Summary::of(&self.latency.get_ok) {
+            self.printer.latency("get->get-ok", &summary, None)?;
+        }
+        Ok((self.printer.into_inner(), self.failed))
+    }
+
+    fn print(&mut self, direction: Direction, time: Option<u64>, events: Vec<(usize, Event)>) -> io::Result<()> {
+        for (offset, event) in events {
+            match (&event, time) {
+                (Event::Frame(frame), Some(time)) => self.latency.observe(direction, time, frame),
+                (Event::Error(..), _) => self.failed = true,
+                _ => {}
+            }
+            self.printer.print(direction, offset, time, &event)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use crate::tcp::{Segment, FLAG_ACK, FLAG_SYN};
+    use super::Conversation;
+
+    #[test]
+    fn test_conversation() {
+        let client: SocketAddr = "10.0.0.1:40000".parse().unwrap();
+        let server: SocketAddr = "10.0.0.2:5672".parse().unwrap();
+        let segment = |seq, flags, payload| Segment { source: client, destination: server, seq, flags, payload };
+
+        // the segments arrive out of order after the syn
+        let mut conversation = Conversation::new(client, server, true, false, None).unwrap();
+        conversation.push(0, &segment(0, FLAG_SYN, b"")).unwrap();
+        conversation.push(1, &segment(5, FLAG_ACK, b"\x00\x00\x09\x01")).unwrap();
+        assert!(conversation.is_empty());
+        conversation.push(2, &segment(1, FLAG_ACK, b"AMQP")).unwrap();
+        assert!(!conversation.is_empty());
+        let (output, failed) = conversation.finish().unwrap();
+        assert!(!failed);
+        assert!(String::from_utf8(output).unwrap().contains("\"type\":\"protocol-header\",\"version\":\"AMQP 0-9-1\""));
+
+        // a hole which is never filled
+        let mut conversation = Conversation::new(client, server, true, false, None).unwrap();
+        conversation.push(1, &segment(0, FLAG_ACK, b"AMQP")).unwrap();
+        conversation.push(2, &segment(6, FLAG_ACK, b"\x09\x01")).unwrap();
+        let (output, failed) = conversation.finish().unwrap();
+        assert!(failed);
+        assert!(String::from_utf8(output).unwrap().contains("2 bytes after a gap in the tcp stream were not decoded"));
+    }
+}
@@ -0,0 +1,164 @@
+//! Round trips of a connection, matched by their channel like a client does.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::mem;
+use amqp_proto::codec::{Arguments, Frame, Payload};
+use crate::stream::Direction;
+
+/// Publish to ack of a channel in confirm mode, the publishes are numbered from 1 after confirm.select
+struct Confirms {
+    next: u64,
+    unconfirmed: BTreeMap<u64, u64>
+}
+
+#[derive(Default)]
+pub struct Latency {
+    confirms: HashMap<u16, Confirms>,
+    gets: HashMap<u16, VecDeque<u64>>,
+    /// Nanoseconds from basic.publish to its basic.ack or basic.nack
+    pub publish_ack: Vec<u64>,
+    pub nacks: usize,
+    /// Nanoseconds from basic.get to basic.get-ok or basic.get-empty
+    pub get_ok: Vec<u64>
+}
+
+impl Latency {
+    pub fn observe(&mut self, direction: Direction, time: u64, frame: &Frame) {
+        let channel = frame.channel();
+        let args = match frame.payload() {
+            Payload::Method(method) => method.args(),
+            _ => return
+        };
+        match (direction, args) {
+            (Direction::Client, Arguments::ConfirmSelect(_)) => {
+                self.confirms.entry(channel).or_insert(Confirms { next: 1, unconfirmed: BTreeMap::new() });
+            }
+            (Direction::Client, Arguments::BasicPublish(_)) => {
+                if let Some(confirms) = self.confirms.get_mut(&channel) {
+                    confirms.unconfirmed.insert(confirms.next, time);
+                    confirms.next += 1;
+                }
+            }
+            (Direction::Server, Arguments::BasicAck(ack)) => {
+                self.confirm(channel, time, ack.delivery_tag(), ack.multiple());
+            }
+            (Direction::Server, Arguments::BasicNack(nack)) => {
+                self.nacks += self.confirm(channel, time, nack.delivery_tag(), nack.multiple());
+            }
+            (Direction::Client, Arguments::BasicGet(_)) => {
+                self.gets.entry(channel).or_default().push_back(time);
+            }
+            (Direction::Server, Arguments::BasicGetOk(_)) | (Direction::Server, Arguments::BasicGetEmpty(_)) => {
+                if let Some(sent) = self.gets.get_mut(&channel).and_then(|gets| gets.pop_front()) {
+                    self.get_ok.push(time.saturating_sub(sent));
+                }
+            }
+            // a closed channel starts over
+            (_, Arguments::ChannelCloseOk(_)) => {
+                self.confirms.remove(&channel);
+                self.gets.remove(&channel);
+            }
+            _ => {}
+        }
+    }
+
+    // delivery tag 0 with multiple confirms every publish
+    fn confirm(&mut self, channel: u16, time: u64, delivery_tag: u64, multiple: bool) -> usize {
+        let confirms = match self.confirms.get_mut(&channel) {
+            Some(confirms) => confirms,
+            None => return 0
+        };
+        let confirmed = if multiple {
+            let rest = if delivery_tag == 0 { BTreeMap::new() } else { confirms.unconfirmed.split_off(&(delivery_tag + 1)) };
+            mem::replace(&mut confirms.unconfirmed, rest).into_values().collect()
+        } else {
+            confirms.unconfirmed.remove(&delivery_tag).into_iter().collect::<Vec<_>>()
+        };
+        self.publish_ack.extend(confirmed.iter().map(|sent| time.saturating_sub(*sent)));
+        confirmed.len()
+    }
+}
+
+/// Distribution of the samples, in nanoseconds
+pub struct Summary {
+    pub count: usize,
+    pub min: u64,
+    pub mean: u64,
+    pub p50: u64,
+    pub p99: u64,
+    pub max: u64
+}
+
+impl Summary {
+    pub fn of(samples: &[u64]) -> Option<Summary> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+        let percentile = |p: usize| samples[(samples.len() * p / 100).min(samples.len() - 1)];
+        Some(Summary {
+            count: samples.len(),
+            min: samples[0],
+            mean: (samples.iter().map(|sample| u128::from(*sample)).sum::<u128>() / samples.len() as u128) as u64,
+            p50: percentile(50),
+            p99: percentile(99),
+            max: samples[samples.len() - 1]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amqp_proto::arguments::basic::{BasicAck, BasicGet, BasicGetEmpty, BasicGetOk, BasicNack, BasicPublish};
+    use amqp_proto::arguments::confirm::ConfirmSelect;
+    use amqp_proto::codec::{Arguments, Frame};
+    use crate::stream::Direction;
+    use super::{Latency, Summary};
+
+    fn observe(latency: &mut Latency, direction: Direction, time: u64, args: Arguments) {
+        latency.observe(direction, time, &Frame::method_frame(1, args));
+    }
+
+    #[test]
+    fn test_publish_ack() {
+        let mut latency = Latency::default();
+        // not in confirm mode yet, it is not acked
+        observe(&mut latency, Direction::Client, 0, Arguments::BasicPublish(BasicPublish::default()));
+        observe(&mut latency, Direction::Client, 0, Arguments::ConfirmSelect(ConfirmSelect::default()));
+        for time in &[10, 20, 30, 40] {
+            observe(&mut latency, Direction::Client, *time, Arguments::BasicPublish(BasicPublish::default()));
+        }
+        let mut ack = BasicAck::default();
+        ack.set_delivery_tag(3u64).set_multiple(true);
+        observe(&mut latency, Direction::Server, 100, Arguments::BasicAck(ack.clone()));
+        assert_eq!(latency.publish_ack, vec![90, 80, 70]);
+        // acked twice
+        observe(&mut latency, Direction::Server, 100, Arguments::BasicAck(ack));
+
+        // delivery tag 0 with multiple is every unconfirmed publish
+        observe(&mut latency, Direction::Client, 50, Arguments::BasicPublish(BasicPublish::default()));
+        let mut nack = BasicNack::default();
+        nack.set_delivery_tag(0u64).set_multiple(true);
+        observe(&mut latency, Direction::Server, 200, Arguments::BasicNack(nack));
+        assert_eq!(latency.publish_ack, vec![90, 80, 70, 160, 150]);
+        assert_eq!(latency.nacks, 2);
+
+        let summary = Summary::of(&latency.publish_ack).unwrap();
+        assert_eq!((summary.count, summary.min, summary.mean, summary.p50, summary.max), (5, 70, 110, 90, 160));
+    }
+
+    #[test]
+    fn test_get() {
+        let mut latency = Latency::default();
+        observe(&mut latency, Direction::Server, 5, Arguments::BasicGetEmpty(BasicGetEmpty::default()));
+        observe(&mut latency, Direction::Client, 10, Arguments::BasicGet(BasicGet::default()));
+        observe(&mut latency, Direction::Client, 20, Arguments::BasicGet(BasicGet::default()));
+        observe(&mut latency, Direction::Server, 25, Arguments::BasicGetOk(BasicGetOk::default()));
+        observe(&mut latency, Direction::Server, 50, Arguments::BasicGetEmpty(BasicGetEmpty::default()));
+        // the reply to the first get is taken as the reply to the oldest
+        assert_eq!(latency.get_ok, vec![15, 30]);
+        observe(&mut latency, Direction::Server, 60, Arguments::BasicGetOk(BasicGetOk::default()));
+        assert_eq!(latency.get_ok, vec![15, 30]);
+    }
+}
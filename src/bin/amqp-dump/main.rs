@@ -0,0 +1,218 @@
+//! amqp-dump decodes a captured AMQP byte stream and prints every frame.
+//!
+//! ```text
+//! amqp-dump [--json] [--server | --interleaved] [--port N] [--limit N | --full] [--no-color] [FILE]
+//! ```
+//!
+//! The capture is read from FILE, or stdin if there is none or it is `-`. It is the bytes the client sent,
+//! the bytes the server sent with `--server`, or both with `--interleaved`: records of a direction byte,
+//! `>` client to server or `<` server to client, a big endian u32 length and that many bytes.
+//!
+//! A pcap or pcapng file is recognised by its magic number. The TCP connections to `--port`, 5672 if it
+//! isn't given, are reassembled and printed one after the other with the time since the connection's
+//! first frame, followed by the publish to ack and get to get-ok latencies.
+//!
+//! Content frames are reassembled into messages per channel. A frame which can't be decoded stops its
+//! direction, it and frames out of order are reported as errors and the exit code is 1.
+//! Long strings and bodies are cut after 128 bytes unless `--limit` or `--full` says otherwise.
+
+mod conversation;
+mod latency;
+mod output;
+mod pcap;
+mod stream;
+mod tcp;
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process;
+use crate::conversation::Conversation;
+use crate::output::Printer;
+use crate::stream::{Direction, Event, Stream};
+
+const USAGE: &str = "usage: amqp-dump [--json] [--server | --interleaved] [--port N] [--limit N | --full] [--no-color] [FILE]";
+
+struct Options {
+    json: bool,
+    server: bool,
+    interleaved: bool,
+    color: bool,
+    limit: Option<usize>,
+    port: u16,
+    path: Option<String>
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            json: false,
+            server: false,
+            interleaved: false,
+            color: io::stdout().is_terminal(),
+            limit: Some(128),
+            port: 5672,
+            path: None
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => options.json = true,
+                "--server" => options.server = true,
+                "--interleaved" => options.interleaved = true,
+                "--no-color" => options.color = false,
+                "--full" => options.limit = None,
+                "--limit" => {
+                    let limit = args.next().ok_or("--limit needs a number of bytes")?;
+                    options.limit = Some(limit.parse().map_err(|_| format!("invalid limit: {}", limit))?);
+                }
+                "--port" => {
+                    let port = args.next().ok_or("--port needs a port number")?;
+                    options.port = port.parse().map_err(|_| format!("invalid port: {}", port))?;
+                }
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                _ if arg.starts_with("--") => return Err(format!("unknown option: {}\n{}", arg, USAGE)),
+                _ if options.path.is_none() => options.path = Some(arg),
+                _ => return Err(USAGE.to_string())
+            }
+        }
+        if options.server && options.interleaved {
+            return Err("--server and --interleaved can't be used together".to_string());
+        }
+        // json is read by programs
+        options.color &= !options.json;
+        Ok(options)
+    }
+}
+
+/// Split an interleaved capture into its records
+fn records(mut capture: &[u8]) -> Result<Vec<(Direction, &[u8])>, String> {
+    let mut records = Vec::new();
+    let mut position = 0;
+    while !capture.is_empty() {
+        if capture.len() < 5 {
+            return Err(format!("record at {} is truncated", position));
+        }
+        let direction = match capture[0] {
+            b'>' => Direction::Client,
+            b'<' => Direction::Server,
+            marker => return Err(format!("record at {} has an invalid direction 0x{:02x}", position, marker))
+        };
+        let length = u32::from_be_bytes([capture[1], capture[2], capture[3], capture[4]]) as usize;
+        if capture.len() - 5 < length {
+            return Err(format!("record at {} is truncated", position));
+        }
+        records.push((direction, &capture[5..5 + length]));
+        capture = &capture[5 + length..];
+        position += 5 + length;
+    }
+    Ok(records)
+}
+
+fn read_capture(path: Option<&str>) -> io::Result<Vec<u8>> {
+    match path {
+        Some(path) if path != "-" => fs::read(path),
+        _ => {
+            let mut capture = Vec::new();
+            io::stdin().read_to_end(&mut capture)?;
+            Ok(capture)
+        }
+    }
+}
+
+fn run(options: Options) -> Result<bool, String> {
+    let capture = read_capture(options.path.as_deref()).map_err(|e| format!("can't read the capture: {}", e))?;
+    if !options.interleaved && !options.server && pcap::is_capture(&capture) {
+        return run_pcap(&capture, &options);
+    }
+    let records = if options.interleaved {
+        records(&capture)?
+    } else if options.server {
+        vec![(Direction::Server, &capture[..])]
+    } else {
+        vec![(Direction::Client, &capture[..])]
+    };
+
+    let stdout = io::stdout();
+    let mut printer = Printer::new(stdout.lock(), options.json, options.color, options.limit);
+    let mut streams = [Stream::new(Direction::Client), Stream::new(Direction::Server)];
+    let mut events = Vec::new();
+    let mut failed = false;
+    let mut print = |direction: Direction, events: &mut Vec<(usize, Event)>| -> Result<(), String> {
+        for (offset, event) in events.drain(..) {
+            failed |= matches!(event, Event::Error(..));
+            printer.print(direction, offset, None, &event).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    };
+    for (direction, bytes) in records {
+        let stream = &mut streams[direction as usize];
+        stream.feed(bytes, &mut events);
+        print(direction, &mut events)?;
+    }
+    for stream in streams.iter_mut() {
+        stream.finish(&mut events);
+        print(stream.direction(), &mut events)?;
+    }
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    Ok(!failed)
+}
+
+fn run_pcap(capture: &[u8], options: &Options) -> Result<bool, String> {
+    let packets = pcap::packets(capture)?;
+    let mut conversations: Vec<Conversation> = Vec::new();
+    let mut open: HashMap<_, usize> = HashMap::new();
+    for packet in packets {
+        let segment = match tcp::segment(packet.link_type, packet.data) {
+            Some(segment) => segment,
+            None => continue
+        };
+        let (client, server) = if segment.destination.port() == options.port {
+            (segment.source, segment.destination)
+        } else if segment.source.port() == options.port {
+            (segment.destination, segment.source)
+        } else {
+            continue;
+        };
+        // a client reusing its port starts a new connection
+        let syn = segment.flags & (tcp::FLAG_SYN | tcp::FLAG_ACK) == tcp::FLAG_SYN;
+        let index = match open.get(&(client, server)) {
+            Some(&index) if !syn || conversations[index].is_empty() => index,
+            _ => {
+                let conversation = Conversation::new(client, server, options.json, options.color, options.limit).map_err(|e| e.to_string())?;
+                conversations.push(conversation);
+                open.insert((client, server), conversations.len() - 1);
+                conversations.len() - 1
+            }
+        };
+        conversations[index].push(packet.time, &segment).map_err(|e| e.to_string())?;
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut failed = false;
+    for conversation in conversations {
+        let (output, errors) = conversation.finish().map_err(|e| e.to_string())?;
+        failed |= errors;
+        out.write_all(&output).map_err(|e| e.to_string())?;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(!failed)
+}
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    match run(options) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("amqp-dump: {}", message);
+            process::exit(2);
+        }
+    }
+}
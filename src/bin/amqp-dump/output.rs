@@ -0,0 +1,243 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+use amqp_proto::codec::{Frame, Payload, ProtocolHeader};
+use crate::latency::Summary;
+use crate::stream::{Direction, Event, Message, method_name};
+
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Prints the events of the streams, a line each
+pub struct Printer<W> {
+    out: W,
+    json: bool,
+    color: bool,
+    /// Bytes of a long string or body which are printed, all of them if None
+    limit: Option<usize>,
+    /// The connection of a pcap capture the events are from
+    connection: Option<String>,
+    // time of the first event, the text times are relative to it
+    start: Option<u64>
+}
+
+impl<W: Write> Printer<W> {
+    pub fn new(out: W, json: bool, color: bool, limit: Option<usize>) -> Self {
+        Printer { out, json, color, limit, connection: None, start: None }
+    }
+
+    /// Printer of the events of a connection, `client -> server`
+    pub fn connection(out: W, json: bool, color: bool, limit: Option<usize>, connection: String) -> io::Result<Self> {
+        let mut printer = Printer::new(out, json, color, limit);
+        if !json {
+            writeln!(printer.out, "connection {}", connection)?;
+        }
+        printer.connection = Some(connection);
+        Ok(printer)
+    }
+
+    /// `time` is nanoseconds since the unix epoch, events of a raw capture have none
+    pub fn print(&mut self, direction: Direction, offset: usize, time: Option<u64>, event: &Event) -> io::Result<()> {
+        if self.json {
+            self.print_json(direction, offset, time, event)
+        } else {
+            self.print_text(direction, offset, time, event)
+        }
+    }
+
+    pub fn latency(&mut self, name: &str, summary: &Summary, nacks: Option<usize>) -> io::Result<()> {
+        if self.json {
+            let mut object = JsonObject::new();
+            if let Some(connection) = &self.connection {
+                object.string("connection", connection);
+            }
+            object.string("type", "latency").string("name", name).number("count", summary.count);
+            for (key, value) in summary_fields(summary).iter() {
+                object.number(key, millis(*value));
+            }
+            if let Some(nacks) = nacks {
+                object.number("nacks", nacks);
+            }
+            writeln!(self.out, "{}", object.finish())
+        } else {
+            let mut line = format!("  {} count={}", name, summary.count);
+            for (key, value) in summary_fields(summary).iter() {
+                line.push_str(&format!(" {}={}", key, millis(*value)));
+            }
+            if let Some(nacks) = nacks {
+                line.push_str(&format!(" nacks={}", nacks));
+            }
+            writeln!(self.out, "{}", line)
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    // the trace format of the frame, cut at the limit
+    fn trace(&self, value: &dyn Display) -> String {
+        match self.limit {
+            Some(limit) => format!("{:.*}", limit, value),
+            None => format!("{:#}", value)
+        }
+    }
+
+    fn body(&self, body: &[u8]) -> String {
+        let end = self.limit.map_or(body.len(), |limit| limit.min(body.len()));
+        let dots = if end < body.len() { "..." } else { "" };
+        format!("\"{}\"{}", body[..end].escape_ascii(), dots)
+    }
+
+    fn print_text(&mut self, direction: Direction, offset: usize, time: Option<u64>, event: &Event) -> io::Result<()> {
+        let mut prefix = format!("{:>8} {}", offset, direction.arrow());
+        if let Some(time) = time {
+            let elapsed = time.saturating_sub(*self.start.get_or_insert(time));
+            prefix = format!("+{}.{:06} {}", elapsed / 1_000_000_000, elapsed % 1_000_000_000 / 1000, prefix);
+        }
+        match event {
+            Event::ProtocolHeader(header) => writeln!(self.out, "{} protocol-header {}", prefix, version(header)),
+            Event::Frame(frame) => {
+                let line = self.trace(frame);
+                writeln!(self.out, "{} {}", prefix, line)
+            }
+            Event::Message(channel, message) => {
+                let line = self.message(message);
+                writeln!(self.out, "{} ch={} message {}", prefix, channel, line)
+            }
+            Event::Error(channel, reason) => {
+                let channel = channel.map(|channel| format!("ch={} ", channel)).unwrap_or_default();
+                if self.color {
+                    writeln!(self.out, "{}{} {}error: {}{}", RED, prefix, channel, reason, RESET)
+                } else {
+                    writeln!(self.out, "{} {}error: {}", prefix, channel, reason)
+                }
+            }
+        }
+    }
+
+    fn message(&self, message: &Message) -> String {
+        let mut line = self.trace(&message.args);
+        if let Some(header) = &message.header {
+            line.push_str(&format!(" props{}", self.trace(header.properties())));
+        }
+        line.push_str(&format!(" body={}", self.body(&message.body)));
+        line
+    }
+
+    fn print_json(&mut self, direction: Direction, offset: usize, time: Option<u64>, event: &Event) -> io::Result<()> {
+        let mut object = JsonObject::new();
+        if let Some(connection) = &self.connection {
+            object.string("connection", connection);
+        }
+        if let Some(time) = time {
+            object.number("time", format!("{}.{:09}", time / 1_000_000_000, time % 1_000_000_000));
+        }
+        object.number("offset", offset).string("direction", direction.name());
+        match event {
+            Event::ProtocolHeader(header) => {
+                object.string("type", "protocol-header").string("version", &version(header));
+            }
+            Event::Frame(frame) => self.frame_json(frame, &mut object),
+            Event::Message(channel, message) => {
+                object.string("type", "message").number("channel", *channel).string("method", &method_name(&message.args))
+                    .string("arguments", &self.trace(&message.args));
+                if let Some(header) = &message.header {
+                    object.string("properties", &self.trace(header.properties()));
+                }
+                object.number("body_size", message.body.len()).string("body", &self.body(&message.body));
+            }
+            Event::Error(channel, reason) => {
+                object.string("type", "error");
+                if let Some(channel) = channel {
+                    object.number("channel", *channel);
+                }
+                object.string("error", reason);
+            }
+        }
+        writeln!(self.out, "{}", object.finish())
+    }
+
+    fn frame_json(&self, frame: &Frame, object: &mut JsonObject) {
+        match frame.payload() {
+            Payload::Method(method) => {
+                object.string("type", "method").number("channel", frame.channel()).string("method", &method_name(method.args()));
+            }
+            Payload::ContentHeader(header) => {
+                object.string("type", "content-header").number("channel", frame.channel()).number("body_size", header.body_size());
+            }
+            Payload::ContentBody(body) => {
+                object.string("type", "content-body").number("channel", frame.channel()).number("size", body.len());
+            }
+            Payload::Heartbeat(_) => {
+                object.string("type", "heartbeat").number("channel", frame.channel());
+            }
+        }
+        object.string("frame", &self.trace(frame));
+    }
+}
+
+fn summary_fields(summary: &Summary) -> [(&'static str, u64); 5] {
+    [("min_ms", summary.min), ("mean_ms", summary.mean), ("p50_ms", summary.p50), ("p99_ms", summary.p99), ("max_ms", summary.max)]
+}
+
+// nanoseconds as milliseconds, `1.234`
+fn millis(nanos: u64) -> String {
+    format!("{}.{:03}", nanos / 1_000_000, nanos % 1_000_000 / 1000)
+}
+
+// `AMQP 0-9-1`
+fn version(header: &ProtocolHeader) -> String {
+    format!("{} {}-{}-{}", String::from_utf8_lossy(header.protocol()), header.major_id(), header.major_version(), header.minor_version())
+}
+
+/// One line json object, the fields are written in order
+struct JsonObject {
+    text: String
+}
+
+impl JsonObject {
+    fn new() -> Self {
+        JsonObject { text: String::from("{") }
+    }
+
+    fn key(&mut self, key: &str) {
+        if self.text.len() > 1 {
+            self.text.push(',');
+        }
+        push_json_str(&mut self.text, key);
+        self.text.push(':');
+    }
+
+    fn string(&mut self, key: &str, value: &str) -> &mut Self {
+        self.key(key);
+        push_json_str(&mut self.text, value);
+        self
+    }
+
+    fn number(&mut self, key: &str, value: impl Display) -> &mut Self {
+        self.key(key);
+        self.text.push_str(&value.to_string());
+        self
+    }
+
+    fn finish(mut self) -> String {
+        self.text.push('}');
+        self.text
+    }
+}
+
+fn push_json_str(text: &mut String, value: &str) {
+    text.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            c if (c as u32) < 0x20 => text.push_str(&format!("\\u{:04x}", c as u32)),
+            c => text.push(c)
+        }
+    }
+    text.push('"');
+}
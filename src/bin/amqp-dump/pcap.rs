@@ -0,0 +1,283 @@
+//! pcap and pcapng files, only what is needed to get the packets and their capture time.
+
+use std::convert::TryInto;
+
+const PCAP_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_NANOS: u32 = 0xa1b2_3c4d;
+const PCAPNG_SECTION: u32 = 0x0a0d_0d0a;
+const PCAPNG_BYTE_ORDER: u32 = 0x1a2b_3c4d;
+
+const BLOCK_INTERFACE: u32 = 1;
+const BLOCK_PACKET: u32 = 2;
+const BLOCK_SIMPLE_PACKET: u32 = 3;
+const BLOCK_ENHANCED_PACKET: u32 = 6;
+const OPTION_TSRESOL: u16 = 9;
+
+/// A captured packet, `time` is nanoseconds since the unix epoch
+pub struct Packet<'a> {
+    pub time: u64,
+    pub link_type: u32,
+    pub data: &'a [u8]
+}
+
+/// The capture is a pcap or pcapng file
+pub fn is_capture(capture: &[u8]) -> bool {
+    if capture.len() < 4 {
+        return false;
+    }
+    let magic = u32::from_be_bytes(capture[..4].try_into().unwrap());
+    [PCAP_MICROS, PCAP_NANOS, PCAPNG_SECTION].iter().any(|m| magic == *m || magic == m.swap_bytes())
+}
+
+pub fn packets(capture: &[u8]) -> Result<Vec<Packet<'_>>, String> {
+    if capture.len() >= 4 && capture[..4] == PCAPNG_SECTION.to_be_bytes() {
+        pcapng_packets(capture)
+    } else {
+        pcap_packets(capture)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Endian(bool);
+
+impl Endian {
+    fn u16(self, bytes: &[u8]) -> u16 {
+        let bytes = bytes[..2].try_into().unwrap();
+        if self.0 { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) }
+    }
+
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = bytes[..4].try_into().unwrap();
+        if self.0 { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) }
+    }
+}
+
+fn pcap_packets(capture: &[u8]) -> Result<Vec<Packet<'_>>, String> {
+    if capture.len() < 24 {
+        return Err("pcap header is truncated".to_string());
+    }
+    let magic = u32::from_be_bytes(capture[..4].try_into().unwrap());
+    let endian = Endian(magic == PCAP_MICROS || magic == PCAP_NANOS);
+    let nanos = magic == PCAP_NANOS || magic == PCAP_NANOS.swap_bytes();
+    // the upper bits of the link type field carry the fcs length
+    let link_type = endian.u32(&capture[20..]) & 0x0fff_ffff;
+
+    let mut packets = Vec::new();
+    let mut rest = &capture[24..];
+    while !rest.is_empty() {
+        let position = capture.len() - rest.len();
+        if rest.len() < 16 {
+            return Err(format!("pcap record at {} is truncated", position));
+        }
+        let seconds = u64::from(endian.u32(rest));
+        let fraction = u64::from(endian.u32(&rest[4..]));
+        let length = endian.u32(&rest[8..]) as usize;
+        if rest.len() - 16 < length {
+            return Err(format!("pcap record at {} is truncated", position));
+        }
+        let time = seconds * 1_000_000_000 + if nanos { fraction } else { fraction * 1000 };
+        packets.push(Packet { time, link_type, data: &rest[16..16 + length] });
+        rest = &rest[16 + length..];
+    }
+    Ok(packets)
+}
+
+struct Interface {
+    link_type: u32,
+    // if_tsresol, a power of 10 or with the high bit set of 2
+    resolution: u8
+}
+
+impl Interface {
+    fn nanos(&self, timestamp: u64) -> u64 {
+        let exponent = u32::from(self.resolution & 0x7f);
+        let nanos = if self.resolution & 0x80 != 0 {
+            (u128::from(timestamp) * 1_000_000_000) >> exponent.min(127)
+        } else if exponent <= 9 {
+            u128::from(timestamp) * 10u128.pow(9 - exponent)
+        } else {
+            u128::from(timestamp) / 10u128.pow(exponent.min(38) - 9)
+        };
+        nanos as u64
+    }
+}
+
+fn pcapng_packets(capture: &[u8]) -> Result<Vec<Packet<'_>>, String> {
+    let mut packets = Vec::new();
+    let mut interfaces: Vec<Interface> = Vec::new();
+    let mut endian = Endian(true);
+    let mut rest = capture;
+    while !rest.is_empty() {
+        let position = capture.len() - rest.len();
+        if rest.len() < 12 {
+            return Err(format!("pcapng block at {} is truncated", position));
+        }
+        let block_type = endian.u32(rest);
+        if block_type == PCAPNG_SECTION {
+            // every section has its own byte order and interfaces
+            endian = Endian(u32::from_be_bytes(rest[8..12].try_into().unwrap()) == PCAPNG_BYTE_ORDER);
+            interfaces.clear();
+        }
+        let length = endian.u32(&rest[4..]) as usize;
+        if length < 12 || !length.is_multiple_of(4) || length > rest.len() {
+            return Err(format!("pcapng block at {} has an invalid length {}", position, length));
+        }
+        let body = &rest[8..length - 4];
+        let truncated = || format!("pcapng block at {} is truncated", position);
+        match block_type {
+            BLOCK_INTERFACE => {
+                if body.len() < 8 {
+                    return Err(truncated());
+                }
+                let resolution = option(endian, &body[8..], OPTION_TSRESOL).and_then(|value| value.first().cloned()).unwrap_or(6);
+                interfaces.push(Interface { link_type: u32::from(endian.u16(body)), resolution });
+            }
+            BLOCK_ENHANCED_PACKET | BLOCK_PACKET => {
+                if body.len() < 20 {
+                    return Err(truncated());
+                }
+                let interface = if block_type == BLOCK_PACKET { endian.u16(body) as usize } else { endian.u32(body) as usize };
+                let interface = interfaces.get(interface).ok_or_else(|| format!("pcapng block at {} has an unknown interface", position))?;
+                let timestamp = u64::from(endian.u32(&body[4..])) << 32 | u64::from(endian.u32(&body[8..]));
+                let length = endian.u32(&body[12..]) as usize;
+                if body.len() - 20 < length {
+                    return Err(truncated());
+                }
+                packets.push(Packet { time: interface.nanos(timestamp), link_type: interface.link_type, data: &body[20..20 + length] });
+            }
+            BLOCK_SIMPLE_PACKET => {
+                let interface = interfaces.first().ok_or_else(|| format!("pcapng block at {} has an unknown interface", position))?;
+                if body.len() < 4 {
+                    return Err(truncated());
+                }
+                // no timestamp, it is taken as the time of the packet before
+                let time = packets.last().map_or(0, |packet: &Packet<'_>| packet.time);
+                let length = (endian.u32(body) as usize).min(body.len() - 4);
+                packets.push(Packet { time, link_type: interface.link_type, data: &body[4..4 + length] });
+            }
+            _ => {}
+        }
+        rest = &rest[length..];
+    }
+    Ok(packets)
+}
+
+// value of an option of a pcapng block
+fn option(endian: Endian, mut options: &[u8], code: u16) -> Option<&[u8]> {
+    while options.len() >= 4 {
+        let option_code = endian.u16(options);
+        let length = endian.u16(&options[2..]) as usize;
+        if option_code == 0 || options.len() - 4 < length {
+            return None;
+        }
+        if option_code == code {
+            return Some(&options[4..4 + length]);
+        }
+        // values are padded to 32 bits
+        options = options.get((4 + length).div_ceil(4) * 4..).unwrap_or(&[]);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_capture, packets};
+
+    // writes integers in the byte order of the file
+    struct Writer {
+        big_endian: bool,
+        bytes: Vec<u8>
+    }
+
+    impl Writer {
+        fn u16(&mut self, value: u16) -> &mut Self {
+            let bytes = if self.big_endian { value.to_be_bytes() } else { value.to_le_bytes() };
+            self.bytes.extend_from_slice(&bytes);
+            self
+        }
+
+        fn u32(&mut self, value: u32) -> &mut Self {
+            let bytes = if self.big_endian { value.to_be_bytes() } else { value.to_le_bytes() };
+            self.bytes.extend_from_slice(&bytes);
+            self
+        }
+
+        fn raw(&mut self, bytes: &[u8]) -> &mut Self {
+            self.bytes.extend_from_slice(bytes);
+            self
+        }
+
+        // a pcapng block, the body is padded to 32 bits
+        fn block(&mut self, block_type: u32, body: &[u8]) -> &mut Self {
+            let padded = body.len().div_ceil(4) * 4;
+            let length = (padded + 12) as u32;
+            self.u32(block_type).u32(length).raw(body).raw(&vec![0; padded - body.len()]).u32(length)
+        }
+    }
+
+    fn writer(big_endian: bool) -> Writer {
+        Writer { big_endian, bytes: Vec::new() }
+    }
+
+    fn pcap(big_endian: bool, magic: u32) -> Vec<u8> {
+        let mut file = writer(big_endian);
+        file.u32(magic).u16(2).u16(4).u32(0).u32(0).u32(65535).u32(1);
+        file.u32(10).u32(500).u32(3).u32(3).raw(b"one");
+        file.u32(11).u32(0).u32(2).u32(60).raw(b"tw");
+        file.bytes
+    }
+
+    fn pcapng(big_endian: bool) -> Vec<u8> {
+        let mut section = writer(big_endian);
+        section.u32(0x1a2b_3c4d).u16(1).u16(0).u32(u32::MAX).u32(u32::MAX);
+        // nanosecond timestamps, the option value is padded to 32 bits
+        let mut interface = writer(big_endian);
+        interface.u16(101).u16(0).u32(65535).u16(9).u16(1).raw(&[9, 0, 0, 0]).u16(0).u16(0);
+        let mut packet = writer(big_endian);
+        packet.u32(0).u32(0).u32(1_500).u32(5).u32(5).raw(b"three");
+        let mut simple = writer(big_endian);
+        simple.u32(4).raw(b"four");
+
+        let mut file = writer(big_endian);
+        file.block(0x0a0d_0d0a, &section.bytes)
+            .block(1, &interface.bytes)
+            .block(6, &packet.bytes)
+            .block(5, b"statistics are skipped")
+            .block(3, &simple.bytes);
+        file.bytes
+    }
+
+    #[test]
+    fn test_pcap() {
+        for big_endian in [false, true].iter().cloned() {
+            let micros = pcap(big_endian, 0xa1b2_c3d4);
+            assert!(is_capture(&micros));
+            let packets = packets(&micros).unwrap();
+            let packets: Vec<_> = packets.iter().map(|packet| (packet.time, packet.link_type, packet.data)).collect();
+            assert_eq!(packets, vec![(10_000_500_000, 1, &b"one"[..]), (11_000_000_000, 1, &b"tw"[..])]);
+
+            let nanos = pcap(big_endian, 0xa1b2_3c4d);
+            assert_eq!(super::packets(&nanos).unwrap()[0].time, 10_000_000_500);
+
+            let truncated = &micros[..micros.len() - 1];
+            assert_eq!(super::packets(truncated).err().unwrap(), "pcap record at 43 is truncated");
+        }
+    }
+
+    #[test]
+    fn test_pcapng() {
+        for big_endian in [false, true].iter().cloned() {
+            let capture = pcapng(big_endian);
+            assert!(is_capture(&capture));
+            let packets = packets(&capture).unwrap();
+            let packets: Vec<_> = packets.iter().map(|packet| (packet.time, packet.link_type, packet.data)).collect();
+            assert_eq!(packets, vec![(1_500, 101, &b"three"[..]), (1_500, 101, &b"four"[..])]);
+
+            // a packet block before its interface
+            let mut capture = writer(big_endian);
+            capture.block(0x0a0d_0d0a, &pcapng(big_endian)[8..24]).block(6, &[0; 20]);
+            assert_eq!(super::packets(&capture.bytes).err().unwrap(), "pcapng block at 28 has an unknown interface");
+        }
+        assert!(!is_capture(b"AMQP\x00\x00\x09\x01"));
+    }
+}
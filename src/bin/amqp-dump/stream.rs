@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use bytes::BytesMut;
+use amqp_proto::codec::{Arguments, ContentHeaderPayload, DecodedFrame, Frame, FrameCodec, Payload, ProtocolHeader};
+
+/// Which peer sent the bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Client,
+    Server
+}
+
+impl Direction {
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Direction::Client => "C>S",
+            Direction::Server => "S>C"
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Client => "client",
+            Direction::Server => "server"
+        }
+    }
+}
+
+/// A content method with its content header and every body frame
+#[derive(Debug, Default)]
+pub struct Message {
+    pub args: Arguments,
+    pub header: Option<ContentHeaderPayload>,
+    pub body: Vec<u8>
+}
+
+impl Message {
+    fn is_complete(&self) -> bool {
+        match &self.header {
+            Some(header) => self.body.len() as u64 == header.body_size(),
+            None => false
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Event {
+    ProtocolHeader(ProtocolHeader),
+    Frame(Frame),
+    Message(u16, Message),
+    /// The stream is broken, or a frame breaks the content rules of its channel
+    Error(Option<u16>, String)
+}
+
+/// Decodes the bytes one peer sent, `offset` is the position in that stream of the event's first byte
+pub struct Stream {
+    direction: Direction,
+    codec: Option<FrameCodec>,
+    buffer: BytesMut,
+    offset: usize,
+    messages: HashMap<u16, Message>,
+    failed: bool
+}
+
+impl Stream {
+    pub fn new(direction: Direction) -> Self {
+        Stream {
+            direction,
+            codec: None,
+            buffer: BytesMut::new(),
+            offset: 0,
+            messages: HashMap::new(),
+            failed: false
+        }
+    }
+
+    #[inline]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Decode every complete frame of the bytes received so far
+    pub fn feed(&mut self, bytes: &[u8], events: &mut Vec<(usize, Event)>) {
+        // after a decode error the frame boundaries are lost, the rest is skipped
+        if self.failed {
+            return;
+        }
+        self.buffer.extend_from_slice(bytes);
+        if self.codec.is_none() {
+            if self.buffer.len() < 4 {
+                return;
+            }
+            // a server only sends a protocol header to reject the client's version
+            self.codec = Some(if self.buffer.starts_with(b"AMQP") {
+                FrameCodec::default()
+            } else {
+                FrameCodec::without_protocol_header()
+            });
+        }
+        let mut codec = self.codec.take().unwrap();
+        self.decode(&mut codec, events);
+        self.codec = Some(codec);
+    }
+
+    fn decode(&mut self, codec: &mut FrameCodec, events: &mut Vec<(usize, Event)>) {
+        loop {
+            let before = self.buffer.len();
+            match codec.decode_frame(&mut self.buffer) {
+                Ok(Some(DecodedFrame::ProtocolHeader(header))) | Ok(Some(DecodedFrame::VersionMismatch(header))) => events.push((self.offset, Event::ProtocolHeader(header))),
+                Ok(Some(DecodedFrame::AmqpFrame(frame))) => {
+                    let offset = self.offset;
+                    let error = self.reassemble(&frame);
+                    events.push((offset, Event::Frame(frame)));
+                    if let Some(event) = error {
+                        events.push((offset, event));
+                    }
+                    self.complete(offset, events);
+                }
+                Ok(None) => return,
+                Err(e) => {
+                    events.push((self.offset, Event::Error(None, e.to_string())));
+                    self.failed = true;
+                    return;
+                }
+            }
+            self.offset += before - self.buffer.len();
+        }
+    }
+
+    /// Report the incomplete frame and messages left at the end of the capture
+    pub fn finish(&mut self, events: &mut Vec<(usize, Event)>) {
+        if self.failed {
+            return;
+        }
+        if self.codec.is_none() && !self.buffer.is_empty() {
+            self.feed(&[], events);
+        }
+        if !self.buffer.is_empty() {
+            events.push((self.offset, Event::Error(None, format!("capture ends inside a frame, {} bytes left", self.buffer.len()))));
+        }
+        let mut channels: Vec<u16> = self.messages.keys().cloned().collect();
+        channels.sort_unstable();
+        for channel in channels {
+            let message = self.messages.remove(&channel).unwrap();
+            events.push((self.offset, Event::Error(Some(channel), format!("capture ends inside the content of {}", method_name(&message.args)))));
+        }
+    }
+
+    // Track the content of each channel, returns the error of a frame out of order
+    fn reassemble(&mut self, frame: &Frame) -> Option<Event> {
+        let channel = frame.channel();
+        let error = |reason: String| Some(Event::Error(Some(channel), reason));
+        match frame.payload() {
+            Payload::Method(method) => {
+                let args = method.args();
+                let pending = if args.class_method().1.has_content() {
+                    self.messages.insert(channel, Message { args: args.clone(), ..Message::default() })
+                } else {
+                    self.messages.remove(&channel)
+                };
+                pending.and_then(|message| error(format!("content of {} is incomplete", method_name(&message.args))))
+            }
+            Payload::ContentHeader(header) => match self.messages.get_mut(&channel) {
+                Some(message) if message.header.is_none() => {
+                    message.header = Some(header.clone());
+                    None
+                }
+                Some(message) => error(format!("second content header for {}", method_name(&message.args))),
+                None => error("content header without a content method".to_string())
+            },
+            Payload::ContentBody(body) => match self.messages.get_mut(&channel) {
+                Some(Message { header: Some(header), body: content, args }) => {
+                    content.extend_from_slice(body);
+                    if content.len() as u64 > header.body_size() {
+                        let reason = format!("content of {} is {} bytes, body_size is {}", method_name(args), content.len(), header.body_size());
+                        self.messages.remove(&channel);
+                        error(reason)
+                    } else {
+                        None
+                    }
+                }
+                _ => error("content body without a content header".to_string())
+            },
+            Payload::Heartbeat(_) => None
+        }
+    }
+
+    fn complete(&mut self, offset: usize, events: &mut Vec<(usize, Event)>) {
+        let channels: Vec<u16> = self.messages.iter().filter(|(_, message)| message.is_complete()).map(|(channel, _)| *channel).collect();
+        for channel in channels {
+            let message = self.messages.remove(&channel).unwrap();
+            events.push((offset, Event::Message(channel, message)));
+        }
+    }
+}
+
+/// `basic.publish`, or the ids of a method which is not in the spec
+pub fn method_name(args: &Arguments) -> String {
+    if let Arguments::Unknown { class_id, method_id, .. } = args {
+        return format!("{}.{}", class_id, method_id);
+    }
+    let (class, method) = args.class_method();
+    format!("{}.{}", class.name(), method.name().replace('-', "_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use amqp_proto::Class;
+    use amqp_proto::arguments::basic::BasicPublish;
+    use amqp_proto::codec::{Arguments, Encode, Frame, Property};
+    use amqp_proto::properties::basic::BasicProperties;
+    use super::{Direction, Event, Stream};
+
+    fn publish(buffer: &mut BytesMut, channel: u16, body: &[u8], body_size: u64) {
+        Frame::method_frame(channel, Arguments::BasicPublish(BasicPublish::default())).encode(buffer);
+        Frame::content_header_frame(channel, Class::Basic, body_size, Property::Basic(BasicProperties::default())).encode(buffer);
+        for chunk in body.chunks(3) {
+            Frame::content_body_frame(channel, chunk.to_vec()).encode(buffer);
+        }
+    }
+
+    #[test]
+    fn test_reassembly() {
+        let mut buffer = BytesMut::from(&b"AMQP\x00\x00\x09\x01"[..]);
+        publish(&mut buffer, 1, b"hello world", 11);
+        publish(&mut buffer, 2, b"toolong", 4);
+        publish(&mut buffer, 3, b"cut", 10);
+
+        // fed a byte at a time, like a capture split at any point
+        let mut stream = Stream::new(Direction::Client);
+        let mut events = Vec::new();
+        for byte in buffer.iter() {
+            stream.feed(&[*byte], &mut events);
+        }
+        stream.finish(&mut events);
+
+        assert!(matches!(events[0], (0, Event::ProtocolHeader(_))));
+        let messages: Vec<_> = events.iter().filter_map(|(_, event)| match event {
+            Event::Message(channel, message) => Some((*channel, message.body.clone())),
+            _ => None
+        }).collect();
+        assert_eq!(messages, vec![(1, b"hello world".to_vec())]);
+        let errors: Vec<_> = events.iter().filter_map(|(_, event)| match event {
+            Event::Error(channel, reason) => Some((*channel, reason.as_str())),
+            _ => None
+        }).collect();
+        assert_eq!(errors, vec![
+            (Some(2), "content of basic.publish is 6 bytes, body_size is 4"),
+            (Some(2), "content body without a content header"),
+            (Some(3), "capture ends inside the content of basic.publish")
+        ]);
+
+        // a broken frame stops the stream
+        let mut stream = Stream::new(Direction::Server);
+        let mut events = Vec::new();
+        stream.feed(b"\x09\x00\x00\x00\x00\x00\x00\xce", &mut events);
+        stream.feed(Frame::heartbeat_frame_bytes(), &mut events);
+        stream.finish(&mut events);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], (0, Event::Error(None, _))));
+    }
+}
@@ -0,0 +1,221 @@
+//! TCP segments of captured packets and the reassembly of their byte streams.
+
+use std::convert::TryInto;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+const LINK_NULL: u32 = 0;
+const LINK_ETHERNET: u32 = 1;
+const LINK_RAW: u32 = 101;
+const LINK_LOOP: u32 = 108;
+const LINK_LINUX_SLL: u32 = 113;
+const LINK_IPV4: u32 = 228;
+const LINK_IPV6: u32 = 229;
+const LINK_LINUX_SLL2: u32 = 276;
+
+const ETHER_IPV4: u16 = 0x0800;
+const ETHER_IPV6: u16 = 0x86dd;
+const ETHER_VLAN: u16 = 0x8100;
+const ETHER_QINQ: u16 = 0x88a8;
+
+const PROTOCOL_TCP: u8 = 6;
+
+pub const FLAG_SYN: u8 = 0x02;
+pub const FLAG_ACK: u8 = 0x10;
+
+pub struct Segment<'a> {
+    pub source: SocketAddr,
+    pub destination: SocketAddr,
+    pub seq: u32,
+    pub flags: u8,
+    pub payload: &'a [u8]
+}
+
+/// The TCP segment of a packet, None if it is something else or a fragment
+pub fn segment(link_type: u32, data: &[u8]) -> Option<Segment<'_>> {
+    let (ether_type, ip) = match link_type {
+        LINK_ETHERNET => {
+            let mut ether_type = u16::from_be_bytes(data.get(12..14)?.try_into().ok()?);
+            let mut rest = data.get(14..)?;
+            while ether_type == ETHER_VLAN || ether_type == ETHER_QINQ {
+                ether_type = u16::from_be_bytes(rest.get(2..4)?.try_into().ok()?);
+                rest = rest.get(4..)?;
+            }
+            (ether_type, rest)
+        }
+        LINK_LINUX_SLL => (u16::from_be_bytes(data.get(14..16)?.try_into().ok()?), data.get(16..)?),
+        LINK_LINUX_SLL2 => (u16::from_be_bytes(data.get(0..2)?.try_into().ok()?), data.get(20..)?),
+        LINK_NULL | LINK_LOOP => (ip_version(data.get(4..)?)?, data.get(4..)?),
+        LINK_RAW => (ip_version(data)?, data),
+        LINK_IPV4 => (ETHER_IPV4, data),
+        LINK_IPV6 => (ETHER_IPV6, data),
+        _ => return None
+    };
+    let (source, destination, tcp) = match ether_type {
+        ETHER_IPV4 => ipv4(ip)?,
+        ETHER_IPV6 => ipv6(ip)?,
+        _ => return None
+    };
+    let source_port = u16::from_be_bytes(tcp.get(0..2)?.try_into().ok()?);
+    let destination_port = u16::from_be_bytes(tcp.get(2..4)?.try_into().ok()?);
+    let seq = u32::from_be_bytes(tcp.get(4..8)?.try_into().ok()?);
+    let offset = usize::from(tcp.get(12)? >> 4) * 4;
+    let flags = *tcp.get(13)?;
+    Some(Segment {
+        source: SocketAddr::new(source, source_port),
+        destination: SocketAddr::new(destination, destination_port),
+        seq,
+        flags,
+        payload: tcp.get(offset..)?
+    })
+}
+
+fn ip_version(ip: &[u8]) -> Option<u16> {
+    match ip.first()? >> 4 {
+        4 => Some(ETHER_IPV4),
+        6 => Some(ETHER_IPV6),
+        _ => None
+    }
+}
+
+fn ipv4(ip: &[u8]) -> Option<(IpAddr, IpAddr, &[u8])> {
+    let header = usize::from(ip.first()? & 0x0f) * 4;
+    let total = usize::from(u16::from_be_bytes(ip.get(2..4)?.try_into().ok()?));
+    let fragment = u16::from_be_bytes(ip.get(6..8)?.try_into().ok()?);
+    // more fragments or a fragment offset
+    if *ip.get(9)? != PROTOCOL_TCP || fragment & 0x3fff != 0 {
+        return None;
+    }
+    let source: [u8; 4] = ip.get(12..16)?.try_into().ok()?;
+    let destination: [u8; 4] = ip.get(16..20)?.try_into().ok()?;
+    // the ethernet trailer pads short packets, it isn't tcp data
+    let end = if total >= header { total.min(ip.len()) } else { ip.len() };
+    Some((Ipv4Addr::from(source).into(), Ipv4Addr::from(destination).into(), ip.get(header..end)?))
+}
+
+fn ipv6(ip: &[u8]) -> Option<(IpAddr, IpAddr, &[u8])> {
+    let length = usize::from(u16::from_be_bytes(ip.get(4..6)?.try_into().ok()?));
+    let source: [u8; 16] = ip.get(8..24)?.try_into().ok()?;
+    let destination: [u8; 16] = ip.get(24..40)?.try_into().ok()?;
+    let mut next = *ip.get(6)?;
+    let mut rest = ip.get(40..(40 + length).min(ip.len()))?;
+    // hop by hop, routing and destination options headers
+    while matches!(next, 0 | 43 | 60) {
+        next = *rest.first()?;
+        rest = rest.get((usize::from(*rest.get(1)?) + 1) * 8..)?;
+    }
+    if next != PROTOCOL_TCP {
+        return None;
+    }
+    Some((Ipv6Addr::from(source).into(), Ipv6Addr::from(destination).into(), rest))
+}
+
+/// One direction of a TCP connection, puts the segments back in order and drops retransmissions
+#[derive(Default)]
+pub struct Reassembly {
+    next: Option<u32>,
+    pending: Vec<(u32, Vec<u8>)>
+}
+
+impl Reassembly {
+    /// Bytes which are now in order, the sequence of the first segment seen starts the stream if there was no SYN
+    pub fn push(&mut self, segment: &Segment<'_>, data: &mut Vec<u8>) {
+        let mut seq = segment.seq;
+        if segment.flags & FLAG_SYN != 0 {
+            seq = seq.wrapping_add(1);
+            self.next.get_or_insert(seq);
+        }
+        if segment.payload.is_empty() {
+            return;
+        }
+        let next = *self.next.get_or_insert(seq);
+        if Self::append(next, seq, segment.payload, data, &mut self.next) {
+            // the segment may fill the hole before segments received earlier
+            while let Some(index) = self.pending.iter().position(|(seq, _)| (seq.wrapping_sub(self.next.unwrap()) as i32) <= 0) {
+                let (seq, payload) = self.pending.swap_remove(index);
+                Self::append(self.next.unwrap(), seq, &payload, data, &mut self.next);
+            }
+        } else {
+            self.pending.push((seq, segment.payload.to_vec()));
+        }
+    }
+
+    // false if the segment is after a hole
+    fn append(next: u32, seq: u32, payload: &[u8], data: &mut Vec<u8>, position: &mut Option<u32>) -> bool {
+        let ahead = seq.wrapping_sub(next) as i32;
+        if ahead > 0 {
+            return false;
+        }
+        let skip = ahead.unsigned_abs() as usize;
+        if skip < payload.len() {
+            data.extend_from_slice(&payload[skip..]);
+            *position = Some(next.wrapping_add((payload.len() - skip) as u32));
+        }
+        true
+    }
+
+    /// Bytes received after a hole, which never got filled
+    pub fn missing(&self) -> usize {
+        self.pending.iter().map(|(_, payload)| payload.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use super::{segment, Reassembly, Segment, FLAG_ACK, FLAG_SYN};
+
+    fn push(reassembly: &mut Reassembly, seq: u32, flags: u8, payload: &[u8]) -> Vec<u8> {
+        let source: SocketAddr = "10.0.0.1:40000".parse().unwrap();
+        let destination: SocketAddr = "10.0.0.2:5672".parse().unwrap();
+        let mut data = Vec::new();
+        reassembly.push(&Segment { source, destination, seq, flags, payload }, &mut data);
+        data
+    }
+
+    #[test]
+    fn test_reassembly() {
+        let mut reassembly = Reassembly::default();
+        assert!(push(&mut reassembly, 100, FLAG_SYN, b"").is_empty());
+        assert_eq!(push(&mut reassembly, 101, FLAG_ACK, b"AMQP"), b"AMQP");
+
+        // out of order, held until the hole before it is filled
+        assert!(push(&mut reassembly, 110, FLAG_ACK, b"world").is_empty());
+        assert!(push(&mut reassembly, 108, FLAG_ACK, b", ").is_empty());
+        assert_eq!(reassembly.missing(), 7);
+        assert_eq!(push(&mut reassembly, 105, FLAG_ACK, b"hel"), b"hel, world");
+        assert_eq!(reassembly.missing(), 0);
+
+        // retransmitted and overlapping segments only add what is new
+        assert!(push(&mut reassembly, 105, FLAG_ACK, b"hel").is_empty());
+        assert_eq!(push(&mut reassembly, 113, FLAG_ACK, b"ld!"), b"!");
+        assert!(push(&mut reassembly, 120, FLAG_ACK, b"lost").is_empty());
+        // the held segment overlaps the one which fills the hole
+        assert_eq!(push(&mut reassembly, 116, FLAG_ACK, b"abcdefg"), b"abcdefgt");
+        assert_eq!(reassembly.missing(), 0);
+
+        // sequence numbers wrap around
+        let mut reassembly = Reassembly::default();
+        assert_eq!(push(&mut reassembly, u32::MAX - 1, FLAG_ACK, b"ab"), b"ab");
+        assert!(push(&mut reassembly, 2, FLAG_ACK, b"ef").is_empty());
+        assert_eq!(push(&mut reassembly, 0, FLAG_ACK, b"cd"), b"cdef");
+    }
+
+    #[test]
+    fn test_segment() {
+        let mut packet = vec![0; 12];
+        packet.extend_from_slice(&[0x08, 0x00]);
+        // ipv4 with a 20 byte header, total length 44, ethernet pads it to 60 bytes
+        packet.extend_from_slice(&[0x45, 0, 0, 44, 0, 0, 0x40, 0, 64, 6, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2]);
+        packet.extend_from_slice(&[0x9c, 0x40, 0x16, 0x28, 0, 0, 0, 7, 0, 0, 0, 0, 0x50, FLAG_ACK, 0, 0, 0, 0, 0, 0]);
+        packet.extend_from_slice(b"AMQP");
+        packet.extend_from_slice(&[0; 2]);
+        let segment = segment(1, &packet).unwrap();
+        assert_eq!(segment.source, "10.0.0.1:40000".parse().unwrap());
+        assert_eq!(segment.destination, "10.0.0.2:5672".parse().unwrap());
+        assert_eq!((segment.seq, segment.flags, segment.payload), (7, FLAG_ACK, &b"AMQP"[..]));
+
+        // a fragment is not decoded
+        packet[20] = 0x20;
+        assert!(super::segment(1, &packet).is_none());
+    }
+}
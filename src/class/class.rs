@@ -1,5 +1,6 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Class {
     Connection,
     Channel,
@@ -7,8 +8,8 @@ pub enum Class {
     Exchange,
     Queue,
     Basic,
-    Tx,
     Confirm,
+    Tx,
     Unknown
 }
 
@@ -26,6 +27,21 @@ impl Class {
             Class::Unknown => 0xffff
         }
     }
+
+    /// Name of the class in the spec
+    pub fn name(&self) -> &'static str {
+        match self {
+            Class::Connection => "connection",
+            Class::Channel => "channel",
+            Class::Access => "access",
+            Class::Exchange => "exchange",
+            Class::Queue => "queue",
+            Class::Basic => "basic",
+            Class::Confirm => "confirm",
+            Class::Tx => "tx",
+            Class::Unknown => "unknown"
+        }
+    }
 }
 
 impl From<u16> for Class {
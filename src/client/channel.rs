@@ -0,0 +1,251 @@
+use std::sync::Mutex;
+use tokio::sync::{mpsc, oneshot};
+use crate::class::Class;
+use crate::frame::base::{Arguments, Frame, Property};
+use crate::frame::header::basic::BasicProperties;
+use crate::frame::method::basic::{BasicAck, BasicConsume, BasicNack, BasicPublish, BasicReject};
+use crate::frame::method::confirm::ConfirmSelect;
+use crate::frame::method::exchange::ExchangeDeclare;
+use crate::frame::method::queue::{QueueDeclare, QueueDeclareOk};
+use crate::frame::method::tx::{TxSelect, TxCommit, TxRollback};
+use crate::error::client::ClientError;
+use crate::error::amqp::{AmqpError, AmqpErrorKind};
+use crate::method::{TxAck, TxOutcome, TxTracker};
+use crate::client::connection::{Closed, Command, Subscribe};
+use crate::client::consumer::Consumer;
+
+// frame type, channel, payload length and frame end octets around a body payload
+const FRAME_OVERHEAD: u32 = 8;
+
+/// A channel multiplexed over the connection, opened by `Connection::open_channel`
+pub struct Channel {
+    id: u16,
+    frame_max: u32,
+    commands: mpsc::UnboundedSender<Command>,
+    // nothing is sent once it is set, the id may already belong to another channel
+    closed: Closed,
+    // publishes are not kept, only counted in the outcome of a commit or rollback
+    tx: Mutex<TxTracker<()>>
+}
+
+impl Channel {
+    #[inline]
+    pub(crate) fn new(id: u16, frame_max: u32, commands: mpsc::UnboundedSender<Command>) -> Self {
+        Channel { id, frame_max, commands, closed: Closed::default(), tx: Mutex::new(TxTracker::new()) }
+    }
+
+    /// Error the channel was closed with, None while it is open
+    #[inline]
+    pub fn closed(&self) -> Option<AmqpError> {
+        self.closed.lock().unwrap().clone()
+    }
+
+    pub(crate) async fn open(&self) -> Result<Arguments, ClientError> {
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Open { channel: self.id, closed: self.closed.clone(), reply })?;
+        self.response(response).await
+    }
+
+    // the closed check and the send are atomic, the driver sets `closed` before it releases the id
+    fn send(&self, command: Command) -> Result<(), ClientError> {
+        let closed = self.closed.lock().unwrap();
+        if let Some(error) = closed.as_ref() {
+            return Err(ClientError::ChannelClosed(error.clone()));
+        }
+        self.commands.send(command).map_err(|_| ClientError::Disconnected)
+    }
+
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Send frames and wait for the synchronous reply of the server
+    pub(crate) async fn call(&self, frames: Vec<Frame>, subscribe: Option<Subscribe>) -> Result<Arguments, ClientError> {
+        let response = self.request(frames, subscribe)?;
+        self.response(response).await
+    }
+
+    // queue a request, its reply is sent to the returned receiver
+    fn request(&self, frames: Vec<Frame>, subscribe: Option<Subscribe>) -> Result<oneshot::Receiver<Result<Arguments, ClientError>>, ClientError> {
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Call { channel: self.id, frames, reply: Some(reply), subscribe })?;
+        Ok(response)
+    }
+
+    async fn response(&self, response: oneshot::Receiver<Result<Arguments, ClientError>>) -> Result<Arguments, ClientError> {
+        match response.await {
+            Ok(ret) => ret,
+            Err(_) => Err(ClientError::Disconnected)
+        }
+    }
+
+    /// Send frames which have no reply
+    fn cast(&self, frames: Vec<Frame>, subscribe: Option<Subscribe>) -> Result<(), ClientError> {
+        self.send(Command::Call { channel: self.id, frames, reply: None, subscribe })
+    }
+
+    #[inline]
+    fn method_frame(&self, args: Arguments) -> Vec<Frame> {
+        vec![Frame::method_frame(self.id, args)]
+    }
+
+    /// Declare a queue, returns None if `no_wait` is set because the server will not reply
+    pub async fn queue_declare(&self, args: QueueDeclare) -> Result<Option<QueueDeclareOk>, ClientError> {
+        if args.no_wait() {
+            self.cast(self.method_frame(Arguments::QueueDeclare(args)), None)?;
+            return Ok(None);
+        }
+        match self.call(self.method_frame(Arguments::QueueDeclare(args)), None).await? {
+            Arguments::QueueDeclareOk(declare_ok) => Ok(Some(declare_ok)),
+            _ => Err(ClientError::UnexpectedFrame("expected queue.declare-ok"))
+        }
+    }
+
+    pub async fn exchange_declare(&self, args: ExchangeDeclare) -> Result<(), ClientError> {
+        if args.no_wait() {
+            return self.cast(self.method_frame(Arguments::ExchangeDeclare(args)), None);
+        }
+        match self.call(self.method_frame(Arguments::ExchangeDeclare(args)), None).await? {
+            Arguments::ExchangeDeclareOk(_) => Ok(()),
+            _ => Err(ClientError::UnexpectedFrame("expected exchange.declare-ok"))
+        }
+    }
+
+    /// Publish a message, the body is split into content body frames by the negotiated frame_max
+    pub fn basic_publish(&self, args: BasicPublish, properties: BasicProperties, body: Vec<u8>) -> Result<(), ClientError> {
+        let mut frames = self.method_frame(Arguments::BasicPublish(args));
+        frames.push(Frame::content_header_frame(self.id, Class::Basic, body.len() as u64, Property::Basic(properties)));
+        // frame_max is 0 if there is no limit, otherwise at least the minimum frame size
+        match self.frame_max.checked_sub(FRAME_OVERHEAD) {
+            Some(max) if max > 0 && body.len() > max as usize => {
+                for chunk in body.chunks(max as usize) {
+                    frames.push(Frame::content_body_frame(self.id, chunk.to_vec()));
+                }
+            }
+            _ => if !body.is_empty() {
+                frames.push(Frame::content_body_frame(self.id, body));
+            }
+        }
+        // the lock keeps the publish and its record in the order of the transaction
+        let mut tx = self.tx.lock().unwrap();
+        self.cast(frames, None)?;
+        tx.publish(());
+        Ok(())
+    }
+
+    /// Start a consumer, deliveries for it are yielded by the returned stream
+    ///
+    /// A `no_wait` consume must carry its own consumer tag, since the server will not reply with one.
+    pub async fn basic_consume(&self, args: BasicConsume) -> Result<Consumer, ClientError> {
+        let (deliveries, receiver) = mpsc::unbounded_channel();
+        if args.no_wait() {
+            let consumer_tag = args.consumer_tag().to_string();
+            if consumer_tag.is_empty() {
+                return Err(ClientError::Unsupported("no_wait consume without consumer tag"));
+            }
+            let subscribe = Subscribe { consumer_tag: Some(consumer_tag.clone()), deliveries };
+            self.cast(self.method_frame(Arguments::BasicConsume(args)), Some(subscribe))?;
+            return Ok(Consumer::new(consumer_tag, receiver));
+        }
+        let subscribe = Subscribe { consumer_tag: None, deliveries };
+        match self.call(self.method_frame(Arguments::BasicConsume(args)), Some(subscribe)).await? {
+            Arguments::BasicConsumeOk(consume_ok) => Ok(Consumer::new(consume_ok.consumer_tag().to_string(), receiver)),
+            _ => Err(ClientError::UnexpectedFrame("expected basic.consume-ok"))
+        }
+    }
+
+    pub fn basic_ack(&self, args: BasicAck) -> Result<(), ClientError> {
+        let mut tx = self.tx.lock().unwrap();
+        self.cast(self.method_frame(Arguments::BasicAck(args.clone())), None)?;
+        tx.ack(TxAck::Ack(args));
+        Ok(())
+    }
+
+    pub fn basic_nack(&self, args: BasicNack) -> Result<(), ClientError> {
+        let mut tx = self.tx.lock().unwrap();
+        self.cast(self.method_frame(Arguments::BasicNack(args.clone())), None)?;
+        tx.ack(TxAck::Nack(args));
+        Ok(())
+    }
+
+    pub fn basic_reject(&self, args: BasicReject) -> Result<(), ClientError> {
+        let mut tx = self.tx.lock().unwrap();
+        self.cast(self.method_frame(Arguments::BasicReject(args.clone())), None)?;
+        tx.ack(TxAck::Reject(args));
+        Ok(())
+    }
+
+    /// Put the channel in confirm mode, refused without asking the server if the channel is transactional
+    pub async fn confirm_select(&self, args: ConfirmSelect) -> Result<(), ClientError> {
+        self.tx.lock().unwrap().check_confirm_select().map_err(ClientError::Refused)?;
+        if args.no_wait() {
+            self.cast(self.method_frame(Arguments::ConfirmSelect(args)), None)?;
+        } else {
+            match self.call(self.method_frame(Arguments::ConfirmSelect(args)), None).await? {
+                Arguments::ConfirmSelectOk(_) => {}
+                _ => return Err(ClientError::UnexpectedFrame("expected confirm.select-ok"))
+            }
+        }
+        self.tx.lock().unwrap().confirm_select().map_err(ClientError::Refused)
+    }
+
+    /// Make the channel transactional, refused without asking the server if the channel is in confirm mode
+    pub async fn tx_select(&self) -> Result<(), ClientError> {
+        self.tx.lock().unwrap().check_select().map_err(ClientError::Refused)?;
+        match self.call(self.method_frame(Arguments::TxSelect(TxSelect)), None).await? {
+            // the mode only changes once the server agreed
+            Arguments::TxSelectOk(_) => self.tx.lock().unwrap().select().map_err(ClientError::Refused),
+            _ => Err(ClientError::UnexpectedFrame("expected tx.select-ok"))
+        }
+    }
+
+    /// Commit the publishes and acks made since the last commit or rollback
+    pub async fn tx_commit(&self) -> Result<TxOutcome<()>, ClientError> {
+        // the transaction ends in the order the commit is sent, its outcome is only reported with tx.commit-ok
+        let (outcome, response) = {
+            let mut tx = self.tx.lock().unwrap();
+            let outcome = tx.commit().map_err(ClientError::Refused)?;
+            (outcome, self.request(self.method_frame(Arguments::TxCommit(TxCommit)), None)?)
+        };
+        match self.response(response).await? {
+            Arguments::TxCommitOk(_) => Ok(outcome),
+            _ => Err(ClientError::UnexpectedFrame("expected tx.commit-ok"))
+        }
+    }
+
+    /// Discard the publishes and acks made since the last commit or rollback
+    pub async fn tx_rollback(&self) -> Result<TxOutcome<()>, ClientError> {
+        let (outcome, response) = {
+            let mut tx = self.tx.lock().unwrap();
+            let outcome = tx.rollback().map_err(ClientError::Refused)?;
+            (outcome, self.request(self.method_frame(Arguments::TxRollback(TxRollback)), None)?)
+        };
+        match self.response(response).await? {
+            Arguments::TxRollbackOk(_) => Ok(outcome),
+            _ => Err(ClientError::UnexpectedFrame("expected tx.rollback-ok"))
+        }
+    }
+
+    /// Close the channel, consumers on it stop receiving deliveries
+    pub async fn close(self) -> Result<(), ClientError> {
+        let error = AmqpError::new(AmqpErrorKind::ReplySuccess, "Goodbye");
+        let response = self.request(vec![error.to_close_frame(self.id)], None)?;
+        // dropping the handle does not close it again
+        *self.closed.lock().unwrap() = Some(error);
+        match self.response(response).await? {
+            Arguments::ChannelCloseOk(_) => Ok(()),
+            _ => Err(ClientError::UnexpectedFrame("expected channel.close-ok"))
+        }
+    }
+}
+
+/// A channel which is not closed is closed in the background, its id is free once the server replied
+impl Drop for Channel {
+    fn drop(&mut self) {
+        let closed = self.closed.lock().unwrap_or_else(|e| e.into_inner());
+        if closed.is_none() {
+            let _ = self.commands.send(Command::Release { channel: self.id });
+        }
+    }
+}
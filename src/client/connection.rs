@@ -0,0 +1,656 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use property::Property;
+use bytes::BytesMut;
+use futures::{future, SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::time::{self, Duration, Instant, Interval};
+use tokio_util::codec::Framed;
+use crate::{ShortStr, LongStr, FieldName, FieldValue, FieldTable};
+use crate::method::{RpcQueue, ChannelIds, ChannelMethod, Method};
+use crate::sasl;
+use crate::sasl::{SaslMechanism, Plain, AmqPlain};
+use crate::frame::base::{Arguments, Frame, Payload, Property as ContentProperty, ProtocolHeader, Encode};
+use crate::frame::frame_codec::{FrameCodec, DecodedFrame};
+use crate::frame::constants::FRAME_MIN_SIZE;
+use crate::frame::header::basic::BasicProperties;
+use crate::frame::method::basic::BasicDeliver;
+use crate::frame::method::channel::{ChannelOpen, ChannelFlowOk, ChannelCloseOk};
+use crate::frame::method::connection::{ConnectionTuneOk, ConnectionOpen, ConnectionCloseOk, ConnectionUpdateSecret};
+use crate::error::FrameDecodeErr;
+use crate::error::client::ClientError;
+use crate::error::amqp::{AmqpError, AmqpErrorKind};
+use crate::client::channel::Channel;
+use crate::client::consumer::Delivery;
+
+/// Options used while opening the connection
+///
+/// Deliveries are queued for their consumer without a bound, a consumer which does not keep up holds every
+/// message the server sends it in memory. The driver never waits on a consumer, since the consumer may itself
+/// wait on a reply the driver has to read.
+#[derive(Property)]
+#[property(get(public), set(public))]
+pub struct ConnectionConfig {
+    vhost: String,
+    username: String,
+    password: String,
+    locale: String,
+    channel_max: u16,
+    frame_max: u32,
+    // seconds, 0 disables heartbeats, otherwise the lower of it and the server's wins
+    heartbeat: u16
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        ConnectionConfig {
+            vhost: String::from("/"),
+            username: String::from("guest"),
+            password: String::from("guest"),
+            locale: String::from("en_US"),
+            channel_max: 2047,
+            frame_max: 131072,
+            heartbeat: 60
+        }
+    }
+}
+
+pub(crate) type Reply = oneshot::Sender<Result<Arguments, ClientError>>;
+
+/// Set once a channel is closed by either peer, shared by its handle and the driver
+pub(crate) type Closed = Arc<Mutex<Option<AmqpError>>>;
+
+/// Register a consumer, the tag is taken from `BasicConsumeOk` if not given
+pub(crate) struct Subscribe {
+    pub(crate) consumer_tag: Option<String>,
+    pub(crate) deliveries: mpsc::UnboundedSender<Delivery>
+}
+
+pub(crate) enum Command {
+    // the only command which creates the state of a channel
+    Open { channel: u16, closed: Closed, reply: Reply },
+    Call { channel: u16, frames: Vec<Frame>, reply: Option<Reply>, subscribe: Option<Subscribe> },
+    // the handle is dropped while the channel is open, it is closed and its id released on close-ok
+    Release { channel: u16 },
+    Close { reply: Reply }
+}
+
+struct Pending {
+    reply: Reply,
+    subscribe: Option<Subscribe>
+}
+
+// content of a BasicDeliver or BasicReturn which is still being received
+struct Incoming {
+    deliver: Option<BasicDeliver>,
+    properties: Option<BasicProperties>,
+    body_size: u64,
+    body: Vec<u8>
+}
+
+#[derive(Default)]
+struct ChannelState {
+    closed: Closed,
+    pending: RpcQueue<Pending>,
+    consumers: HashMap<String, mpsc::UnboundedSender<Delivery>>,
+    incoming: Option<Incoming>
+}
+
+/// Whether RabbitMQ blocked the connection with `ConnectionBlocked`, the server stops reading from a blocked
+/// connection when it runs low on memory or disk, publishers should wait until it is unblocked
+#[derive(Clone)]
+pub struct Blocked {
+    reason: watch::Receiver<Option<String>>
+}
+
+impl Blocked {
+    #[inline]
+    pub fn is_blocked(&self) -> bool {
+        self.reason.borrow().is_some()
+    }
+
+    /// Reason the server gave, None if the connection is not blocked
+    #[inline]
+    pub fn reason(&self) -> Option<String> {
+        self.reason.borrow().clone()
+    }
+
+    /// Wait until the connection is unblocked, returns at once if it is not blocked
+    pub async fn wait(&self) -> Result<(), ClientError> {
+        let mut reason = self.reason.clone();
+        loop {
+            if reason.borrow().is_none() {
+                return Ok(());
+            }
+            if reason.recv().await.is_none() {
+                return Err(ClientError::Disconnected);
+            }
+        }
+    }
+}
+
+/// A client connection, frames of all channels are multiplexed over one transport which is driven by a spawned task
+pub struct Connection {
+    commands: mpsc::UnboundedSender<Command>,
+    channel_ids: Arc<Mutex<ChannelIds>>,
+    frame_max: u32,
+    server_properties: FieldTable,
+    blocked: Blocked
+}
+
+impl Connection {
+    /// Run the handshake on an established transport, authenticate with PLAIN or AMQPLAIN mechanism
+    pub async fn open<IO>(io: IO, config: ConnectionConfig) -> Result<Connection, ClientError>
+        where IO: AsyncRead + AsyncWrite + Unpin + Send + 'static
+    {
+        let mechanisms: Vec<Box<dyn SaslMechanism + Send>> = vec![
+            Box::new(Plain::new(&config.username, &config.password)),
+            Box::new(AmqPlain::new(&config.username, &config.password))
+        ];
+        Connection::open_with_mechanisms(io, config, mechanisms).await
+    }
+
+    /// Run the handshake, authenticate with the first of the mechanisms the server supports
+    pub async fn open_with_mechanisms<IO>(mut io: IO, config: ConnectionConfig, mechanisms: Vec<Box<dyn SaslMechanism + Send>>) -> Result<Connection, ClientError>
+        where IO: AsyncRead + AsyncWrite + Unpin + Send + 'static
+    {
+        let mut buffer = BytesMut::with_capacity(8);
+        ProtocolHeader::default().encode(&mut buffer);
+        io.write_all(&buffer).await?;
+        let mut framed = Framed::new(io, FrameCodec::client());
+
+        let mut start = match next_method(&mut framed).await? {
+            Arguments::ConnectionStart(start) => start,
+            _ => return Err(ClientError::UnexpectedFrame("expected connection.start"))
+        };
+        let mut mechanism = sasl::select_mechanism(&start.mechanisms().to_string(), mechanisms)?;
+        let start_ok = sasl::start_ok(mechanism.as_mut(), client_properties()?, &config.locale)?;
+        framed.send(Frame::method_frame(0, Arguments::ConnectionStartOk(start_ok))).await?;
+
+        let tune = loop {
+            match next_method(&mut framed).await? {
+                Arguments::ConnectionTune(tune) => break tune,
+                Arguments::ConnectionSecure(secure) => {
+                    let secure_ok = sasl::secure_ok(mechanism.as_mut(), &secure)?;
+                    framed.send(Frame::method_frame(0, Arguments::ConnectionSecureOk(secure_ok))).await?;
+                }
+                Arguments::ConnectionClose(close) => return Err(ClientError::ConnectionClosed(AmqpError::from(&close))),
+                _ => return Err(ClientError::UnexpectedFrame("expected connection.tune"))
+            }
+        };
+        let channel_max = negotiate(config.channel_max as u32, tune.channel_max() as u32) as u16;
+        let frame_max = negotiate(config.frame_max, tune.frame_max());
+        // a body frame has to fit at least a few bytes
+        if frame_max != 0 && frame_max < FRAME_MIN_SIZE {
+            return Err(ClientError::Unsupported("frame_max below the minimum frame size of 4096"));
+        }
+        let heartbeat = match config.heartbeat {
+            0 => 0,
+            heartbeat => negotiate(heartbeat as u32, tune.heartbeat() as u32) as u16
+        };
+        let mut tune_ok = ConnectionTuneOk::default();
+        tune_ok.set_channel_max(channel_max).set_frame_max(frame_max).set_heartbeat(heartbeat);
+        framed.send(Frame::method_frame(0, Arguments::ConnectionTuneOk(tune_ok))).await?;
+        framed.codec_mut().set_frame_max(frame_max);
+
+        let mut open = ConnectionOpen::default();
+        open.set_vhost(ShortStr::with_bytes(config.vhost.as_bytes())?);
+        framed.send(Frame::method_frame(0, Arguments::ConnectionOpen(open))).await?;
+        match next_method(&mut framed).await? {
+            Arguments::ConnectionOpenOk(_) => {},
+            Arguments::ConnectionClose(close) => return Err(ClientError::ConnectionClosed(AmqpError::from(&close))),
+            _ => return Err(ClientError::UnexpectedFrame("expected connection.open-ok"))
+        }
+
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let channel_ids = Arc::new(Mutex::new(ChannelIds::new(channel_max)));
+        let (blocked, reason) = watch::channel(None);
+        let heartbeat = match heartbeat {
+            0 => None,
+            heartbeat => Some(Heartbeat::new(Duration::from_secs(heartbeat as u64)))
+        };
+        let driver = Driver { framed, commands: receiver, channels: HashMap::new(), channel_ids: channel_ids.clone(), closing: None, failed: None, blocked, heartbeat };
+        tokio::spawn(driver.run());
+        Ok(Connection {
+            commands,
+            channel_ids,
+            frame_max,
+            server_properties: std::mem::take(start.mut_server_properties()),
+            blocked: Blocked { reason }
+        })
+    }
+
+    /// Properties the server sent in `ConnectionStart`
+    #[inline]
+    pub fn server_properties(&self) -> &FieldTable {
+        &self.server_properties
+    }
+
+    /// Blocked state of the connection, the handle can be cloned and given to every publisher
+    #[inline]
+    pub fn blocked(&self) -> Blocked {
+        self.blocked.clone()
+    }
+
+    /// Replace the secret the connection authenticated with, an OAuth2 client sends its refreshed access token
+    /// before the old one expires. RabbitMQ closes the connection if it does not accept the new secret.
+    pub async fn update_secret(&self, new_secret: &str, reason: &str) -> Result<(), ClientError> {
+        let mut update_secret = ConnectionUpdateSecret::default();
+        update_secret.set_new_secret(LongStr::with_bytes(new_secret.as_bytes())?).set_reason(ShortStr::with_bytes(reason.as_bytes())?);
+        let (reply, response) = oneshot::channel();
+        let frames = vec![Frame::method_frame(0, Arguments::ConnectionUpdateSecret(update_secret))];
+        if self.commands.send(Command::Call { channel: 0, frames, reply: Some(reply), subscribe: None }).is_err() {
+            return Err(ClientError::Disconnected);
+        }
+        match response.await {
+            Ok(Ok(Arguments::ConnectionUpdateSecretOk(_))) => Ok(()),
+            Ok(Ok(_)) => Err(ClientError::UnexpectedFrame("expected connection.update-secret-ok")),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(ClientError::Disconnected)
+        }
+    }
+
+    pub async fn open_channel(&self) -> Result<Channel, ClientError> {
+        let id = match self.channel_ids.lock().unwrap().allocate() {
+            Some(id) => id,
+            None => return Err(ClientError::ChannelLimit)
+        };
+        let channel = Channel::new(id, self.frame_max, self.commands.clone());
+        match channel.open().await? {
+            Arguments::ChannelOpenOk(_) => Ok(channel),
+            _ => Err(ClientError::UnexpectedFrame("expected channel.open-ok"))
+        }
+    }
+
+    /// Close the connection, all channels are closed by the server as well
+    pub async fn close(self) -> Result<(), ClientError> {
+        let (reply, response) = oneshot::channel();
+        if self.commands.send(Command::Close { reply }).is_err() {
+            return Err(ClientError::Disconnected);
+        }
+        match response.await {
+            Ok(ret) => ret.map(|_| ()),
+            Err(_) => Err(ClientError::Disconnected)
+        }
+    }
+}
+
+// zero means no limit, otherwise the lower one wins
+#[inline]
+fn negotiate(client: u32, server: u32) -> u32 {
+    if client == 0 || server == 0 {
+        std::cmp::max(client, server)
+    } else {
+        std::cmp::min(client, server)
+    }
+}
+
+fn client_properties() -> Result<FieldTable, FrameDecodeErr> {
+    let mut capabilities = FieldTable::new();
+    capabilities.insert(FieldName::with_bytes(b"publisher_confirms")?, FieldValue::from_bool(true));
+    capabilities.insert(FieldName::with_bytes(b"consumer_cancel_notify")?, FieldValue::from_bool(true));
+    capabilities.insert(FieldName::with_bytes(b"connection.blocked")?, FieldValue::from_bool(true));
+    let mut properties = FieldTable::new();
+    properties.insert(FieldName::with_bytes(b"product")?, FieldValue::from_long_string(LongStr::with_bytes(b"amqp-proto")?));
+    properties.insert(FieldName::with_bytes(b"version")?, FieldValue::from_long_string(LongStr::with_bytes(env!("CARGO_PKG_VERSION").as_bytes())?));
+    properties.insert(FieldName::with_bytes(b"capabilities")?, FieldValue::from_field_table(capabilities));
+    Ok(properties)
+}
+
+async fn next_method<IO>(framed: &mut Framed<IO, FrameCodec>) -> Result<Arguments, ClientError>
+    where IO: AsyncRead + AsyncWrite + Unpin
+{
+    loop {
+        match framed.next().await {
+            Some(Ok(DecodedFrame::AmqpFrame(frame))) => {
+                match frame.into_payload() {
+                    Payload::Method(payload) => return Ok(payload.into_args()),
+                    Payload::Heartbeat(_) => continue,
+                    _ => return Err(ClientError::UnexpectedFrame("expected method frame"))
+                }
+            }
+            Some(Ok(DecodedFrame::ProtocolHeader(_))) => return Err(ClientError::UnexpectedFrame("protocol header")),
+            Some(Ok(DecodedFrame::VersionMismatch(header))) => return Err(ClientError::VersionMismatch(header)),
+            Some(Err(e)) => return Err(ClientError::Decode(e)),
+            None => return Err(ClientError::Disconnected)
+        }
+    }
+}
+
+// only the connection close error can be told to every waiter, others are reported as disconnected
+#[inline]
+fn replicate(e: &ClientError) -> ClientError {
+    match e {
+        ClientError::ConnectionClosed(e) => ClientError::ConnectionClosed(e.clone()),
+        _ => ClientError::Disconnected
+    }
+}
+
+// heartbeat frames are sent every interval, the server is gone after two intervals without a frame
+struct Heartbeat {
+    ticks: Interval,
+    interval: Duration,
+    last_read: Instant
+}
+
+impl Heartbeat {
+    fn new(interval: Duration) -> Self {
+        Heartbeat { ticks: time::interval_at(Instant::now() + interval, interval), interval, last_read: Instant::now() }
+    }
+
+    #[inline]
+    fn is_missed(&self) -> bool {
+        self.last_read.elapsed() > self.interval * 2
+    }
+}
+
+// the next heartbeat tick, never if heartbeats are disabled
+async fn tick(heartbeat: &mut Option<Heartbeat>) {
+    match heartbeat {
+        Some(heartbeat) => {
+            heartbeat.ticks.tick().await;
+        }
+        None => future::pending().await
+    }
+}
+
+/// Owns the transport, writes frames from the handles and routes inbound frames to their channel
+struct Driver<IO> {
+    framed: Framed<IO, FrameCodec>,
+    commands: mpsc::UnboundedReceiver<Command>,
+    channels: HashMap<u16, ChannelState>,
+    channel_ids: Arc<Mutex<ChannelIds>>,
+    closing: Option<Reply>,
+    // the client closed the connection because of this error
+    failed: Option<AmqpError>,
+    blocked: watch::Sender<Option<String>>,
+    heartbeat: Option<Heartbeat>
+}
+
+impl<IO> Driver<IO> where IO: AsyncRead + AsyncWrite + Unpin {
+    async fn run(mut self) {
+        let err = loop {
+            tokio::select! {
+                frame = self.framed.next() => {
+                    if let Some(heartbeat) = self.heartbeat.as_mut() {
+                        heartbeat.last_read = Instant::now();
+                    }
+                    let ret = match frame {
+                        Some(Ok(DecodedFrame::AmqpFrame(frame))) => self.handle_frame(frame).await,
+                        Some(Ok(DecodedFrame::ProtocolHeader(_))) | Some(Ok(DecodedFrame::VersionMismatch(_))) => {
+                            Err(ClientError::UnexpectedFrame("protocol header"))
+                        }
+                        Some(Err(e)) => Err(ClientError::Decode(e)),
+                        None => Err(ClientError::Disconnected)
+                    };
+                    match ret {
+                        Ok(true) => continue,
+                        Ok(false) => break ClientError::Disconnected,
+                        Err(e) => break e
+                    }
+                }
+                command = self.commands.recv() => {
+                    match command {
+                        Some(command) => if let Err(e) = self.handle_command(command).await {
+                            break e;
+                        },
+                        // every handle is dropped
+                        None => break ClientError::Disconnected
+                    }
+                }
+                _ = tick(&mut self.heartbeat) => {
+                    if matches!(&self.heartbeat, Some(heartbeat) if heartbeat.is_missed()) {
+                        break ClientError::MissedHeartbeats;
+                    }
+                    if let Err(e) = self.framed.send(Frame::heartbeat_frame()).await {
+                        break ClientError::from(e);
+                    }
+                }
+            }
+        };
+        self.fail(err);
+    }
+
+    async fn handle_command(&mut self, command: Command) -> Result<(), ClientError> {
+        match command {
+            Command::Open { channel, closed, reply } => {
+                let state = self.channels.entry(channel).or_default();
+                state.closed = closed;
+                let open = Arguments::ChannelOpen(ChannelOpen::default());
+                state.pending.push(open.method(), Pending { reply, subscribe: None });
+                self.framed.send(Frame::method_frame(channel, open)).await?;
+            }
+            Command::Call { channel, frames, reply, subscribe } => {
+                let state = match self.channels.get_mut(&channel) {
+                    Some(state) => state,
+                    None if channel == 0 => self.channels.entry(0).or_default(),
+                    // the channel is gone, its frames are not sent
+                    None => {
+                        if let Some(reply) = reply {
+                            let error = AmqpError::new(AmqpErrorKind::ChannelError, "CHANNEL_ERROR - channel is not open");
+                            let _ = reply.send(Err(ClientError::ChannelClosed(error)));
+                        }
+                        return Ok(());
+                    }
+                };
+                match (reply, subscribe) {
+                    (Some(reply), subscribe) => {
+                        let request = match frames.first().map(|frame| frame.payload()) {
+                            Some(Payload::Method(payload)) => *payload.method(),
+                            _ => return Err(ClientError::UnexpectedFrame("request without method frame"))
+                        };
+                        state.pending.push(request, Pending { reply, subscribe });
+                    }
+                    (None, Some(Subscribe { consumer_tag: Some(consumer_tag), deliveries })) => {
+                        state.consumers.insert(consumer_tag, deliveries);
+                    }
+                    _ => {}
+                }
+                for frame in frames {
+                    self.framed.send(frame).await?;
+                }
+            }
+            Command::Release { channel } => {
+                // nothing is sent after connection.close
+                if self.closing.is_some() || self.failed.is_some() {
+                    return Ok(());
+                }
+                if let Some(state) = self.channels.get_mut(&channel) {
+                    // nobody waits for the reply
+                    let (reply, _) = oneshot::channel();
+                    state.pending.push(Method::Channel(ChannelMethod::Close), Pending { reply, subscribe: None });
+                    self.framed.send(AmqpError::new(AmqpErrorKind::ReplySuccess, "Goodbye").to_close_frame(channel)).await?;
+                }
+            }
+            Command::Close { reply } => {
+                self.closing = Some(reply);
+                self.framed.send(AmqpError::new(AmqpErrorKind::ReplySuccess, "Goodbye").to_close_frame(0)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns false once the connection is closed
+    async fn handle_frame(&mut self, frame: Frame) -> Result<bool, ClientError> {
+        let channel = frame.channel();
+        // once the client sent connection.close everything but connection.close and close-ok is discarded
+        if self.failed.is_some() && channel != 0 {
+            return Ok(true);
+        }
+        match frame.into_payload() {
+            Payload::Heartbeat(_) => Ok(true),
+            Payload::Method(payload) => {
+                if let Arguments::Unknown { class_id, method_id, .. } = payload.args() {
+                    if self.failed.is_none() {
+                        let text = format!("NOT_IMPLEMENTED - unknown method {}.{}", class_id, method_id);
+                        let error = AmqpError::with_ids(AmqpErrorKind::NotImplemented, &text, *class_id, *method_id);
+                        self.framed.send(error.to_close_frame(0)).await?;
+                        self.failed = Some(error);
+                    }
+                    Ok(true)
+                } else if channel == 0 {
+                    self.handle_connection_method(payload.into_args()).await
+                } else {
+                    self.handle_channel_method(channel, payload.into_args()).await?;
+                    Ok(true)
+                }
+            }
+            Payload::ContentHeader(header) => {
+                let incoming = match self.channels.get_mut(&channel).and_then(|state| state.incoming.as_mut()) {
+                    Some(incoming) => incoming,
+                    None => return Err(ClientError::UnexpectedFrame("content header without method"))
+                };
+                incoming.body_size = header.body_size();
+                incoming.properties = match header.into_properties() {
+                    ContentProperty::Basic(properties) => Some(properties),
+                    _ => Some(BasicProperties::default())
+                };
+                self.complete_content(channel);
+                Ok(true)
+            }
+            Payload::ContentBody(body) => {
+                match self.channels.get_mut(&channel).and_then(|state| state.incoming.as_mut()) {
+                    Some(incoming) if incoming.properties.is_some() => incoming.body.extend_from_slice(&body),
+                    _ => return Err(ClientError::UnexpectedFrame("content body without header"))
+                }
+                self.complete_content(channel);
+                Ok(true)
+            }
+        }
+    }
+
+    async fn handle_connection_method(&mut self, args: Arguments) -> Result<bool, ClientError> {
+        match args {
+            Arguments::ConnectionClose(close) => {
+                self.framed.send(Frame::method_frame(0, Arguments::ConnectionCloseOk(ConnectionCloseOk))).await?;
+                Err(ClientError::ConnectionClosed(AmqpError::from(&close)))
+            }
+            Arguments::ConnectionCloseOk(close_ok) => {
+                if let Some(error) = self.failed.take() {
+                    return Err(ClientError::ConnectionClosed(error));
+                }
+                if let Some(reply) = self.closing.take() {
+                    let _ = reply.send(Ok(Arguments::ConnectionCloseOk(close_ok)));
+                }
+                Ok(false)
+            }
+            Arguments::ConnectionBlocked(blocked) => {
+                let _ = self.blocked.broadcast(Some(blocked.reason().to_string()));
+                Ok(true)
+            }
+            Arguments::ConnectionUnblocked(_) => {
+                let _ = self.blocked.broadcast(None);
+                Ok(true)
+            }
+            args @ Arguments::ConnectionUpdateSecretOk(_) => {
+                match self.channels.get_mut(&0).map(|state| state.pending.complete(args.method())) {
+                    Some(Ok((_, pending))) => {
+                        let _ = pending.reply.send(Ok(args));
+                        Ok(true)
+                    }
+                    _ => Err(ClientError::UnexpectedFrame("connection.update-secret-ok without request"))
+                }
+            }
+            _ => Ok(true)
+        }
+    }
+
+    async fn handle_channel_method(&mut self, channel: u16, args: Arguments) -> Result<(), ClientError> {
+        let state = match self.channels.get_mut(&channel) {
+            Some(state) => state,
+            None => return Err(ClientError::UnexpectedFrame("method on a channel which is not open"))
+        };
+        match args {
+            Arguments::BasicDeliver(deliver) => {
+                state.incoming = Some(Incoming { deliver: Some(deliver), properties: None, body_size: 0, body: Vec::new() });
+            }
+            Arguments::BasicReturn(_) => {
+                // returned messages are dropped, but their content frames still need to be consumed
+                state.incoming = Some(Incoming { deliver: None, properties: None, body_size: 0, body: Vec::new() });
+            }
+            // publisher confirms are not tracked
+            Arguments::BasicAck(_) | Arguments::BasicNack(_) => {}
+            Arguments::BasicCancel(cancel) => {
+                state.consumers.remove(&cancel.consumer_tag().to_string());
+            }
+            Arguments::ChannelFlow(flow) => {
+                let mut flow_ok = ChannelFlowOk::default();
+                flow_ok.set_active(flow.active());
+                self.framed.send(Frame::method_frame(channel, Arguments::ChannelFlowOk(flow_ok))).await?;
+            }
+            Arguments::ChannelClose(close) => {
+                self.framed.send(Frame::method_frame(channel, Arguments::ChannelCloseOk(ChannelCloseOk))).await?;
+                let error = AmqpError::from(&close);
+                if let Some(mut state) = self.channels.remove(&channel) {
+                    // the handle stops sending before the id can be given to another channel
+                    *state.closed.lock().unwrap() = Some(error.clone());
+                    for (_, pending) in state.pending.drain() {
+                        let _ = pending.reply.send(Err(ClientError::ChannelClosed(error.clone())));
+                    }
+                }
+                self.channel_ids.lock().unwrap().release(channel);
+            }
+            args => {
+                let pending = match state.pending.complete(args.method()) {
+                    Ok((_, pending)) => pending,
+                    Err(_) => return Err(ClientError::UnexpectedFrame("reply does not match the request"))
+                };
+                match &args {
+                    Arguments::BasicConsumeOk(consume_ok) => {
+                        if let Some(subscribe) = pending.subscribe {
+                            state.consumers.insert(consume_ok.consumer_tag().to_string(), subscribe.deliveries);
+                        }
+                    }
+                    Arguments::BasicCancelOk(cancel_ok) => {
+                        state.consumers.remove(&cancel_ok.consumer_tag().to_string());
+                    }
+                    Arguments::ChannelCloseOk(_) => {
+                        self.channels.remove(&channel);
+                        self.channel_ids.lock().unwrap().release(channel);
+                    }
+                    _ => {}
+                }
+                let _ = pending.reply.send(Ok(args));
+            }
+        }
+        Ok(())
+    }
+
+    // hand the message to its consumer once the whole body is received
+    fn complete_content(&mut self, channel: u16) {
+        let state = match self.channels.get_mut(&channel) {
+            Some(state) => state,
+            None => return
+        };
+        let completed = match &state.incoming {
+            Some(incoming) => incoming.properties.is_some() && incoming.body.len() as u64 >= incoming.body_size,
+            None => false
+        };
+        if !completed {
+            return;
+        }
+        if let Some(Incoming { deliver: Some(deliver), properties: Some(properties), body, .. }) = state.incoming.take() {
+            let consumer_tag = deliver.consumer_tag().to_string();
+            let delivered = match state.consumers.get(&consumer_tag) {
+                Some(deliveries) => deliveries.send(Delivery::new(deliver, properties, body)).is_ok(),
+                None => true
+            };
+            // the consumer stream is dropped
+            if !delivered {
+                state.consumers.remove(&consumer_tag);
+            }
+        }
+    }
+
+    fn fail(&mut self, err: ClientError) {
+        for (_, mut state) in self.channels.drain() {
+            for (_, pending) in state.pending.drain() {
+                let _ = pending.reply.send(Err(replicate(&err)));
+            }
+        }
+        if let Some(reply) = self.closing.take() {
+            let _ = reply.send(Err(err));
+        }
+    }
+}
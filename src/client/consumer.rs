@@ -0,0 +1,56 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use property::Property;
+use futures::Stream;
+use tokio::sync::mpsc;
+use crate::frame::method::basic::BasicDeliver;
+use crate::frame::header::basic::BasicProperties;
+
+/// A message delivered to a consumer, the method, content header and content body are reassembled
+#[derive(Property)]
+#[property(get(public), set(disable))]
+pub struct Delivery {
+    deliver: BasicDeliver,
+    properties: BasicProperties,
+    body: Vec<u8>
+}
+
+impl Delivery {
+    #[inline]
+    pub(crate) fn new(deliver: BasicDeliver, properties: BasicProperties, body: Vec<u8>) -> Self {
+        Delivery { deliver, properties, body }
+    }
+
+    /// Take the message body out of the delivery
+    #[inline]
+    pub fn into_body(self) -> Vec<u8> {
+        self.body
+    }
+}
+
+/// Stream of deliveries returned by `Channel::basic_consume`, ends when the consumer is cancelled or the channel is closed
+pub struct Consumer {
+    consumer_tag: String,
+    deliveries: mpsc::UnboundedReceiver<Delivery>
+}
+
+impl Consumer {
+    #[inline]
+    pub(crate) fn new(consumer_tag: String, deliveries: mpsc::UnboundedReceiver<Delivery>) -> Self {
+        Consumer { consumer_tag, deliveries }
+    }
+
+    #[inline]
+    pub fn consumer_tag(&self) -> &str {
+        &self.consumer_tag
+    }
+}
+
+impl Stream for Consumer {
+    type Item = Delivery;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.deliveries.poll_recv(cx)
+    }
+}
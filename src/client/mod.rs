@@ -0,0 +1,316 @@
+mod channel;
+mod connection;
+mod consumer;
+
+pub use channel::Channel;
+pub use connection::{Blocked, Connection, ConnectionConfig};
+pub use consumer::{Consumer, Delivery};
+pub use crate::error::client::ClientError;
+
+#[cfg(test)]
+mod tests {
+    use futures::{SinkExt, StreamExt};
+    use tokio_util::codec::Framed;
+    use crate::{ShortStr, LongStr};
+    use crate::client::{ClientError, Connection, ConnectionConfig};
+    use crate::err::{AmqpError, AmqpErrorKind};
+    use crate::codec::{Arguments, Frame, FrameCodec, DecodedFrame, Payload, Property};
+    use crate::class::Class;
+    use crate::properties::basic::BasicProperties;
+    use crate::arguments::connection::{ConnectionStart, ConnectionTune, ConnectionOpenOk, ConnectionCloseOk, ConnectionBlocked, ConnectionUnblocked, ConnectionUpdateSecretOk};
+    use crate::arguments::channel::{ChannelOpenOk, ChannelCloseOk};
+    use crate::arguments::queue::{QueueDeclare, QueueDeclareOk};
+    use crate::arguments::basic::{BasicAck, BasicConsume, BasicConsumeOk, BasicDeliver, BasicNack, BasicReject};
+    use crate::arguments::confirm::ConfirmSelect;
+    use crate::arguments::tx::{TxSelectOk, TxCommitOk};
+    use crate::TxAck;
+
+    async fn expect_method(server: &mut Framed<tokio::io::DuplexStream, FrameCodec>) -> (u16, Arguments) {
+        match server.next().await {
+            Some(Ok(DecodedFrame::AmqpFrame(frame))) => {
+                let channel = frame.channel();
+                match frame.into_payload() {
+                    Payload::Method(payload) => (channel, payload.into_args()),
+                    _ => panic!("Expected method frame")
+                }
+            }
+            _ => panic!("Expected amqp frame")
+        }
+    }
+
+    // the connection is opened with a frame_max of 4096
+    async fn handshake(server: &mut Framed<tokio::io::DuplexStream, FrameCodec>) {
+        handshake_with_heartbeat(server, 0).await;
+    }
+
+    // the server proposes a heartbeat, returns the one the client chose
+    async fn handshake_with_heartbeat(server: &mut Framed<tokio::io::DuplexStream, FrameCodec>, heartbeat: u16) -> u16 {
+        assert!(matches!(server.next().await, Some(Ok(DecodedFrame::ProtocolHeader(_)))));
+        let mut start = ConnectionStart::default();
+        start.set_version_minor(9).set_mechanisms(LongStr::with_bytes(b"AMQPLAIN PLAIN").unwrap());
+        server.send(Frame::method_frame(0, Arguments::ConnectionStart(start))).await.unwrap();
+        match expect_method(server).await {
+            (0, Arguments::ConnectionStartOk(start_ok)) => assert_eq!(start_ok.response().to_string(), "\0guest\0guest"),
+            _ => panic!("Expected connection.start-ok")
+        }
+        let mut tune = ConnectionTune::default();
+        tune.set_channel_max(16u16).set_frame_max(4096u32).set_heartbeat(heartbeat);
+        server.send(Frame::method_frame(0, Arguments::ConnectionTune(tune))).await.unwrap();
+        let heartbeat = match expect_method(server).await {
+            (0, Arguments::ConnectionTuneOk(tune_ok)) => {
+                assert_eq!(tune_ok.frame_max(), 4096);
+                tune_ok.heartbeat()
+            }
+            _ => panic!("Expected connection.tune-ok")
+        };
+        assert!(matches!(expect_method(server).await, (0, Arguments::ConnectionOpen(_))));
+        server.send(Frame::method_frame(0, Arguments::ConnectionOpenOk(ConnectionOpenOk::default()))).await.unwrap();
+        heartbeat
+    }
+
+    async fn open_channel_ok(server: &mut Framed<tokio::io::DuplexStream, FrameCodec>, channel: u16) {
+        assert_eq!(expect_method(server).await.0, channel);
+        server.send(Frame::method_frame(channel, Arguments::ChannelOpenOk(ChannelOpenOk::default()))).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_open_declare_consume() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut server = Framed::new(server_io, FrameCodec::default());
+            handshake(&mut server).await;
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::ChannelOpen(_))));
+            server.send(Frame::method_frame(1, Arguments::ChannelOpenOk(ChannelOpenOk::default()))).await.unwrap();
+
+            let queue_name = match expect_method(&mut server).await {
+                (1, Arguments::QueueDeclare(declare)) => declare.queue_name().to_string(),
+                _ => panic!("Expected queue.declare")
+            };
+            let mut declare_ok = QueueDeclareOk::default();
+            declare_ok.set_queue_name(ShortStr::with_bytes(queue_name.as_bytes()).unwrap()).set_message_count(3u32);
+            server.send(Frame::method_frame(1, Arguments::QueueDeclareOk(declare_ok))).await.unwrap();
+
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::BasicConsume(_))));
+            let mut consume_ok = BasicConsumeOk::default();
+            consume_ok.set_consumer_tag(ShortStr::with_bytes(b"ctag-1").unwrap());
+            server.send(Frame::method_frame(1, Arguments::BasicConsumeOk(consume_ok))).await.unwrap();
+
+            let mut deliver = BasicDeliver::default();
+            deliver.set_consumer_tag(ShortStr::with_bytes(b"ctag-1").unwrap()).set_delivery_tag(7u64);
+            server.send(Frame::method_frame(1, Arguments::BasicDeliver(deliver))).await.unwrap();
+            server.send(Frame::content_header_frame(1, Class::Basic, 11, Property::Basic(BasicProperties::default()))).await.unwrap();
+            server.send(Frame::content_body_frame(1, b"hello ".to_vec())).await.unwrap();
+            server.send(Frame::content_body_frame(1, b"world".to_vec())).await.unwrap();
+
+            assert!(matches!(expect_method(&mut server).await, (0, Arguments::ConnectionClose(_))));
+            server.send(Frame::method_frame(0, Arguments::ConnectionCloseOk(ConnectionCloseOk))).await.unwrap();
+        });
+
+        let connection = Connection::open(client_io, ConnectionConfig::default()).await.unwrap();
+        let channel = connection.open_channel().await.unwrap();
+        assert_eq!(channel.id(), 1);
+
+        let mut declare = QueueDeclare::default();
+        declare.set_queue_name(ShortStr::with_bytes(b"tasks").unwrap());
+        let declare_ok = channel.queue_declare(declare).await.unwrap().unwrap();
+        assert_eq!(declare_ok.queue_name().to_string(), "tasks");
+        assert_eq!(declare_ok.message_count(), 3);
+
+        let mut consumer = channel.basic_consume(BasicConsume::default()).await.unwrap();
+        assert_eq!(consumer.consumer_tag(), "ctag-1");
+        let delivery = consumer.next().await.unwrap();
+        assert_eq!(delivery.deliver().delivery_tag(), 7);
+        assert_eq!(delivery.body(), &b"hello world"[..]);
+
+        connection.close().await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_blocked_update_secret() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut server = Framed::new(server_io, FrameCodec::default());
+            handshake(&mut server).await;
+
+            match expect_method(&mut server).await {
+                (0, Arguments::ConnectionUpdateSecret(update_secret)) => assert_eq!(update_secret.new_secret().to_string(), "token"),
+                _ => panic!("Expected connection.update-secret")
+            }
+            // the replies follow blocked and unblocked, the client has seen them once update_secret returns
+            let mut blocked = ConnectionBlocked::default();
+            blocked.set_reason(ShortStr::with_bytes(b"low on memory").unwrap());
+            server.send(Frame::method_frame(0, Arguments::ConnectionBlocked(blocked))).await.unwrap();
+            server.send(Frame::method_frame(0, Arguments::ConnectionUpdateSecretOk(ConnectionUpdateSecretOk))).await.unwrap();
+            assert!(matches!(expect_method(&mut server).await, (0, Arguments::ConnectionUpdateSecret(_))));
+            server.send(Frame::method_frame(0, Arguments::ConnectionUnblocked(ConnectionUnblocked))).await.unwrap();
+            server.send(Frame::method_frame(0, Arguments::ConnectionUpdateSecretOk(ConnectionUpdateSecretOk))).await.unwrap();
+
+            assert!(matches!(expect_method(&mut server).await, (0, Arguments::ConnectionClose(_))));
+            server.send(Frame::method_frame(0, Arguments::ConnectionCloseOk(ConnectionCloseOk))).await.unwrap();
+        });
+
+        let connection = Connection::open(client_io, ConnectionConfig::default()).await.unwrap();
+        let blocked = connection.blocked();
+        assert!(!blocked.is_blocked());
+        connection.update_secret("token", "refresh").await.unwrap();
+        assert_eq!(blocked.reason().as_deref(), Some("low on memory"));
+        connection.update_secret("token", "refresh").await.unwrap();
+        assert!(!blocked.is_blocked());
+        blocked.wait().await.unwrap();
+
+        connection.close().await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_channel_closed_by_server() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut server = Framed::new(server_io, FrameCodec::default());
+            handshake(&mut server).await;
+            open_channel_ok(&mut server, 1).await;
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::QueueDeclare(_))));
+            let error = AmqpError::new(AmqpErrorKind::NotFound, "NOT_FOUND - no queue 'tasks'");
+            server.send(error.to_close_frame(1)).await.unwrap();
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::ChannelCloseOk(_))));
+
+            // the next frame on channel 1 opens a new channel, nothing of the closed one is sent
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::ChannelOpen(_))));
+            server.send(Frame::method_frame(1, Arguments::ChannelOpenOk(ChannelOpenOk::default()))).await.unwrap();
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::ChannelClose(_))));
+            server.send(Frame::method_frame(1, Arguments::ChannelCloseOk(ChannelCloseOk))).await.unwrap();
+
+            // a dropped handle closes its channel, close-ok frees the id
+            open_channel_ok(&mut server, 1).await;
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::ChannelClose(_))));
+            server.send(Frame::method_frame(1, Arguments::ChannelCloseOk(ChannelCloseOk))).await.unwrap();
+            assert!(matches!(expect_method(&mut server).await, (0, Arguments::ConnectionUpdateSecret(_))));
+            server.send(Frame::method_frame(0, Arguments::ConnectionUpdateSecretOk(ConnectionUpdateSecretOk))).await.unwrap();
+            open_channel_ok(&mut server, 1).await;
+
+            assert!(matches!(expect_method(&mut server).await, (0, Arguments::ConnectionClose(_))));
+            server.send(Frame::method_frame(0, Arguments::ConnectionCloseOk(ConnectionCloseOk))).await.unwrap();
+        });
+
+        let connection = Connection::open(client_io, ConnectionConfig::default()).await.unwrap();
+        let closed = connection.open_channel().await.unwrap();
+        let mut declare = QueueDeclare::default();
+        declare.set_queue_name(ShortStr::with_bytes(b"tasks").unwrap());
+        match closed.queue_declare(declare.clone()).await {
+            Err(ClientError::ChannelClosed(error)) => assert_eq!(error.kind(), Some(AmqpErrorKind::NotFound)),
+            _ => panic!("Expected channel closed")
+        }
+        assert_eq!(closed.closed().unwrap().kind(), Some(AmqpErrorKind::NotFound));
+
+        let channel = connection.open_channel().await.unwrap();
+        assert_eq!(channel.id(), 1);
+        assert!(matches!(closed.queue_declare(declare).await, Err(ClientError::ChannelClosed(_))));
+        assert!(matches!(closed.basic_publish(Default::default(), Default::default(), Vec::new()), Err(ClientError::ChannelClosed(_))));
+        channel.close().await.unwrap();
+
+        drop(connection.open_channel().await.unwrap());
+        // the reply on channel 0 follows close-ok
+        connection.update_secret("token", "refresh").await.unwrap();
+        let channel = connection.open_channel().await.unwrap();
+        assert_eq!(channel.id(), 1);
+
+        // the channel is not closed after connection.close
+        connection.close().await.unwrap();
+        drop(channel);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_frame_max_too_small() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut server = Framed::new(server_io, FrameCodec::default());
+            assert!(matches!(server.next().await, Some(Ok(DecodedFrame::ProtocolHeader(_)))));
+            let mut start = ConnectionStart::default();
+            start.set_mechanisms(LongStr::with_bytes(b"PLAIN").unwrap());
+            server.send(Frame::method_frame(0, Arguments::ConnectionStart(start))).await.unwrap();
+            assert!(matches!(expect_method(&mut server).await, (0, Arguments::ConnectionStartOk(_))));
+            let mut tune = ConnectionTune::default();
+            tune.set_frame_max(7u32);
+            server.send(Frame::method_frame(0, Arguments::ConnectionTune(tune))).await.unwrap();
+            assert!(server.next().await.is_none());
+        });
+
+        let mut config = ConnectionConfig::default();
+        config.set_frame_max(0u32);
+        assert!(matches!(Connection::open(client_io, config).await, Err(ClientError::Unsupported(_))));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transaction() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut server = Framed::new(server_io, FrameCodec::default());
+            handshake(&mut server).await;
+            open_channel_ok(&mut server, 1).await;
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::TxSelect(_))));
+            server.send(Frame::method_frame(1, Arguments::TxSelectOk(TxSelectOk))).await.unwrap();
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::BasicAck(_))));
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::BasicNack(_))));
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::BasicReject(_))));
+            // confirm.select is refused by the client, commit is next
+            assert!(matches!(expect_method(&mut server).await, (1, Arguments::TxCommit(_))));
+            server.send(Frame::method_frame(1, Arguments::TxCommitOk(TxCommitOk))).await.unwrap();
+
+            assert!(matches!(expect_method(&mut server).await, (0, Arguments::ConnectionClose(_))));
+            server.send(Frame::method_frame(0, Arguments::ConnectionCloseOk(ConnectionCloseOk))).await.unwrap();
+        });
+
+        let connection = Connection::open(client_io, ConnectionConfig::default()).await.unwrap();
+        let channel = connection.open_channel().await.unwrap();
+        channel.tx_select().await.unwrap();
+        channel.basic_ack(BasicAck::default()).unwrap();
+        channel.basic_nack(BasicNack::default()).unwrap();
+        channel.basic_reject(BasicReject::default()).unwrap();
+        match channel.confirm_select(ConfirmSelect::default()).await {
+            Err(ClientError::Refused(error)) => assert_eq!(error.kind(), Some(AmqpErrorKind::PreconditionFailed)),
+            _ => panic!("Expected refused")
+        }
+        let outcome = channel.tx_commit().await.unwrap();
+        assert!(outcome.is_committed());
+        assert!(matches!(outcome.acks(), [TxAck::Ack(_), TxAck::Nack(_), TxAck::Reject(_)]));
+
+        connection.close().await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut server = Framed::new(server_io, FrameCodec::default());
+            // the lower interval wins
+            assert_eq!(handshake_with_heartbeat(&mut server, 1).await, 1);
+            // the client sends heartbeats, the server stays silent until the client gives up
+            let mut heartbeats = 0;
+            while let Some(Ok(DecodedFrame::AmqpFrame(frame))) = server.next().await {
+                if let Payload::Heartbeat(_) = frame.payload() {
+                    heartbeats += 1;
+                }
+            }
+            assert!(heartbeats >= 1);
+        });
+
+        let connection = Connection::open(client_io, ConnectionConfig::default()).await.unwrap();
+        assert!(matches!(connection.close().await, Err(ClientError::MissedHeartbeats)));
+        server.await.unwrap();
+
+        // a heartbeat of 0 is not negotiated up
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut server = Framed::new(server_io, FrameCodec::default());
+            assert_eq!(handshake_with_heartbeat(&mut server, 60).await, 0);
+        });
+        let mut config = ConnectionConfig::default();
+        config.set_heartbeat(0u16);
+        let _connection = Connection::open(client_io, config).await.unwrap();
+        server.await.unwrap();
+    }
+}
@@ -1,5 +1,15 @@
+use std::convert::TryFrom;
 use std::fmt::Formatter;
+use property::Property;
+use crate::ShortStr;
+use crate::class::Class;
+use crate::method::{Method, MethodId, get_method_type};
+use crate::error::FrameDecodeErr;
+use crate::frame::base::{Arguments, Frame};
+use crate::frame::method::connection::ConnectionClose;
+use crate::frame::method::channel::ChannelClose;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum AmqpErrorKind {
     ReplySuccess,
     ContentTooLarge,        // channel
@@ -69,35 +79,252 @@ impl AmqpErrorKind {
             AmqpErrorKind::InternalError => 541
         }
     }
+
+    /// Hard errors close the connection, soft errors only close the channel
+    pub fn is_hard_error(&self) -> bool {
+        !matches!(self, AmqpErrorKind::ReplySuccess
+            | AmqpErrorKind::ContentTooLarge
+            | AmqpErrorKind::NoConsumers
+            | AmqpErrorKind::AccessRefused
+            | AmqpErrorKind::NotFound
+            | AmqpErrorKind::ResourceLocked
+            | AmqpErrorKind::PreconditionFailed)
+    }
 }
 
+impl TryFrom<u16> for AmqpErrorKind {
+    type Error = FrameDecodeErr;
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        match code {
+            200 => Ok(AmqpErrorKind::ReplySuccess),
+            311 => Ok(AmqpErrorKind::ContentTooLarge),
+            313 => Ok(AmqpErrorKind::NoConsumers),
+            320 => Ok(AmqpErrorKind::ConnectionForced),
+            402 => Ok(AmqpErrorKind::InvalidPath),
+            403 => Ok(AmqpErrorKind::AccessRefused),
+            404 => Ok(AmqpErrorKind::NotFound),
+            405 => Ok(AmqpErrorKind::ResourceLocked),
+            406 => Ok(AmqpErrorKind::PreconditionFailed),
+            501 => Ok(AmqpErrorKind::FrameError),
+            502 => Ok(AmqpErrorKind::SyntaxError),
+            503 => Ok(AmqpErrorKind::CommandInvalid),
+            504 => Ok(AmqpErrorKind::ChannelError),
+            505 => Ok(AmqpErrorKind::UnexpectedFrame),
+            506 => Ok(AmqpErrorKind::ResourceError),
+            530 => Ok(AmqpErrorKind::NotAllowed),
+            540 => Ok(AmqpErrorKind::NotImplemented),
+            541 => Ok(AmqpErrorKind::InternalError),
+            _ => Err(FrameDecodeErr::SyntaxError("unknown reply code"))
+        }
+    }
+}
+
+/// Error carried by `ConnectionClose` and `ChannelClose`
+///
+/// The class and method ids are zero if the error is not caused by a method, they are kept as received so the
+/// ids of a method which is not in the spec are reported back.
+#[derive(Property, Clone, Debug)]
+#[property(get(public), set(public))]
 pub struct AmqpError {
-    kind: AmqpErrorKind
+    // fixed at construction like `hard`, which is derived from it
+    #[property(set(disable))]
+    reply_code: u16,
+    reply_text: String,
+    class_id: u16,
+    method_id: u16,
+    // closes the connection instead of the channel, set from the reply code or the close method
+    #[property(set(disable))]
+    hard: bool
 }
 
 impl AmqpError {
-    pub fn kind(&self) -> &AmqpErrorKind {
-        &self.kind
+    pub fn new(kind: AmqpErrorKind, reply_text: &str) -> Self {
+        AmqpError { reply_code: kind.code(), reply_text: reply_text.to_string(), class_id: 0, method_id: 0, hard: kind.is_hard_error() }
+    }
+
+    /// Error caused by the method
+    pub fn with_method(kind: AmqpErrorKind, reply_text: &str, method: Method) -> Self {
+        AmqpError::with_ids(kind, reply_text, method.class().class_id(), method.method_id())
+    }
+
+    /// Error caused by a method given by its ids, such as one which is not in the spec
+    pub fn with_ids(kind: AmqpErrorKind, reply_text: &str, class_id: u16, method_id: u16) -> Self {
+        let mut error = AmqpError::new(kind, reply_text);
+        error.class_id = class_id;
+        error.method_id = method_id;
+        error
+    }
+
+    /// The method which caused the error, None if the ids are zero or not in the spec
+    #[inline]
+    pub fn method(&self) -> Option<Method> {
+        get_method_type(Class::from(self.class_id), self.method_id).ok()
+    }
+
+    /// None if the reply code is not defined by the spec
+    #[inline]
+    pub fn kind(&self) -> Option<AmqpErrorKind> {
+        AmqpErrorKind::try_from(self.reply_code).ok()
+    }
+
+    /// Build `ConnectionClose` for hard errors or errors on channel 0, `ChannelClose` otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amqp_proto::err::{AmqpError, AmqpErrorKind};
+    /// use amqp_proto::codec::Arguments;
+    ///
+    /// let frame = AmqpError::new(AmqpErrorKind::NotFound, "NOT_FOUND - no queue 'q'").to_close_frame(1);
+    /// assert_eq!(frame.channel(), 1);
+    ///
+    /// let frame = AmqpError::new(AmqpErrorKind::FrameError, "FRAME_ERROR").to_close_frame(1);
+    /// assert_eq!(frame.channel(), 0);
+    /// ```
+    pub fn to_close_frame(&self, channel: u16) -> Frame {
+        // reply text longer than a short string is truncated
+        let mut len = std::cmp::min(self.reply_text.len(), u8::MAX as usize);
+        while !self.reply_text.is_char_boundary(len) {
+            len -= 1;
+        }
+        let reply_text = ShortStr::with_bytes(&self.reply_text.as_bytes()[..len]).unwrap_or_default();
+        if self.hard || channel == 0 {
+            let mut close = ConnectionClose::default();
+            close.set_reply_code(self.reply_code).set_reply_text(reply_text).set_class_id(self.class_id).set_method_id(self.method_id);
+            Frame::method_frame(0, Arguments::ConnectionClose(close))
+        } else {
+            let mut close = ChannelClose::default();
+            close.set_reply_code(self.reply_code).set_reply_text(reply_text).set_class_id(self.class_id).set_method_id(self.method_id);
+            Frame::method_frame(channel, Arguments::ChannelClose(close))
+        }
     }
 }
 
 impl From<AmqpErrorKind> for AmqpError {
     fn from(kind: AmqpErrorKind) -> Self {
-        AmqpError { kind }
+        AmqpError::new(kind, "")
     }
 }
 
-impl std::fmt::Display for AmqpError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+impl From<&ConnectionClose> for AmqpError {
+    fn from(close: &ConnectionClose) -> Self {
+        AmqpError {
+            reply_code: close.reply_code(),
+            reply_text: close.reply_text().to_string(),
+            class_id: close.class_id(),
+            method_id: close.method_id(),
+            hard: true
+        }
     }
 }
 
-impl std::fmt::Debug for AmqpError {
+impl From<&ChannelClose> for AmqpError {
+    fn from(close: &ChannelClose) -> Self {
+        AmqpError {
+            reply_code: close.reply_code(),
+            reply_text: close.reply_text().to_string(),
+            class_id: close.class_id(),
+            method_id: close.method_id(),
+            hard: false
+        }
+    }
+}
+
+impl From<&FrameDecodeErr> for AmqpError {
+    fn from(e: &FrameDecodeErr) -> Self {
+        let mut error = AmqpError::new(e.kind(), &e.to_string());
+        if let FrameDecodeErr::DecodeError(detail) = e {
+            if let (Some(class_id), Some(method_id)) = (detail.class_id(), detail.method_id()) {
+                error.class_id = class_id;
+                error.method_id = method_id;
+            }
+        }
+        // the peer can not be trusted after a malformed frame
+        error.hard = true;
+        error
+    }
+}
+
+impl std::fmt::Display for AmqpError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{}, {}", self.reply_code, self.reply_text)?;
+        if self.class_id == 0 && self.method_id == 0 {
+            Ok(())
+        } else {
+            write!(f, " (class {}, method {})", self.class_id, self.method_id)
+        }
     }
 }
 
 impl std::error::Error for AmqpError {
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use crate::ShortStr;
+    use crate::class::Class;
+    use crate::method::{Method, QueueMethod};
+    use crate::frame::method::connection::ConnectionClose;
+    use crate::frame::method::channel::ChannelClose;
+    use crate::error::amqp::{AmqpError, AmqpErrorKind};
+    use crate::frame::base::{Arguments, Payload};
+
+    #[test]
+    fn test_error_kind() {
+        for code in 0..1000u16 {
+            if let Ok(kind) = AmqpErrorKind::try_from(code) {
+                assert_eq!(kind.code(), code);
+            }
+        }
+        assert_eq!(AmqpErrorKind::try_from(404).ok(), Some(AmqpErrorKind::NotFound));
+        assert!(AmqpErrorKind::try_from(0).is_err());
+        assert!(AmqpErrorKind::try_from(599).is_err());
+
+        assert!(!AmqpErrorKind::ReplySuccess.is_hard_error());
+        assert!(!AmqpErrorKind::PreconditionFailed.is_hard_error());
+        assert!(AmqpErrorKind::ConnectionForced.is_hard_error());
+        assert!(AmqpErrorKind::ChannelError.is_hard_error());
+        assert!(!AmqpError::new(AmqpErrorKind::NotFound, "").hard());
+        assert!(AmqpError::new(AmqpErrorKind::FrameError, "").hard());
+    }
+
+    #[test]
+    fn test_from_close() {
+        let method = Method::Queue(QueueMethod::Declare);
+        let mut close = ChannelClose::default();
+        close.set_reply_code(404u16).set_reply_text(ShortStr::with_bytes(b"NOT_FOUND").unwrap()).set_method(method);
+        let error = AmqpError::from(&close);
+        assert_eq!(error.reply_code(), 404);
+        assert_eq!(error.reply_text(), "NOT_FOUND");
+        assert_eq!((error.class_id(), error.method_id()), (Class::Queue.class_id(), 10));
+        assert_eq!(error.method(), Some(method));
+        assert_eq!(error.kind(), Some(AmqpErrorKind::NotFound));
+        assert!(!error.hard());
+        assert_eq!(error.to_close_frame(1).channel(), 1);
+
+        // connection.close is hard whatever its reply code
+        let mut close = ConnectionClose::default();
+        close.set_reply_code(200u16).set_reply_text(ShortStr::with_bytes(b"Goodbye").unwrap());
+        let error = AmqpError::from(&close);
+        assert_eq!(error.kind(), Some(AmqpErrorKind::ReplySuccess));
+        assert_eq!(error.method(), None);
+        assert!(error.hard());
+        assert_eq!(error.to_close_frame(1).channel(), 0);
+
+        close.set_reply_code(999u16);
+        assert_eq!(AmqpError::from(&close).kind(), None);
+
+        // the ids of a vendor method are sent back as they are
+        let error = AmqpError::with_ids(AmqpErrorKind::NotImplemented, "NOT_IMPLEMENTED", 60, 250);
+        assert_eq!(error.method(), None);
+        match error.to_close_frame(0).into_payload() {
+            Payload::Method(payload) => match payload.into_args() {
+                Arguments::ConnectionClose(close) => assert_eq!((close.class_id(), close.method_id(), close.method()), (60, 250, None)),
+                _ => panic!("Expected connection.close")
+            },
+            _ => panic!("Expected method frame")
+        }
+    }
+}
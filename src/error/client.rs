@@ -0,0 +1,76 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use crate::error::{FrameDecodeErr, EncodeError};
+use crate::error::sasl::SaslError;
+use crate::error::amqp::AmqpError;
+use crate::frame::base::ProtocolHeader;
+
+#[derive(Debug)]
+pub enum ClientError {
+    Io(io::Error),
+    Decode(FrameDecodeErr),
+    Encode(EncodeError),
+    Sasl(SaslError),
+    // server closed the connection
+    ConnectionClosed(AmqpError),
+    // server closed the channel
+    ChannelClosed(AmqpError),
+    // refused by the client before it was sent, such as tx.select on a channel in confirm mode
+    Refused(AmqpError),
+    UnexpectedFrame(&'static str),
+    // server does not support the protocol version, with the version it sent back
+    VersionMismatch(ProtocolHeader),
+    Unsupported(&'static str),
+    ChannelLimit,
+    // nothing was received from the server for two heartbeat intervals
+    MissedHeartbeats,
+    Disconnected,
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Io(e) => write!(f, "Io error: {}", e),
+            ClientError::Decode(e) => write!(f, "Decode error: {}", e),
+            ClientError::Encode(e) => write!(f, "Encode error: {}", e),
+            ClientError::Sasl(e) => write!(f, "Sasl error: {}", e),
+            ClientError::ConnectionClosed(e) => write!(f, "Connection closed: {}", e),
+            ClientError::ChannelClosed(e) => write!(f, "Channel closed: {}", e),
+            ClientError::Refused(e) => write!(f, "Refused: {}", e),
+            ClientError::UnexpectedFrame(e) => write!(f, "Unexpected frame: {}", e),
+            ClientError::VersionMismatch(header) => write!(f, "Version mismatch: server supports {}-{}-{}-{}",
+                header.major_id(), header.minor_id(), header.major_version(), header.minor_version()),
+            ClientError::Unsupported(e) => write!(f, "Unsupported: {}", e),
+            ClientError::ChannelLimit => write!(f, "No channel id available"),
+            ClientError::MissedHeartbeats => write!(f, "Missed heartbeats from the server"),
+            ClientError::Disconnected => write!(f, "Disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+impl From<FrameDecodeErr> for ClientError {
+    fn from(e: FrameDecodeErr) -> Self {
+        ClientError::Decode(e)
+    }
+}
+
+impl From<EncodeError> for ClientError {
+    fn from(e: EncodeError) -> Self {
+        ClientError::Encode(e)
+    }
+}
+
+impl From<SaslError> for ClientError {
+    fn from(e: SaslError) -> Self {
+        ClientError::Sasl(e)
+    }
+}
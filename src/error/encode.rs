@@ -0,0 +1,41 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// A value which does not fit its wire format, or an io error of the writer it is encoded for
+#[derive(Debug)]
+pub enum EncodeError {
+    Io(io::Error),
+    // short strings are at most 255 bytes
+    ShortStrTooLong(usize),
+    // long strings, field arrays and field tables are at most u32::MAX bytes
+    LongStrTooLong(usize),
+    FieldArrayTooLong(usize),
+    FieldTableTooLong(usize),
+    // field names start with '$', '#' or a letter and are at most 128 bytes
+    InvalidFieldName(String),
+    // the whole frame is larger than the negotiated frame_max
+    FrameTooLarge { size: usize, frame_max: u32 },
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::Io(e) => write!(f, "Io error: {}", e),
+            EncodeError::ShortStrTooLong(len) => write!(f, "ShortStr of {} bytes is too long", len),
+            EncodeError::LongStrTooLong(len) => write!(f, "LongStr of {} bytes is too long", len),
+            EncodeError::FieldArrayTooLong(len) => write!(f, "FieldArray of {} bytes is too long", len),
+            EncodeError::FieldTableTooLong(len) => write!(f, "FieldTable of {} bytes is too long", len),
+            EncodeError::InvalidFieldName(name) => write!(f, "Invalid field name {:?}", name),
+            EncodeError::FrameTooLarge { size, frame_max } => write!(f, "Frame of {} bytes is larger than frame_max {}", size, frame_max),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl From<io::Error> for EncodeError {
+    fn from(e: io::Error) -> Self {
+        EncodeError::Io(e)
+    }
+}
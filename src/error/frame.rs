@@ -1,13 +1,215 @@
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt;
 use std::io;
+use crate::error::amqp::AmqpErrorKind;
+
+// frame type, channel and payload length octets before the payload
+const FRAME_HEADER_SIZE: usize = 7;
 
 #[derive(Debug)]
 pub enum FrameDecodeErr {
     Incomplete,
     SyntaxError(&'static str),
-    DecodeError(String),
+    DecodeError(Box<DecodeError>),
+}
+
+/// One step of the path to the field which failed to decode
+#[derive(Debug, Clone)]
+pub enum FieldPath {
+    /// Field of a method arguments or content header properties, e.g. `BasicProperties.headers`
+    Field(&'static str),
+    /// Entry of a field table
+    Key(String),
+    /// Item of a field array
+    Index(usize)
+}
+
+/// Where and why a frame failed to decode, and the error the peer should be told
+///
+/// The context is filled in while the error is returned through the decoders, so nothing is paid until decoding fails.
+#[derive(Debug)]
+pub struct DecodeError {
+    kind: AmqpErrorKind,
+    reason: Cow<'static, str>,
+    // bytes left in the payload from the start of the failed field, turned into the offset by the frame decoder
+    remaining: Option<usize>,
+    offset: Option<usize>,
+    channel: Option<u16>,
+    class_id: Option<u16>,
+    method_id: Option<u16>,
+    // outermost step first
+    path: Vec<FieldPath>
+}
+
+impl DecodeError {
+    pub(crate) fn new<R>(kind: AmqpErrorKind, reason: R) -> Self where R: Into<Cow<'static, str>> {
+        DecodeError { kind, reason: reason.into(), remaining: None, offset: None, channel: None, class_id: None, method_id: None, path: Vec::new() }
+    }
+
+    /// Error code which should be sent back in `ConnectionClose`, `FrameError` or `SyntaxError`, `InternalError` for an io error
+    #[inline]
+    pub fn kind(&self) -> AmqpErrorKind {
+        self.kind
+    }
+
+    #[inline]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Byte offset of the failed field from the start of the frame
+    #[inline]
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    #[inline]
+    pub fn channel(&self) -> Option<u16> {
+        self.channel
+    }
+
+    #[inline]
+    pub fn class_id(&self) -> Option<u16> {
+        self.class_id
+    }
+
+    #[inline]
+    pub fn method_id(&self) -> Option<u16> {
+        self.method_id
+    }
+
+    #[inline]
+    pub fn path(&self) -> &[FieldPath] {
+        &self.path
+    }
+
+    /// Field path such as `BasicProperties.headers["x-foo"][2]`
+    pub fn path_string(&self) -> String {
+        let mut path = String::new();
+        for step in &self.path {
+            match step {
+                FieldPath::Field(field) if path.is_empty() => path.push_str(field),
+                FieldPath::Field(field) => {
+                    path.push('.');
+                    path.push_str(field);
+                }
+                FieldPath::Key(key) => path.push_str(&format!("[{:?}]", key)),
+                FieldPath::Index(index) => path.push_str(&format!("[{}]", index))
+            }
+        }
+        path
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)?;
+        if !self.path.is_empty() {
+            write!(f, ", field {}", self.path_string())?;
+        }
+        match (self.class_id, self.method_id) {
+            (Some(class_id), Some(method_id)) => write!(f, ", method {}.{}", class_id, method_id)?,
+            (Some(class_id), None) => write!(f, ", class {}", class_id)?,
+            _ => {}
+        }
+        if let Some(channel) = self.channel {
+            write!(f, ", channel {}", channel)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, ", offset {}", offset)?;
+        }
+        write!(f, " ({})", self.kind.to_string())
+    }
+}
+
+impl FrameDecodeErr {
+    /// Error code the peer should be told about this error, `FrameError` or `SyntaxError`, `InternalError` for an io error
+    ///
+    /// A decoder only sees one frame. A well-formed frame which is invalid in its context, such as content
+    /// without a content method or a method on a channel which is not open, is reported by the caller which
+    /// tracks the channels, with `UnexpectedFrame`, `ChannelError` or `CommandInvalid`.
+    pub fn kind(&self) -> AmqpErrorKind {
+        match self {
+            FrameDecodeErr::Incomplete => AmqpErrorKind::FrameError,
+            FrameDecodeErr::SyntaxError(_) => AmqpErrorKind::SyntaxError,
+            FrameDecodeErr::DecodeError(e) => e.kind
+        }
+    }
+
+    #[inline]
+    pub(crate) fn new<R>(kind: AmqpErrorKind, reason: R) -> FrameDecodeErr where R: Into<Cow<'static, str>> {
+        FrameDecodeErr::DecodeError(Box::new(DecodeError::new(kind, reason)))
+    }
+
+    // a field runs out of a complete payload, the frame is malformed
+    fn into_detail(self) -> Box<DecodeError> {
+        match self {
+            FrameDecodeErr::Incomplete => Box::new(DecodeError::new(AmqpErrorKind::FrameError, "payload too short")),
+            FrameDecodeErr::SyntaxError(reason) => Box::new(DecodeError::new(AmqpErrorKind::SyntaxError, reason)),
+            FrameDecodeErr::DecodeError(e) => e
+        }
+    }
+
+    fn push(self, input: &[u8], step: FieldPath) -> FrameDecodeErr {
+        let mut e = self.into_detail();
+        if e.remaining.is_none() {
+            e.remaining = Some(input.len());
+        }
+        e.path.insert(0, step);
+        FrameDecodeErr::DecodeError(e)
+    }
+
+    /// The field decoded from `input` failed
+    #[inline]
+    pub(crate) fn field(self, input: &[u8], field: &'static str) -> FrameDecodeErr {
+        self.push(input, FieldPath::Field(field))
+    }
+
+    /// The value of a field table entry decoded from `input` failed
+    #[inline]
+    pub(crate) fn key(self, input: &[u8], key: String) -> FrameDecodeErr {
+        self.push(input, FieldPath::Key(key))
+    }
+
+    /// The item of a field array decoded from `input` failed
+    #[inline]
+    pub(crate) fn index(self, input: &[u8], index: usize) -> FrameDecodeErr {
+        self.push(input, FieldPath::Index(index))
+    }
+
+    /// The failure is at the start of `input`, without a field name
+    pub(crate) fn at(self, input: &[u8]) -> FrameDecodeErr {
+        let mut e = self.into_detail();
+        if e.remaining.is_none() {
+            e.remaining = Some(input.len());
+        }
+        FrameDecodeErr::DecodeError(e)
+    }
+
+    /// Record the class of the payload
+    pub(crate) fn class(self, class_id: u16) -> FrameDecodeErr {
+        let mut e = self.into_detail();
+        e.class_id = Some(class_id);
+        FrameDecodeErr::DecodeError(e)
+    }
+
+    /// Record the class and method of the payload
+    pub(crate) fn method(self, class_id: u16, method_id: u16) -> FrameDecodeErr {
+        let mut e = self.into_detail();
+        e.class_id = Some(class_id);
+        e.method_id = Some(method_id);
+        FrameDecodeErr::DecodeError(e)
+    }
+
+    /// Record the channel, and turn the position into an offset from the start of the frame
+    pub(crate) fn frame(self, channel: u16, payload: &[u8]) -> FrameDecodeErr {
+        let mut e = self.into_detail();
+        e.channel = Some(channel);
+        e.offset = Some(FRAME_HEADER_SIZE + payload.len() - e.remaining.unwrap_or(payload.len()));
+        FrameDecodeErr::DecodeError(e)
+    }
 }
 
 impl Display for FrameDecodeErr {
@@ -15,7 +217,7 @@ impl Display for FrameDecodeErr {
         match self {
             FrameDecodeErr::Incomplete => write!(f, "Incomplete"),
             FrameDecodeErr::SyntaxError(e) => write!(f, "Syntax error: {}", e),
-            FrameDecodeErr::DecodeError(e) => write!(f, "Decode error while -> {}", e)
+            FrameDecodeErr::DecodeError(e) => write!(f, "Decode error: {}", e)
         }
     }
 }
@@ -24,6 +226,6 @@ impl std::error::Error for FrameDecodeErr {}
 
 impl From<io::Error> for FrameDecodeErr {
     fn from(e: io::Error) -> Self {
-        FrameDecodeErr::DecodeError(format!("found io error: {}", e))
+        FrameDecodeErr::new(AmqpErrorKind::InternalError, format!("found io error: {}", e))
     }
-}
\ No newline at end of file
+}
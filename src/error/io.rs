@@ -0,0 +1,34 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use crate::error::FrameDecodeErr;
+
+/// Error of the blocking `FrameReader`
+#[derive(Debug)]
+pub enum FrameReadErr {
+    Io(io::Error),
+    Decode(FrameDecodeErr),
+}
+
+impl Display for FrameReadErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameReadErr::Io(e) => write!(f, "Io error: {}", e),
+            FrameReadErr::Decode(e) => write!(f, "Decode error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FrameReadErr {}
+
+impl From<io::Error> for FrameReadErr {
+    fn from(e: io::Error) -> Self {
+        FrameReadErr::Io(e)
+    }
+}
+
+impl From<FrameDecodeErr> for FrameReadErr {
+    fn from(e: FrameDecodeErr) -> Self {
+        FrameReadErr::Decode(e)
+    }
+}
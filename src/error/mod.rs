@@ -1,8 +1,18 @@
-
 pub mod amqp;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "proxy")]
+pub mod proxy;
+#[cfg(feature = "mux")]
+pub mod mux;
+pub mod sasl;
 
 mod frame;
-pub use frame::FrameDecodeErr;
+mod encode;
+mod io;
+pub use frame::{FrameDecodeErr, DecodeError, FieldPath};
+pub use encode::EncodeError;
+pub use io::FrameReadErr;
 use nom::error::ErrorKind;
 
-pub(crate) type NomErr<'a> = (&'a [u8], ErrorKind);
\ No newline at end of file
+pub(crate) type NomErr<'a> = (&'a [u8], ErrorKind);
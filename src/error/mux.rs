@@ -0,0 +1,27 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MuxError {
+    // an inbound frame is on a channel which is not open
+    ChannelNotOpen(u16),
+    // an outbound frame is not on the channel of the handle which sent it
+    WrongChannel(u16),
+    // the channel is closed or the multiplexer is dropped
+    Closed,
+    // the inbound queue of the channel is at its limit, the frame is dropped
+    Overflow(u16),
+}
+
+impl Display for MuxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MuxError::ChannelNotOpen(channel) => write!(f, "Channel {} is not open", channel),
+            MuxError::WrongChannel(channel) => write!(f, "Frame on channel {} sent by another channel", channel),
+            MuxError::Closed => write!(f, "Closed"),
+            MuxError::Overflow(channel) => write!(f, "Inbound queue of channel {} is at its limit", channel),
+        }
+    }
+}
+
+impl std::error::Error for MuxError {}
@@ -0,0 +1,49 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use crate::error::{FrameDecodeErr, EncodeError};
+
+#[derive(Debug)]
+pub enum ProxyError {
+    Io(io::Error),
+    Decode(FrameDecodeErr),
+    Encode(EncodeError),
+    // a rewritten frame is on a channel the client has not opened
+    ChannelNotOpen(u16),
+    UnexpectedFrame(&'static str),
+    // a message body on the channel is larger than the pipeline buffers
+    ContentTooLarge { channel: u16, size: u64, max: u64 },
+}
+
+impl Display for ProxyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyError::Io(e) => write!(f, "Io error: {}", e),
+            ProxyError::Decode(e) => write!(f, "Decode error: {}", e),
+            ProxyError::Encode(e) => write!(f, "Encode error: {}", e),
+            ProxyError::ChannelNotOpen(channel) => write!(f, "Channel {} is not open", channel),
+            ProxyError::UnexpectedFrame(e) => write!(f, "Unexpected frame: {}", e),
+            ProxyError::ContentTooLarge { channel, size, max } => write!(f, "Content of {} bytes on channel {} is larger than {}", size, channel, max),
+        }
+    }
+}
+
+impl std::error::Error for ProxyError {}
+
+impl From<io::Error> for ProxyError {
+    fn from(e: io::Error) -> Self {
+        ProxyError::Io(e)
+    }
+}
+
+impl From<FrameDecodeErr> for ProxyError {
+    fn from(e: FrameDecodeErr) -> Self {
+        ProxyError::Decode(e)
+    }
+}
+
+impl From<EncodeError> for ProxyError {
+    fn from(e: EncodeError) -> Self {
+        ProxyError::Encode(e)
+    }
+}
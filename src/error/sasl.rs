@@ -0,0 +1,45 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use crate::error::FrameDecodeErr;
+
+#[derive(Debug)]
+pub enum SaslError {
+    // none of the mechanisms offered by the server is supported
+    NoMechanism,
+    // the challenge or response does not follow the mechanism format
+    Malformed(&'static str),
+    // a challenge arrived after the exchange is finished, or the mechanism has no challenge
+    UnexpectedChallenge,
+    // credentials are rejected by the verifier
+    AuthenticationFailed,
+    // the server proof of SCRAM does not match, the server does not know the password
+    ServerSignatureMismatch,
+    // the SCRAM server asks for more iterations than the client accepts
+    TooManyIterations(u32),
+    // random source is not available
+    Nonce,
+    Decode(FrameDecodeErr),
+}
+
+impl Display for SaslError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SaslError::NoMechanism => write!(f, "No supported mechanism"),
+            SaslError::Malformed(e) => write!(f, "Malformed message: {}", e),
+            SaslError::UnexpectedChallenge => write!(f, "Unexpected challenge"),
+            SaslError::AuthenticationFailed => write!(f, "Authentication failed"),
+            SaslError::ServerSignatureMismatch => write!(f, "Server signature mismatch"),
+            SaslError::TooManyIterations(iterations) => write!(f, "Too many SCRAM iterations: {}", iterations),
+            SaslError::Nonce => write!(f, "Failed to generate nonce"),
+            SaslError::Decode(e) => write!(f, "Decode error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SaslError {}
+
+impl From<FrameDecodeErr> for SaslError {
+    fn from(e: FrameDecodeErr) -> Self {
+        SaslError::Decode(e)
+    }
+}
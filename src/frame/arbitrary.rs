@@ -0,0 +1,180 @@
+use proptest::prelude::*;
+use proptest::collection::{vec, hash_map};
+use proptest::option;
+use proptest::strategy::{LazyJust, Union};
+use crate::{ShortStr, LongStr, Decimal, FieldName, FieldValue, FieldArray, FieldTable};
+use crate::class::Class;
+use crate::method::get_method_type;
+use crate::frame::base::{Frame, Property, Arguments};
+use crate::frame::header::basic::BasicProperties;
+use crate::frame::method::connection::*;
+use crate::frame::method::channel::*;
+use crate::frame::method::access::*;
+use crate::frame::method::exchange::*;
+use crate::frame::method::queue::*;
+use crate::frame::method::basic::*;
+use crate::frame::method::tx::*;
+use crate::frame::method::confirm::*;
+
+/// Valid UTF-8 ShortStr, at most 63 chars so it always fits the 255 bytes
+pub fn short_str() -> impl Strategy<Value = ShortStr> {
+    "\\PC{0,63}".prop_map(|value| ShortStr::with_bytes(value.as_bytes()).unwrap())
+}
+
+/// Valid UTF-8 LongStr
+pub fn long_str() -> impl Strategy<Value = LongStr> {
+    "\\PC{0,256}".prop_map(|value| LongStr::with_bytes(value.as_bytes()).unwrap())
+}
+
+/// Field table key, starts with a letter, '$' or '#'
+pub fn field_name() -> impl Strategy<Value = FieldName> {
+    "[a-zA-Z$#][a-zA-Z0-9$#_.:-]{0,31}".prop_map(|value| FieldName::with_bytes(value.as_bytes()).unwrap())
+}
+
+pub fn decimal() -> impl Strategy<Value = Decimal> {
+    (any::<u8>(), any::<u32>()).prop_map(|(scale, value)| Decimal::new(scale, value))
+}
+
+// every bit pattern, NaNs included, floats compare by their bits
+fn float32() -> impl Strategy<Value = f32> {
+    any::<u32>().prop_map(f32::from_bits)
+}
+
+fn float64() -> impl Strategy<Value = f64> {
+    any::<u64>().prop_map(f64::from_bits)
+}
+
+/// Field value of every kind, arrays and tables are nested at most 3 levels
+pub fn field_value() -> BoxedStrategy<FieldValue> {
+    let leaf = prop_oneof![
+        any::<bool>().prop_map(FieldValue::Boolean),
+        any::<u8>().prop_map(FieldValue::U8),
+        any::<i8>().prop_map(FieldValue::I8),
+        any::<u16>().prop_map(FieldValue::U16),
+        any::<i16>().prop_map(FieldValue::I16),
+        any::<u32>().prop_map(FieldValue::U32),
+        any::<i32>().prop_map(FieldValue::I32),
+        any::<u64>().prop_map(FieldValue::U64),
+        any::<i64>().prop_map(FieldValue::I64),
+        float32().prop_map(FieldValue::F32),
+        float64().prop_map(FieldValue::F64),
+        any::<u64>().prop_map(FieldValue::Timestamp),
+        decimal().prop_map(FieldValue::Decimal),
+        long_str().prop_map(FieldValue::LongStr),
+        long_str().prop_map(FieldValue::BytesArray),
+        LazyJust::new(|| FieldValue::Void)
+    ];
+    leaf.prop_recursive(3, 32, 4, |inner| prop_oneof![
+        vec(inner.clone(), 0..4).prop_map(FieldValue::FieldArray),
+        hash_map(field_name(), inner, 0..4).prop_map(FieldValue::FieldTable)
+    ]).boxed()
+}
+
+pub fn field_array() -> impl Strategy<Value = FieldArray> {
+    vec(field_value(), 0..8)
+}
+
+pub fn field_table() -> impl Strategy<Value = FieldTable> {
+    hash_map(field_name(), field_value(), 0..8)
+}
+
+/// Basic properties, every property is present or absent independently and the flags follow
+pub fn basic_properties() -> impl Strategy<Value = BasicProperties> {
+    let first = (option::of(short_str()), option::of(short_str()), option::of(field_table()), option::of(any::<u8>()),
+        option::of(any::<u8>()), option::of(short_str()), option::of(short_str()));
+    let second = (option::of(short_str()), option::of(short_str()), option::of(any::<u64>()), option::of(short_str()),
+        option::of(short_str()), option::of(short_str()), option::of(short_str()));
+    (first, second).prop_map(|(first, second)| {
+        let (content_type, content_encoding, headers, delivery_mode, priority, correlation_id, reply_to) = first;
+        let (expiration, message_id, timestamp, basic_type, user_id, app_id, cluster_id) = second;
+        let mut properties = BasicProperties::default();
+        if let Some(v) = content_type { properties.set_content_type(v); }
+        if let Some(v) = content_encoding { properties.set_content_encoding(v); }
+        if let Some(v) = headers { properties.set_headers(v); }
+        if let Some(v) = delivery_mode { properties.set_delivery_mode(v); }
+        if let Some(v) = priority { properties.set_priority(v); }
+        if let Some(v) = correlation_id { properties.set_correlation_id(v); }
+        if let Some(v) = reply_to { properties.set_reply_to(v); }
+        if let Some(v) = expiration { properties.set_expiration(v); }
+        if let Some(v) = message_id { properties.set_message_id(v); }
+        if let Some(v) = timestamp { properties.set_timestamp(v); }
+        if let Some(v) = basic_type { properties.set_basic_type(v); }
+        if let Some(v) = user_id { properties.set_user_id(v); }
+        if let Some(v) = app_id { properties.set_app_id(v); }
+        if let Some(v) = cluster_id { properties.set_cluster_id(v); }
+        properties
+    })
+}
+
+// A flat union, the nested unions a derive makes of this many variants overflow the stack of a test thread
+macro_rules! arguments_union {
+    ($($args:ident),+) => {
+        Union::new(vec![$(any::<$args>().prop_map(Arguments::$args).boxed()),+])
+    }
+}
+
+/// Class and method ids which are not in the spec with any bytes as their arguments
+pub fn unknown_arguments() -> impl Strategy<Value = Arguments> {
+    (any::<u16>(), any::<u16>(), vec(any::<u8>(), 0..64))
+        .prop_filter("known method", |(class_id, method_id, _)| get_method_type(Class::from(*class_id), *method_id).is_err())
+        .prop_map(|(class_id, method_id, raw)| Arguments::Unknown { class_id, method_id, raw: raw.into() })
+}
+
+/// Arguments of every method and of unknown ones
+pub fn arguments() -> impl Strategy<Value = Arguments> {
+    arguments_union!(
+        ConnectionStart, ConnectionStartOk, ConnectionSecure, ConnectionSecureOk, ConnectionTune,
+        ConnectionTuneOk, ConnectionOpen, ConnectionOpenOk, ConnectionClose, ConnectionCloseOk, ConnectionBlocked,
+        ConnectionUnblocked, ConnectionUpdateSecret, ConnectionUpdateSecretOk,
+        ChannelOpen, ChannelOpenOk, ChannelFlow, ChannelFlowOk, ChannelClose, ChannelCloseOk,
+        AccessRequest, AccessRequestOk,
+        ExchangeDeclare, ExchangeDeclareOk, ExchangeDelete, ExchangeDeleteOk, ExchangeBind, ExchangeBindOk,
+        ExchangeUnbind, ExchangeUnbindOk,
+        QueueDeclare, QueueDeclareOk, QueueBind, QueueBindOk, QueueUnbind, QueueUnbindOk, QueuePurge,
+        QueuePurgeOk, QueueDelete, QueueDeleteOk,
+        BasicQos, BasicQosOk, BasicConsume, BasicConsumeOk, BasicCancel, BasicCancelOk, BasicPublish, BasicReturn,
+        BasicDeliver, BasicGet, BasicGetOk, BasicGetEmpty, BasicAck, BasicReject, BasicRecoverAsync, BasicRecover,
+        BasicRecoverOk, BasicNack,
+        TxSelect, TxSelectOk, TxCommit, TxCommitOk, TxRollback, TxRollbackOk,
+        ConfirmSelect, ConfirmSelectOk
+    ).or(unknown_arguments().boxed())
+}
+
+/// Heartbeat, method, content header of basic and unknown classes and content body frames
+pub fn frame() -> impl Strategy<Value = Frame> {
+    prop_oneof![
+        1 => LazyJust::new(Frame::heartbeat_frame),
+        6 => (any::<u16>(), arguments()).prop_map(|(channel, args)| Frame::method_frame(channel, args)),
+        2 => (any::<u16>(), any::<u64>(), basic_properties())
+            .prop_map(|(channel, body_size, properties)| Frame::content_header_frame(channel, Class::Basic, body_size, Property::Basic(properties))),
+        1 => (any::<u16>(), any::<u16>().prop_filter("known class", |class_id| Class::from(*class_id) == Class::Unknown), any::<u64>(), vec(any::<u8>(), 0..64))
+            .prop_map(|(channel, class_id, body_size, raw)| Frame::content_header_frame(channel, Class::Unknown, body_size, Property::Unknown { class_id, raw: raw.into() })),
+        1 => (any::<u16>(), vec(any::<u8>(), 0..256)).prop_map(|(channel, body)| Frame::content_body_frame(channel, body))
+    ]
+}
+
+macro_rules! impl_arbitrary {
+    ($($t:ty => $strategy:expr),+) => {
+        $(
+            impl Arbitrary for $t {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<$t>;
+
+                fn arbitrary_with(_: ()) -> Self::Strategy {
+                    $strategy.boxed()
+                }
+            }
+        )+
+    }
+}
+
+impl_arbitrary! {
+    ShortStr => short_str(),
+    LongStr => long_str(),
+    FieldName => field_name(),
+    Decimal => decimal(),
+    FieldValue => field_value(),
+    BasicProperties => basic_properties(),
+    Frame => frame(),
+    Arguments => arguments()
+}
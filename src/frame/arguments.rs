@@ -0,0 +1,530 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
+use bytes::{Bytes, BytesMut};
+use crate::class::Class;
+use crate::error::{EncodeError, FrameDecodeErr};
+use crate::frame::base::{Decode, Encode};
+use crate::frame::method::connection::{ConnectionStart, ConnectionStartOk, ConnectionSecure, ConnectionSecureOk, ConnectionTune, ConnectionTuneOk, ConnectionOpen, ConnectionOpenOk, ConnectionClose, ConnectionCloseOk, ConnectionBlocked, ConnectionUnblocked, ConnectionUpdateSecret, ConnectionUpdateSecretOk};
+use crate::frame::method::channel::{ChannelOpen, ChannelOpenOk, ChannelFlow, ChannelFlowOk, ChannelClose, ChannelCloseOk};
+use crate::frame::method::exchange::{ExchangeDeclare, ExchangeDeclareOk, ExchangeDelete, ExchangeDeleteOk, ExchangeBind, ExchangeBindOk, ExchangeUnbind, ExchangeUnbindOk};
+use crate::frame::method::queue::{QueueDeclare, QueueDeclareOk, QueueBind, QueueBindOk, QueueUnbind, QueueUnbindOk, QueuePurge, QueuePurgeOk, QueueDelete, QueueDeleteOk};
+use crate::frame::method::basic::{BasicQos, BasicQosOk, BasicConsume, BasicConsumeOk, BasicCancel, BasicCancelOk, BasicPublish, BasicReturn, BasicDeliver, BasicGet, BasicGetOk, BasicGetEmpty, BasicAck, BasicReject, BasicRecoverAsync, BasicRecover, BasicRecoverOk, BasicNack};
+use crate::frame::method::tx::{TxSelect, TxSelectOk, TxCommit, TxCommitOk, TxRollback, TxRollbackOk};
+use crate::frame::method::access::{AccessRequest, AccessRequestOk};
+use crate::frame::method::confirm::{ConfirmSelect, ConfirmSelectOk};
+use crate::method::{Method, ConnectionMethod, ChannelMethod, ExchangeMethod, QueueMethod, BasicMethod, TxMethod, AccessMethod, ConfirmMethod};
+
+/// Arguments of a method frame, one variant per method of the spec
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Arguments {
+    ConnectionStart(ConnectionStart),
+    ConnectionStartOk(ConnectionStartOk),
+    ConnectionSecure(ConnectionSecure),
+    ConnectionSecureOk(ConnectionSecureOk),
+    ConnectionTune(ConnectionTune),
+    ConnectionTuneOk(ConnectionTuneOk),
+    ConnectionOpen(ConnectionOpen),
+    ConnectionOpenOk(ConnectionOpenOk),
+    ConnectionClose(ConnectionClose),
+    ConnectionCloseOk(ConnectionCloseOk),
+    ConnectionBlocked(ConnectionBlocked),
+    ConnectionUnblocked(ConnectionUnblocked),
+    ConnectionUpdateSecret(ConnectionUpdateSecret),
+    ConnectionUpdateSecretOk(ConnectionUpdateSecretOk),
+
+    ChannelOpen(ChannelOpen),
+    ChannelOpenOk(ChannelOpenOk),
+    ChannelFlow(ChannelFlow),
+    ChannelFlowOk(ChannelFlowOk),
+    ChannelClose(ChannelClose),
+    ChannelCloseOk(ChannelCloseOk),
+
+    ExchangeDeclare(ExchangeDeclare),
+    ExchangeDeclareOk(ExchangeDeclareOk),
+    ExchangeDelete(ExchangeDelete),
+    ExchangeDeleteOk(ExchangeDeleteOk),
+    ExchangeBind(ExchangeBind),
+    ExchangeBindOk(ExchangeBindOk),
+    ExchangeUnbind(ExchangeUnbind),
+    ExchangeUnbindOk(ExchangeUnbindOk),
+
+    QueueDeclare(QueueDeclare),
+    QueueDeclareOk(QueueDeclareOk),
+    QueueBind(QueueBind),
+    QueueBindOk(QueueBindOk),
+    QueueUnbind(QueueUnbind),
+    QueueUnbindOk(QueueUnbindOk),
+    QueuePurge(QueuePurge),
+    QueuePurgeOk(QueuePurgeOk),
+    QueueDelete(QueueDelete),
+    QueueDeleteOk(QueueDeleteOk),
+
+    BasicQos(BasicQos),
+    BasicQosOk(BasicQosOk),
+    BasicConsume(BasicConsume),
+    BasicConsumeOk(BasicConsumeOk),
+    BasicCancel(BasicCancel),
+    BasicCancelOk(BasicCancelOk),
+    BasicPublish(BasicPublish),
+    BasicReturn(BasicReturn),
+    BasicDeliver(BasicDeliver),
+    BasicGet(BasicGet),
+    BasicGetOk(BasicGetOk),
+    BasicGetEmpty(BasicGetEmpty),
+    BasicAck(BasicAck),
+    BasicReject(BasicReject),
+    BasicRecoverAsync(BasicRecoverAsync),
+    BasicRecover(BasicRecover),
+    BasicRecoverOk(BasicRecoverOk),
+    BasicNack(BasicNack),
+
+    TxSelect(TxSelect),
+    TxSelectOk(TxSelectOk),
+    TxCommit(TxCommit),
+    TxCommitOk(TxCommitOk),
+    TxRollback(TxRollback),
+    TxRollbackOk(TxRollbackOk),
+
+    AccessRequest(AccessRequest),
+    AccessRequestOk(AccessRequestOk),
+
+    ConfirmSelect(ConfirmSelect),
+    ConfirmSelectOk(ConfirmSelectOk),
+
+    /// A class or method which is not in the spec, such as a vendor extension, `raw` is the arguments as received
+    Unknown { class_id: u16, method_id: u16, raw: Bytes }
+}
+
+impl Encode for Arguments {
+    fn encode(&self, buffer: &mut BytesMut) {
+        match self {
+            Arguments::ConnectionStart(args) => args.encode(buffer),
+            Arguments::ConnectionStartOk(args) => args.encode(buffer),
+            Arguments::ConnectionSecure(args) => args.encode(buffer),
+            Arguments::ConnectionSecureOk(args) => args.encode(buffer),
+            Arguments::ConnectionTune(args) => args.encode(buffer),
+            Arguments::ConnectionTuneOk(args) => args.encode(buffer),
+            Arguments::ConnectionOpen(args) => args.encode(buffer),
+            Arguments::ConnectionOpenOk(args) => args.encode(buffer),
+            Arguments::ConnectionClose(args) => args.encode(buffer),
+            Arguments::ConnectionCloseOk(args) => args.encode(buffer),
+            Arguments::ConnectionBlocked(args) => args.encode(buffer),
+            Arguments::ConnectionUnblocked(args) => args.encode(buffer),
+            Arguments::ConnectionUpdateSecret(args) => args.encode(buffer),
+            Arguments::ConnectionUpdateSecretOk(args) => args.encode(buffer),
+
+            Arguments::ChannelOpen(args) => args.encode(buffer),
+            Arguments::ChannelOpenOk(args) => args.encode(buffer),
+            Arguments::ChannelFlow(args) => args.encode(buffer),
+            Arguments::ChannelFlowOk(args) => args.encode(buffer),
+            Arguments::ChannelClose(args) => args.encode(buffer),
+            Arguments::ChannelCloseOk(args) => args.encode(buffer),
+
+            Arguments::ExchangeDeclare(args) => args.encode(buffer),
+            Arguments::ExchangeDeclareOk(args) => args.encode(buffer),
+            Arguments::ExchangeDelete(args) => args.encode(buffer),
+            Arguments::ExchangeDeleteOk(args) => args.encode(buffer),
+            Arguments::ExchangeBind(args) => args.encode(buffer),
+            Arguments::ExchangeBindOk(args) => args.encode(buffer),
+            Arguments::ExchangeUnbind(args) => args.encode(buffer),
+            Arguments::ExchangeUnbindOk(args) => args.encode(buffer),
+
+            Arguments::QueueDeclare(args) => args.encode(buffer),
+            Arguments::QueueDeclareOk(args) => args.encode(buffer),
+            Arguments::QueueBind(args) => args.encode(buffer),
+            Arguments::QueueBindOk(args) => args.encode(buffer),
+            Arguments::QueueUnbind(args) => args.encode(buffer),
+            Arguments::QueueUnbindOk(args) => args.encode(buffer),
+            Arguments::QueuePurge(args) => args.encode(buffer),
+            Arguments::QueuePurgeOk(args) => args.encode(buffer),
+            Arguments::QueueDelete(args) => args.encode(buffer),
+            Arguments::QueueDeleteOk(args) => args.encode(buffer),
+
+            Arguments::BasicQos(args) => args.encode(buffer),
+            Arguments::BasicQosOk(args) => args.encode(buffer),
+            Arguments::BasicConsume(args) => args.encode(buffer),
+            Arguments::BasicConsumeOk(args) => args.encode(buffer),
+            Arguments::BasicCancel(args) => args.encode(buffer),
+            Arguments::BasicCancelOk(args) => args.encode(buffer),
+            Arguments::BasicPublish(args) => args.encode(buffer),
+            Arguments::BasicReturn(args) => args.encode(buffer),
+            Arguments::BasicDeliver(args) => args.encode(buffer),
+            Arguments::BasicGet(args) => args.encode(buffer),
+            Arguments::BasicGetOk(args) => args.encode(buffer),
+            Arguments::BasicGetEmpty(args) => args.encode(buffer),
+            Arguments::BasicAck(args) => args.encode(buffer),
+            Arguments::BasicReject(args) => args.encode(buffer),
+            Arguments::BasicRecoverAsync(args) => args.encode(buffer),
+            Arguments::BasicRecover(args) => args.encode(buffer),
+            Arguments::BasicRecoverOk(args) => args.encode(buffer),
+            Arguments::BasicNack(args) => args.encode(buffer),
+
+            Arguments::TxSelect(args) => args.encode(buffer),
+            Arguments::TxSelectOk(args) => args.encode(buffer),
+            Arguments::TxCommit(args) => args.encode(buffer),
+            Arguments::TxCommitOk(args) => args.encode(buffer),
+            Arguments::TxRollback(args) => args.encode(buffer),
+            Arguments::TxRollbackOk(args) => args.encode(buffer),
+
+            Arguments::AccessRequest(args) => args.encode(buffer),
+            Arguments::AccessRequestOk(args) => args.encode(buffer),
+
+            Arguments::ConfirmSelect(args) => args.encode(buffer),
+            Arguments::ConfirmSelectOk(args) => args.encode(buffer),
+
+            Arguments::Unknown { raw, .. } => buffer.extend_from_slice(raw)
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        match self {
+            Arguments::ConnectionStart(args) => args.encoded_len(),
+            Arguments::ConnectionStartOk(args) => args.encoded_len(),
+            Arguments::ConnectionSecure(args) => args.encoded_len(),
+            Arguments::ConnectionSecureOk(args) => args.encoded_len(),
+            Arguments::ConnectionTune(args) => args.encoded_len(),
+            Arguments::ConnectionTuneOk(args) => args.encoded_len(),
+            Arguments::ConnectionOpen(args) => args.encoded_len(),
+            Arguments::ConnectionOpenOk(args) => args.encoded_len(),
+            Arguments::ConnectionClose(args) => args.encoded_len(),
+            Arguments::ConnectionCloseOk(args) => args.encoded_len(),
+            Arguments::ConnectionBlocked(args) => args.encoded_len(),
+            Arguments::ConnectionUnblocked(args) => args.encoded_len(),
+            Arguments::ConnectionUpdateSecret(args) => args.encoded_len(),
+            Arguments::ConnectionUpdateSecretOk(args) => args.encoded_len(),
+
+            Arguments::ChannelOpen(args) => args.encoded_len(),
+            Arguments::ChannelOpenOk(args) => args.encoded_len(),
+            Arguments::ChannelFlow(args) => args.encoded_len(),
+            Arguments::ChannelFlowOk(args) => args.encoded_len(),
+            Arguments::ChannelClose(args) => args.encoded_len(),
+            Arguments::ChannelCloseOk(args) => args.encoded_len(),
+
+            Arguments::ExchangeDeclare(args) => args.encoded_len(),
+            Arguments::ExchangeDeclareOk(args) => args.encoded_len(),
+            Arguments::ExchangeDelete(args) => args.encoded_len(),
+            Arguments::ExchangeDeleteOk(args) => args.encoded_len(),
+            Arguments::ExchangeBind(args) => args.encoded_len(),
+            Arguments::ExchangeBindOk(args) => args.encoded_len(),
+            Arguments::ExchangeUnbind(args) => args.encoded_len(),
+            Arguments::ExchangeUnbindOk(args) => args.encoded_len(),
+
+            Arguments::QueueDeclare(args) => args.encoded_len(),
+            Arguments::QueueDeclareOk(args) => args.encoded_len(),
+            Arguments::QueueBind(args) => args.encoded_len(),
+            Arguments::QueueBindOk(args) => args.encoded_len(),
+            Arguments::QueueUnbind(args) => args.encoded_len(),
+            Arguments::QueueUnbindOk(args) => args.encoded_len(),
+            Arguments::QueuePurge(args) => args.encoded_len(),
+            Arguments::QueuePurgeOk(args) => args.encoded_len(),
+            Arguments::QueueDelete(args) => args.encoded_len(),
+            Arguments::QueueDeleteOk(args) => args.encoded_len(),
+
+            Arguments::BasicQos(args) => args.encoded_len(),
+            Arguments::BasicQosOk(args) => args.encoded_len(),
+            Arguments::BasicConsume(args) => args.encoded_len(),
+            Arguments::BasicConsumeOk(args) => args.encoded_len(),
+            Arguments::BasicCancel(args) => args.encoded_len(),
+            Arguments::BasicCancelOk(args) => args.encoded_len(),
+            Arguments::BasicPublish(args) => args.encoded_len(),
+            Arguments::BasicReturn(args) => args.encoded_len(),
+            Arguments::BasicDeliver(args) => args.encoded_len(),
+            Arguments::BasicGet(args) => args.encoded_len(),
+            Arguments::BasicGetOk(args) => args.encoded_len(),
+            Arguments::BasicGetEmpty(args) => args.encoded_len(),
+            Arguments::BasicAck(args) => args.encoded_len(),
+            Arguments::BasicReject(args) => args.encoded_len(),
+            Arguments::BasicRecoverAsync(args) => args.encoded_len(),
+            Arguments::BasicRecover(args) => args.encoded_len(),
+            Arguments::BasicRecoverOk(args) => args.encoded_len(),
+            Arguments::BasicNack(args) => args.encoded_len(),
+
+            Arguments::TxSelect(args) => args.encoded_len(),
+            Arguments::TxSelectOk(args) => args.encoded_len(),
+            Arguments::TxCommit(args) => args.encoded_len(),
+            Arguments::TxCommitOk(args) => args.encoded_len(),
+            Arguments::TxRollback(args) => args.encoded_len(),
+            Arguments::TxRollbackOk(args) => args.encoded_len(),
+
+            Arguments::AccessRequest(args) => args.encoded_len(),
+            Arguments::AccessRequestOk(args) => args.encoded_len(),
+
+            Arguments::ConfirmSelect(args) => args.encoded_len(),
+            Arguments::ConfirmSelectOk(args) => args.encoded_len(),
+
+            Arguments::Unknown { raw, .. } => raw.len()
+        }
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        match self {
+            Arguments::ConnectionStart(args) => args.try_encode(buffer),
+            Arguments::ConnectionStartOk(args) => args.try_encode(buffer),
+            Arguments::ConnectionSecure(args) => args.try_encode(buffer),
+            Arguments::ConnectionSecureOk(args) => args.try_encode(buffer),
+            Arguments::ConnectionTune(args) => args.try_encode(buffer),
+            Arguments::ConnectionTuneOk(args) => args.try_encode(buffer),
+            Arguments::ConnectionOpen(args) => args.try_encode(buffer),
+            Arguments::ConnectionOpenOk(args) => args.try_encode(buffer),
+            Arguments::ConnectionClose(args) => args.try_encode(buffer),
+            Arguments::ConnectionCloseOk(args) => args.try_encode(buffer),
+            Arguments::ConnectionBlocked(args) => args.try_encode(buffer),
+            Arguments::ConnectionUnblocked(args) => args.try_encode(buffer),
+            Arguments::ConnectionUpdateSecret(args) => args.try_encode(buffer),
+            Arguments::ConnectionUpdateSecretOk(args) => args.try_encode(buffer),
+
+            Arguments::ChannelOpen(args) => args.try_encode(buffer),
+            Arguments::ChannelOpenOk(args) => args.try_encode(buffer),
+            Arguments::ChannelFlow(args) => args.try_encode(buffer),
+            Arguments::ChannelFlowOk(args) => args.try_encode(buffer),
+            Arguments::ChannelClose(args) => args.try_encode(buffer),
+            Arguments::ChannelCloseOk(args) => args.try_encode(buffer),
+
+            Arguments::ExchangeDeclare(args) => args.try_encode(buffer),
+            Arguments::ExchangeDeclareOk(args) => args.try_encode(buffer),
+            Arguments::ExchangeDelete(args) => args.try_encode(buffer),
+            Arguments::ExchangeDeleteOk(args) => args.try_encode(buffer),
+            Arguments::ExchangeBind(args) => args.try_encode(buffer),
+            Arguments::ExchangeBindOk(args) => args.try_encode(buffer),
+            Arguments::ExchangeUnbind(args) => args.try_encode(buffer),
+            Arguments::ExchangeUnbindOk(args) => args.try_encode(buffer),
+
+            Arguments::QueueDeclare(args) => args.try_encode(buffer),
+            Arguments::QueueDeclareOk(args) => args.try_encode(buffer),
+            Arguments::QueueBind(args) => args.try_encode(buffer),
+            Arguments::QueueBindOk(args) => args.try_encode(buffer),
+            Arguments::QueueUnbind(args) => args.try_encode(buffer),
+            Arguments::QueueUnbindOk(args) => args.try_encode(buffer),
+            Arguments::QueuePurge(args) => args.try_encode(buffer),
+            Arguments::QueuePurgeOk(args) => args.try_encode(buffer),
+            Arguments::QueueDelete(args) => args.try_encode(buffer),
+            Arguments::QueueDeleteOk(args) => args.try_encode(buffer),
+
+            Arguments::BasicQos(args) => args.try_encode(buffer),
+            Arguments::BasicQosOk(args) => args.try_encode(buffer),
+            Arguments::BasicConsume(args) => args.try_encode(buffer),
+            Arguments::BasicConsumeOk(args) => args.try_encode(buffer),
+            Arguments::BasicCancel(args) => args.try_encode(buffer),
+            Arguments::BasicCancelOk(args) => args.try_encode(buffer),
+            Arguments::BasicPublish(args) => args.try_encode(buffer),
+            Arguments::BasicReturn(args) => args.try_encode(buffer),
+            Arguments::BasicDeliver(args) => args.try_encode(buffer),
+            Arguments::BasicGet(args) => args.try_encode(buffer),
+            Arguments::BasicGetOk(args) => args.try_encode(buffer),
+            Arguments::BasicGetEmpty(args) => args.try_encode(buffer),
+            Arguments::BasicAck(args) => args.try_encode(buffer),
+            Arguments::BasicReject(args) => args.try_encode(buffer),
+            Arguments::BasicRecoverAsync(args) => args.try_encode(buffer),
+            Arguments::BasicRecover(args) => args.try_encode(buffer),
+            Arguments::BasicRecoverOk(args) => args.try_encode(buffer),
+            Arguments::BasicNack(args) => args.try_encode(buffer),
+
+            Arguments::TxSelect(args) => args.try_encode(buffer),
+            Arguments::TxSelectOk(args) => args.try_encode(buffer),
+            Arguments::TxCommit(args) => args.try_encode(buffer),
+            Arguments::TxCommitOk(args) => args.try_encode(buffer),
+            Arguments::TxRollback(args) => args.try_encode(buffer),
+            Arguments::TxRollbackOk(args) => args.try_encode(buffer),
+
+            Arguments::AccessRequest(args) => args.try_encode(buffer),
+            Arguments::AccessRequestOk(args) => args.try_encode(buffer),
+
+            Arguments::ConfirmSelect(args) => args.try_encode(buffer),
+            Arguments::ConfirmSelectOk(args) => args.try_encode(buffer),
+
+            Arguments::Unknown { raw, .. } => {
+                buffer.extend_from_slice(raw);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Arguments {
+    /// Class and method of the arguments, `Unknown` arguments have the unknown method of their class,
+    /// or `Class::Unknown` and the default method if the class is unknown as well
+    pub fn class_method(&self) -> (Class, Method) {
+        match self {
+            Arguments::ConnectionStart(_) => (Class::Connection, Method::Connection(ConnectionMethod::Start)),
+            Arguments::ConnectionStartOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::StartOk)),
+            Arguments::ConnectionSecure(_) => (Class::Connection, Method::Connection(ConnectionMethod::Secure)),
+            Arguments::ConnectionSecureOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::SecureOk)),
+            Arguments::ConnectionTune(_) => (Class::Connection, Method::Connection(ConnectionMethod::Tune)),
+            Arguments::ConnectionTuneOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::TuneOk)),
+            Arguments::ConnectionOpen(_) => (Class::Connection, Method::Connection(ConnectionMethod::Open)),
+            Arguments::ConnectionOpenOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::OpenOk)),
+            Arguments::ConnectionClose(_) => (Class::Connection, Method::Connection(ConnectionMethod::Close)),
+            Arguments::ConnectionCloseOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::CloseOk)),
+            Arguments::ConnectionBlocked(_) => (Class::Connection, Method::Connection(ConnectionMethod::Blocked)),
+            Arguments::ConnectionUnblocked(_) => (Class::Connection, Method::Connection(ConnectionMethod::Unblocked)),
+            Arguments::ConnectionUpdateSecret(_) => (Class::Connection, Method::Connection(ConnectionMethod::UpdateSecret)),
+            Arguments::ConnectionUpdateSecretOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::UpdateSecretOk)),
+
+            Arguments::ChannelOpen(_) => (Class::Channel, Method::Channel(ChannelMethod::Open)),
+            Arguments::ChannelOpenOk(_) => (Class::Channel, Method::Channel(ChannelMethod::OpenOk)),
+            Arguments::ChannelFlow(_) => (Class::Channel, Method::Channel(ChannelMethod::Flow)),
+            Arguments::ChannelFlowOk(_) => (Class::Channel, Method::Channel(ChannelMethod::FlowOk)),
+            Arguments::ChannelClose(_) => (Class::Channel, Method::Channel(ChannelMethod::Close)),
+            Arguments::ChannelCloseOk(_) => (Class::Channel, Method::Channel(ChannelMethod::CloseOk)),
+
+            Arguments::ExchangeDeclare(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Declare)),
+            Arguments::ExchangeDeclareOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::DeclareOk)),
+            Arguments::ExchangeDelete(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Delete)),
+            Arguments::ExchangeDeleteOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::DeleteOk)),
+            Arguments::ExchangeBind(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Bind)),
+            Arguments::ExchangeBindOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::BindOk)),
+            Arguments::ExchangeUnbind(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Unbind)),
+            Arguments::ExchangeUnbindOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::UnbindOk)),
+
+            Arguments::QueueDeclare(_) => (Class::Queue, Method::Queue(QueueMethod::Declare)),
+            Arguments::QueueDeclareOk(_) => (Class::Queue, Method::Queue(QueueMethod::DeclareOk)),
+            Arguments::QueueBind(_) => (Class::Queue, Method::Queue(QueueMethod::Bind)),
+            Arguments::QueueBindOk(_) => (Class::Queue, Method::Queue(QueueMethod::BindOk)),
+            Arguments::QueueUnbind(_) => (Class::Queue, Method::Queue(QueueMethod::Unbind)),
+            Arguments::QueueUnbindOk(_) => (Class::Queue, Method::Queue(QueueMethod::UnbindOk)),
+            Arguments::QueuePurge(_) => (Class::Queue, Method::Queue(QueueMethod::Purge)),
+            Arguments::QueuePurgeOk(_) => (Class::Queue, Method::Queue(QueueMethod::PurgeOk)),
+            Arguments::QueueDelete(_) => (Class::Queue, Method::Queue(QueueMethod::Delete)),
+            Arguments::QueueDeleteOk(_) => (Class::Queue, Method::Queue(QueueMethod::DeleteOk)),
+
+            Arguments::BasicQos(_) => (Class::Basic, Method::Basic(BasicMethod::Qos)),
+            Arguments::BasicQosOk(_) => (Class::Basic, Method::Basic(BasicMethod::QosOk)),
+            Arguments::BasicConsume(_) => (Class::Basic, Method::Basic(BasicMethod::Consume)),
+            Arguments::BasicConsumeOk(_) => (Class::Basic, Method::Basic(BasicMethod::ConsumeOk)),
+            Arguments::BasicCancel(_) => (Class::Basic, Method::Basic(BasicMethod::Cancel)),
+            Arguments::BasicCancelOk(_) => (Class::Basic, Method::Basic(BasicMethod::CancelOk)),
+            Arguments::BasicPublish(_) => (Class::Basic, Method::Basic(BasicMethod::Publish)),
+            Arguments::BasicReturn(_) => (Class::Basic, Method::Basic(BasicMethod::Return)),
+            Arguments::BasicDeliver(_) => (Class::Basic, Method::Basic(BasicMethod::Deliver)),
+            Arguments::BasicGet(_) => (Class::Basic, Method::Basic(BasicMethod::Get)),
+            Arguments::BasicGetOk(_) => (Class::Basic, Method::Basic(BasicMethod::GetOk)),
+            Arguments::BasicGetEmpty(_) => (Class::Basic, Method::Basic(BasicMethod::GetEmpty)),
+            Arguments::BasicAck(_) => (Class::Basic, Method::Basic(BasicMethod::Ack)),
+            Arguments::BasicReject(_) => (Class::Basic, Method::Basic(BasicMethod::Reject)),
+            Arguments::BasicRecoverAsync(_) => (Class::Basic, Method::Basic(BasicMethod::RecoverAsync)),
+            Arguments::BasicRecover(_) => (Class::Basic, Method::Basic(BasicMethod::Recover)),
+            Arguments::BasicRecoverOk(_) => (Class::Basic, Method::Basic(BasicMethod::RecoverOk)),
+            Arguments::BasicNack(_) => (Class::Basic, Method::Basic(BasicMethod::Nack)),
+
+            Arguments::TxSelect(_) => (Class::Tx, Method::Tx(TxMethod::Select)),
+            Arguments::TxSelectOk(_) => (Class::Tx, Method::Tx(TxMethod::SelectOk)),
+            Arguments::TxCommit(_) => (Class::Tx, Method::Tx(TxMethod::Commit)),
+            Arguments::TxCommitOk(_) => (Class::Tx, Method::Tx(TxMethod::CommitOk)),
+            Arguments::TxRollback(_) => (Class::Tx, Method::Tx(TxMethod::Rollback)),
+            Arguments::TxRollbackOk(_) => (Class::Tx, Method::Tx(TxMethod::RollbackOk)),
+
+            Arguments::AccessRequest(_) => (Class::Access, Method::Access(AccessMethod::Request)),
+            Arguments::AccessRequestOk(_) => (Class::Access, Method::Access(AccessMethod::RequestOk)),
+
+            Arguments::ConfirmSelect(_) => (Class::Confirm, Method::Confirm(ConfirmMethod::Select)),
+            Arguments::ConfirmSelectOk(_) => (Class::Confirm, Method::Confirm(ConfirmMethod::SelectOk)),
+
+            Arguments::Unknown { class_id, .. } => {
+                let class = Class::from(*class_id);
+                let method = match class {
+                    Class::Connection => Method::Connection(ConnectionMethod::Unknown),
+                    Class::Channel => Method::Channel(ChannelMethod::Unknown),
+                    Class::Exchange => Method::Exchange(ExchangeMethod::Unknown),
+                    Class::Queue => Method::Queue(QueueMethod::Unknown),
+                    Class::Basic => Method::Basic(BasicMethod::Unknown),
+                    Class::Tx => Method::Tx(TxMethod::Unknown),
+                    Class::Access => Method::Access(AccessMethod::Unknown),
+                    Class::Confirm => Method::Confirm(ConfirmMethod::Unknown),
+                    Class::Unknown => Method::default()
+                };
+                (class, method)
+            }
+        }
+    }
+
+    /// Value of the `no_wait` flag, false if the method does not carry it
+    pub fn no_wait(&self) -> bool {
+        match self {
+            Arguments::ExchangeDeclare(args) => args.no_wait(),
+            Arguments::ExchangeDelete(args) => args.no_wait(),
+            Arguments::ExchangeBind(args) => args.no_wait(),
+            Arguments::ExchangeUnbind(args) => args.no_wait(),
+            Arguments::QueueDeclare(args) => args.no_wait(),
+            Arguments::QueueBind(args) => args.no_wait(),
+            Arguments::QueuePurge(args) => args.no_wait(),
+            Arguments::QueueDelete(args) => args.no_wait(),
+            Arguments::BasicConsume(args) => args.no_wait(),
+            Arguments::BasicCancel(args) => args.no_wait(),
+            Arguments::ConfirmSelect(args) => args.no_wait(),
+            _ => false
+        }
+    }
+
+    // decode the arguments of a method of the spec, unknown methods are kept raw by the caller
+    pub(crate) fn decode_method(method: Method, buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        match method {
+            Method::Connection(ConnectionMethod::Start) => ConnectionStart::decode(buffer),
+            Method::Connection(ConnectionMethod::StartOk) => ConnectionStartOk::decode(buffer),
+            Method::Connection(ConnectionMethod::Secure) => ConnectionSecure::decode(buffer),
+            Method::Connection(ConnectionMethod::SecureOk) => ConnectionSecureOk::decode(buffer),
+            Method::Connection(ConnectionMethod::Tune) => ConnectionTune::decode(buffer),
+            Method::Connection(ConnectionMethod::TuneOk) => ConnectionTuneOk::decode(buffer),
+            Method::Connection(ConnectionMethod::Open) => ConnectionOpen::decode(buffer),
+            Method::Connection(ConnectionMethod::OpenOk) => ConnectionOpenOk::decode(buffer),
+            Method::Connection(ConnectionMethod::Close) => ConnectionClose::decode(buffer),
+            Method::Connection(ConnectionMethod::CloseOk) => ConnectionCloseOk::decode(buffer),
+            Method::Connection(ConnectionMethod::Blocked) => ConnectionBlocked::decode(buffer),
+            Method::Connection(ConnectionMethod::Unblocked) => ConnectionUnblocked::decode(buffer),
+            Method::Connection(ConnectionMethod::UpdateSecret) => ConnectionUpdateSecret::decode(buffer),
+            Method::Connection(ConnectionMethod::UpdateSecretOk) => ConnectionUpdateSecretOk::decode(buffer),
+            Method::Channel(ChannelMethod::Open) => ChannelOpen::decode(buffer),
+            Method::Channel(ChannelMethod::OpenOk) => ChannelOpenOk::decode(buffer),
+            Method::Channel(ChannelMethod::Flow) => ChannelFlow::decode(buffer),
+            Method::Channel(ChannelMethod::FlowOk) => ChannelFlowOk::decode(buffer),
+            Method::Channel(ChannelMethod::Close) => ChannelClose::decode(buffer),
+            Method::Channel(ChannelMethod::CloseOk) => ChannelCloseOk::decode(buffer),
+            Method::Exchange(ExchangeMethod::Declare) => ExchangeDeclare::decode(buffer),
+            Method::Exchange(ExchangeMethod::DeclareOk) => ExchangeDeclareOk::decode(buffer),
+            Method::Exchange(ExchangeMethod::Delete) => ExchangeDelete::decode(buffer),
+            Method::Exchange(ExchangeMethod::DeleteOk) => ExchangeDeleteOk::decode(buffer),
+            Method::Exchange(ExchangeMethod::Bind) => ExchangeBind::decode(buffer),
+            Method::Exchange(ExchangeMethod::BindOk) => ExchangeBindOk::decode(buffer),
+            Method::Exchange(ExchangeMethod::Unbind) => ExchangeUnbind::decode(buffer),
+            Method::Exchange(ExchangeMethod::UnbindOk) => ExchangeUnbindOk::decode(buffer),
+            Method::Queue(QueueMethod::Declare) => QueueDeclare::decode(buffer),
+            Method::Queue(QueueMethod::DeclareOk) => QueueDeclareOk::decode(buffer),
+            Method::Queue(QueueMethod::Bind) => QueueBind::decode(buffer),
+            Method::Queue(QueueMethod::BindOk) => QueueBindOk::decode(buffer),
+            Method::Queue(QueueMethod::Unbind) => QueueUnbind::decode(buffer),
+            Method::Queue(QueueMethod::UnbindOk) => QueueUnbindOk::decode(buffer),
+            Method::Queue(QueueMethod::Purge) => QueuePurge::decode(buffer),
+            Method::Queue(QueueMethod::PurgeOk) => QueuePurgeOk::decode(buffer),
+            Method::Queue(QueueMethod::Delete) => QueueDelete::decode(buffer),
+            Method::Queue(QueueMethod::DeleteOk) => QueueDeleteOk::decode(buffer),
+            Method::Basic(BasicMethod::Qos) => BasicQos::decode(buffer),
+            Method::Basic(BasicMethod::QosOk) => BasicQosOk::decode(buffer),
+            Method::Basic(BasicMethod::Consume) => BasicConsume::decode(buffer),
+            Method::Basic(BasicMethod::ConsumeOk) => BasicConsumeOk::decode(buffer),
+            Method::Basic(BasicMethod::Cancel) => BasicCancel::decode(buffer),
+            Method::Basic(BasicMethod::CancelOk) => BasicCancelOk::decode(buffer),
+            Method::Basic(BasicMethod::Publish) => BasicPublish::decode(buffer),
+            Method::Basic(BasicMethod::Return) => BasicReturn::decode(buffer),
+            Method::Basic(BasicMethod::Deliver) => BasicDeliver::decode(buffer),
+            Method::Basic(BasicMethod::Get) => BasicGet::decode(buffer),
+            Method::Basic(BasicMethod::GetOk) => BasicGetOk::decode(buffer),
+            Method::Basic(BasicMethod::GetEmpty) => BasicGetEmpty::decode(buffer),
+            Method::Basic(BasicMethod::Ack) => BasicAck::decode(buffer),
+            Method::Basic(BasicMethod::Reject) => BasicReject::decode(buffer),
+            Method::Basic(BasicMethod::RecoverAsync) => BasicRecoverAsync::decode(buffer),
+            Method::Basic(BasicMethod::Recover) => BasicRecover::decode(buffer),
+            Method::Basic(BasicMethod::RecoverOk) => BasicRecoverOk::decode(buffer),
+            Method::Basic(BasicMethod::Nack) => BasicNack::decode(buffer),
+            Method::Tx(TxMethod::Select) => TxSelect::decode(buffer),
+            Method::Tx(TxMethod::SelectOk) => TxSelectOk::decode(buffer),
+            Method::Tx(TxMethod::Commit) => TxCommit::decode(buffer),
+            Method::Tx(TxMethod::CommitOk) => TxCommitOk::decode(buffer),
+            Method::Tx(TxMethod::Rollback) => TxRollback::decode(buffer),
+            Method::Tx(TxMethod::RollbackOk) => TxRollbackOk::decode(buffer),
+            Method::Access(AccessMethod::Request) => AccessRequest::decode(buffer),
+            Method::Access(AccessMethod::RequestOk) => AccessRequestOk::decode(buffer),
+            Method::Confirm(ConfirmMethod::Select) => ConfirmSelect::decode(buffer),
+            Method::Confirm(ConfirmMethod::SelectOk) => ConfirmSelectOk::decode(buffer),
+            _ => Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown method"))
+        }
+    }
+}
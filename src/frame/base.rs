@@ -1,13 +1,16 @@
 use property::Property;
-use bytes::{BytesMut, BufMut};
+use bytes::{Bytes, BytesMut, BufMut};
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hasher, Hash};
+use std::fmt;
 use std::vec::Vec;
 use std::string::String;
 use nom::number::streaming::{be_i8, be_u8, be_i16, be_u16, be_i32, be_u32, be_u64, be_i64, be_f32, be_f64};
 use nom::bytes::streaming::take;
 use nom::error::ErrorKind;
-use crate::error::{NomErr, FrameDecodeErr};
+use crate::error::{NomErr, FrameDecodeErr, EncodeError};
+use crate::error::amqp::AmqpErrorKind;
 use crate::frame::header::connection::ConnectionProperties;
 use crate::frame::header::channel::ChannelProperties;
 use crate::frame::header::access::AccessProperties;
@@ -15,26 +18,34 @@ use crate::frame::header::exchange::ExchangeProperties;
 use crate::frame::header::queue::QueueProperties;
 use crate::frame::header::basic::BasicProperties;
 use crate::frame::header::tx::TxProperties;
-use crate::frame::method::connection::{ConnectionStart, ConnectionStartOk, ConnectionSecure, ConnectionSecureOk, ConnectionTune, ConnectionTuneOk, ConnectionOpen, ConnectionOpenOk, ConnectionClose, ConnectionCloseOk};
-use crate::frame::method::channel::{ChannelOpen, ChannelOpenOk, ChannelFlow, ChannelFlowOk, ChannelClose, ChannelCloseOk};
-use crate::frame::method::access::{AccessRequest, AccessRequestOk};
-use crate::frame::method::exchange::{ExchangeDeclare, ExchangeDelete, ExchangeDeclareOk, ExchangeDeleteOk, ExchangeBind, ExchangeBindOk, ExchangeUnbind, ExchangeUnbindOk};
-use crate::frame::method::queue::{QueueDeclare, QueueBind, QueueBindOk, QueueDeclareOk, QueueUnbind, QueueUnbindOk, QueuePurge, QueuePurgeOk, QueueDelete, QueueDeleteOk};
-use crate::frame::method::basic::{BasicQos, BasicQosOk, BasicConsume, BasicConsumeOk, BasicCancel, BasicCancelOk, BasicPublish, BasicReturn, BasicDeliver, BasicGet, BasicGetOk, BasicGetEmpty, BasicAck, BasicReject, BasicRecoverAsync, BasicRecover, BasicRecoverOk, BasicNack};
-use crate::frame::method::tx::{TxSelect, TxSelectOk, TxCommit, TxCommitOk, TxRollback, TxRollbackOk};
-use crate::frame::method::confirm::{ConfirmSelect, ConfirmSelectOk};
+use crate::frame::method::connection::ConnectionClose;
 use crate::class::Class;
-use crate::method::{Method, MethodId, get_method_type, ConnectionMethod, ChannelMethod, AccessMethod, ExchangeMethod, QueueMethod, BasicMethod, TxMethod, ConfirmMethod};
+use crate::method::{Method, MethodId, get_method_type};
 use crate::frame::header::confirm::ConfirmProperties;
+use crate::frame::constants::{FRAME_METHOD, FRAME_HEADER, FRAME_BODY, FRAME_HEARTBEAT};
+pub use crate::frame::arguments::Arguments;
 
 // amqp0-9-1 field name length allowed is 128
 const MAX_FIELD_NAME_LEN: usize = 128;
 // max long string bytes length allowed
 const MAX_LONG_STR_LEN: usize = 64 * 1024;
+// max nesting of field arrays and tables, decoding recurses once per level
+const MAX_NESTING_DEPTH: usize = 64;
 
 pub trait Encode {
     // write data to bytes buffer
     fn encode(&self, buffer: &mut BytesMut);
+
+    // number of bytes encode writes, to reserve the buffer or check frame_max before encoding
+    fn encoded_len(&self) -> usize;
+
+    // encode, values which do not fit their wire format are an error instead of being cut;
+    // types which always fit keep this infallible default
+    #[inline]
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.encode(buffer);
+        Ok(())
+    }
 }
 
 pub trait Decode<T> {
@@ -51,6 +62,11 @@ macro_rules! encode_impl_for_primitive {
                 fn encode(&self, buffer: &mut BytesMut) {
                     buffer.[<put_ $t>](*self);
                 }
+
+                #[inline]
+                fn encoded_len(&self) -> usize {
+                    std::mem::size_of::<$t>()
+                }
             }
         }
     )*}
@@ -69,7 +85,7 @@ macro_rules! decode_impl_for_primitive {
                         Err(e) => {
                             match e {
                                 nom::Err::Incomplete(_) => return Err(FrameDecodeErr::Incomplete),
-                                _ => return Err(FrameDecodeErr::DecodeError(format!("decode primitive -> {}", e)))
+                                _ => return Err(FrameDecodeErr::new(AmqpErrorKind::SyntaxError, format!("decode primitive -> {}", e)))
                             }
                         }
                     }
@@ -85,8 +101,8 @@ pub(crate) fn take_bytes(buffer: &[u8], count: usize) -> Result<(&[u8], &[u8]),
         Ok(v) => Ok(v),
         Err(e) => {
             match e {
-                nom::Err::Incomplete(_) => return Err(FrameDecodeErr::Incomplete),
-                _ => return Err(FrameDecodeErr::DecodeError(format!("take bytes -> {}", e)))
+                nom::Err::Incomplete(_) => Err(FrameDecodeErr::Incomplete),
+                _ => Err(FrameDecodeErr::new(AmqpErrorKind::SyntaxError, format!("take bytes -> {}", e)))
             }
         }
     }
@@ -94,7 +110,7 @@ pub(crate) fn take_bytes(buffer: &[u8], count: usize) -> Result<(&[u8], &[u8]),
 
 pub type Timestamp = u64;
 
-#[derive(Default, Debug, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct ShortStr (String);
 
 impl std::hash::Hash for ShortStr {
@@ -103,14 +119,19 @@ impl std::hash::Hash for ShortStr {
     }
 }
 
-impl ToString for ShortStr {
+impl fmt::Display for ShortStr {
     #[inline]
-    fn to_string(&self) -> String {
-        self.0.clone()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
 impl ShortStr {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
     /// Create a ShortStr from bytes
     ///
     /// # Examples
@@ -153,6 +174,21 @@ impl Encode for ShortStr {
         buffer.put_u8(self.0.len() as u8);
         buffer.extend_from_slice(&self.0.as_bytes());
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1 + self.0.len()
+    }
+
+    /// A ShortStr built from invalid UTF-8 can grow over 255 bytes
+    #[inline]
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        if self.0.len() > u8::MAX as usize {
+            return Err(EncodeError::ShortStrTooLong(self.0.len()));
+        }
+        self.encode(buffer);
+        Ok(())
+    }
 }
 
 impl Decode<ShortStr> for ShortStr {
@@ -170,31 +206,44 @@ impl Decode<ShortStr> for ShortStr {
     fn decode(buffer: &[u8]) -> Result<(&[u8], ShortStr), FrameDecodeErr> {
         let (buffer, length) = match u8::decode(buffer) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ShortStr length -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         let (buffer, data) = match take_bytes(buffer, length as usize) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ShortStr bytes -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         let short_str = match ShortStr::with_bytes(data) {
             Ok(short_str) => short_str,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode but build ShortStr failed -> {}", e)))
+            Err(e) => return Err(e.at(data))
         };
         Ok((buffer, short_str))
     }
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct LongStr(String);
+/// LongStr keeps the bytes as they are on the wire, it also carries binary data such as SASL responses
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct LongStr(Vec<u8>);
 
-impl ToString for LongStr {
+impl fmt::Display for LongStr {
+    /// Invalid utf8 is replaced
     #[inline]
-    fn to_string(&self) -> String {
-        self.0.clone()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(&self.0))
     }
 }
 
 impl LongStr {
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The string, None if it is not valid utf8
+    #[inline]
+    pub fn to_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0).ok()
+    }
+
     /// Create a LongStr from bytes, the length will be convert to big endian
     ///
     /// # Examples
@@ -211,7 +260,7 @@ impl LongStr {
         if bytes.len() > MAX_LONG_STR_LEN {
             Err(FrameDecodeErr::SyntaxError("LongStr too long"))
         } else {
-            Ok(LongStr(String::from_utf8_lossy(bytes).to_string()))
+            Ok(LongStr(bytes.to_vec()))
         }
     }
 }
@@ -236,7 +285,21 @@ impl Encode for LongStr {
     #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u32(self.0.len() as u32);
-        buffer.extend_from_slice(self.0.as_bytes());
+        buffer.extend_from_slice(&self.0);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        4 + self.0.len()
+    }
+
+    #[inline]
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        if self.0.len() > u32::MAX as usize {
+            return Err(EncodeError::LongStrTooLong(self.0.len()));
+        }
+        self.encode(buffer);
+        Ok(())
     }
 }
 
@@ -256,15 +319,15 @@ impl Decode<LongStr> for LongStr {
     fn decode(buffer: &[u8]) -> Result<(&[u8], LongStr), FrameDecodeErr> {
         let (buffer, length) = match u32::decode(buffer) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode LongStr length -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         let (buffer, data) = match take_bytes(buffer, length as usize) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode LongStr bytes -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         let long_str = match LongStr::with_bytes(data) {
             Ok(long_str) => long_str,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode but build LongStr failed -> {}", e)))
+            Err(e) => return Err(e.at(data))
         };
         Ok((buffer, long_str))
     }
@@ -272,7 +335,7 @@ impl Decode<LongStr> for LongStr {
 
 pub type ByteArray = LongStr;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Decimal {
     scale: u8,
     value: u32
@@ -283,6 +346,42 @@ impl Decimal {
     pub fn new( scale: u8, value: u32) -> Self {
         Decimal { scale, value }
     }
+
+    /// Number of decimal digits after the point
+    #[inline]
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    #[inline]
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Render the value with the point, a scale of 2 renders 12345 as 123.45
+    ///
+    /// # Examples
+    /// ```rust
+    /// use amqp_proto::Decimal;
+    ///
+    /// assert_eq!(Decimal::new(2, 12345).to_string(), "123.45");
+    /// assert_eq!(Decimal::new(3, 5).to_string(), "0.005");
+    /// assert_eq!(Decimal::new(0, 7).to_string(), "7");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.value.to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            f.write_str(&digits)
+        } else if digits.len() > scale {
+            let (int, frac) = digits.split_at(digits.len() - scale);
+            write!(f, "{}.{}", int, frac)
+        } else {
+            write!(f, "0.{}{}", "0".repeat(scale - digits.len()), digits)
+        }
+    }
 }
 
 impl Encode for Decimal {
@@ -307,6 +406,11 @@ impl Encode for Decimal {
         buffer.put_u8(self.scale);
         buffer.put_u32(self.value);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        5
+    }
 }
 
 impl Decode<Decimal> for Decimal {
@@ -326,27 +430,32 @@ impl Decode<Decimal> for Decimal {
     fn decode(buffer: &[u8]) -> Result<(&[u8], Decimal), FrameDecodeErr> {
         let (buffer, scale) = match u8::decode(buffer) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode Decimal scale -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         let (buffer, value) = match u32::decode(buffer) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode Decimal value -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         Ok((buffer, Decimal { scale, value }))
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FieldName(ShortStr);
 
-impl ToString for FieldName {
+impl fmt::Display for FieldName {
     #[inline]
-    fn to_string(&self) -> String {
-        self.0.to_string()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
     }
 }
 
 impl FieldName {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
     /// Create FieldName from bytes.
     ///
     /// # Examples
@@ -362,11 +471,7 @@ impl FieldName {
     #[inline]
     pub fn with_bytes(bytes: &[u8]) -> Result<FieldName, FrameDecodeErr> {
         // field name first letter should be '$'  '#' or letter
-        let is_start_char_ok = match bytes[0] {
-            b'$' | b'#' => true,
-            b'a'..=b'z' | b'A'..=b'Z' => true,
-            _ => false
-        };
+        let is_start_char_ok = matches!(bytes.first(), Some(b'$') | Some(b'#') | Some(b'a'..=b'z') | Some(b'A'..=b'Z'));
 
         if !is_start_char_ok {
             return Err(FrameDecodeErr::SyntaxError("FieldName start char error"));
@@ -379,7 +484,7 @@ impl FieldName {
 
         match ShortStr::with_bytes(bytes) {
             Ok(value) => Ok(FieldName(value)),
-            Err(e) => Err(FrameDecodeErr::DecodeError(format!("build FieldName failed -> {}", e)))
+            Err(e) => Err(e)
         }
     }
 }
@@ -396,6 +501,20 @@ impl Encode for FieldName {
     fn encode(&self, buffer: &mut BytesMut) {
         self.0.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len()
+    }
+
+    #[inline]
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        if FieldName::with_bytes(self.as_str().as_bytes()).is_err() {
+            return Err(EncodeError::InvalidFieldName(self.to_string()));
+        }
+        self.encode(buffer);
+        Ok(())
+    }
 }
 
 impl Decode<FieldName> for FieldName {
@@ -406,27 +525,28 @@ impl Decode<FieldName> for FieldName {
     /// ```rust
     /// use amqp_proto::FieldName;
     /// use amqp_proto::codec::Decode;
+    /// use amqp_proto::err::AmqpErrorKind;
     ///
     /// let (_, field_name) = FieldName::decode(&[5u8, 104, 101, 108, 108, 111]).unwrap();
     /// assert_eq!(field_name.to_string(), String::from("hello"));
     ///
     /// let err = FieldName::decode(&[5u8, 104, 101, 108, 108]).unwrap_err();
-    /// assert!(format!("{}", err).contains("decode FieldName bytes"));
+    /// assert_eq!(err.kind(), AmqpErrorKind::FrameError);
     /// ```
     #[inline]
     fn decode(buffer: &[u8]) -> Result<(&[u8], FieldName), FrameDecodeErr>{
         let (buffer, length) = match u8::decode(buffer) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldName length -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         let (buffer, data) = match take_bytes(buffer, length as usize) {
             Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldName bytes -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
 
         match FieldName::with_bytes(data) {
             Ok(v) => Ok((buffer, v)),
-            Err(e) => Err(FrameDecodeErr::DecodeError(format!("decode but build FieldName failed -> {}", e)))
+            Err(e) => Err(e.at(data))
         }
     }
 }
@@ -464,6 +584,24 @@ impl Encode for FieldArray {
             index += 1;
         }
     }
+
+    fn encoded_len(&self) -> usize {
+        self.iter().fold(4, |len, item| len + item.encoded_len())
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        let index = buffer.len();
+        buffer.put_u32(0);
+        for item in self {
+            item.try_encode(buffer)?;
+        }
+        let len = buffer.len() - index - std::mem::size_of::<u32>();
+        if len > u32::MAX as usize {
+            return Err(EncodeError::FieldArrayTooLong(len));
+        }
+        buffer[index..index + std::mem::size_of::<u32>()].copy_from_slice(&(len as u32).to_be_bytes());
+        Ok(())
+    }
 }
 
 impl Decode<FieldArray> for FieldArray {
@@ -484,34 +622,40 @@ impl Decode<FieldArray> for FieldArray {
         // array bytes length
         let (buffer, length) = match u32::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldArray length -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
 
         // array bytes
         let (buffer, data) = match take_bytes(buffer, length as usize) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldArray bytes->{}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
 
-        let mut arr: Vec<FieldValue> = Vec::new();
-        let mut tmp = data;
-        loop {
-            let (retain, value) = match FieldValue::decode(tmp) {
-                Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("read FieldArray item failed -> {}", e)))
-            };
-            tmp = retain;
-            arr.push(value);
-            if tmp.len() == 0 {
-                return Ok((buffer, arr))
-            }
-        }
+        decode_array_items(data, 0).map(|arr| (buffer, arr))
+    }
+}
+
+/// Decode the items of a FieldArray nested `depth` levels deep
+pub(crate) fn decode_array_items(data: &[u8], depth: usize) -> Result<FieldArray, FrameDecodeErr> {
+    if depth > MAX_NESTING_DEPTH {
+        return Err(FrameDecodeErr::new(AmqpErrorKind::SyntaxError, "field array nested too deep").at(data));
     }
+    let mut arr: Vec<FieldValue> = Vec::new();
+    let mut tmp = data;
+    while !tmp.is_empty() {
+        let (retain, value) = match decode_field_value(tmp, depth) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.index(tmp, arr.len()))
+        };
+        tmp = retain;
+        arr.push(value);
+    }
+    Ok(arr)
 }
 
 pub type BytesArray = LongStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum FieldValueKind {
     Boolean,        // 0 = False, else True
     I8,             // Octet
@@ -588,7 +732,7 @@ impl From<u8> for FieldValueKind {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum FieldValue {
     Boolean(bool),
     U8(u8),
@@ -610,6 +754,73 @@ pub enum FieldValue {
     Void
 }
 
+/// Floats are equal if their bits are equal, the same bits are put on the wire. A NaN equals a NaN
+/// with the same bits and 0.0 differs from -0.0, which makes FieldValue `Eq` and `Hash`.
+impl PartialEq for FieldValue {
+    fn eq(&self, other: &FieldValue) -> bool {
+        match (self, other) {
+            (FieldValue::Boolean(a), FieldValue::Boolean(b)) => a == b,
+            (FieldValue::U8(a), FieldValue::U8(b)) => a == b,
+            (FieldValue::I8(a), FieldValue::I8(b)) => a == b,
+            (FieldValue::U16(a), FieldValue::U16(b)) => a == b,
+            (FieldValue::I16(a), FieldValue::I16(b)) => a == b,
+            (FieldValue::U32(a), FieldValue::U32(b)) => a == b,
+            (FieldValue::I32(a), FieldValue::I32(b)) => a == b,
+            (FieldValue::U64(a), FieldValue::U64(b)) => a == b,
+            (FieldValue::I64(a), FieldValue::I64(b)) => a == b,
+            (FieldValue::F32(a), FieldValue::F32(b)) => a.to_bits() == b.to_bits(),
+            (FieldValue::F64(a), FieldValue::F64(b)) => a.to_bits() == b.to_bits(),
+            (FieldValue::Timestamp(a), FieldValue::Timestamp(b)) => a == b,
+            (FieldValue::Decimal(a), FieldValue::Decimal(b)) => a == b,
+            (FieldValue::LongStr(a), FieldValue::LongStr(b)) => a == b,
+            (FieldValue::FieldArray(a), FieldValue::FieldArray(b)) => a == b,
+            (FieldValue::FieldTable(a), FieldValue::FieldTable(b)) => a == b,
+            (FieldValue::BytesArray(a), FieldValue::BytesArray(b)) => a == b,
+            (FieldValue::Void, FieldValue::Void) => true,
+            _ => false
+        }
+    }
+}
+
+impl Eq for FieldValue {}
+
+impl Hash for FieldValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            FieldValue::Boolean(v) => v.hash(state),
+            FieldValue::U8(v) => v.hash(state),
+            FieldValue::I8(v) => v.hash(state),
+            FieldValue::U16(v) => v.hash(state),
+            FieldValue::I16(v) => v.hash(state),
+            FieldValue::U32(v) => v.hash(state),
+            FieldValue::I32(v) => v.hash(state),
+            FieldValue::U64(v) => v.hash(state),
+            FieldValue::I64(v) => v.hash(state),
+            FieldValue::F32(v) => v.to_bits().hash(state),
+            FieldValue::F64(v) => v.to_bits().hash(state),
+            FieldValue::Timestamp(v) => v.hash(state),
+            FieldValue::Decimal(v) => v.hash(state),
+            FieldValue::LongStr(v) => v.hash(state),
+            FieldValue::FieldArray(v) => v.hash(state),
+            FieldValue::FieldTable(v) => hash_field_table(v, state),
+            FieldValue::BytesArray(v) => v.hash(state),
+            FieldValue::Void => {}
+        }
+    }
+}
+
+// field table entries have no order, their hashes are summed up
+pub(crate) fn hash_field_table<H: Hasher>(table: &FieldTable, state: &mut H) {
+    let sum = table.iter().fold(0u64, |sum, entry| {
+        let mut hasher = DefaultHasher::new();
+        entry.hash(&mut hasher);
+        sum.wrapping_add(hasher.finish())
+    });
+    table.len().hash(state);
+    sum.hash(state);
+}
+
 impl FieldValue {
     #[inline]
     pub fn from_bool(value: bool) -> FieldValue {
@@ -892,6 +1103,41 @@ impl Encode for FieldValue {
             FieldValue::Void => {}
         }
     }
+
+    fn encoded_len(&self) -> usize {
+        1 + match self {
+            FieldValue::Boolean(_) | FieldValue::U8(_) | FieldValue::I8(_) => 1,
+            FieldValue::U16(_) | FieldValue::I16(_) => 2,
+            FieldValue::U32(_) | FieldValue::I32(_) | FieldValue::F32(_) => 4,
+            FieldValue::U64(_) | FieldValue::I64(_) | FieldValue::F64(_) | FieldValue::Timestamp(_) => 8,
+            FieldValue::Decimal(v) => v.encoded_len(),
+            FieldValue::LongStr(v) | FieldValue::BytesArray(v) => v.encoded_len(),
+            FieldValue::FieldArray(v) => v.encoded_len(),
+            FieldValue::FieldTable(v) => v.encoded_len(),
+            FieldValue::Void => 0
+        }
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        match self {
+            FieldValue::LongStr(v) | FieldValue::BytesArray(v) => {
+                buffer.put_u8(self.get_value_kind().as_u8());
+                v.try_encode(buffer)
+            }
+            FieldValue::FieldArray(v) => {
+                buffer.put_u8(self.get_value_kind().as_u8());
+                v.try_encode(buffer)
+            }
+            FieldValue::FieldTable(v) => {
+                buffer.put_u8(self.get_value_kind().as_u8());
+                v.try_encode(buffer)
+            }
+            _ => {
+                self.encode(buffer);
+                Ok(())
+            }
+        }
+    }
 }
 
 impl Decode<FieldValue> for FieldValue {
@@ -1008,46 +1254,59 @@ impl Decode<FieldValue> for FieldValue {
     /// let (_, v) = FieldValue::decode(&[b'V']).unwrap();
     /// assert!(matches!(v, FieldValue::Void));
     /// ```
+    #[inline]
     fn decode(buffer: &[u8]) -> Result<(&[u8], FieldValue), FrameDecodeErr> {
-        let (buffer, value_type) = match u8::decode(buffer) {
-            Ok(v) => v,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldValue type -> {}", e)))
-        };
-        match FieldValueKind::from(value_type) {
-            FieldValueKind::Boolean => {
-                match u8::decode(buffer) {
-                    Ok((buffer, value)) => {
-                        if value == 0u8 {
-                            Ok((buffer, FieldValue::from_bool(false)))
-                        } else {
-                            Ok((buffer, FieldValue::from_bool(true)))
-                        }
-                    },
-                    Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldValue boolean -> {}", e)))
-                }
+        decode_field_value(buffer, 0)
+    }
+}
+
+/// Decode a FieldValue inside of an array or table nested `depth` levels deep
+fn decode_field_value(buffer: &[u8], depth: usize) -> Result<(&[u8], FieldValue), FrameDecodeErr> {
+    let (buffer, value_type) = match u8::decode(buffer) {
+        Ok(v) => v,
+        Err(e) => return Err(e.at(buffer))
+    };
+    match FieldValueKind::from(value_type) {
+        FieldValueKind::Boolean => {
+            match u8::decode(buffer) {
+                Ok((buffer, value)) => {
+                    if value == 0u8 {
+                        Ok((buffer, FieldValue::from_bool(false)))
+                    } else {
+                        Ok((buffer, FieldValue::from_bool(true)))
+                    }
+                },
+                Err(e) => Err(e.at(buffer))
             }
-            FieldValueKind::I8 => i8::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue i8 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_i8(v))),
-            FieldValueKind::U8 => u8::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue u8 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_u8(v))),
-            FieldValueKind::I16 => i16::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue i16 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_i16(v))),
-            FieldValueKind::U16 => u16::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue u16 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_u16(v))),
-            FieldValueKind::I32 => i32::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue i32 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_i32(v))),
-            FieldValueKind::U32 => u32::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue u32 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_u32(v))),
-            FieldValueKind::I64 => i64::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue i64 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_i64(v))),
-            FieldValueKind::U64 => u64::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue u64 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_u64(v))),
-            FieldValueKind::F32 => f32::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue f32 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_f32(v))),
-            FieldValueKind::F64 => f64::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue f64 -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_f64(v))),
-            FieldValueKind::Timestamp => u64::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue timestamp -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_timestamp(v))),
-            FieldValueKind::Decimal => Decimal::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue decimal -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_decimal(v))),
-            FieldValueKind::LongStr => LongStr::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue long string -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_long_string(v))),
-            FieldValueKind::FieldArray => FieldArray::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue FieldArray -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_field_array(v))),
-            FieldValueKind::BytesArray => ByteArray::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue ByteArray -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_bytes_array(v))),
-            FieldValueKind::FieldTable => FieldTable::decode(buffer).map_err(|e| FrameDecodeErr::DecodeError(format!("decode FieldValue FieldTable -> {}", e))).map(|(buffer, v)|(buffer, FieldValue::from_field_table(v))),
-            FieldValueKind::Void => Ok((buffer, FieldValue::from_void())),
-            FieldValueKind::Unknown => return Err(FrameDecodeErr::DecodeError(format!("decode FieldValue failed, unknown field value kind")))
         }
+        FieldValueKind::I8 => i8::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_i8(v))),
+        FieldValueKind::U8 => u8::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_u8(v))),
+        FieldValueKind::I16 => i16::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_i16(v))),
+        FieldValueKind::U16 => u16::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_u16(v))),
+        FieldValueKind::I32 => i32::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_i32(v))),
+        FieldValueKind::U32 => u32::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_u32(v))),
+        FieldValueKind::I64 => i64::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_i64(v))),
+        FieldValueKind::U64 => u64::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_u64(v))),
+        FieldValueKind::F32 => f32::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_f32(v))),
+        FieldValueKind::F64 => f64::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_f64(v))),
+        FieldValueKind::Timestamp => u64::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_timestamp(v))),
+        FieldValueKind::Decimal => Decimal::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_decimal(v))),
+        FieldValueKind::LongStr => LongStr::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_long_string(v))),
+        FieldValueKind::FieldArray => decode_nested(buffer, |data| decode_array_items(data, depth + 1)).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_field_array(v))),
+        FieldValueKind::BytesArray => ByteArray::decode(buffer).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_bytes_array(v))),
+        FieldValueKind::FieldTable => decode_nested(buffer, |data| decode_table_entries(data, depth + 1)).map_err(|e| e.at(buffer)).map(|(buffer, v)|(buffer, FieldValue::from_field_table(v))),
+        FieldValueKind::Void => Ok((buffer, FieldValue::from_void())),
+        FieldValueKind::Unknown => Err(FrameDecodeErr::new(AmqpErrorKind::SyntaxError, "unknown field value kind"))
     }
 }
 
+// take the length prefixed bytes of a nested array or table and decode them
+fn decode_nested<T>(buffer: &[u8], decode: impl FnOnce(&[u8]) -> Result<T, FrameDecodeErr>) -> Result<(&[u8], T), FrameDecodeErr> {
+    let (buffer, length) = u32::decode(buffer).map_err(|e| e.at(buffer))?;
+    let (rest, data) = take_bytes(buffer, length as usize).map_err(|e| e.at(buffer))?;
+    decode(data).map(|value| (rest, value))
+}
+
 pub type FieldTable = HashMap<FieldName, FieldValue>;
 
 impl Encode for FieldTable {
@@ -1098,6 +1357,25 @@ impl Encode for FieldTable {
             index += 1;
         }
     }
+
+    fn encoded_len(&self) -> usize {
+        self.iter().fold(4, |len, (k, v)| len + k.encoded_len() + v.encoded_len())
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        let index = buffer.len();
+        buffer.put_u32(0);
+        for (k, v) in self {
+            k.try_encode(buffer)?;
+            v.try_encode(buffer)?;
+        }
+        let len = buffer.len() - index - std::mem::size_of::<u32>();
+        if len > u32::MAX as usize {
+            return Err(EncodeError::FieldTableTooLong(len));
+        }
+        buffer[index..index + std::mem::size_of::<u32>()].copy_from_slice(&(len as u32).to_be_bytes());
+        Ok(())
+    }
 }
 
 impl Decode<FieldTable> for FieldTable {
@@ -1134,40 +1412,47 @@ impl Decode<FieldTable> for FieldTable {
     fn decode(buffer: &[u8]) -> Result<(&[u8], FieldTable), FrameDecodeErr> {
         let (buffer, length) = match u32::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldTable length -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
         let (buffer, data) = match take_bytes(buffer, length as usize) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldTable bytes -> {}", e)))
+            Err(e) => return Err(e.at(buffer))
         };
 
-        let mut table = FieldTable::new();
-        let mut tmp = data;
-        loop {
-            let (retain, name) = match FieldName::decode(tmp) {
-                Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldTable FieldName failed: {}", e)))
-            };
-            let (retain, value) = match FieldValue::decode(retain) {
-                Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode FieldTable FieldValue failed: {}", e)))
-            };
-            tmp = retain;
-            table.insert(name, value);
-            if tmp.len() == 0 {
-                return Ok((buffer, table))
-            }
-        }
+        decode_table_entries(data, 0).map(|table| (buffer, table))
+    }
+}
+
+/// Decode the entries of a FieldTable nested `depth` levels deep
+pub(crate) fn decode_table_entries(data: &[u8], depth: usize) -> Result<FieldTable, FrameDecodeErr> {
+    if depth > MAX_NESTING_DEPTH {
+        return Err(FrameDecodeErr::new(AmqpErrorKind::SyntaxError, "field table nested too deep").at(data));
+    }
+    let mut table = FieldTable::new();
+    let mut tmp = data;
+    while !tmp.is_empty() {
+        let (retain, name) = match FieldName::decode(tmp) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.at(tmp))
+        };
+        let (retain, value) = match decode_field_value(retain, depth) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.key(retain, name.to_string()))
+        };
+        tmp = retain;
+        table.insert(name, value);
     }
+    Ok(table)
 }
 
 
 
 
 /// frame end octet, every frame should be end with 0xce
-pub const FRAME_END: u8 = 0xce;
+pub use crate::frame::constants::FRAME_END;
 
 /// frame type, amqp protocol contains METHOD, HEARTBEAT, HEADER, CONTENT BODY frame
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum FrameType {
     METHOD,
     HEADER,
@@ -1180,10 +1465,10 @@ impl FrameType {
     #[inline]
     pub fn frame_type_id(&self) -> u8 {
         match self {
-            FrameType::METHOD => 1,
-            FrameType::HEADER => 2,
-            FrameType::BODY => 3,
-            FrameType::HEARTBEAT => 4,
+            FrameType::METHOD => FRAME_METHOD,
+            FrameType::HEADER => FRAME_HEADER,
+            FrameType::BODY => FRAME_BODY,
+            FrameType::HEARTBEAT => FRAME_HEARTBEAT,
             FrameType::UNKNOWN => 0xff
         }
     }
@@ -1200,17 +1485,17 @@ impl From<u8> for FrameType {
     #[inline]
     fn from(type_id: u8) -> Self {
         match type_id {
-            1 => FrameType::METHOD,
-            2 => FrameType::HEADER,
-            3 => FrameType::BODY,
-            4 => FrameType::HEARTBEAT,
+            FRAME_METHOD => FrameType::METHOD,
+            FRAME_HEADER => FrameType::HEADER,
+            FRAME_BODY => FrameType::BODY,
+            FRAME_HEARTBEAT => FrameType::HEARTBEAT,
             _ => FrameType::UNKNOWN
         }
     }
 }
 
 /// While tcp connection is established, the client should send protocol header to server
-#[derive(Property)]
+#[derive(Property, Clone, Debug, PartialEq, Eq, Hash)]
 #[property(get(public), set(public))]
 pub struct ProtocolHeader {
     protocol: Vec<u8>,
@@ -1254,6 +1539,11 @@ impl Encode for ProtocolHeader {
         buffer.put_u8(self.major_version);
         buffer.put_u8(self.minor_version);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.protocol.len() + 4
+    }
 }
 
 impl Decode<ProtocolHeader> for ProtocolHeader {
@@ -1278,29 +1568,35 @@ impl Decode<ProtocolHeader> for ProtocolHeader {
                     return Err(FrameDecodeErr::SyntaxError("Wrong protocol, expected AMQP"))
                 } else { (buffer, protocol) }
             }
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode protocol scheme -> {}", e)))
+            Err(FrameDecodeErr::Incomplete) => return Err(FrameDecodeErr::Incomplete),
+            Err(e) => return Err(e.field(buffer, "ProtocolHeader.protocol"))
         };
         let (buffer, major_id) = match u8::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode major_id -> {}", e)))
+            Err(FrameDecodeErr::Incomplete) => return Err(FrameDecodeErr::Incomplete),
+            Err(e) => return Err(e.field(buffer, "ProtocolHeader.major_id"))
         };
         let (buffer, minor_id) = match u8::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode minor_id -> {}", e)))
+            Err(FrameDecodeErr::Incomplete) => return Err(FrameDecodeErr::Incomplete),
+            Err(e) => return Err(e.field(buffer, "ProtocolHeader.minor_id"))
         };
         let (buffer, major_version) = match u8::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode major_version -> {}", e)))
+            Err(FrameDecodeErr::Incomplete) => return Err(FrameDecodeErr::Incomplete),
+            Err(e) => return Err(e.field(buffer, "ProtocolHeader.major_version"))
         };
         let (buffer, minor_version) = match u8::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode minor_version -> {}", e)))
+            Err(FrameDecodeErr::Incomplete) => return Err(FrameDecodeErr::Incomplete),
+            Err(e) => return Err(e.field(buffer, "ProtocolHeader.minor_version"))
         };
         Ok((buffer, ProtocolHeader { protocol: Vec::from(protocol), major_id, minor_id, major_version, minor_version }))
     }
 }
 
 /// This is Content Header Frame  properties
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Property {
     Connection(ConnectionProperties),
     Channel(ChannelProperties),
@@ -1309,7 +1605,9 @@ pub enum Property {
     Queue(QueueProperties),
     Basic(BasicProperties),
     Tx(TxProperties),
-    Confirm(ConfirmProperties)
+    Confirm(ConfirmProperties),
+    /// Properties of a class which is not in the spec, `raw` is the property flags and list as received
+    Unknown { class_id: u16, raw: Bytes }
 }
 
 impl Default for Property {
@@ -1329,159 +1627,56 @@ impl Encode for Property {
             Property::Queue(properties) => properties.encode(buffer),
             Property::Basic(properties) => properties.encode(buffer),
             Property::Tx(properties) => properties.encode(buffer),
-            Property::Confirm(properties) => properties.encode(buffer)
+            Property::Confirm(properties) => properties.encode(buffer),
+            Property::Unknown { raw, .. } => buffer.extend_from_slice(raw)
         }
     }
-}
 
-pub enum Arguments {
-    ConnectionStart(ConnectionStart),
-    ConnectionStartOk(ConnectionStartOk),
-    ConnectionSecure(ConnectionSecure),
-    ConnectionSecureOk(ConnectionSecureOk),
-    ConnectionTune(ConnectionTune),
-    ConnectionTuneOk(ConnectionTuneOk),
-    ConnectionOpen(ConnectionOpen),
-    ConnectionOpenOk(ConnectionOpenOk),
-    ConnectionClose(ConnectionClose),
-    ConnectionCloseOk(ConnectionCloseOk),
-
-    ChannelOpen(ChannelOpen),
-    ChannelOpenOk(ChannelOpenOk),
-    ChannelFlow(ChannelFlow),
-    ChannelFlowOk(ChannelFlowOk),
-    ChannelClose(ChannelClose),
-    ChannelCloseOk(ChannelCloseOk),
-
-    AccessRequest(AccessRequest),
-    AccessRequestOk(AccessRequestOk),
-
-    ExchangeDeclare(ExchangeDeclare),
-    ExchangeDeclareOk(ExchangeDeclareOk),
-    ExchangeDelete(ExchangeDelete),
-    ExchangeDeleteOk(ExchangeDeleteOk),
-    ExchangeBind(ExchangeBind),
-    ExchangeBindOk(ExchangeBindOk),
-    ExchangeUnbind(ExchangeUnbind),
-    ExchangeUnbindOk(ExchangeUnbindOk),
-
-    QueueDeclare(QueueDeclare),
-    QueueDeclareOk(QueueDeclareOk),
-    QueueBind(QueueBind),
-    QueueBindOk(QueueBindOk),
-    QueueUnbind(QueueUnbind),
-    QueueUnbindOk(QueueUnbindOk),
-    QueuePurge(QueuePurge),
-    QueuePurgeOk(QueuePurgeOk),
-    QueueDelete(QueueDelete),
-    QueueDeleteOk(QueueDeleteOk),
-
-    BasicQos(BasicQos),
-    BasicQosOk(BasicQosOk),
-    BasicConsume(BasicConsume),
-    BasicConsumeOk(BasicConsumeOk),
-    BasicCancel(BasicCancel),
-    BasicCancelOk(BasicCancelOk),
-    BasicPublish(BasicPublish),
-    BasicReturn(BasicReturn),
-    BasicDeliver(BasicDeliver),
-    BasicGet(BasicGet),
-    BasicGetOk(BasicGetOk),
-    BasicGetEmpty(BasicGetEmpty),
-    BasicAck(BasicAck),
-    BasicReject(BasicReject),
-    BasicRecoverAsync(BasicRecoverAsync),
-    BasicRecover(BasicRecover),
-    BasicRecoverOk(BasicRecoverOk),
-    BasicNack(BasicNack),
-
-    TxSelect(TxSelect),
-    TxSelectOk(TxSelectOk),
-    TxCommit(TxCommit),
-    TxCommitOk(TxCommitOk),
-    TxRollback(TxRollback),
-    TxRollbackOk(TxRollbackOk),
-
-    ConfirmSelect(ConfirmSelect),
-    ConfirmSelectOk(ConfirmSelectOk)
-}
-
-impl Encode for Arguments {
-    fn encode(&self, buffer: &mut BytesMut) {
+    fn encoded_len(&self) -> usize {
+        match self {
+            Property::Connection(properties) => properties.encoded_len(),
+            Property::Channel(properties) => properties.encoded_len(),
+            Property::Access(properties) => properties.encoded_len(),
+            Property::Exchange(properties) => properties.encoded_len(),
+            Property::Queue(properties) => properties.encoded_len(),
+            Property::Basic(properties) => properties.encoded_len(),
+            Property::Tx(properties) => properties.encoded_len(),
+            Property::Confirm(properties) => properties.encoded_len(),
+            Property::Unknown { raw, .. } => raw.len()
+        }
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
         match self {
-            Arguments::ConnectionStart(args) => args.encode(buffer),
-            Arguments::ConnectionStartOk(args) => args.encode(buffer),
-            Arguments::ConnectionSecure(args) => args.encode(buffer),
-            Arguments::ConnectionSecureOk(args) => args.encode(buffer),
-            Arguments::ConnectionTune(args) => args.encode(buffer),
-            Arguments::ConnectionTuneOk(args) => args.encode(buffer),
-            Arguments::ConnectionOpen(args) => args.encode(buffer),
-            Arguments::ConnectionOpenOk(args) => args.encode(buffer),
-            Arguments::ConnectionClose(args) => args.encode(buffer),
-            Arguments::ConnectionCloseOk(args) => args.encode(buffer),
-
-            Arguments::ChannelOpen(args) => args.encode(buffer),
-            Arguments::ChannelOpenOk(args) => args.encode(buffer),
-            Arguments::ChannelFlow(args) => args.encode(buffer),
-            Arguments::ChannelFlowOk(args) => args.encode(buffer),
-            Arguments::ChannelClose(args) => args.encode(buffer),
-            Arguments::ChannelCloseOk(args) => args.encode(buffer),
-
-            Arguments::AccessRequest(args) => args.encode(buffer),
-            Arguments::AccessRequestOk(args) => args.encode(buffer),
-
-            Arguments::ExchangeDeclare(args) => args.encode(buffer),
-            Arguments::ExchangeDeclareOk(args) => args.encode(buffer),
-            Arguments::ExchangeDelete(args) => args.encode(buffer),
-            Arguments::ExchangeDeleteOk(args) => args.encode(buffer),
-            Arguments::ExchangeBind(args) => args.encode(buffer),
-            Arguments::ExchangeBindOk(args) => args.encode(buffer),
-            Arguments::ExchangeUnbind(args) => args.encode(buffer),
-            Arguments::ExchangeUnbindOk(args) => args.encode(buffer),
-
-            Arguments::QueueDeclare(args) => args.encode(buffer),
-            Arguments::QueueDeclareOk(args) => args.encode(buffer),
-            Arguments::QueueBind(args) => args.encode(buffer),
-            Arguments::QueueBindOk(args) => args.encode(buffer),
-            Arguments::QueueUnbind(args) => args.encode(buffer),
-            Arguments::QueueUnbindOk(args) => args.encode(buffer),
-            Arguments::QueuePurge(args) => args.encode(buffer),
-            Arguments::QueuePurgeOk(args) => args.encode(buffer),
-            Arguments::QueueDelete(args) => args.encode(buffer),
-            Arguments::QueueDeleteOk(args) => args.encode(buffer),
-
-            Arguments::BasicQos(args) => args.encode(buffer),
-            Arguments::BasicQosOk(args) => args.encode(buffer),
-            Arguments::BasicConsume(args) => args.encode(buffer),
-            Arguments::BasicConsumeOk(args) => args.encode(buffer),
-            Arguments::BasicCancel(args) => args.encode(buffer),
-            Arguments::BasicCancelOk(args) => args.encode(buffer),
-            Arguments::BasicPublish(args) => args.encode(buffer),
-            Arguments::BasicDeliver(args) => args.encode(buffer),
-            Arguments::BasicReturn(args) => args.encode(buffer),
-            Arguments::BasicGet(args) => args.encode(buffer),
-            Arguments::BasicGetOk(args) => args.encode(buffer),
-            Arguments::BasicGetEmpty(args) => args.encode(buffer),
-            Arguments::BasicAck(args) => args.encode(buffer),
-            Arguments::BasicReject(args) => args.encode(buffer),
-            Arguments::BasicRecoverAsync(args) => args.encode(buffer),
-            Arguments::BasicRecover(args) => args.encode(buffer),
-            Arguments::BasicRecoverOk(args) => args.encode(buffer),
-            Arguments::BasicNack(args) => args.encode(buffer),
-
-            Arguments::TxSelect(args) => args.encode(buffer),
-            Arguments::TxSelectOk(args) => args.encode(buffer),
-            Arguments::TxCommit(args) => args.encode(buffer),
-            Arguments::TxCommitOk(args) => args.encode(buffer),
-            Arguments::TxRollback(args) => args.encode(buffer),
-            Arguments::TxRollbackOk(args) => args.encode(buffer),
-
-            Arguments::ConfirmSelect(args) => args.encode(buffer),
-            Arguments::ConfirmSelectOk(args) => args.encode(buffer)
+            Property::Connection(properties) => properties.try_encode(buffer),
+            Property::Channel(properties) => properties.try_encode(buffer),
+            Property::Access(properties) => properties.try_encode(buffer),
+            Property::Exchange(properties) => properties.try_encode(buffer),
+            Property::Queue(properties) => properties.try_encode(buffer),
+            Property::Basic(properties) => properties.try_encode(buffer),
+            Property::Tx(properties) => properties.try_encode(buffer),
+            Property::Confirm(properties) => properties.try_encode(buffer),
+            Property::Unknown { raw, .. } => {
+                buffer.extend_from_slice(raw);
+                Ok(())
+            }
         }
     }
 }
 
+impl Arguments {
+    #[inline]
+    pub fn method(&self) -> Method {
+        self.class_method().1
+    }
+
+    /// The peer will reply to these arguments, the method is synchronous and `no_wait` is not set
+    #[inline]
+    pub fn expects_reply(&self) -> bool {
+        self.method().is_synchronous() && !self.no_wait()
+    }
+}
+
 impl Default for Arguments {
     #[inline]
     fn default() -> Self {
@@ -1489,7 +1684,7 @@ impl Default for Arguments {
     }
 }
 
-#[derive(Property, Default)]
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
 #[property(get(public), set(public))]
 pub struct MethodPayload {
     class: Class,
@@ -1500,154 +1695,76 @@ pub struct MethodPayload {
 impl Encode for MethodPayload {
     #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u16(self.class.class_id());
-        buffer.put_u16(self.method.method_id());
+        match &self.args {
+            Arguments::Unknown { class_id, method_id, .. } => {
+                buffer.put_u16(*class_id);
+                buffer.put_u16(*method_id);
+            }
+            _ => {
+                buffer.put_u16(self.class.class_id());
+                buffer.put_u16(self.method.method_id());
+            }
+        }
         self.args.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        4 + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        match &self.args {
+            Arguments::Unknown { class_id, method_id, .. } => {
+                buffer.put_u16(*class_id);
+                buffer.put_u16(*method_id);
+            }
+            _ => {
+                buffer.put_u16(self.class.class_id());
+                buffer.put_u16(self.method.method_id());
+            }
+        }
+        self.args.try_encode(buffer)
+    }
 }
 
 impl Decode<MethodPayload> for MethodPayload {
     fn decode(buffer: &[u8]) -> Result<(&[u8], MethodPayload), FrameDecodeErr>{
         let (buffer, class_id) = match u16::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => {
-                match e {
-                    FrameDecodeErr::Incomplete => return Err(e),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("decode MethodPayload class id failed -> {}", e)))
-                }
-            }
+            Err(e) => return Err(e.at(buffer))
         };
         let (buffer, method_id) = match u16::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => {
-                match e {
-                    FrameDecodeErr::Incomplete => return Err(e),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("decode MethodPayload method id failed -> {}", e)))
-                }
-            }
+            Err(e) => return Err(e.at(buffer).class(class_id))
         };
         let class = Class::from(class_id);
-        if let Class::Unknown = class { 
-            return Err(FrameDecodeErr::DecodeError(format!("decode MethodPayload unknown class: {}", class_id)));
-        }
         let method = match get_method_type(class, method_id) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode MethodPayload unknown method -> {}", e)))
-        };
-        let arguments = match method {
-            Method::Connection(method_type) => {
-                match method_type {
-                    ConnectionMethod::Start => ConnectionStart::decode(buffer),
-                    ConnectionMethod::StartOk => ConnectionStartOk::decode(buffer),
-                    ConnectionMethod::Tune => ConnectionTune::decode(buffer),
-                    ConnectionMethod::TuneOk => ConnectionTuneOk::decode(buffer),
-                    ConnectionMethod::Secure => ConnectionSecure::decode(buffer),
-                    ConnectionMethod::SecureOk => ConnectionSecureOk::decode(buffer),
-                    ConnectionMethod::Open => ConnectionOpen::decode(buffer),
-                    ConnectionMethod::OpenOk => ConnectionOpenOk::decode(buffer),
-                    ConnectionMethod::Close => ConnectionClose::decode(buffer),
-                    ConnectionMethod::CloseOk => ConnectionCloseOk::decode(buffer),
-                    ConnectionMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown connection method"))
-                }
-            },
-            Method::Channel(method_type) => {
-                match method_type {
-                    ChannelMethod::Open => ChannelOpen::decode(buffer),
-                    ChannelMethod::OpenOk => ChannelOpenOk::decode(buffer),
-                    ChannelMethod::Flow => ChannelFlow::decode(buffer),
-                    ChannelMethod::FlowOk => ChannelFlowOk::decode(buffer),
-                    ChannelMethod::Close => ChannelClose::decode(buffer),
-                    ChannelMethod::CloseOk => ChannelCloseOk::decode(buffer),
-                    ChannelMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown channel method"))
-                }
-            },
-            Method::Access(method_type) => {
-                match method_type {
-                    AccessMethod::Request => AccessRequest::decode(buffer),
-                    AccessMethod::RequestOk => AccessRequestOk::decode(buffer),
-                    AccessMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown access method"))
-                }
-            },
-            Method::Exchange(method_type) => {
-                match method_type {
-                    ExchangeMethod::Declare => ExchangeDeclare::decode(buffer),
-                    ExchangeMethod::DeclareOk => ExchangeDeclareOk::decode(buffer),
-                    ExchangeMethod::Bind => ExchangeBind::decode(buffer),
-                    ExchangeMethod::BindOk => ExchangeBindOk::decode(buffer),
-                    ExchangeMethod::Unbind => ExchangeUnbind::decode(buffer),
-                    ExchangeMethod::UnbindOk => ExchangeUnbindOk::decode(buffer),
-                    ExchangeMethod::Delete => ExchangeDelete::decode(buffer),
-                    ExchangeMethod::DeleteOk => ExchangeDeleteOk::decode(buffer),
-                    ExchangeMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown exchange method"))
-                }
-            },
-            Method::Queue(method_type) => {
-                match method_type {
-                    QueueMethod::Declare => QueueDeclare::decode(buffer),
-                    QueueMethod::DeclareOk => QueueDeclareOk::decode(buffer),
-                    QueueMethod::Bind => QueueBind::decode(buffer),
-                    QueueMethod::BindOk => QueueBindOk::decode(buffer),
-                    QueueMethod::Unbind => QueueUnbind::decode(buffer),
-                    QueueMethod::UnbindOk => QueueUnbindOk::decode(buffer),
-                    QueueMethod::Purge => QueuePurge::decode(buffer),
-                    QueueMethod::PurgeOk => QueuePurgeOk::decode(buffer),
-                    QueueMethod::Delete => QueueDelete::decode(buffer),
-                    QueueMethod::DeleteOk => QueueDeleteOk::decode(buffer),
-                    QueueMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown queue method"))
-                }
-            },
-            Method::Basic(method_type) => {
-                match method_type {
-                    BasicMethod::Qos => BasicQos::decode(buffer),
-                    BasicMethod::QosOk => BasicQosOk::decode(buffer),
-                    BasicMethod::Consume => BasicConsume::decode(buffer),
-                    BasicMethod::ConsumeOk => BasicConsumeOk::decode(buffer),
-                    BasicMethod::Cancel => BasicCancel::decode(buffer),
-                    BasicMethod::CancelOk => BasicCancelOk::decode(buffer),
-                    BasicMethod::Publish => BasicPublish::decode(buffer),
-                    BasicMethod::Return => BasicReturn::decode(buffer),
-                    BasicMethod::Deliver => BasicDeliver::decode(buffer),
-                    BasicMethod::Get => BasicGet::decode(buffer),
-                    BasicMethod::GetEmpty => BasicGetEmpty::decode(buffer),
-                    BasicMethod::GetOk => BasicGetOk::decode(buffer),
-                    BasicMethod::Reject => BasicReject::decode(buffer),
-                    BasicMethod::RecoverAsync => BasicRecoverAsync::decode(buffer),
-                    BasicMethod::Recover => BasicRecover::decode(buffer),
-                    BasicMethod::RecoverOk => BasicRecoverOk::decode(buffer),
-                    BasicMethod::Ack => BasicAck::decode(buffer),
-                    BasicMethod::Nack => BasicNack::decode(buffer),
-                    BasicMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown basic method"))
-                }
-            },
-            Method::Tx(method_type) => {
-                match method_type {
-                    TxMethod::Select => TxSelect::decode(buffer),
-                    TxMethod::SelectOk => TxSelectOk::decode(buffer),
-                    TxMethod::Commit => TxCommit::decode(buffer),
-                    TxMethod::CommitOk => TxCommitOk::decode(buffer),
-                    TxMethod::Rollback => TxRollback::decode(buffer),
-                    TxMethod::RollbackOk => TxRollbackOk::decode(buffer),
-                    TxMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown tx method"))
-                }
-            },
-            Method::Confirm(method_type) => {
-                match method_type {
-                    ConfirmMethod::Select => ConfirmSelect::decode(buffer),
-                    ConfirmMethod::SelectOk => ConfirmSelectOk::decode(buffer),
-                    ConfirmMethod::Unknown => return Err(FrameDecodeErr::SyntaxError("decode MethodPayload unknown confirm method"))
-                }
+            // kept as received, a proxy forwards it and a peer can answer with not-implemented
+            Err(_) => {
+                let args = Arguments::Unknown { class_id, method_id, raw: Bytes::copy_from_slice(buffer) };
+                let (class, method) = args.class_method();
+                return Ok((&[], MethodPayload { class, method, args }));
             }
         };
-
-        let (buffer, args) = match arguments {
+        let (buffer, args) = match Arguments::decode_method(method, buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode MethodPayload error: {}", e)))
+            Err(e) => return Err(e.at(buffer).method(class_id, method_id))
         };
         Ok((buffer, MethodPayload { class, method, args}))
     }
 }
 
-#[derive(Property, Default)]
+impl MethodPayload {
+    /// Take the method arguments out of the payload
+    #[inline]
+    pub fn into_args(self) -> Arguments {
+        self.args
+    }
+}
+
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
 #[property(get(public), set(public))]
 pub struct ContentHeaderPayload {
     class: Class,
@@ -1659,11 +1776,29 @@ pub struct ContentHeaderPayload {
 impl Encode for ContentHeaderPayload {
     #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u16(self.class.class_id());
+        match &self.properties {
+            Property::Unknown { class_id, .. } => buffer.put_u16(*class_id),
+            _ => buffer.put_u16(self.class.class_id())
+        }
         buffer.put_u16(self.weight);
         buffer.put_u64(self.body_size);
         self.properties.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        12 + self.properties.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        match &self.properties {
+            Property::Unknown { class_id, .. } => buffer.put_u16(*class_id),
+            _ => buffer.put_u16(self.class.class_id())
+        }
+        buffer.put_u16(self.weight);
+        buffer.put_u64(self.body_size);
+        self.properties.try_encode(buffer)
+    }
 }
 
 impl Decode<ContentHeaderPayload> for ContentHeaderPayload {
@@ -1671,19 +1806,16 @@ impl Decode<ContentHeaderPayload> for ContentHeaderPayload {
         // pase payload
         let (buffer, class_id) = match u16::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ContentHeaderPayload class id failed -> {}", e)))
+            Err(e) => return Err(e.field(buffer, "ContentHeaderPayload.class_id"))
         };
         let class_type = Class::from(class_id);
-        if let Class::Unknown = class_type {
-            return Err(FrameDecodeErr::DecodeError(format!("decode ContentHeaderPayload class tyep unknown -> {}", class_id)));
-        }
         let (buffer, weight) = match u16::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ContentHeaderPayload weight -> {}", e)))
+            Err(e) => return Err(e.field(buffer, "ContentHeaderPayload.weight"))
         };
         let (buffer, body_size) = match u64::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ContentHeaderPayload body_size -> {}", e)))
+            Err(e) => return Err(e.field(buffer, "ContentHeaderPayload.body_size"))
         };
 
         let properties = match class_type {
@@ -1695,22 +1827,36 @@ impl Decode<ContentHeaderPayload> for ContentHeaderPayload {
             Class::Basic => BasicProperties::decode(buffer),
             Class::Tx => TxProperties::decode(buffer),
             Class::Confirm => ConfirmProperties::decode(buffer),
-            Class::Unknown => return Err(FrameDecodeErr::SyntaxError("decode ContentHeaderPayload unknown class"))
+            Class::Unknown => Ok((&[][..], Property::Unknown { class_id, raw: Bytes::copy_from_slice(buffer) }))
         };
         let (buffer, properties) = match properties {
             Ok(properties) => properties,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ContentHeaderPayload properties -> {}", e)))
+            Err(e) => return Err(e.at(buffer).class(class_id))
         };
         Ok((buffer, ContentHeaderPayload { class: class_type, weight, body_size, properties }))
     }
 }
 
+impl ContentHeaderPayload {
+    /// Take the properties out of the payload
+    #[inline]
+    pub fn into_properties(self) -> Property {
+        self.properties
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct HeartbeatPayload;
 
 impl Encode for HeartbeatPayload {
     #[inline]
     fn encode(&self, _: &mut BytesMut) {
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
+    }
 }
 
 impl Decode<HeartbeatPayload> for HeartbeatPayload {
@@ -1720,6 +1866,7 @@ impl Decode<HeartbeatPayload> for HeartbeatPayload {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Payload {
     Heartbeat(HeartbeatPayload),
     Method(MethodPayload),
@@ -1744,10 +1891,32 @@ impl Encode for Payload {
             Payload::ContentBody(content_body) => buffer.extend_from_slice(content_body.as_slice()),
         }
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        match self {
+            Payload::Heartbeat(heartbeat) => heartbeat.encoded_len(),
+            Payload::Method(method) => method.encoded_len(),
+            Payload::ContentHeader(content_header) => content_header.encoded_len(),
+            Payload::ContentBody(content_body) => content_body.len(),
+        }
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        match self {
+            Payload::Method(method) => method.try_encode(buffer),
+            Payload::ContentHeader(content_header) => content_header.try_encode(buffer),
+            _ => {
+                self.encode(buffer);
+                Ok(())
+            }
+        }
+    }
 }
 
 // frame
-#[derive(Default)]
+#[derive(Property, Default, Clone, Debug)]
+#[property(get(public), set(disable))]
 pub struct Frame {
     frame_type: FrameType,
     channel: u16,
@@ -1757,12 +1926,12 @@ pub struct Frame {
 
 impl Frame {
     // Heartbeat frame is const
-    const HEARTBEAT_FRAME_BYTES: [u8; 8] = [8u8, 0, 0, 0, 0, 0, 0, FRAME_END];
+    const HEARTBEAT_FRAME_BYTES: [u8; 8] = [FRAME_HEARTBEAT, 0, 0, 0, 0, 0, 0, FRAME_END];
 
     /// The whole frame bytes length, should first decode the frame
     #[inline]
     pub fn decoded_frame_len(&self) -> usize {
-        (self.length + 8u32) as usize
+        self.length as usize + 8
     }
 
 
@@ -1772,86 +1941,59 @@ impl Frame {
         &Frame::HEARTBEAT_FRAME_BYTES
     }
 
+    /// Take the payload out of the frame
+    #[inline]
+    pub fn into_payload(self) -> Payload {
+        self.payload
+    }
+
+    /// Build a heartbeat frame, it is always sent on channel 0
+    #[inline]
+    pub fn heartbeat_frame() -> Self {
+        Frame { frame_type: FrameType::HEARTBEAT, channel: 0, length: 0u32, payload: Payload::Heartbeat(HeartbeatPayload) }
+    }
+
+    /// Build a content header frame, should be sent after a method frame which carries content
+    pub fn content_header_frame(channel: u16, class: Class, body_size: u64, properties: Property) -> Self {
+        let payload = ContentHeaderPayload { class, weight: 0, body_size, properties };
+        Frame { frame_type: FrameType::HEADER, channel, length: 0u32, payload: Payload::ContentHeader(payload) }
+    }
+
+    /// Build a content body frame, the body should not larger than `frame_max - 8`
+    #[inline]
+    pub fn content_body_frame(channel: u16, body: Vec<u8>) -> Self {
+        Frame { frame_type: FrameType::BODY, channel, length: 0u32, payload: Payload::ContentBody(body) }
+    }
+
     pub fn method_frame(channel: u16, args: Arguments) -> Self {
-        let (class, method) = match &args {
-            Arguments::ConnectionStart(_) => (Class::Connection, Method::Connection(ConnectionMethod::Start)),
-            Arguments::ConnectionStartOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::StartOk)),
-            Arguments::ConnectionSecure(_) => (Class::Connection, Method::Connection(ConnectionMethod::Secure)),
-            Arguments::ConnectionSecureOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::SecureOk)),
-            Arguments::ConnectionTune(_) => (Class::Connection, Method::Connection(ConnectionMethod::Tune)),
-            Arguments::ConnectionTuneOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::TuneOk)),
-            Arguments::ConnectionOpen(_) => (Class::Connection, Method::Connection(ConnectionMethod::Open)),
-            Arguments::ConnectionOpenOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::OpenOk)),
-            Arguments::ConnectionClose(_) => (Class::Connection, Method::Connection(ConnectionMethod::Close)),
-            Arguments::ConnectionCloseOk(_) => (Class::Connection, Method::Connection(ConnectionMethod::CloseOk)),
-
-            Arguments::ChannelOpen(_) => (Class::Channel, Method::Channel(ChannelMethod::Open)),
-            Arguments::ChannelOpenOk(_) => (Class::Channel, Method::Channel(ChannelMethod::OpenOk)),
-            Arguments::ChannelFlow(_) => (Class::Channel, Method::Channel(ChannelMethod::Flow)),
-            Arguments::ChannelFlowOk(_) => (Class::Channel, Method::Channel(ChannelMethod::FlowOk)),
-            Arguments::ChannelClose(_) => (Class::Channel, Method::Channel(ChannelMethod::Close)),
-            Arguments::ChannelCloseOk(_) => (Class::Channel, Method::Channel(ChannelMethod::CloseOk)),
-
-            Arguments::AccessRequest(_) => (Class::Access, Method::Access(AccessMethod::Request)),
-            Arguments::AccessRequestOk(_) => (Class::Access, Method::Access(AccessMethod::RequestOk)),
-
-            Arguments::ExchangeDeclare(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Declare)),
-            Arguments::ExchangeDeclareOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::DeclareOk)),
-            Arguments::ExchangeDelete(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Delete)),
-            Arguments::ExchangeDeleteOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::DeleteOk)),
-            Arguments::ExchangeBind(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Bind)),
-            Arguments::ExchangeBindOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::BindOk)),
-            Arguments::ExchangeUnbind(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::Unbind)),
-            Arguments::ExchangeUnbindOk(_) => (Class::Exchange, Method::Exchange(ExchangeMethod::UnbindOk)),
-
-            Arguments::QueueDeclare(_) => (Class::Queue, Method::Queue(QueueMethod::Declare)),
-            Arguments::QueueDeclareOk(_) => (Class::Queue, Method::Queue(QueueMethod::DeclareOk)),
-            Arguments::QueueBind(_) => (Class::Queue, Method::Queue(QueueMethod::Bind)),
-            Arguments::QueueBindOk(_) => (Class::Queue, Method::Queue(QueueMethod::BindOk)),
-            Arguments::QueueUnbind(_) => (Class::Queue, Method::Queue(QueueMethod::Unbind)),
-            Arguments::QueueUnbindOk(_) => (Class::Queue, Method::Queue(QueueMethod::UnbindOk)),
-            Arguments::QueuePurge(_) => (Class::Queue, Method::Queue(QueueMethod::Purge)),
-            Arguments::QueuePurgeOk(_) => (Class::Queue, Method::Queue(QueueMethod::PurgeOk)),
-            Arguments::QueueDelete(_) => (Class::Queue, Method::Queue(QueueMethod::Delete)),
-            Arguments::QueueDeleteOk(_) => (Class::Queue, Method::Queue(QueueMethod::DeleteOk)),
-
-            Arguments::BasicQos(_) => (Class::Basic, Method::Basic(BasicMethod::Qos)),
-            Arguments::BasicQosOk(_) => (Class::Basic, Method::Basic(BasicMethod::QosOk)),
-            Arguments::BasicConsume(_) => (Class::Basic, Method::Basic(BasicMethod::Consume)),
-            Arguments::BasicConsumeOk(_) => (Class::Basic, Method::Basic(BasicMethod::ConsumeOk)),
-            Arguments::BasicCancel(_) => (Class::Basic, Method::Basic(BasicMethod::Cancel)),
-            Arguments::BasicCancelOk(_) => (Class::Basic, Method::Basic(BasicMethod::CancelOk)),
-            Arguments::BasicPublish(_) => (Class::Basic, Method::Basic(BasicMethod::Publish)),
-            Arguments::BasicDeliver(_) => (Class::Basic, Method::Basic(BasicMethod::Deliver)),
-            Arguments::BasicReturn(_) => (Class::Basic, Method::Basic(BasicMethod::Return)),
-            Arguments::BasicGet(_) => (Class::Basic, Method::Basic(BasicMethod::Get)),
-            Arguments::BasicGetOk(_) => (Class::Basic, Method::Basic(BasicMethod::GetOk)),
-            Arguments::BasicGetEmpty(_) => (Class::Basic, Method::Basic(BasicMethod::GetEmpty)),
-            Arguments::BasicAck(_) => (Class::Basic, Method::Basic(BasicMethod::Ack)),
-            Arguments::BasicReject(_) => (Class::Basic, Method::Basic(BasicMethod::Reject)),
-            Arguments::BasicRecoverAsync(_) => (Class::Basic, Method::Basic(BasicMethod::RecoverAsync)),
-            Arguments::BasicRecover(_) => (Class::Basic, Method::Basic(BasicMethod::Recover)),
-            Arguments::BasicRecoverOk(_) => (Class::Basic, Method::Basic(BasicMethod::RecoverOk)),
-            Arguments::BasicNack(_) => (Class::Basic, Method::Basic(BasicMethod::Nack)),
-
-            Arguments::TxSelect(_) => (Class::Tx, Method::Tx(TxMethod::Select)),
-            Arguments::TxSelectOk(_) => (Class::Tx, Method::Tx(TxMethod::SelectOk)),
-            Arguments::TxCommit(_) => (Class::Tx, Method::Tx(TxMethod::Commit)),
-            Arguments::TxCommitOk(_) => (Class::Tx, Method::Tx(TxMethod::CommitOk)),
-            Arguments::TxRollback(_) => (Class::Tx, Method::Tx(TxMethod::Rollback)),
-            Arguments::TxRollbackOk(_) => (Class::Tx, Method::Tx(TxMethod::RollbackOk)),
-
-            Arguments::ConfirmSelect(_) => (Class::Confirm, Method::Confirm(ConfirmMethod::Select)),
-            Arguments::ConfirmSelectOk(_) => (Class::Confirm, Method::Confirm(ConfirmMethod::SelectOk))
-        };
+        let (class, method) = args.class_method();
         let payload = MethodPayload { class, method, args };
         Frame { frame_type: FrameType::METHOD, channel: channel, length: 0u32, payload: Payload::Method(payload) }
     }
 }
 
+impl PartialEq for Frame {
+    /// The length is only known after encoding, frames are equal if they would be encoded the same
+    fn eq(&self, other: &Frame) -> bool {
+        self.frame_type == other.frame_type && self.channel == other.channel && self.payload == other.payload
+    }
+}
+
+impl Eq for Frame {}
+
+impl Hash for Frame {
+    /// The length is left out like in `eq`
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.frame_type.hash(state);
+        self.channel.hash(state);
+        self.payload.hash(state);
+    }
+}
+
 impl Encode for Frame {
     #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
+        buffer.reserve(self.encoded_len());
         buffer.put_u8(self.frame_type.frame_type_id());
         buffer.put_u16(self.channel);
 
@@ -1872,6 +2014,33 @@ impl Encode for Frame {
         // append frame end byte
         buffer.put_u8(FRAME_END);
     }
+
+    /// Type, channel and length, the payload and the frame end byte
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        7 + self.payload.encoded_len() + 1
+    }
+
+    /// Nothing of the frame is left in the buffer if it fails
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        let start = buffer.len();
+        buffer.reserve(self.encoded_len());
+        buffer.put_u8(self.frame_type.frame_type_id());
+        buffer.put_u16(self.channel);
+        buffer.put_u32(0);
+        if let Err(e) = self.payload.try_encode(buffer) {
+            buffer.truncate(start);
+            return Err(e);
+        }
+        let payload_len = buffer.len() - start - 7;
+        if payload_len > u32::MAX as usize {
+            buffer.truncate(start);
+            return Err(EncodeError::FrameTooLarge { size: payload_len + 8, frame_max: u32::MAX });
+        }
+        buffer[start + 3..start + 7].copy_from_slice(&(payload_len as u32).to_be_bytes());
+        buffer.put_u8(FRAME_END);
+        Ok(())
+    }
 }
 
 impl Decode<Frame> for Frame {
@@ -1881,7 +2050,7 @@ impl Decode<Frame> for Frame {
             Err(e) => {
                 match e {
                     FrameDecodeErr::Incomplete => return Err(FrameDecodeErr::Incomplete),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("decode Frame frame_type -> {}", e)))
+                    _ => return Err(e.field(buffer, "Frame.frame_type"))
                 }
             }
         };
@@ -1890,7 +2059,7 @@ impl Decode<Frame> for Frame {
             Err(e) => {
                 match e {
                     FrameDecodeErr::Incomplete => return Err(FrameDecodeErr::Incomplete),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("decode Frame channle id -> {}", e)))
+                    _ => return Err(e.field(buffer, "Frame.channel"))
                 }
             }
         };
@@ -1899,7 +2068,7 @@ impl Decode<Frame> for Frame {
             Err(e) => {
                 match e {
                     FrameDecodeErr::Incomplete => return Err(FrameDecodeErr::Incomplete),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("decode Frame payload length -> {}", e)))
+                    _ => return Err(e.field(buffer, "Frame.length"))
                 }
             }
         };
@@ -1909,23 +2078,24 @@ impl Decode<Frame> for Frame {
             Err(e) => {
                 match e {
                     FrameDecodeErr::Incomplete => return Err(FrameDecodeErr::Incomplete),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("decode Frame payload data -> {}", e)))
+                    _ => return Err(e.field(buffer, "Frame.payload"))
                 }
             }
         };
         // read frame end
         let (buffer, _) = match u8::decode(buffer) {
-            Ok((buffer, frame_end)) => {
+            Ok((retain, frame_end)) => {
                 if FRAME_END == frame_end {
-                    (buffer, frame_end)
+                    (retain, frame_end)
                 } else {
-                    return Err(FrameDecodeErr::DecodeError(format!("decode Frame end error: {}", frame_end)))
+                    return Err(FrameDecodeErr::new(AmqpErrorKind::FrameError, format!("frame end error: {}", frame_end))
+                        .field(&[], "Frame.end").frame(channel, payload_data))
                 }
             },
             Err(e) => {
                 match e {
                     FrameDecodeErr::Incomplete => return Err(FrameDecodeErr::Incomplete),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("decode Frame end -> {}", e)))
+                    _ => return Err(e.field(buffer, "Frame.end"))
                 }
             }
         };
@@ -1934,19 +2104,19 @@ impl Decode<Frame> for Frame {
             FrameType::HEARTBEAT => {
                 match HeartbeatPayload::decode(payload_data) {
                     Ok((_, heartbeat_payload)) => Ok((buffer, Frame { frame_type, channel, length, payload: Payload::Heartbeat(heartbeat_payload)})),
-                    Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode Frame heartbeat payload failed -> {}", e)))
+                    Err(e) => Err(e.frame(channel, payload_data))
                 }
             }
             FrameType::METHOD => {
                 match MethodPayload::decode(payload_data) {
                     Ok((_, method_payload)) => Ok((buffer, Frame { frame_type, channel, length, payload: Payload::Method(method_payload)})),
-                    Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode Frame heartbeat payload failed -> {}", e)))
+                    Err(e) => Err(e.frame(channel, payload_data))
                 }
             }
             FrameType::HEADER => {
                 match ContentHeaderPayload::decode(payload_data) {
                     Ok((_, content_header_payload)) => Ok((buffer, Frame { frame_type, channel, length, payload: Payload::ContentHeader(content_header_payload)})),
-                    Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode Frame content header payload failed -> {}", e)))
+                    Err(e) => Err(e.frame(channel, payload_data))
                 }
             }
             FrameType::BODY => {
@@ -1954,7 +2124,8 @@ impl Decode<Frame> for Frame {
                 payload.extend_from_slice(payload_data);
                 Ok((buffer, Frame { frame_type, channel, length, payload: Payload::ContentBody(payload) }))
             }
-            FrameType::UNKNOWN => return Err(FrameDecodeErr::DecodeError(format!("decode Frame unknown frame type: {}", frame_type.frame_type_id()))),
+            FrameType::UNKNOWN => Err(FrameDecodeErr::new(AmqpErrorKind::FrameError, format!("unknown frame type: {}", frame_type.frame_type_id()))
+                .at(payload_data).frame(channel, payload_data)),
         }
     }
 }
@@ -0,0 +1,195 @@
+use std::io;
+use std::io::{Read, Write};
+use bytes::BytesMut;
+use crate::error::{FrameReadErr, EncodeError};
+use crate::frame::base::{Encode, Frame, ProtocolHeader};
+use crate::frame::frame_codec::{DecodedFrame, FrameCodec};
+
+const READ_SIZE: usize = 8 * 1024;
+
+/// Reads frames from a blocking `std::io::Read`, such as a `std::net::TcpStream`
+///
+/// The bytes are buffered and decoded by `FrameCodec`, a frame is returned once it is complete.
+pub struct FrameReader<R> {
+    reader: R,
+    codec: FrameCodec,
+    buffer: BytesMut,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Reader for a stream which starts with a protocol header, such as the client side stream a server reads
+    pub fn new(reader: R) -> Self {
+        FrameReader::with_codec(reader, FrameCodec::default())
+    }
+
+    /// Reader for the server side stream a client reads, a protocol header sent by the server in place of the
+    /// first frame is returned as a `VersionMismatch`
+    pub fn client(reader: R) -> Self {
+        FrameReader::with_codec(reader, FrameCodec::client())
+    }
+
+    /// Reader for a stream which does not start with a protocol header, every byte is decoded as a frame
+    pub fn without_protocol_header(reader: R) -> Self {
+        FrameReader::with_codec(reader, FrameCodec::without_protocol_header())
+    }
+
+    fn with_codec(reader: R, codec: FrameCodec) -> Self {
+        FrameReader {
+            reader,
+            codec,
+            buffer: BytesMut::with_capacity(READ_SIZE),
+        }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Set the `frame_max` of the codec once it is negotiated, a larger frame fails to read
+    #[inline]
+    pub fn codec_mut(&mut self) -> &mut FrameCodec {
+        &mut self.codec
+    }
+
+    /// The bytes read but not decoded yet are lost
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Block until the next frame is read, returns None if the stream ends between two frames
+    pub fn read_frame(&mut self) -> Result<Option<DecodedFrame>, FrameReadErr> {
+        loop {
+            if let Some(frame) = self.codec.decode_frame(&mut self.buffer)? {
+                return Ok(Some(frame));
+            }
+            let len = self.buffer.len();
+            self.buffer.resize(len + READ_SIZE, 0);
+            let read = self.reader.read(&mut self.buffer[len..]);
+            self.buffer.truncate(len + *read.as_ref().unwrap_or(&0));
+            match read {
+                Ok(0) if self.buffer.is_empty() => return Ok(None),
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream ends inside a frame").into()),
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into())
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = Result<DecodedFrame, FrameReadErr>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+/// Writes frames to a blocking `std::io::Write`
+///
+/// Frames are written as they come by default. With a batch size the encoded frames are buffered until
+/// the batch size is reached or `flush` is called, a frame is never split across two writes of the buffer.
+/// A failed write keeps the bytes the writer did not take, the next write or `flush` continues with them.
+pub struct FrameWriter<W> {
+    writer: W,
+    codec: FrameCodec,
+    buffer: BytesMut,
+    batch_size: usize,
+}
+
+impl<W: Write> FrameWriter<W> {
+    pub fn new(writer: W) -> Self {
+        FrameWriter::with_batch_size(writer, 0)
+    }
+
+    /// Buffer the encoded frames until there are at least `batch_size` bytes
+    pub fn with_batch_size(writer: W, batch_size: usize) -> Self {
+        FrameWriter {
+            writer,
+            codec: FrameCodec::default(),
+            buffer: BytesMut::with_capacity(batch_size),
+            batch_size,
+        }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Set the `frame_max` of the codec once it is negotiated, a larger frame fails to write
+    #[inline]
+    pub fn codec_mut(&mut self) -> &mut FrameCodec {
+        &mut self.codec
+    }
+
+    #[inline]
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Bytes encoded but not written yet
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// The protocol header is buffered like a frame
+    pub fn write_protocol_header(&mut self, header: &ProtocolHeader) -> io::Result<()> {
+        header.encode(&mut self.buffer);
+        self.write_batch()
+    }
+
+    pub fn write_frame(&mut self, frame: &Frame) -> Result<(), EncodeError> {
+        self.codec.encode_frame(frame, &mut self.buffer)?;
+        Ok(self.write_batch()?)
+    }
+
+    /// Write the buffered frames and flush the writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.write_buffer()?;
+        self.writer.flush()
+    }
+
+    /// Flush the buffered frames and return the writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+
+    #[inline]
+    fn write_batch(&mut self) -> io::Result<()> {
+        if self.buffer.len() >= self.batch_size {
+            self.write_buffer()?;
+        }
+        Ok(())
+    }
+
+    // the written bytes leave the buffer as they go, so they are not written again after an error
+    fn write_buffer(&mut self) -> io::Result<()> {
+        while !self.buffer.is_empty() {
+            match self.writer.write(&self.buffer) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "writer takes no more bytes")),
+                Ok(written) => {
+                    let _ = self.buffer.split_to(written);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e)
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,831 @@
+use property::Property;
+use crate::error::FrameDecodeErr;
+use crate::error::amqp::AmqpErrorKind;
+use crate::class::Class;
+use crate::method::{BasicMethod, MethodId};
+use crate::frame::base::{Decode, take_bytes, decode_array_items, decode_table_entries, ShortStr, LongStr, Decimal, FieldName, FieldValue, FieldValueKind, FieldArray, FieldTable, FrameType, Frame, MethodPayload, Arguments, FRAME_END};
+use crate::frame::method::basic::{BasicPublish, BasicReturn, BasicDeliver};
+
+/// Decode a value which borrows from the input buffer instead of copying it
+pub trait DecodeRef<'a>: Sized {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr>;
+}
+
+/// Borrowed ShortStr, the bytes are kept as they are on the wire
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShortStrRef<'a>(&'a [u8]);
+
+impl<'a> ShortStrRef<'a> {
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// The string, None if it is not valid utf8
+    #[inline]
+    pub fn to_str(&self) -> Option<&'a str> {
+        std::str::from_utf8(self.0).ok()
+    }
+
+    #[inline]
+    pub fn into_owned(self) -> ShortStr {
+        // the length always fits in an octet
+        ShortStr::with_bytes(self.0).unwrap_or_default()
+    }
+}
+
+impl<'a> DecodeRef<'a> for ShortStrRef<'a> {
+    /// Decode ShortStr without copying it
+    ///
+    /// # Examples
+    /// ```
+    /// use amqp_proto::borrowed::{DecodeRef, ShortStrRef};
+    ///
+    /// let (_, short_str) = ShortStrRef::decode_ref(&[5u8, 104, 101, 108, 108, 111]).unwrap();
+    ///
+    /// assert_eq!(short_str.as_bytes(), b"hello");
+    /// assert_eq!(short_str.into_owned().to_string(), String::from("hello"));
+    /// ```
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, length) = match u8::decode(buffer) {
+            Ok(v) => v,
+            Err(e) => return Err(e.at(buffer))
+        };
+        match take_bytes(buffer, length as usize) {
+            Ok((buffer, data)) => Ok((buffer, ShortStrRef(data))),
+            Err(e) => Err(e.at(buffer))
+        }
+    }
+}
+
+/// Borrowed LongStr, also used for byte arrays
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LongStrRef<'a>(&'a [u8]);
+
+impl<'a> LongStrRef<'a> {
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// The string, None if it is not valid utf8
+    #[inline]
+    pub fn to_str(&self) -> Option<&'a str> {
+        std::str::from_utf8(self.0).ok()
+    }
+
+    #[inline]
+    pub fn into_owned(self) -> Result<LongStr, FrameDecodeErr> {
+        LongStr::with_bytes(self.0)
+    }
+}
+
+impl<'a> DecodeRef<'a> for LongStrRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, length) = match u32::decode(buffer) {
+            Ok(v) => v,
+            Err(e) => return Err(e.at(buffer))
+        };
+        match take_bytes(buffer, length as usize) {
+            Ok((buffer, data)) => Ok((buffer, LongStrRef(data))),
+            Err(e) => Err(e.at(buffer))
+        }
+    }
+}
+
+/// Borrowed FieldValue, nested strings, arrays and tables point into the input buffer
+#[derive(Debug)]
+pub enum FieldValueRef<'a> {
+    Boolean(bool),
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Timestamp(u64),
+    Decimal(Decimal),
+    LongStr(LongStrRef<'a>),
+    FieldArray(FieldArrayRef<'a>),
+    FieldTable(FieldTableRef<'a>),
+    BytesArray(LongStrRef<'a>),
+    Void
+}
+
+impl<'a> FieldValueRef<'a> {
+    /// Copy the value, nested arrays and tables are decoded
+    pub fn into_owned(self) -> Result<FieldValue, FrameDecodeErr> {
+        let value = match self {
+            FieldValueRef::Boolean(v) => FieldValue::from_bool(v),
+            FieldValueRef::U8(v) => FieldValue::from_u8(v),
+            FieldValueRef::I8(v) => FieldValue::from_i8(v),
+            FieldValueRef::U16(v) => FieldValue::from_u16(v),
+            FieldValueRef::I16(v) => FieldValue::from_i16(v),
+            FieldValueRef::U32(v) => FieldValue::from_u32(v),
+            FieldValueRef::I32(v) => FieldValue::from_i32(v),
+            FieldValueRef::U64(v) => FieldValue::from_u64(v),
+            FieldValueRef::I64(v) => FieldValue::from_i64(v),
+            FieldValueRef::F32(v) => FieldValue::from_f32(v),
+            FieldValueRef::F64(v) => FieldValue::from_f64(v),
+            FieldValueRef::Timestamp(v) => FieldValue::from_timestamp(v),
+            FieldValueRef::Decimal(v) => FieldValue::from_decimal(v),
+            FieldValueRef::LongStr(v) => FieldValue::from_long_string(v.into_owned()?),
+            FieldValueRef::FieldArray(v) => FieldValue::from_field_array(v.into_owned()?),
+            FieldValueRef::FieldTable(v) => FieldValue::from_field_table(v.into_owned()?),
+            FieldValueRef::BytesArray(v) => FieldValue::from_bytes_array(v.into_owned()?),
+            FieldValueRef::Void => FieldValue::from_void()
+        };
+        Ok(value)
+    }
+}
+
+impl<'a> DecodeRef<'a> for FieldValueRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, value_type) = match u8::decode(buffer) {
+            Ok(v) => v,
+            Err(e) => return Err(e.at(buffer))
+        };
+        let value = match FieldValueKind::from(value_type) {
+            FieldValueKind::Boolean => u8::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::Boolean(v != 0))),
+            FieldValueKind::I8 => i8::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::I8(v))),
+            FieldValueKind::U8 => u8::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::U8(v))),
+            FieldValueKind::I16 => i16::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::I16(v))),
+            FieldValueKind::U16 => u16::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::U16(v))),
+            FieldValueKind::I32 => i32::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::I32(v))),
+            FieldValueKind::U32 => u32::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::U32(v))),
+            FieldValueKind::I64 => i64::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::I64(v))),
+            FieldValueKind::U64 => u64::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::U64(v))),
+            FieldValueKind::F32 => f32::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::F32(v))),
+            FieldValueKind::F64 => f64::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::F64(v))),
+            FieldValueKind::Timestamp => u64::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::Timestamp(v))),
+            FieldValueKind::Decimal => Decimal::decode(buffer).map(|(buffer, v)| (buffer, FieldValueRef::Decimal(v))),
+            FieldValueKind::LongStr => LongStrRef::decode_ref(buffer).map(|(buffer, v)| (buffer, FieldValueRef::LongStr(v))),
+            FieldValueKind::FieldArray => FieldArrayRef::decode_ref(buffer).map(|(buffer, v)| (buffer, FieldValueRef::FieldArray(v))),
+            FieldValueKind::BytesArray => LongStrRef::decode_ref(buffer).map(|(buffer, v)| (buffer, FieldValueRef::BytesArray(v))),
+            FieldValueKind::FieldTable => FieldTableRef::decode_ref(buffer).map(|(buffer, v)| (buffer, FieldValueRef::FieldTable(v))),
+            FieldValueKind::Void => Ok((buffer, FieldValueRef::Void)),
+            FieldValueKind::Unknown => return Err(FrameDecodeErr::new(AmqpErrorKind::SyntaxError, "unknown field value kind"))
+        };
+        value.map_err(|e| e.at(buffer))
+    }
+}
+
+/// Borrowed FieldArray, the items are decoded while iterating
+#[derive(Clone, Copy, Debug)]
+pub struct FieldArrayRef<'a>(&'a [u8]);
+
+impl<'a> FieldArrayRef<'a> {
+    /// Encoded items without the length prefix
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> FieldArrayIter<'a> {
+        FieldArrayIter { data: self.0, index: 0 }
+    }
+
+    pub fn into_owned(self) -> Result<FieldArray, FrameDecodeErr> {
+        decode_array_items(self.0, 0)
+    }
+}
+
+impl<'a> DecodeRef<'a> for FieldArrayRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, length) = match u32::decode(buffer) {
+            Ok(v) => v,
+            Err(e) => return Err(e.at(buffer))
+        };
+        match take_bytes(buffer, length as usize) {
+            Ok((buffer, data)) => Ok((buffer, FieldArrayRef(data))),
+            Err(e) => Err(e.at(buffer))
+        }
+    }
+}
+
+impl<'a> IntoIterator for FieldArrayRef<'a> {
+    type Item = Result<FieldValueRef<'a>, FrameDecodeErr>;
+    type IntoIter = FieldArrayIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the items of a FieldArrayRef, stops after the first error
+pub struct FieldArrayIter<'a> {
+    data: &'a [u8],
+    index: usize
+}
+
+impl<'a> Iterator for FieldArrayIter<'a> {
+    type Item = Result<FieldValueRef<'a>, FrameDecodeErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let data = self.data;
+        match FieldValueRef::decode_ref(data) {
+            Ok((retain, value)) => {
+                self.data = retain;
+                self.index += 1;
+                Some(Ok(value))
+            }
+            Err(e) => {
+                self.data = &[];
+                Some(Err(e.index(data, self.index)))
+            }
+        }
+    }
+}
+
+/// Borrowed FieldTable, the entries are decoded while iterating, in wire order
+#[derive(Clone, Copy, Debug)]
+pub struct FieldTableRef<'a>(&'a [u8]);
+
+impl<'a> FieldTableRef<'a> {
+    /// Encoded entries without the length prefix
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> FieldTableIter<'a> {
+        FieldTableIter { data: self.0 }
+    }
+
+    /// Find the value of a field, the entries before it are decoded
+    ///
+    /// # Examples
+    /// ```
+    /// use amqp_proto::borrowed::{DecodeRef, FieldTableRef, FieldValueRef};
+    ///
+    /// let bytes = [0u8, 0, 0, 11, 5, b'x', b'-', b't', b't', b'l', b'I', 0, 0, 0x3, 0xe8];
+    /// let (_, table) = FieldTableRef::decode_ref(&bytes).unwrap();
+    ///
+    /// assert!(matches!(table.get("x-ttl").unwrap(), Some(FieldValueRef::I32(1000))));
+    /// assert!(table.get("x-expires").unwrap().is_none());
+    /// ```
+    pub fn get(&self, name: &str) -> Result<Option<FieldValueRef<'a>>, FrameDecodeErr> {
+        for entry in self.iter() {
+            let (key, value) = entry?;
+            if key.as_bytes() == name.as_bytes() {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn into_owned(self) -> Result<FieldTable, FrameDecodeErr> {
+        decode_table_entries(self.0, 0)
+    }
+}
+
+impl<'a> DecodeRef<'a> for FieldTableRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, length) = match u32::decode(buffer) {
+            Ok(v) => v,
+            Err(e) => return Err(e.at(buffer))
+        };
+        match take_bytes(buffer, length as usize) {
+            Ok((buffer, data)) => Ok((buffer, FieldTableRef(data))),
+            Err(e) => Err(e.at(buffer))
+        }
+    }
+}
+
+impl<'a> IntoIterator for FieldTableRef<'a> {
+    type Item = Result<(ShortStrRef<'a>, FieldValueRef<'a>), FrameDecodeErr>;
+    type IntoIter = FieldTableIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the entries of a FieldTableRef, stops after the first error. Field names are checked like
+/// the owned `FieldName` decoder does.
+pub struct FieldTableIter<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> Iterator for FieldTableIter<'a> {
+    type Item = Result<(ShortStrRef<'a>, FieldValueRef<'a>), FrameDecodeErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let data = self.data;
+        self.data = &[];
+        let (retain, name) = match ShortStrRef::decode_ref(data) {
+            Ok(ret) => ret,
+            Err(e) => return Some(Err(e.at(data)))
+        };
+        if let Err(e) = FieldName::with_bytes(name.as_bytes()) {
+            return Some(Err(e.at(data)));
+        }
+        let (retain, value) = match FieldValueRef::decode_ref(retain) {
+            Ok(ret) => ret,
+            Err(e) => return Some(Err(e.key(retain, String::from_utf8_lossy(name.as_bytes()).to_string())))
+        };
+        self.data = retain;
+        Some(Ok((name, value)))
+    }
+}
+
+/// Borrowed `BasicPublish` arguments
+#[derive(Property, Clone, Copy, Debug)]
+#[property(get(public, type = "copy"), set(disable))]
+pub struct BasicPublishRef<'a> {
+    ticket: u16,
+    exchange_name: ShortStrRef<'a>,
+    routing_key: ShortStrRef<'a>,
+    mandatory: bool,
+    immediate: bool
+}
+
+impl<'a> BasicPublishRef<'a> {
+    pub fn into_owned(self) -> BasicPublish {
+        let mut publish = BasicPublish::default();
+        publish.set_ticket(self.ticket)
+            .set_exchange_name(self.exchange_name.into_owned())
+            .set_routing_key(self.routing_key.into_owned())
+            .set_mandatory(self.mandatory)
+            .set_immediate(self.immediate);
+        publish
+    }
+}
+
+impl<'a> DecodeRef<'a> for BasicPublishRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, ticket) = match u16::decode(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicPublish.ticket"))
+        };
+        let (buffer, exchange_name) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicPublish.exchange_name"))
+        };
+        let (buffer, routing_key) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicPublish.routing_key"))
+        };
+        let (buffer, flags) = match u8::decode(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicPublish.flags"))
+        };
+        let mandatory = flags & (1 << 0) != 0;
+        let immediate = flags & (1 << 1) != 0;
+        Ok((buffer, BasicPublishRef { ticket, exchange_name, routing_key, mandatory, immediate }))
+    }
+}
+
+/// Borrowed `BasicReturn` arguments
+#[derive(Property, Clone, Copy, Debug)]
+#[property(get(public, type = "copy"), set(disable))]
+pub struct BasicReturnRef<'a> {
+    reply_code: u16,
+    reply_text: ShortStrRef<'a>,
+    exchange_name: ShortStrRef<'a>,
+    routing_key: ShortStrRef<'a>
+}
+
+impl<'a> BasicReturnRef<'a> {
+    pub fn into_owned(self) -> BasicReturn {
+        let mut basic_return = BasicReturn::default();
+        basic_return.set_reply_code(self.reply_code)
+            .set_reply_text(self.reply_text.into_owned())
+            .set_exchange_name(self.exchange_name.into_owned())
+            .set_routing_key(self.routing_key.into_owned());
+        basic_return
+    }
+}
+
+impl<'a> DecodeRef<'a> for BasicReturnRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, reply_code) = match u16::decode(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicReturn.reply_code"))
+        };
+        let (buffer, reply_text) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicReturn.reply_text"))
+        };
+        let (buffer, exchange_name) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicReturn.exchange_name"))
+        };
+        let (buffer, routing_key) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicReturn.routing_key"))
+        };
+        Ok((buffer, BasicReturnRef { reply_code, reply_text, exchange_name, routing_key }))
+    }
+}
+
+/// Borrowed `BasicDeliver` arguments
+#[derive(Property, Clone, Copy, Debug)]
+#[property(get(public, type = "copy"), set(disable))]
+pub struct BasicDeliverRef<'a> {
+    consumer_tag: ShortStrRef<'a>,
+    delivery_tag: u64,
+    redelivered: bool,
+    exchange_name: ShortStrRef<'a>,
+    routing_key: ShortStrRef<'a>
+}
+
+impl<'a> BasicDeliverRef<'a> {
+    pub fn into_owned(self) -> BasicDeliver {
+        let mut deliver = BasicDeliver::default();
+        deliver.set_consumer_tag(self.consumer_tag.into_owned())
+            .set_delivery_tag(self.delivery_tag)
+            .set_redelivered(self.redelivered)
+            .set_exchange_name(self.exchange_name.into_owned())
+            .set_routing_key(self.routing_key.into_owned());
+        deliver
+    }
+}
+
+impl<'a> DecodeRef<'a> for BasicDeliverRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let (buffer, consumer_tag) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicDeliver.consumer_tag"))
+        };
+        let (buffer, delivery_tag) = match u64::decode(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicDeliver.delivery_tag"))
+        };
+        let (buffer, flags) = match u8::decode(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicDeliver.flags"))
+        };
+        let redelivered = flags & (1 << 0) != 0;
+        let (buffer, exchange_name) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicDeliver.exchange_name"))
+        };
+        let (buffer, routing_key) = match ShortStrRef::decode_ref(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.field(buffer, "BasicDeliver.routing_key"))
+        };
+        Ok((buffer, BasicDeliverRef { consumer_tag, delivery_tag, redelivered, exchange_name, routing_key }))
+    }
+}
+
+/// Method payload with the arguments left undecoded
+#[derive(Property, Clone, Copy, Debug)]
+#[property(get(public, type = "copy"), set(disable))]
+pub struct MethodPayloadRef<'a> {
+    class_id: u16,
+    method_id: u16,
+    // encoded arguments
+    args: &'a [u8],
+    // whole payload, decoded again by into_owned
+    #[property(get(disable))]
+    payload: &'a [u8]
+}
+
+impl<'a> MethodPayloadRef<'a> {
+    /// Decode the arguments of a `BasicPublish`, None for other methods
+    pub fn basic_publish(&self) -> Option<Result<BasicPublishRef<'a>, FrameDecodeErr>> {
+        self.decode_args::<BasicPublishRef>(BasicMethod::Publish)
+    }
+
+    /// Decode the arguments of a `BasicReturn`, None for other methods
+    pub fn basic_return(&self) -> Option<Result<BasicReturnRef<'a>, FrameDecodeErr>> {
+        self.decode_args::<BasicReturnRef>(BasicMethod::Return)
+    }
+
+    /// Decode the arguments of a `BasicDeliver`, None for other methods
+    pub fn basic_deliver(&self) -> Option<Result<BasicDeliverRef<'a>, FrameDecodeErr>> {
+        self.decode_args::<BasicDeliverRef>(BasicMethod::Deliver)
+    }
+
+    fn decode_args<T>(&self, method: BasicMethod) -> Option<Result<T, FrameDecodeErr>> where T: DecodeRef<'a> {
+        let (class_id, method_id) = (Class::Basic.class_id(), method.method_id());
+        if self.class_id != class_id || self.method_id != method_id {
+            return None;
+        }
+        Some(T::decode_ref(self.args).map(|(_, args)| args).map_err(|e| e.method(class_id, method_id)))
+    }
+
+    /// Decode all the arguments
+    pub fn into_owned(self) -> Result<MethodPayload, FrameDecodeErr> {
+        MethodPayload::decode(self.payload).map(|(_, payload)| payload)
+    }
+
+    /// Decode the arguments only
+    #[inline]
+    pub fn into_args(self) -> Result<Arguments, FrameDecodeErr> {
+        self.into_owned().map(MethodPayload::into_args)
+    }
+}
+
+impl<'a> DecodeRef<'a> for MethodPayloadRef<'a> {
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let payload = buffer;
+        let (buffer, class_id) = match u16::decode(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.at(buffer))
+        };
+        let (args, method_id) = match u16::decode(buffer) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e.at(buffer).class(class_id))
+        };
+        Ok((&[], MethodPayloadRef { class_id, method_id, args, payload }))
+    }
+}
+
+/// Frame with the payload left undecoded, enough to route it without allocating
+#[derive(Property, Clone, Copy, Debug)]
+#[property(get(public, type = "copy"), set(disable))]
+pub struct FrameRef<'a> {
+    frame_type: FrameType,
+    channel: u16,
+    payload: &'a [u8],
+    // whole frame, decoded again by into_owned
+    #[property(get(disable))]
+    frame: &'a [u8]
+}
+
+impl<'a> FrameRef<'a> {
+    /// Method payload of a method frame, None for other frames
+    ///
+    /// # Examples
+    /// ```
+    /// use bytes::BytesMut;
+    /// use amqp_proto::ShortStr;
+    /// use amqp_proto::arguments::basic::BasicDeliver;
+    /// use amqp_proto::borrowed::{DecodeRef, FrameRef};
+    /// use amqp_proto::codec::{Arguments, Encode, Frame};
+    ///
+    /// let mut deliver = BasicDeliver::default();
+    /// deliver.set_delivery_tag(7u64).set_routing_key(ShortStr::with_bytes(b"orders").unwrap());
+    /// let mut buffer = BytesMut::with_capacity(64);
+    /// Frame::method_frame(1, Arguments::BasicDeliver(deliver)).encode(&mut buffer);
+    ///
+    /// let (_, frame) = FrameRef::decode_ref(&buffer).unwrap();
+    /// let deliver = frame.method().unwrap().unwrap().basic_deliver().unwrap().unwrap();
+    ///
+    /// assert_eq!(frame.channel(), 1);
+    /// assert_eq!(deliver.delivery_tag(), 7);
+    /// assert_eq!(deliver.routing_key().as_bytes(), b"orders");
+    /// ```
+    pub fn method(&self) -> Option<Result<MethodPayloadRef<'a>, FrameDecodeErr>> {
+        match self.frame_type {
+            FrameType::METHOD => Some(MethodPayloadRef::decode_ref(self.payload)
+                .map(|(_, payload)| payload)
+                .map_err(|e| e.frame(self.channel, self.payload))),
+            _ => None
+        }
+    }
+
+    /// Decode the whole frame
+    pub fn into_owned(self) -> Result<Frame, FrameDecodeErr> {
+        Frame::decode(self.frame).map(|(_, frame)| frame)
+    }
+}
+
+impl<'a> DecodeRef<'a> for FrameRef<'a> {
+    /// Split a frame, returns Incomplete until the whole frame is buffered
+    fn decode_ref(buffer: &'a [u8]) -> Result<(&'a [u8], Self), FrameDecodeErr> {
+        let frame = buffer;
+        let (buffer, frame_type) = u8::decode(buffer)?;
+        let (buffer, channel) = u16::decode(buffer)?;
+        let (buffer, length) = u32::decode(buffer)?;
+        let (buffer, payload) = take_bytes(buffer, length as usize)?;
+        let (buffer, frame_end) = u8::decode(buffer)?;
+        if frame_end != FRAME_END {
+            return Err(FrameDecodeErr::new(AmqpErrorKind::FrameError, format!("frame end error: {}", frame_end))
+                .field(&[], "Frame.end").frame(channel, payload));
+        }
+        let frame_type = FrameType::from(frame_type);
+        if let FrameType::UNKNOWN = frame_type {
+            return Err(FrameDecodeErr::new(AmqpErrorKind::FrameError, "unknown frame type").at(payload).frame(channel, payload));
+        }
+        let frame = &frame[..frame.len() - buffer.len()];
+        Ok((buffer, FrameRef { frame_type, channel, payload, frame }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use crate::{ShortStr, FieldName};
+    use crate::error::FrameDecodeErr;
+    use crate::error::amqp::AmqpErrorKind;
+    use crate::frame::base::{Encode, Decode, FieldValue, FieldTable, Frame, FrameType, Payload, Arguments};
+    use crate::frame::method::basic::{BasicPublish, BasicReturn};
+    use crate::frame::method::queue::QueueDeclare;
+    use super::{DecodeRef, FieldArrayRef, FieldTableRef, FieldValueRef, FrameRef};
+
+    #[test]
+    fn test_truncated_field_table() {
+        // the second entry claims a 5 byte name but only 3 bytes are left
+        let bytes = [0u8, 0, 0, 8, 1, b'a', b'b', 1, 5, b'x', b'-', b't'];
+        let (_, table) = FieldTableRef::decode_ref(&bytes).unwrap();
+        let mut iter = table.iter();
+        let (name, value) = iter.next().unwrap().unwrap();
+        assert_eq!(name.as_bytes(), b"a");
+        assert!(matches!(value, FieldValueRef::I8(1)));
+        match iter.next() {
+            Some(Err(FrameDecodeErr::DecodeError(e))) => assert_eq!(e.kind(), AmqpErrorKind::FrameError),
+            _ => panic!("Expected decode error")
+        }
+        assert!(iter.next().is_none());
+        assert!(table.get("x-ttl").is_err());
+        assert!(table.into_owned().is_err());
+
+        // the value of the entry is cut off
+        let bytes = [0u8, 0, 0, 4, 1, b'a', b'I', 0];
+        let (_, table) = FieldTableRef::decode_ref(&bytes).unwrap();
+        match table.iter().next() {
+            Some(Err(FrameDecodeErr::DecodeError(e))) => assert_eq!(e.path_string(), "[\"a\"]"),
+            _ => panic!("Expected decode error")
+        }
+
+        // the length prefix runs past the buffer
+        assert!(FieldTableRef::decode_ref(&[0u8, 0, 0, 8, 1, b'a']).is_err());
+    }
+
+    #[test]
+    fn test_invalid_field_name() {
+        // an empty name and a name which starts with a digit fail like they do in the owned decoder
+        for bytes in [[0u8, 0, 0, 3, 0, b'b', 1], [0u8, 0, 0, 3, 1, b'1', b'V']].iter() {
+            let (_, table) = FieldTableRef::decode_ref(bytes).unwrap();
+            let mut iter = table.iter();
+            assert!(matches!(iter.next(), Some(Err(_))));
+            assert!(iter.next().is_none());
+            assert!(FieldTable::decode(bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn test_truncated_field_array() {
+        // the second item is an i32 with two bytes
+        let bytes = [0u8, 0, 0, 5, b'b', 7, b'I', 0, 0];
+        let (_, array) = FieldArrayRef::decode_ref(&bytes).unwrap();
+        let mut iter = array.iter();
+        assert!(matches!(iter.next(), Some(Ok(FieldValueRef::I8(7)))));
+        match iter.next() {
+            Some(Err(FrameDecodeErr::DecodeError(e))) => assert_eq!(e.path_string(), "[1]"),
+            _ => panic!("Expected decode error")
+        }
+        assert!(iter.next().is_none());
+        assert!(array.into_owned().is_err());
+
+        // unknown type tag
+        let (_, array) = FieldArrayRef::decode_ref(&[0u8, 0, 0, 2, b'!', 0]).unwrap();
+        assert!(matches!(array.iter().next(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_basic_publish_ref() {
+        let mut publish = BasicPublish::default();
+        publish.set_exchange_name(ShortStr::with_bytes(b"amq.topic").unwrap())
+            .set_routing_key(ShortStr::with_bytes(b"orders.new").unwrap())
+            .set_mandatory(true);
+        let mut buffer = BytesMut::with_capacity(64);
+        Frame::method_frame(2, Arguments::BasicPublish(publish.clone())).encode(&mut buffer);
+
+        let (retain, frame) = FrameRef::decode_ref(&buffer).unwrap();
+        assert!(retain.is_empty());
+        let method = frame.method().unwrap().unwrap();
+        assert!(method.basic_return().is_none());
+        assert!(method.basic_deliver().is_none());
+        let publish_ref = method.basic_publish().unwrap().unwrap();
+        assert_eq!(publish_ref.exchange_name().to_str(), Some("amq.topic"));
+        assert_eq!(publish_ref.routing_key().as_bytes(), b"orders.new");
+        assert!(publish_ref.mandatory());
+        assert!(!publish_ref.immediate());
+        assert_eq!(publish_ref.into_owned(), publish);
+    }
+
+    #[test]
+    fn test_basic_return_ref() {
+        let mut basic_return = BasicReturn::default();
+        basic_return.set_reply_code(312u16)
+            .set_reply_text(ShortStr::with_bytes(b"NO_ROUTE").unwrap())
+            .set_exchange_name(ShortStr::with_bytes(b"amq.direct").unwrap())
+            .set_routing_key(ShortStr::with_bytes(b"nowhere").unwrap());
+        let mut buffer = BytesMut::with_capacity(64);
+        Frame::method_frame(1, Arguments::BasicReturn(basic_return.clone())).encode(&mut buffer);
+
+        let (_, frame) = FrameRef::decode_ref(&buffer).unwrap();
+        let method = frame.method().unwrap().unwrap();
+        assert_eq!((method.class_id(), method.method_id()), (60, 50));
+        assert!(method.basic_publish().is_none());
+        let return_ref = method.basic_return().unwrap().unwrap();
+        assert_eq!(return_ref.reply_code(), 312);
+        assert_eq!(return_ref.reply_text().as_bytes(), b"NO_ROUTE");
+        assert_eq!(return_ref.exchange_name().as_bytes(), b"amq.direct");
+        assert_eq!(return_ref.routing_key().as_bytes(), b"nowhere");
+        assert_eq!(return_ref.into_owned(), basic_return);
+
+        // the arguments stop after the reply text
+        let args_len = 2 + 1 + 8;
+        let length = (4 + args_len) as u8;
+        let mut bytes = vec![1u8, 0, 1, 0, 0, 0, length, 0, 60, 0, 50];
+        bytes.extend_from_slice(&buffer[11..11 + args_len]);
+        bytes.push(0xce);
+        let (_, frame) = FrameRef::decode_ref(&bytes).unwrap();
+        match frame.method().unwrap().unwrap().basic_return() {
+            Some(Err(FrameDecodeErr::DecodeError(e))) => {
+                assert_eq!(e.path_string(), "BasicReturn.exchange_name");
+                assert_eq!((e.class_id(), e.method_id()), (Some(60), Some(50)));
+            }
+            _ => panic!("Expected decode error")
+        }
+    }
+
+    #[test]
+    fn test_frame_ref_incomplete() {
+        let mut buffer = BytesMut::with_capacity(64);
+        Frame::content_body_frame(4, b"hello".to_vec()).encode(&mut buffer);
+        for end in 0..buffer.len() {
+            assert!(matches!(FrameRef::decode_ref(&buffer[..end]), Err(FrameDecodeErr::Incomplete)));
+        }
+
+        // a second frame is left in the buffer
+        Frame::heartbeat_frame().encode(&mut buffer);
+        let (retain, frame) = FrameRef::decode_ref(&buffer).unwrap();
+        assert_eq!(frame.frame_type(), FrameType::BODY);
+        assert_eq!(frame.channel(), 4);
+        assert_eq!(frame.payload(), b"hello");
+        assert!(frame.method().is_none());
+        assert_eq!(retain, Frame::heartbeat_frame_bytes());
+    }
+
+    #[test]
+    fn test_frame_ref_bad_frame_end() {
+        let mut buffer = BytesMut::with_capacity(64);
+        Frame::content_body_frame(4, b"hello".to_vec()).encode(&mut buffer);
+        let last = buffer.len() - 1;
+        buffer[last] = 0;
+        match FrameRef::decode_ref(&buffer) {
+            Err(FrameDecodeErr::DecodeError(e)) => {
+                assert_eq!(e.kind(), AmqpErrorKind::FrameError);
+                assert_eq!(e.channel(), Some(4));
+                assert_eq!(e.path_string(), "Frame.end");
+            }
+            _ => panic!("Expected decode error")
+        }
+
+        let mut buffer = BytesMut::with_capacity(64);
+        Frame::heartbeat_frame().encode(&mut buffer);
+        buffer[0] = 2 + 10;
+        assert!(matches!(FrameRef::decode_ref(&buffer), Err(FrameDecodeErr::DecodeError(_))));
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let mut arguments = FieldTable::new();
+        arguments.insert(FieldName::with_bytes(b"x-max-length").unwrap(), FieldValue::from_u32(100));
+        arguments.insert(FieldName::with_bytes(b"x-tags").unwrap(), FieldValue::from_field_array(vec![FieldValue::from_bool(true), FieldValue::from_i64(-1)]));
+        let mut declare = QueueDeclare::default();
+        declare.set_queue_name(ShortStr::with_bytes(b"orders").unwrap())
+            .set_durable(true)
+            .set_args(arguments.clone());
+        let mut buffer = BytesMut::with_capacity(128);
+        Frame::method_frame(3, Arguments::QueueDeclare(declare)).encode(&mut buffer);
+
+        let (_, owned) = Frame::decode(&buffer).unwrap();
+        let (_, frame) = FrameRef::decode_ref(&buffer).unwrap();
+        let method = frame.method().unwrap().unwrap();
+        match owned.clone().into_payload() {
+            Payload::Method(payload) => assert_eq!(method.into_owned().unwrap(), payload),
+            _ => panic!("Expected method payload")
+        }
+        assert_eq!(frame.into_owned().unwrap(), owned);
+
+        // the table of the arguments, found by its encoded bytes
+        let mut table = BytesMut::with_capacity(64);
+        arguments.encode(&mut table);
+        let offset = buffer.windows(table.len()).position(|w| w == &table[..]).unwrap();
+        let (_, table_ref) = FieldTableRef::decode_ref(&buffer[offset..]).unwrap();
+        assert_eq!(table_ref.into_owned().unwrap(), arguments);
+        let tags = table_ref.get("x-tags").unwrap().unwrap();
+        assert_eq!(tags.into_owned().unwrap(), arguments[&FieldName::with_bytes(b"x-tags").unwrap()]);
+    }
+}
@@ -0,0 +1,8 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
+pub const FRAME_METHOD: u8 = 1;
+pub const FRAME_HEADER: u8 = 2;
+pub const FRAME_BODY: u8 = 3;
+pub const FRAME_HEARTBEAT: u8 = 8;
+pub const FRAME_MIN_SIZE: u32 = 4096;
+pub const FRAME_END: u8 = 206;
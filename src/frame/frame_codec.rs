@@ -1,64 +1,185 @@
-use tokio_util::codec::Decoder;
+#[cfg(feature = "tokio-codec")]
+use tokio_util::codec::{Decoder, Encoder};
+use std::convert::TryInto;
 use bytes::BytesMut;
-use crate::error::FrameDecodeErr;
+use crate::error::{FrameDecodeErr, EncodeError};
+use crate::error::amqp::AmqpErrorKind;
 use crate::frame::base::{ProtocolHeader, Frame};
 use crate::frame::frame_codec::DecodedFrame::AmqpFrame;
-use crate::codec::Decode;
+use crate::codec::{Decode, Encode};
 
 pub const PROTOCOL_HEADER_SIZE: usize = 8;
+// frame type, channel and payload length octets before the payload
+const FRAME_HEADER_SIZE: usize = 7;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DecodedFrame {
     ProtocolHeader(ProtocolHeader),
-    AmqpFrame(Frame)
+    AmqpFrame(Frame),
+    /// The server sent its protocol header in place of connection.start, it does not support the client's version
+    /// and closes the connection
+    VersionMismatch(ProtocolHeader)
+}
+
+/// The peer which reads the stream, it decides what the stream starts with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Reads the server's stream, which starts with a frame
+    Client,
+    /// Reads the client's stream, which starts with a protocol header
+    Server
 }
 
 pub struct FrameCodec {
-    header_received: bool,
+    role: Role,
+    // the protocol header or the first frame was decoded
+    started: bool,
+    // 0 if there is no limit
+    frame_max: u32,
 }
 
 impl Default for FrameCodec {
+    #[inline]
     fn default() -> Self {
+        FrameCodec::server()
+    }
+}
+
+impl FrameCodec {
+    /// Codec for the stream a client reads, a protocol header in place of the first frame is a `VersionMismatch`
+    #[inline]
+    pub fn client() -> Self {
         FrameCodec {
-            header_received: false,
+            role: Role::Client,
+            started: false,
+            frame_max: 0,
         }
     }
-}
 
-impl Decoder for FrameCodec {
-    type Item = DecodedFrame;
-    type Error = FrameDecodeErr;
+    /// Codec for the stream a server reads, the same as `default`
+    #[inline]
+    pub fn server() -> Self {
+        FrameCodec {
+            role: Role::Server,
+            started: false,
+            frame_max: 0,
+        }
+    }
 
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        // parse amqp header
-        if !self.header_received {
+    /// Codec for a stream which does not start with a protocol header, every byte is decoded as a frame
+    #[inline]
+    pub fn without_protocol_header() -> Self {
+        FrameCodec {
+            role: Role::Client,
+            started: true,
+            frame_max: 0,
+        }
+    }
+
+    #[inline]
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    #[inline]
+    pub fn frame_max(&self) -> u32 {
+        self.frame_max
+    }
+
+    /// Frames larger than the negotiated `frame_max` fail to encode and to decode, 0 is no limit
+    ///
+    /// A frame which is too large to decode fails as soon as its header arrives, its payload is not buffered.
+    #[inline]
+    pub fn set_frame_max(&mut self, frame_max: u32) -> &mut Self {
+        self.frame_max = frame_max;
+        self
+    }
+
+    /// Decode the protocol header or the frame at the start of `src` and remove its bytes,
+    /// returns None if `src` does not hold a whole one yet
+    ///
+    /// It does not depend on a runtime, the tokio `Decoder`, `FuturesFramed` and `FrameReader` all decode with it.
+    pub fn decode_frame(&mut self, src: &mut BytesMut) -> Result<Option<DecodedFrame>, FrameDecodeErr> {
+        // parse amqp header, the server's stream starts with one only to reject the version
+        let header_expected = match self.role {
+            Role::Server => !self.started,
+            Role::Client => !self.started && src.first() == Some(&b'A')
+        };
+        if header_expected {
             match ProtocolHeader::decode(src) {
                 Ok((_, header)) => {
                     let _ = src.split_to(PROTOCOL_HEADER_SIZE);
-                    return Ok(Some(DecodedFrame::ProtocolHeader(header)))
+                    self.started = true;
+                    return match self.role {
+                        Role::Server => Ok(Some(DecodedFrame::ProtocolHeader(header))),
+                        Role::Client => Ok(Some(DecodedFrame::VersionMismatch(header)))
+                    };
                 },
                 Err(e) => {
                     match e {
                         FrameDecodeErr::Incomplete => return Ok(None),
-                        _ => return Err(FrameDecodeErr::DecodeError(format!("codec decode ProtocolHeader failed -> {}", e)))
+                        _ => return Err(e)
                     }
                 }
             }
         }
 
         // +-frame type: u8-+---channel id: u16---+-----length: u32-----+----payload---+--frame end--+
-        // |   1|2|3|4      |       0x0000        |     payload length  |              |  0xce       |
+        // |   1|2|3|8      |       0x0000        |     payload length  |              |  0xce       |
         // +----------------+---------------------+---------------------+--------------+-------------+
+        if self.frame_max != 0 && src.len() >= FRAME_HEADER_SIZE {
+            let length = u32::from_be_bytes(src[3..FRAME_HEADER_SIZE].try_into().unwrap());
+            let size = u64::from(length) + 8;
+            if size > u64::from(self.frame_max) {
+                return Err(FrameDecodeErr::new(AmqpErrorKind::FrameError,
+                    format!("frame size {} is larger than frame_max {}", size, self.frame_max)));
+            }
+        }
         match Frame::decode(&src[..]) {
             Ok((_, frame)) => {
                 let _ = src.split_to(frame.decoded_frame_len());
+                self.started = true;
                 Ok(Some(AmqpFrame(frame)))
             }
             Err(e) => {
                 match e {
                     FrameDecodeErr::Incomplete => Ok(None),
-                    _ => return Err(FrameDecodeErr::DecodeError(format!("codec decode Frame failed -> {}", e)))
+                    _ => Err(e)
                 }
             }
         }
     }
+
+    /// Encode a frame to the end of `dst`, a frame larger than `frame_max` or with a value which does not fit
+    /// its wire format is an error and leaves `dst` as it was
+    pub fn encode_frame(&mut self, frame: &Frame, dst: &mut BytesMut) -> Result<(), EncodeError> {
+        if self.frame_max != 0 {
+            let size = frame.encoded_len();
+            if size > self.frame_max as usize {
+                return Err(EncodeError::FrameTooLarge { size, frame_max: self.frame_max });
+            }
+        }
+        frame.try_encode(dst)
+    }
+}
+
+#[cfg(feature = "tokio-codec")]
+impl Decoder for FrameCodec {
+    type Item = DecodedFrame;
+    type Error = FrameDecodeErr;
+
+    #[inline]
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.decode_frame(src)
+    }
+}
+
+#[cfg(feature = "tokio-codec")]
+impl Encoder<Frame> for FrameCodec {
+    type Error = EncodeError;
+
+    #[inline]
+    fn encode(&mut self, frame: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode_frame(&frame, dst)
+    }
 }
@@ -0,0 +1,136 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use bytes::{Buf, BytesMut};
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::{Sink, Stream};
+use crate::error::{FrameDecodeErr, EncodeError};
+use crate::frame::base::Frame;
+use crate::frame::frame_codec::{DecodedFrame, FrameCodec};
+
+const READ_SIZE: usize = 8 * 1024;
+// frames are written out before more are accepted once this many bytes wait
+const BACKPRESSURE_BOUNDARY: usize = 8 * 1024;
+
+/// Frames over a `futures::io` stream, for runtimes other than tokio such as async-std and smol
+///
+/// A `Stream` of decoded frames and a `Sink` of frames like `tokio_util::codec::Framed` with a `FrameCodec`.
+pub struct FuturesFramed<T> {
+    io: T,
+    codec: FrameCodec,
+    read_buffer: BytesMut,
+    write_buffer: BytesMut,
+    eof: bool,
+}
+
+impl<T> FuturesFramed<T> {
+    pub fn new(io: T, codec: FrameCodec) -> Self {
+        FuturesFramed {
+            io,
+            codec,
+            read_buffer: BytesMut::with_capacity(READ_SIZE),
+            write_buffer: BytesMut::new(),
+            eof: false,
+        }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    #[inline]
+    pub fn codec_mut(&mut self) -> &mut FrameCodec {
+        &mut self.codec
+    }
+
+    /// The buffered bytes are lost
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+}
+
+impl<T: AsyncRead + Unpin> Stream for FuturesFramed<T> {
+    type Item = Result<DecodedFrame, FrameDecodeErr>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.codec.decode_frame(&mut this.read_buffer) {
+                Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                Ok(None) => {}
+                Err(e) => return Poll::Ready(Some(Err(e)))
+            }
+            if this.eof {
+                if this.read_buffer.is_empty() {
+                    return Poll::Ready(None);
+                }
+                this.read_buffer.clear();
+                let e = io::Error::new(io::ErrorKind::UnexpectedEof, "stream ends inside a frame");
+                return Poll::Ready(Some(Err(e.into())));
+            }
+            let len = this.read_buffer.len();
+            this.read_buffer.resize(len + READ_SIZE, 0);
+            let read = Pin::new(&mut this.io).poll_read(cx, &mut this.read_buffer[len..]);
+            let read = match read {
+                Poll::Ready(Ok(read)) => read,
+                Poll::Ready(Err(e)) => {
+                    this.read_buffer.truncate(len);
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+                Poll::Pending => {
+                    this.read_buffer.truncate(len);
+                    return Poll::Pending;
+                }
+            };
+            this.read_buffer.truncate(len + read);
+            this.eof = read == 0;
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> Sink<Frame> for FuturesFramed<T> {
+    type Error = EncodeError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.write_buffer.len() >= BACKPRESSURE_BOUNDARY {
+            return self.poll_write_buffer(cx).map_err(EncodeError::from);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, frame: Frame) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        this.codec.encode_frame(&frame, &mut this.write_buffer)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        futures::ready!(self.as_mut().poll_write_buffer(cx))?;
+        Pin::new(&mut self.get_mut().io).poll_flush(cx).map_err(EncodeError::from)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        futures::ready!(self.as_mut().poll_write_buffer(cx))?;
+        Pin::new(&mut self.get_mut().io).poll_close(cx).map_err(EncodeError::from)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> FuturesFramed<T> {
+    fn poll_write_buffer(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        while !this.write_buffer.is_empty() {
+            let written = futures::ready!(Pin::new(&mut this.io).poll_write(cx, &this.write_buffer))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write frame")));
+            }
+            this.write_buffer.advance(written);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
@@ -3,25 +3,30 @@ use property::Property;
 use crate::frame::base::{Encode, Property, Decode};
 use crate::error::FrameDecodeErr;
 
-#[derive(Property, Default)]
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
 #[property(get(public), set(public))]
 pub struct AccessProperties {
-    flags: u32,
+    flags: u16,
 }
 
 impl Encode for AccessProperties {
     #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u32(self.flags);
+        buffer.put_u16(self.flags);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2
     }
 }
 
 impl Decode<Property> for AccessProperties {
     #[inline]
     fn decode(buffer: &[u8]) -> Result<(&[u8], Property), FrameDecodeErr>{
-        let (buffer, flags) = match u32::decode(buffer) {
+        let (buffer, flags) = match u16::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode AccessProperties flags -> {}", e)))
+            Err(e) => return Err(e.field(buffer, "AccessProperties.flags"))
         };
         Ok((buffer, Property::Access(AccessProperties { flags })))
     }
@@ -1,13 +1,14 @@
+use std::hash::{Hash, Hasher};
 use property::Property;
 use crate::{ShortStr, FieldTable, Timestamp};
-use crate::frame::base::{Encode, Property, Decode};
+use crate::frame::base::{Encode, Property, Decode, hash_field_table};
 use bytes::{BytesMut, BufMut};
-use crate::error::FrameDecodeErr;
+use crate::error::{FrameDecodeErr, EncodeError};
 
-#[derive(Property, Default)]
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
 #[property(get(public), set(disable))]
 pub struct BasicProperties {
-    flags: u32,
+    flags: u16,
     content_type: ShortStr,
     content_encoding: ShortStr,
     headers: FieldTable,
@@ -24,6 +25,26 @@ pub struct BasicProperties {
     cluster_id: ShortStr
 }
 
+impl Hash for BasicProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.flags.hash(state);
+        self.content_type.hash(state);
+        self.content_encoding.hash(state);
+        hash_field_table(&self.headers, state);
+        self.delivery_mode.hash(state);
+        self.priority.hash(state);
+        self.correlation_id.hash(state);
+        self.reply_to.hash(state);
+        self.expiration.hash(state);
+        self.message_id.hash(state);
+        self.timestamp.hash(state);
+        self.basic_type.hash(state);
+        self.user_id.hash(state);
+        self.app_id.hash(state);
+        self.cluster_id.hash(state);
+    }
+}
+
 impl BasicProperties {
     #[inline]
     pub fn set_content_type(&mut self, content_type: ShortStr) {
@@ -112,7 +133,7 @@ impl BasicProperties {
 
 impl Encode for BasicProperties {
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u32(self.flags);
+        buffer.put_u16(self.flags);
         if self.flags & BasicProperties::CONTENT_TYPE_FLAG != 0 {
             self.content_type.encode(buffer);
         }
@@ -169,19 +190,99 @@ impl Encode for BasicProperties {
             self.cluster_id.encode(buffer);
         }
     }
+
+    fn encoded_len(&self) -> usize {
+        let fields = [
+            (BasicProperties::CONTENT_TYPE_FLAG, self.content_type.encoded_len()),
+            (BasicProperties::CONTENT_ENCODING_FLAG, self.content_encoding.encoded_len()),
+            (BasicProperties::HEADERS_FLAG, self.headers.encoded_len()),
+            (BasicProperties::DELIVERY_FLAG, 1),
+            (BasicProperties::PRIORITY_FLAG, 1),
+            (BasicProperties::CORRELATION_ID_FLAG, self.correlation_id.encoded_len()),
+            (BasicProperties::REPLY_TO_FLAG, self.reply_to.encoded_len()),
+            (BasicProperties::EXPIRATION_FLAG, self.expiration.encoded_len()),
+            (BasicProperties::MESSAGE_ID_FLAG, self.message_id.encoded_len()),
+            (BasicProperties::TIMESTAMP_FLAG, 8),
+            (BasicProperties::BASIC_TYPE_FLAG, self.basic_type.encoded_len()),
+            (BasicProperties::USER_ID_FLAG, self.user_id.encoded_len()),
+            (BasicProperties::APP_ID_FLAG, self.app_id.encoded_len()),
+            (BasicProperties::CLUSTER_ID_FLAG, self.cluster_id.encoded_len()),
+        ];
+        fields.iter().filter(|(flag, _)| self.flags & flag != 0).fold(2, |len, (_, field_len)| len + field_len)
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.flags);
+        if self.flags & BasicProperties::CONTENT_TYPE_FLAG != 0 {
+            self.content_type.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::CONTENT_ENCODING_FLAG != 0 {
+            self.content_encoding.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::HEADERS_FLAG != 0 {
+            self.headers.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::DELIVERY_FLAG != 0 {
+            buffer.put_u8(self.delivery_mode);
+        }
+
+        if self.flags & BasicProperties::PRIORITY_FLAG != 0 {
+            buffer.put_u8(self.priority);
+        }
+
+        if self.flags & BasicProperties::CORRELATION_ID_FLAG != 0 {
+            self.correlation_id.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::REPLY_TO_FLAG != 0 {
+            self.reply_to.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::EXPIRATION_FLAG != 0 {
+            self.expiration.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::MESSAGE_ID_FLAG != 0 {
+            self.message_id.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::TIMESTAMP_FLAG != 0 {
+            buffer.put_u64(self.timestamp);
+        }
+
+        if self.flags & BasicProperties::BASIC_TYPE_FLAG != 0 {
+            self.basic_type.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::USER_ID_FLAG != 0 {
+            self.user_id.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::APP_ID_FLAG != 0 {
+            self.app_id.try_encode(buffer)?;
+        }
+
+        if self.flags & BasicProperties::CLUSTER_ID_FLAG != 0 {
+            self.cluster_id.try_encode(buffer)?;
+        }
+        Ok(())
+    }
 }
 
 impl Decode<Property> for BasicProperties {
     fn decode(buffer: &[u8]) -> Result<(&[u8], Property), FrameDecodeErr>{
-        let (buffer, flags) = match u32::decode(buffer) {
+        let (buffer, flags) = match u16::decode(buffer) {
             Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties flags -> {}", e))),
+            Err(e) => return Err(e.field(buffer, "BasicProperties.flags")),
         };
         let mut properties = BasicProperties::default();
         let buffer = if flags & BasicProperties::CONTENT_TYPE_FLAG != 0 {
             let (buffer, content_type) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties content type -> {}", e))),
+                Err(e) => return Err(e.field(buffer, "BasicProperties.content_type")),
             };
             properties.set_content_type(content_type);
             buffer
@@ -190,7 +291,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::CONTENT_ENCODING_FLAG != 0 {
             let (buffer, content_encoding) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties content-encoding -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.content_encoding"))
             };
             properties.set_content_encoding(content_encoding);
             buffer
@@ -199,7 +300,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::HEADERS_FLAG != 0 {
             let (buffer, headers) = match FieldTable::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties headers -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.headers"))
             };
             properties.set_headers(headers);
             buffer
@@ -208,7 +309,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::DELIVERY_FLAG != 0 {
             let (buffer, delivery_mode) = match u8::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties delivery mode -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.delivery_mode"))
             };
             properties.set_delivery_mode(delivery_mode);
             buffer
@@ -217,7 +318,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::PRIORITY_FLAG != 0 {
             let (buffer, priority) = match u8::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicPropertiespriority -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.priority"))
             };
             properties.set_priority(priority);
             buffer
@@ -226,7 +327,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::CORRELATION_ID_FLAG != 0 {
             let (buffer, correlation_id) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties correlation id -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.correlation_id"))
             };
             properties.set_correlation_id(correlation_id);
             buffer
@@ -235,7 +336,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::REPLY_TO_FLAG != 0 {
             let (buffer, reply_to) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties reply_to -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.reply_to"))
             };
             properties.set_reply_to(reply_to);
             buffer
@@ -244,7 +345,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::EXPIRATION_FLAG != 0 {
             let (buffer, expiration) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties expiration -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.expiration"))
             };
             properties.set_expiration(expiration);
             buffer
@@ -253,7 +354,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::MESSAGE_ID_FLAG != 0 {
             let (buffer, message_id) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties message_id -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.message_id"))
             };
             properties.set_message_id(message_id);
             buffer
@@ -262,7 +363,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::TIMESTAMP_FLAG != 0 {
             let (buffer, timestamp) = match u64::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties timestamp -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.timestamp"))
             };
             properties.set_timestamp(timestamp);
             buffer
@@ -271,7 +372,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::BASIC_TYPE_FLAG != 0 {
             let (buffer, basic_type) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties basic_type -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.basic_type"))
             };
             properties.set_basic_type(basic_type);
             buffer
@@ -280,7 +381,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::USER_ID_FLAG != 0 {
             let (buffer, user_id) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties user_id -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.user_id"))
             };
             properties.set_user_id(user_id);
             buffer
@@ -289,7 +390,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::APP_ID_FLAG != 0 {
             let (buffer, app_id) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties app_id -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.app_id"))
             };
             properties.set_app_id(app_id);
             buffer
@@ -298,7 +399,7 @@ impl Decode<Property> for BasicProperties {
         let buffer = if flags & BasicProperties::CLUSTER_ID_FLAG != 0 {
             let (buffer, cluster_id) = match ShortStr::decode(buffer) {
                 Ok(ret) => ret,
-                Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicProperties cluster_id -> {}", e)))
+                Err(e) => return Err(e.field(buffer, "BasicProperties.cluster_id"))
             };
             properties.set_cluster_id(cluster_id);
             buffer
@@ -308,18 +409,18 @@ impl Decode<Property> for BasicProperties {
 }
 
 impl BasicProperties {
-    const CONTENT_TYPE_FLAG: u32 = 1 << 15;
-    const CONTENT_ENCODING_FLAG: u32 = 1 << 14;
-    const HEADERS_FLAG: u32 = 1 << 13;
-    const DELIVERY_FLAG: u32 = 1 << 12;
-    const PRIORITY_FLAG: u32 = 1 << 11;
-    const CORRELATION_ID_FLAG: u32 = 1 << 10;
-    const REPLY_TO_FLAG: u32 = 1 << 9;
-    const EXPIRATION_FLAG: u32 = 1 << 8;
-    const MESSAGE_ID_FLAG: u32 = 1 << 7;
-    const TIMESTAMP_FLAG: u32 = 1 << 6;
-    const BASIC_TYPE_FLAG: u32 = 1 << 5;
-    const USER_ID_FLAG: u32 = 1 << 4;
-    const APP_ID_FLAG: u32 = 1 << 3;
-    const CLUSTER_ID_FLAG: u32 = 1 << 2;
+    pub const CONTENT_TYPE_FLAG: u16 = 1 << 15;
+    pub const CONTENT_ENCODING_FLAG: u16 = 1 << 14;
+    pub const HEADERS_FLAG: u16 = 1 << 13;
+    pub const DELIVERY_FLAG: u16 = 1 << 12;
+    pub const PRIORITY_FLAG: u16 = 1 << 11;
+    pub const CORRELATION_ID_FLAG: u16 = 1 << 10;
+    pub const REPLY_TO_FLAG: u16 = 1 << 9;
+    pub const EXPIRATION_FLAG: u16 = 1 << 8;
+    pub const MESSAGE_ID_FLAG: u16 = 1 << 7;
+    pub const TIMESTAMP_FLAG: u16 = 1 << 6;
+    pub const BASIC_TYPE_FLAG: u16 = 1 << 5;
+    pub const USER_ID_FLAG: u16 = 1 << 4;
+    pub const APP_ID_FLAG: u16 = 1 << 3;
+    pub const CLUSTER_ID_FLAG: u16 = 1 << 2;
 }
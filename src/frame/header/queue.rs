@@ -3,24 +3,26 @@ use crate::frame::base::{Encode, Decode, Property};
 use bytes::{BytesMut, BufMut};
 use crate::error::FrameDecodeErr;
 
-#[derive(Property, Default)]
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
 #[property(get(public), set(public))]
 pub struct QueueProperties {
-    flags: u32,
+    flags: u16,
 }
 
 impl Encode for QueueProperties {
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u32(self.flags);
+        buffer.put_u16(self.flags);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2
     }
 }
 
 impl Decode<Property> for QueueProperties {
     fn decode(buffer: &[u8]) -> Result<(&[u8], Property), FrameDecodeErr>{
-        let (buffer, flags) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(e)
-        };
+        let (buffer, flags) = u16::decode(buffer)?;
         Ok((buffer, Property::Queue(QueueProperties { flags })))
     }
 }
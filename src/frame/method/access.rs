@@ -1,10 +1,13 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
 use property::Property;
-use bytes::{BytesMut, BufMut};
-use crate::frame::base::{ShortStr, Encode, Arguments, Decode};
-use crate::error::FrameDecodeErr;
+use bytes::{BufMut, BytesMut};
+use crate::error::{EncodeError, FrameDecodeErr};
+use crate::frame::base::{Arguments, Decode, Encode, ShortStr};
 
-// Accesss is deprecated in amqp0-9-1, this is just for compatibility
-#[derive(Property, Default)]
+/// Request an access ticket, deprecated in 0-9-1 but still answered by brokers
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct AccessRequest {
     realm: ShortStr,
@@ -16,49 +19,70 @@ pub struct AccessRequest {
 }
 
 impl Encode for AccessRequest {
-    #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
         self.realm.encode(buffer);
-        // just fill 0
-        buffer.put_u8(0);
+        let mut flags = 0u8;
+        flags |= self.exclusive as u8;
+        flags |= (self.passive as u8) << 1;
+        flags |= (self.active as u8) << 2;
+        flags |= (self.write as u8) << 3;
+        flags |= (self.read as u8) << 4;
+        buffer.put_u8(flags);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.realm.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.realm.try_encode(buffer)?;
+        let mut flags = 0u8;
+        flags |= self.exclusive as u8;
+        flags |= (self.passive as u8) << 1;
+        flags |= (self.active as u8) << 2;
+        flags |= (self.write as u8) << 3;
+        flags |= (self.read as u8) << 4;
+        buffer.put_u8(flags);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for AccessRequest {
-    #[inline]
     fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
-        let (buffer, realm) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode AccessRequest realm -> {}", e)))
-        };
-        let (_, _) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode AccessRequest flags -> {}", e)))
-        };
-        Ok((buffer, Arguments::AccessRequest(AccessRequest { realm, exclusive: false, passive: false, active: false, write: false, read: false })))
+        let (buffer, realm) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "AccessRequest.realm"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "AccessRequest.flags"))?;
+        let exclusive = flags & 1 != 0;
+        let passive = flags & (1 << 1) != 0;
+        let active = flags & (1 << 2) != 0;
+        let write = flags & (1 << 3) != 0;
+        let read = flags & (1 << 4) != 0;
+        Ok((buffer, Arguments::AccessRequest(AccessRequest { realm, exclusive, passive, active, write, read })))
     }
 }
 
-#[derive(Property, Default)]
+/// Grant an access ticket
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct AccessRequestOk {
     ticket: u16
 }
 
 impl Encode for AccessRequestOk {
-    #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2
+    }
 }
 
 impl Decode<Arguments> for AccessRequestOk {
-    #[inline]
     fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
-        let (_, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode AccessRequestOk ticket -> {}", e)))
-        };
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "AccessRequestOk.ticket"))?;
         Ok((buffer, Arguments::AccessRequestOk(AccessRequestOk { ticket })))
     }
 }
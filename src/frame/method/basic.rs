@@ -1,10 +1,14 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
+use std::hash::{Hash, Hasher};
 use property::Property;
-use bytes::{BytesMut, BufMut};
-use crate::{ShortStr, FieldTable};
-use crate::frame::base::{Encode, Arguments, Decode};
-use crate::error::FrameDecodeErr;
+use bytes::{BufMut, BytesMut};
+use crate::error::{EncodeError, FrameDecodeErr};
+use crate::frame::base::{Arguments, Decode, Encode, ShortStr, FieldTable, hash_field_table};
 
-#[derive(Property, Default)]
+/// Specify quality of service
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicQos {
     prefetch_size: u32,
@@ -13,52 +17,53 @@ pub struct BasicQos {
 }
 
 impl Encode for BasicQos {
-    #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u32(self.prefetch_size);
         buffer.put_u16(self.prefetch_count);
-        buffer.put_u8(if self.global { 1 } else { 0 });
+        buffer.put_u8(self.global as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        7
     }
 }
 
 impl Decode<Arguments> for BasicQos {
     fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
-        let (buffer, prefetch_size) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicQos prefetch_size -> {}", e)))
-        };
-        let (buffer, prefetch_count) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicQos prefetch_count -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicQos flags -> {}", e)))
-        };
-        let global = if 0 != (flags & (1 << 0)) {
-            true
-        } else {
-            false
-        };
-        Ok((buffer, Arguments::BasicQos(BasicQos { prefetch_size, prefetch_count, global})))
+        let (buffer, prefetch_size) = u32::decode(buffer).map_err(|e| e.field(buffer, "BasicQos.prefetch_size"))?;
+        let (buffer, prefetch_count) = u16::decode(buffer).map_err(|e| e.field(buffer, "BasicQos.prefetch_count"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicQos.flags"))?;
+        let global = flags & 1 != 0;
+        Ok((buffer, Arguments::BasicQos(BasicQos { prefetch_size, prefetch_count, global })))
     }
 }
 
+/// Confirm the requested qos
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct BasicQosOk;
 
 impl Encode for BasicQosOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for BasicQosOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    #[inline]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::BasicQosOk(BasicQosOk)))
     }
 }
 
-#[derive(Property, Default)]
+/// Start a queue consumer
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicConsume {
     ticket: u16,
@@ -68,55 +73,75 @@ pub struct BasicConsume {
     no_ack: bool,
     exclusive: bool,
     no_wait: bool,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     args: FieldTable
 }
 
+impl Hash for BasicConsume {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticket.hash(state);
+        self.queue_name.hash(state);
+        self.consumer_tag.hash(state);
+        self.no_local.hash(state);
+        self.no_ack.hash(state);
+        self.exclusive.hash(state);
+        self.no_wait.hash(state);
+        hash_field_table(&self.args, state);
+    }
+}
+
 impl Encode for BasicConsume {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.queue_name.encode(buffer);
         self.consumer_tag.encode(buffer);
-        let mut flag = 0u8;
-        flag |= if self.no_local { 1 } else { 0 };
-        flag |= if self.no_ack { 1 << 1 } else { 0 };
-        flag |= if self.exclusive { 1 << 2 } else { 0 };
-        flag |= if self.no_wait { 1 << 3 } else { 0 };
-        buffer.put_u8(flag);
+        let mut flags = 0u8;
+        flags |= self.no_local as u8;
+        flags |= (self.no_ack as u8) << 1;
+        flags |= (self.exclusive as u8) << 2;
+        flags |= (self.no_wait as u8) << 3;
+        buffer.put_u8(flags);
         self.args.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.queue_name.encoded_len() + self.consumer_tag.encoded_len() + 1 + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.queue_name.try_encode(buffer)?;
+        self.consumer_tag.try_encode(buffer)?;
+        let mut flags = 0u8;
+        flags |= self.no_local as u8;
+        flags |= (self.no_ack as u8) << 1;
+        flags |= (self.exclusive as u8) << 2;
+        flags |= (self.no_wait as u8) << 3;
+        buffer.put_u8(flags);
+        self.args.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for BasicConsume {
     fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicConsume ticket -> {}", e)))
-        };
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicConsume queue_name -> {}", e)))
-        };
-        let (buffer, consumer_tag) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicConsume consumer_tag -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicConsume flags -> {}", e)))
-        };
-        let (buffer, args) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicDeliver consumer_tag -> {}", e)))
-        };
-        let no_local = if flags & (1 << 0) != 0 { true } else { false };
-        let no_ack = if flags & (1 << 1) != 0 { true } else { false };
-        let exclusive = if flags & (1 << 2) != 0 { true } else { false };
-        let no_wait = if flags & (1 << 3) != 0 { true } else { false };
-        Ok((buffer, Arguments::BasicConsume(BasicConsume { ticket, queue_name, consumer_tag, no_local, no_ack, exclusive, no_wait, args})))
-    }
-}
-
-#[derive(Property, Default)]
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "BasicConsume.ticket"))?;
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicConsume.queue_name"))?;
+        let (buffer, consumer_tag) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicConsume.consumer_tag"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicConsume.flags"))?;
+        let no_local = flags & 1 != 0;
+        let no_ack = flags & (1 << 1) != 0;
+        let exclusive = flags & (1 << 2) != 0;
+        let no_wait = flags & (1 << 3) != 0;
+        let (buffer, args) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "BasicConsume.args"))?;
+        Ok((buffer, Arguments::BasicConsume(BasicConsume { ticket, queue_name, consumer_tag, no_local, no_ack, exclusive, no_wait, args })))
+    }
+}
+
+/// Confirm a new consumer
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicConsumeOk {
     consumer_tag: ShortStr
@@ -126,19 +151,28 @@ impl Encode for BasicConsumeOk {
     fn encode(&self, buffer: &mut BytesMut) {
         self.consumer_tag.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.consumer_tag.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.consumer_tag.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for BasicConsumeOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, consumer_tag) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicConsumeOk consumer_tag -> {}", e)))
-        };
-        Ok((buffer, Arguments::BasicConsumeOk(BasicConsumeOk { consumer_tag})))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, consumer_tag) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicConsumeOk.consumer_tag"))?;
+        Ok((buffer, Arguments::BasicConsumeOk(BasicConsumeOk { consumer_tag })))
     }
 }
 
-#[derive(Property, Default)]
+/// End a queue consumer
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicCancel {
     consumer_tag: ShortStr,
@@ -148,26 +182,33 @@ pub struct BasicCancel {
 impl Encode for BasicCancel {
     fn encode(&self, buffer: &mut BytesMut) {
         self.consumer_tag.encode(buffer);
-        buffer.put_u8(if self.no_wait { 1 } else { 0 });
+        buffer.put_u8(self.no_wait as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.consumer_tag.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.consumer_tag.try_encode(buffer)?;
+        buffer.put_u8(self.no_wait as u8);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for BasicCancel {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, consumer_tag) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicCancel consumer_tag -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicCancel flags -> {}", e)))
-        };
-        let no_wait = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, consumer_tag) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicCancel.consumer_tag"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicCancel.flags"))?;
+        let no_wait = flags & 1 != 0;
         Ok((buffer, Arguments::BasicCancel(BasicCancel { consumer_tag, no_wait })))
     }
 }
 
-#[derive(Property, Default)]
+/// Confirm a cancelled consumer
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicCancelOk {
     consumer_tag: ShortStr
@@ -177,19 +218,28 @@ impl Encode for BasicCancelOk {
     fn encode(&self, buffer: &mut BytesMut) {
         self.consumer_tag.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.consumer_tag.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.consumer_tag.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for BasicCancelOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, consumer_tag) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicCancelOk consumer_tag -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, consumer_tag) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicCancelOk.consumer_tag"))?;
         Ok((buffer, Arguments::BasicCancelOk(BasicCancelOk { consumer_tag })))
     }
 }
 
-#[derive(Property, Default)]
+/// Publish a message
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicPublish {
     ticket: u16,
@@ -204,38 +254,44 @@ impl Encode for BasicPublish {
         buffer.put_u16(self.ticket);
         self.exchange_name.encode(buffer);
         self.routing_key.encode(buffer);
-        let mut flag = 0u8;
-        flag |= if self.mandatory { 1 } else { 0 };
-        flag |= if self.immediate { 1 << 1 } else { 0 };
-        buffer.put_u8(flag);
+        let mut flags = 0u8;
+        flags |= self.mandatory as u8;
+        flags |= (self.immediate as u8) << 1;
+        buffer.put_u8(flags);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.exchange_name.encoded_len() + self.routing_key.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.exchange_name.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        let mut flags = 0u8;
+        flags |= self.mandatory as u8;
+        flags |= (self.immediate as u8) << 1;
+        buffer.put_u8(flags);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for BasicPublish {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicPublish ticket -> {}", e)))
-        };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicPublish exchange_name -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicPublish routing_key -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicPublish flags -> {}", e)))
-        };
-        let mandatory = if flags & (1 << 0) != 0 { true } else { false };
-        let immediate = if flags & (1 << 1) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "BasicPublish.ticket"))?;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicPublish.exchange_name"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicPublish.routing_key"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicPublish.flags"))?;
+        let mandatory = flags & 1 != 0;
+        let immediate = flags & (1 << 1) != 0;
         Ok((buffer, Arguments::BasicPublish(BasicPublish { ticket, exchange_name, routing_key, mandatory, immediate })))
     }
 }
 
-#[derive(Property, Default)]
+/// Return a failed message
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicReturn {
     reply_code: u16,
@@ -251,31 +307,34 @@ impl Encode for BasicReturn {
         self.exchange_name.encode(buffer);
         self.routing_key.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.reply_text.encoded_len() + self.exchange_name.encoded_len() + self.routing_key.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.reply_code);
+        self.reply_text.try_encode(buffer)?;
+        self.exchange_name.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for BasicReturn {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, reply_code) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicReturn reply_code -> {}", e)))
-        };
-        let (buffer, reply_text) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicReturn reply_text -> {}", e)))
-        };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicReturn exchange_name -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicReturn routing_key -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, reply_code) = u16::decode(buffer).map_err(|e| e.field(buffer, "BasicReturn.reply_code"))?;
+        let (buffer, reply_text) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicReturn.reply_text"))?;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicReturn.exchange_name"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicReturn.routing_key"))?;
         Ok((buffer, Arguments::BasicReturn(BasicReturn { reply_code, reply_text, exchange_name, routing_key })))
     }
 }
 
-#[derive(Property, Default)]
+/// Notify the client of a consumer message
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicDeliver {
     consumer_tag: ShortStr,
@@ -289,40 +348,41 @@ impl Encode for BasicDeliver {
     fn encode(&self, buffer: &mut BytesMut) {
         self.consumer_tag.encode(buffer);
         buffer.put_u64(self.delivery_tag);
-        buffer.put_u8(if self.redelivered { 1 } else { 0 });
+        buffer.put_u8(self.redelivered as u8);
         self.exchange_name.encode(buffer);
         self.routing_key.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.consumer_tag.encoded_len() + 9 + self.exchange_name.encoded_len() + self.routing_key.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.consumer_tag.try_encode(buffer)?;
+        buffer.put_u64(self.delivery_tag);
+        buffer.put_u8(self.redelivered as u8);
+        self.exchange_name.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for BasicDeliver {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, consumer_tag) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicDeliver consumer_tag -> {}", e)))
-        };
-        let (buffer, delivery_tag) = match u64::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicDeliver delivery_tag -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicDeliver flags -> {}", e)))
-        };
-        let redelivered = if flags & (1 << 0) != 0 { true } else { false };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicDeliver exchange_name -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicDeliver routing_key -> {}", e)))
-        };
-        Ok((buffer, Arguments::BasicDeliver(BasicDeliver { consumer_tag, delivery_tag, redelivered, exchange_name, routing_key})))
-    }
-}
-
-#[derive(Property, Default)]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, consumer_tag) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicDeliver.consumer_tag"))?;
+        let (buffer, delivery_tag) = u64::decode(buffer).map_err(|e| e.field(buffer, "BasicDeliver.delivery_tag"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicDeliver.flags"))?;
+        let redelivered = flags & 1 != 0;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicDeliver.exchange_name"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicDeliver.routing_key"))?;
+        Ok((buffer, Arguments::BasicDeliver(BasicDeliver { consumer_tag, delivery_tag, redelivered, exchange_name, routing_key })))
+    }
+}
+
+/// Direct access to a queue
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicGet {
     ticket: u16,
@@ -334,30 +394,35 @@ impl Encode for BasicGet {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.queue_name.encode(buffer);
-        buffer.put_u8(if self.no_ack { 1 } else { 0 });
+        buffer.put_u8(self.no_ack as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.queue_name.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.queue_name.try_encode(buffer)?;
+        buffer.put_u8(self.no_ack as u8);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for BasicGet {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGet ticket -> {}", e)))
-        };
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGet queue_name -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGet flags -> {}", e)))
-        };
-        let no_ack = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "BasicGet.ticket"))?;
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicGet.queue_name"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicGet.flags"))?;
+        let no_ack = flags & 1 != 0;
         Ok((buffer, Arguments::BasicGet(BasicGet { ticket, queue_name, no_ack })))
     }
 }
 
-#[derive(Property, Default)]
+/// Provide client with a message
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicGetOk {
     delivery_tag: u64,
@@ -370,41 +435,42 @@ pub struct BasicGetOk {
 impl Encode for BasicGetOk {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u64(self.delivery_tag);
-        buffer.put_u8(if self.redelivered { 1 } else { 0 });
+        buffer.put_u8(self.redelivered as u8);
         self.exchange_name.encode(buffer);
         self.routing_key.encode(buffer);
         buffer.put_u32(self.message_count);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        9 + self.exchange_name.encoded_len() + self.routing_key.encoded_len() + 4
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u64(self.delivery_tag);
+        buffer.put_u8(self.redelivered as u8);
+        self.exchange_name.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        buffer.put_u32(self.message_count);
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for BasicGetOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, delivery_tag) = match u64::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGetOk delivery_tag -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGetOk flags -> {}", e)))
-        };
-        let redelivered = if flags & (1 << 0) != 0 { true } else { false };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGetOk exchange_name -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGetOk routing_key -> {}", e)))
-        };
-        let (buffer, message_count) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGetOk message_count -> {}", e)))
-        };
-        Ok((buffer, Arguments::BasicGetOk(BasicGetOk {delivery_tag, redelivered, exchange_name, routing_key, message_count})))
-    }
-}
-
-#[derive(Property, Default)]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, delivery_tag) = u64::decode(buffer).map_err(|e| e.field(buffer, "BasicGetOk.delivery_tag"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicGetOk.flags"))?;
+        let redelivered = flags & 1 != 0;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicGetOk.exchange_name"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicGetOk.routing_key"))?;
+        let (buffer, message_count) = u32::decode(buffer).map_err(|e| e.field(buffer, "BasicGetOk.message_count"))?;
+        Ok((buffer, Arguments::BasicGetOk(BasicGetOk { delivery_tag, redelivered, exchange_name, routing_key, message_count })))
+    }
+}
+
+/// Indicate no messages available
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicGetEmpty {
     cluster_id: ShortStr
@@ -414,19 +480,28 @@ impl Encode for BasicGetEmpty {
     fn encode(&self, buffer: &mut BytesMut) {
         self.cluster_id.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.cluster_id.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.cluster_id.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for BasicGetEmpty {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, cluster_id) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicGetEmpty cluster_id -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, cluster_id) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "BasicGetEmpty.cluster_id"))?;
         Ok((buffer, Arguments::BasicGetEmpty(BasicGetEmpty { cluster_id })))
     }
 }
 
-#[derive(Property, Default)]
+/// Acknowledge one or more messages
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicAck {
     delivery_tag: u64,
@@ -436,26 +511,27 @@ pub struct BasicAck {
 impl Encode for BasicAck {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u64(self.delivery_tag);
-        buffer.put_u8(if self.multiple { 1 } else { 0 });
+        buffer.put_u8(self.multiple as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        9
     }
 }
 
 impl Decode<Arguments> for BasicAck {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, delivery_tag) = match u64::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicAck delivery_tag -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicAck flags -> {}", e)))
-        };
-        let multiple = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, delivery_tag) = u64::decode(buffer).map_err(|e| e.field(buffer, "BasicAck.delivery_tag"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicAck.flags"))?;
+        let multiple = flags & 1 != 0;
         Ok((buffer, Arguments::BasicAck(BasicAck { delivery_tag, multiple })))
     }
 }
 
-#[derive(Property, Default)]
+/// Reject an incoming message
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicReject {
     delivery_tag: u64,
@@ -465,26 +541,27 @@ pub struct BasicReject {
 impl Encode for BasicReject {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u64(self.delivery_tag);
-        buffer.put_u8(if self.requeue { 1 } else { 0 });
+        buffer.put_u8(self.requeue as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        9
     }
 }
 
 impl Decode<Arguments> for BasicReject {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, delivery_tag) = match u64::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicReject delivery_tag -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicReject flags -> {}", e)))
-        };
-        let requeue = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, delivery_tag) = u64::decode(buffer).map_err(|e| e.field(buffer, "BasicReject.delivery_tag"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicReject.flags"))?;
+        let requeue = flags & 1 != 0;
         Ok((buffer, Arguments::BasicReject(BasicReject { delivery_tag, requeue })))
     }
 }
 
-#[derive(Property, Default)]
+/// Redeliver unacknowledged messages
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicRecoverAsync {
     requeue: bool
@@ -492,22 +569,26 @@ pub struct BasicRecoverAsync {
 
 impl Encode for BasicRecoverAsync {
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u8(if self.requeue { 1 } else { 0 });
+        buffer.put_u8(self.requeue as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1
     }
 }
 
 impl Decode<Arguments> for BasicRecoverAsync {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicRecoverAsync flags -> {}", e)))
-        };
-        let requeue = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicRecoverAsync.flags"))?;
+        let requeue = flags & 1 != 0;
         Ok((buffer, Arguments::BasicRecoverAsync(BasicRecoverAsync { requeue })))
     }
 }
 
-#[derive(Property, Default)]
+/// Redeliver unacknowledged messages
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicRecover {
     requeue: bool
@@ -515,37 +596,48 @@ pub struct BasicRecover {
 
 impl Encode for BasicRecover {
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u8(if self.requeue { 1 } else { 0 });
+        buffer.put_u8(self.requeue as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1
     }
 }
 
 impl Decode<Arguments> for BasicRecover {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicRecover flags -> {}", e)))
-        };
-        let requeue = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicRecover.flags"))?;
+        let requeue = flags & 1 != 0;
         Ok((buffer, Arguments::BasicRecover(BasicRecover { requeue })))
     }
 }
 
+/// Confirm recovery
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct BasicRecoverOk;
 
 impl Encode for BasicRecoverOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for BasicRecoverOk {
     #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::BasicRecoverOk(BasicRecoverOk)))
     }
 }
 
-#[derive(Property, Default)]
+/// RabbitMQ extension, reject one or more incoming messages
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct BasicNack {
     delivery_tag: u64,
@@ -556,26 +648,24 @@ pub struct BasicNack {
 impl Encode for BasicNack {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u64(self.delivery_tag);
-        let mut flag = 0u8;
-        flag |= if self.multiple { 1 } else { 0 };
-        flag |= if self.requeue { 1 << 1 } else { 0 };
-        buffer.put_u8(flag);
+        let mut flags = 0u8;
+        flags |= self.multiple as u8;
+        flags |= (self.requeue as u8) << 1;
+        buffer.put_u8(flags);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        9
     }
 }
 
 impl Decode<Arguments> for BasicNack {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, delivery_tag) = match u64::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicNack delivery_tag -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode BasicNack flags -> {}", e)))
-        };
-        let multiple = if flags & (1 << 0) != 0 { true } else { false };
-        let requeue = if flags & (1 << 1) != 0 { true } else { false };
-        Ok((buffer, Arguments::BasicNack(BasicNack {delivery_tag, multiple, requeue })))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, delivery_tag) = u64::decode(buffer).map_err(|e| e.field(buffer, "BasicNack.delivery_tag"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "BasicNack.flags"))?;
+        let multiple = flags & 1 != 0;
+        let requeue = flags & (1 << 1) != 0;
+        Ok((buffer, Arguments::BasicNack(BasicNack { delivery_tag, multiple, requeue })))
     }
 }
-
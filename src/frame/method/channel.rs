@@ -1,35 +1,46 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
 use property::Property;
-use bytes::{BytesMut, BufMut};
-use crate::error::FrameDecodeErr;
-use crate::frame::base::{ShortStr, Encode, Arguments, Decode};
+use bytes::{BufMut, BytesMut};
 use crate::class::Class;
-use crate::LongStr;
-use crate::method::{Method, get_method_type, MethodId};
+use crate::error::{EncodeError, FrameDecodeErr};
+use crate::frame::base::{Arguments, Decode, Encode, ShortStr, LongStr};
+use crate::method::{get_method_type, Method, MethodId};
 
-#[derive(Property, Default)]
+/// Open a channel for use
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ChannelOpen {
     out_of_band: ShortStr
 }
 
 impl Encode for ChannelOpen {
-    #[inline]
     fn encode(&self, buffer: &mut BytesMut) {
         self.out_of_band.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.out_of_band.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.out_of_band.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ChannelOpen {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, out_of_band) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelOpen out_of_band -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, out_of_band) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ChannelOpen.out_of_band"))?;
         Ok((buffer, Arguments::ChannelOpen(ChannelOpen { out_of_band })))
     }
 }
 
-#[derive(Property, Default)]
+/// Signal that the channel is ready
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ChannelOpenOk {
     channel_id: LongStr
@@ -39,19 +50,28 @@ impl Encode for ChannelOpenOk {
     fn encode(&self, buffer: &mut BytesMut) {
         self.channel_id.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.channel_id.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.channel_id.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ChannelOpenOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, channel_id) = match LongStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelOpenOk channel_id -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, channel_id) = LongStr::decode(buffer).map_err(|e| e.field(buffer, "ChannelOpenOk.channel_id"))?;
         Ok((buffer, Arguments::ChannelOpenOk(ChannelOpenOk { channel_id })))
     }
 }
 
-#[derive(Property, Default)]
+/// Enable/disable flow from peer
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ChannelFlow {
     active: bool
@@ -59,22 +79,26 @@ pub struct ChannelFlow {
 
 impl Encode for ChannelFlow {
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u8(if self.active { 1 } else { 0})
+        buffer.put_u8(self.active as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1
     }
 }
 
 impl Decode<Arguments> for ChannelFlow {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelFlow flags -> {}", e)))
-        };
-        let active = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ChannelFlow.flags"))?;
+        let active = flags & 1 != 0;
         Ok((buffer, Arguments::ChannelFlow(ChannelFlow { active })))
     }
 }
 
-#[derive(Property, Default)]
+/// Confirm a flow method
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ChannelFlowOk {
     active: bool
@@ -82,80 +106,98 @@ pub struct ChannelFlowOk {
 
 impl Encode for ChannelFlowOk {
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u8(if self.active { 1 } else { 0})
+        buffer.put_u8(self.active as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1
     }
 }
 
 impl Decode<Arguments> for ChannelFlowOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelFlowOk flags -> {}", e)))
-        };
-        let active = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ChannelFlowOk.flags"))?;
+        let active = flags & 1 != 0;
         Ok((buffer, Arguments::ChannelFlowOk(ChannelFlowOk { active })))
     }
 }
 
-#[derive(Property, Default)]
+/// Request a channel close
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ChannelClose {
     reply_code: u16,
     reply_text: ShortStr,
-    class: Class,
-    method: Method
+    class_id: u16,
+    method_id: u16
+}
+
+impl ChannelClose {
+    /// The method which caused the close, None if the ids are zero or not in the spec
+    pub fn method(&self) -> Option<Method> {
+        get_method_type(Class::from(self.class_id), self.method_id).ok()
+    }
+
+    /// Set the class and method ids to those of the method which caused the close
+    pub fn set_method(&mut self, method: Method) -> &mut Self {
+        self.class_id = method.class().class_id();
+        self.method_id = method.method_id();
+        self
+    }
 }
 
 impl Encode for ChannelClose {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.reply_code);
         self.reply_text.encode(buffer);
-        buffer.put_u16(self.class.class_id());
-        buffer.put_u16(self.method.method_id());
+        buffer.put_u16(self.class_id);
+        buffer.put_u16(self.method_id);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.reply_text.encoded_len() + 4
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.reply_code);
+        self.reply_text.try_encode(buffer)?;
+        buffer.put_u16(self.class_id);
+        buffer.put_u16(self.method_id);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for ChannelClose {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, reply_code) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelClose reply_code -> {}", e)))
-        };
-        let (buffer, reply_text) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelClose reply_text -> {}", e)))
-        };
-        let (buffer, class_id) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelClose class_id -> {}", e)))
-        };
-        let (buffer, method_id) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelClose method_id -> {}", e)))
-        };
-        let class = Class::from(class_id);
-        if let Class::Unknown = class {
-            return Err(FrameDecodeErr::SyntaxError("decode ChannelClose class unknown"));
-        }
-        let method = match get_method_type(class.clone(), method_id) {
-            Ok(method) => method,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ChannelClose method -> {}", e)))
-        };
-        Ok((buffer, Arguments::ChannelClose(ChannelClose { reply_code, reply_text, class, method })))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, reply_code) = u16::decode(buffer).map_err(|e| e.field(buffer, "ChannelClose.reply_code"))?;
+        let (buffer, reply_text) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ChannelClose.reply_text"))?;
+        let (buffer, class_id) = u16::decode(buffer).map_err(|e| e.field(buffer, "ChannelClose.class_id"))?;
+        let (buffer, method_id) = u16::decode(buffer).map_err(|e| e.field(buffer, "ChannelClose.method_id"))?;
+        Ok((buffer, Arguments::ChannelClose(ChannelClose { reply_code, reply_text, class_id, method_id })))
     }
 }
 
+/// Confirm a channel close
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ChannelCloseOk;
 
 impl Encode for ChannelCloseOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for ChannelCloseOk {
     #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::ChannelCloseOk(ChannelCloseOk)))
     }
 }
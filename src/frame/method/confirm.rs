@@ -1,9 +1,13 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
 use property::Property;
-use bytes::{BytesMut, BufMut};
+use bytes::{BufMut, BytesMut};
 use crate::error::FrameDecodeErr;
 use crate::frame::base::{Arguments, Decode, Encode};
 
-#[derive(Property, Default)]
+/// RabbitMQ extension, publisher confirms for the channel
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConfirmSelect {
     no_wait: bool
@@ -11,31 +15,41 @@ pub struct ConfirmSelect {
 
 impl Encode for ConfirmSelect {
     fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u8(if self.no_wait { 1 } else { 0 });
+        buffer.put_u8(self.no_wait as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1
     }
 }
 
 impl Decode<Arguments> for ConfirmSelect {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConfirmSelect flags -> {}", e)))
-        };
-        let no_wait = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ConfirmSelect.flags"))?;
+        let no_wait = flags & 1 != 0;
         Ok((buffer, Arguments::ConfirmSelect(ConfirmSelect { no_wait })))
     }
 }
 
+/// Confirm select successful
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ConfirmSelectOk;
 
 impl Encode for ConfirmSelectOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for ConfirmSelectOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    #[inline]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::ConfirmSelectOk(ConfirmSelectOk)))
     }
 }
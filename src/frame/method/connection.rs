@@ -1,19 +1,34 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
+use std::hash::{Hash, Hasher};
 use property::Property;
-use bytes::{BytesMut, BufMut};
-use crate::{ShortStr, FieldTable, LongStr};
-use crate::frame::base::{Encode, Arguments, Decode};
-use crate::error::FrameDecodeErr;
+use bytes::{BufMut, BytesMut};
 use crate::class::Class;
-use crate::method::{Method, get_method_type, MethodId};
+use crate::error::{EncodeError, FrameDecodeErr};
+use crate::frame::base::{Arguments, Decode, Encode, ShortStr, LongStr, FieldTable, hash_field_table};
+use crate::method::{get_method_type, Method, MethodId};
 
-#[derive(Property, Default)]
+/// Start connection negotiation
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionStart {
     version_major: u8,
     version_minor: u8,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     server_properties: FieldTable,
     mechanisms: LongStr,
-    locales: LongStr,
+    locales: LongStr
+}
+
+impl Hash for ConnectionStart {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.version_major.hash(state);
+        self.version_minor.hash(state);
+        hash_field_table(&self.server_properties, state);
+        self.mechanisms.hash(state);
+        self.locales.hash(state);
+    }
 }
 
 impl Encode for ConnectionStart {
@@ -24,43 +39,54 @@ impl Encode for ConnectionStart {
         self.mechanisms.encode(buffer);
         self.locales.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.server_properties.encoded_len() + self.mechanisms.encoded_len() + self.locales.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u8(self.version_major);
+        buffer.put_u8(self.version_minor);
+        self.server_properties.try_encode(buffer)?;
+        self.mechanisms.try_encode(buffer)?;
+        self.locales.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ConnectionStart {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, version_major) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStart version_major -> {}", e)))
-        };
-        let (buffer, version_minor) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStart version_minor -> {}", e)))
-        };
-        let (buffer, server_properties) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStart server_properties -> {}", e)))
-        };
-        let (buffer, mechanisms) = match LongStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStart mechanisms -> {}", e)))
-        };
-        let (buffer, locales) = match LongStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStart locales -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, version_major) = u8::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStart.version_major"))?;
+        let (buffer, version_minor) = u8::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStart.version_minor"))?;
+        let (buffer, server_properties) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStart.server_properties"))?;
+        let (buffer, mechanisms) = LongStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStart.mechanisms"))?;
+        let (buffer, locales) = LongStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStart.locales"))?;
         Ok((buffer, Arguments::ConnectionStart(ConnectionStart { version_major, version_minor, server_properties, mechanisms, locales })))
     }
 }
 
-#[derive(Property, Default)]
+/// Select security mechanism and locale
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionStartOk {
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     client_properties: FieldTable,
     mechanism: ShortStr,
     response: LongStr,
     locale: ShortStr
 }
 
+impl Hash for ConnectionStartOk {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_field_table(&self.client_properties, state);
+        self.mechanism.hash(state);
+        self.response.hash(state);
+        self.locale.hash(state);
+    }
+}
+
 impl Encode for ConnectionStartOk {
     fn encode(&self, buffer: &mut BytesMut) {
         self.client_properties.encode(buffer);
@@ -68,31 +94,34 @@ impl Encode for ConnectionStartOk {
         self.response.encode(buffer);
         self.locale.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.client_properties.encoded_len() + self.mechanism.encoded_len() + self.response.encoded_len() + self.locale.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.client_properties.try_encode(buffer)?;
+        self.mechanism.try_encode(buffer)?;
+        self.response.try_encode(buffer)?;
+        self.locale.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ConnectionStartOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, client_properties) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStartOk client_properties -> {}", e)))
-        };
-        let (buffer, mechanism) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStartOk mechanism -> {}", e)))
-        };
-        let (buffer, response) = match LongStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStartOk response -> {}", e)))
-        };
-        let (buffer, locale) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionStartOk locale -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, client_properties) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStartOk.client_properties"))?;
+        let (buffer, mechanism) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStartOk.mechanism"))?;
+        let (buffer, response) = LongStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStartOk.response"))?;
+        let (buffer, locale) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionStartOk.locale"))?;
         Ok((buffer, Arguments::ConnectionStartOk(ConnectionStartOk { client_properties, mechanism, response, locale })))
     }
 }
 
-#[derive(Property, Default)]
+/// Security mechanism challenge
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionSecure {
     challenge: LongStr
@@ -102,19 +131,28 @@ impl Encode for ConnectionSecure {
     fn encode(&self, buffer: &mut BytesMut) {
         self.challenge.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.challenge.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.challenge.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ConnectionSecure {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, challenge) = match LongStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionSecure challenge -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, challenge) = LongStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionSecure.challenge"))?;
         Ok((buffer, Arguments::ConnectionSecure(ConnectionSecure { challenge })))
     }
 }
 
-#[derive(Property, Default)]
+/// Security mechanism response
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionSecureOk {
     response: LongStr
@@ -124,19 +162,28 @@ impl Encode for ConnectionSecureOk {
     fn encode(&self, buffer: &mut BytesMut) {
         self.response.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.response.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.response.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ConnectionSecureOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, response) = match LongStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionSecureOk response -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, response) = LongStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionSecureOk.response"))?;
         Ok((buffer, Arguments::ConnectionSecureOk(ConnectionSecureOk { response })))
     }
 }
 
-#[derive(Property, Default)]
+/// Propose connection tuning parameters
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionTune {
     channel_max: u16,
@@ -150,27 +197,25 @@ impl Encode for ConnectionTune {
         buffer.put_u32(self.frame_max);
         buffer.put_u16(self.heartbeat);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        8
+    }
 }
 
 impl Decode<Arguments> for ConnectionTune {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, channel_max) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionTune channel_max -> {}", e)))
-        };
-        let (buffer, frame_max) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionTune frame_max -> {}", e)))
-        };
-        let (buffer, heartbeat) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionTune heartbeat -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, channel_max) = u16::decode(buffer).map_err(|e| e.field(buffer, "ConnectionTune.channel_max"))?;
+        let (buffer, frame_max) = u32::decode(buffer).map_err(|e| e.field(buffer, "ConnectionTune.frame_max"))?;
+        let (buffer, heartbeat) = u16::decode(buffer).map_err(|e| e.field(buffer, "ConnectionTune.heartbeat"))?;
         Ok((buffer, Arguments::ConnectionTune(ConnectionTune { channel_max, frame_max, heartbeat })))
     }
 }
 
-#[derive(Property, Default)]
+/// Negotiate connection tuning parameters
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionTuneOk {
     channel_max: u16,
@@ -184,31 +229,29 @@ impl Encode for ConnectionTuneOk {
         buffer.put_u32(self.frame_max);
         buffer.put_u16(self.heartbeat);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        8
+    }
 }
 
 impl Decode<Arguments> for ConnectionTuneOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, channel_max) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionTuneOk channel_max -> {}", e)))
-        };
-        let (buffer, frame_max) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionTuneOk frame_max -> {}", e)))
-        };
-        let (buffer, heartbeat) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionTuneOk heartbeat -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, channel_max) = u16::decode(buffer).map_err(|e| e.field(buffer, "ConnectionTuneOk.channel_max"))?;
+        let (buffer, frame_max) = u32::decode(buffer).map_err(|e| e.field(buffer, "ConnectionTuneOk.frame_max"))?;
+        let (buffer, heartbeat) = u16::decode(buffer).map_err(|e| e.field(buffer, "ConnectionTuneOk.heartbeat"))?;
         Ok((buffer, Arguments::ConnectionTuneOk(ConnectionTuneOk { channel_max, frame_max, heartbeat })))
     }
 }
 
-#[derive(Property, Default)]
+/// Open connection to virtual host
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionOpen {
     vhost: ShortStr,
-    capabilities: ShortStr,  // rabbitmq used
+    capabilities: ShortStr,
     insist: bool
 }
 
@@ -216,30 +259,35 @@ impl Encode for ConnectionOpen {
     fn encode(&self, buffer: &mut BytesMut) {
         self.vhost.encode(buffer);
         self.capabilities.encode(buffer);
-        buffer.put_u8(if self.insist {1u8} else {0u8});
+        buffer.put_u8(self.insist as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.vhost.encoded_len() + self.capabilities.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.vhost.try_encode(buffer)?;
+        self.capabilities.try_encode(buffer)?;
+        buffer.put_u8(self.insist as u8);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for ConnectionOpen {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, vhost) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionOpen vhost -> {}", e)))
-        };
-        let (buffer, capabilities) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionOpen capabilities -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionOpen flags -> {}", e)))
-        };
-        let insist = if flags & (1 << 0) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, vhost) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionOpen.vhost"))?;
+        let (buffer, capabilities) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionOpen.capabilities"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ConnectionOpen.flags"))?;
+        let insist = flags & 1 != 0;
         Ok((buffer, Arguments::ConnectionOpen(ConnectionOpen { vhost, capabilities, insist })))
     }
 }
 
-#[derive(Property, Default)]
+/// Signal that connection is ready
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionOpenOk {
     known_hosts: ShortStr
@@ -249,77 +297,210 @@ impl Encode for ConnectionOpenOk {
     fn encode(&self, buffer: &mut BytesMut) {
         self.known_hosts.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.known_hosts.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.known_hosts.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ConnectionOpenOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, known_hosts) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionOpenOk known_hosts -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, known_hosts) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionOpenOk.known_hosts"))?;
         Ok((buffer, Arguments::ConnectionOpenOk(ConnectionOpenOk { known_hosts })))
     }
 }
 
-#[derive(Property, Default)]
+/// Request a connection close
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ConnectionClose {
     reply_code: u16,
     reply_text: ShortStr,
-    class: Class,
-    method: Method
+    class_id: u16,
+    method_id: u16
+}
+
+impl ConnectionClose {
+    /// The method which caused the close, None if the ids are zero or not in the spec
+    pub fn method(&self) -> Option<Method> {
+        get_method_type(Class::from(self.class_id), self.method_id).ok()
+    }
+
+    /// Set the class and method ids to those of the method which caused the close
+    pub fn set_method(&mut self, method: Method) -> &mut Self {
+        self.class_id = method.class().class_id();
+        self.method_id = method.method_id();
+        self
+    }
 }
 
 impl Encode for ConnectionClose {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.reply_code);
         self.reply_text.encode(buffer);
-        buffer.put_u16(self.class.class_id());
-        buffer.put_u16(self.method.method_id())
+        buffer.put_u16(self.class_id);
+        buffer.put_u16(self.method_id);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.reply_text.encoded_len() + 4
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.reply_code);
+        self.reply_text.try_encode(buffer)?;
+        buffer.put_u16(self.class_id);
+        buffer.put_u16(self.method_id);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for ConnectionClose {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, reply_code) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionClose reply_code -> {}", e)))
-        };
-        let (buffer, reply_text) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionClose reply_text -> {}", e)))
-        };
-        let (buffer, class_id) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionClose class_id -> {}", e)))
-        };
-        let (buffer, method_id) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionClose method_id -> {}", e)))
-        };
-        let class = Class::from(class_id);
-        if let Class::Unknown = class {
-            return Err(FrameDecodeErr::SyntaxError("decode ConnectionClose class unknown"));
-        }
-        let method = match get_method_type(class.clone(), method_id) {
-            Ok(method) => method,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ConnectionClose method -> {}", e)))
-        };
-        Ok((buffer, Arguments::ConnectionClose(ConnectionClose { reply_code, reply_text, class, method })))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, reply_code) = u16::decode(buffer).map_err(|e| e.field(buffer, "ConnectionClose.reply_code"))?;
+        let (buffer, reply_text) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionClose.reply_text"))?;
+        let (buffer, class_id) = u16::decode(buffer).map_err(|e| e.field(buffer, "ConnectionClose.class_id"))?;
+        let (buffer, method_id) = u16::decode(buffer).map_err(|e| e.field(buffer, "ConnectionClose.method_id"))?;
+        Ok((buffer, Arguments::ConnectionClose(ConnectionClose { reply_code, reply_text, class_id, method_id })))
     }
 }
 
+/// Confirm a connection close
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ConnectionCloseOk;
 
 impl Encode for ConnectionCloseOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for ConnectionCloseOk {
     #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::ConnectionCloseOk(ConnectionCloseOk)))
     }
 }
+
+/// RabbitMQ extension, the server stops reading from the connection until it sends `ConnectionUnblocked`
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
+#[property(get(public), set(public))]
+pub struct ConnectionBlocked {
+    reason: ShortStr
+}
+
+impl Encode for ConnectionBlocked {
+    fn encode(&self, buffer: &mut BytesMut) {
+        self.reason.encode(buffer);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.reason.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.reason.try_encode(buffer)?;
+        Ok(())
+    }
+}
+
+impl Decode<Arguments> for ConnectionBlocked {
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, reason) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionBlocked.reason"))?;
+        Ok((buffer, Arguments::ConnectionBlocked(ConnectionBlocked { reason })))
+    }
+}
+
+/// RabbitMQ extension, the server reads from the connection again
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
+pub struct ConnectionUnblocked;
+
+impl Encode for ConnectionUnblocked {
+    #[inline]
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
+    }
+}
+
+impl Decode<Arguments> for ConnectionUnblocked {
+    #[inline]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        Ok((buffer, Arguments::ConnectionUnblocked(ConnectionUnblocked)))
+    }
+}
+
+/// RabbitMQ extension, replaces the secret the connection authenticated with, such as an expiring OAuth2 token
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
+#[property(get(public), set(public))]
+pub struct ConnectionUpdateSecret {
+    new_secret: LongStr,
+    reason: ShortStr
+}
+
+impl Encode for ConnectionUpdateSecret {
+    fn encode(&self, buffer: &mut BytesMut) {
+        self.new_secret.encode(buffer);
+        self.reason.encode(buffer);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.new_secret.encoded_len() + self.reason.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.new_secret.try_encode(buffer)?;
+        self.reason.try_encode(buffer)?;
+        Ok(())
+    }
+}
+
+impl Decode<Arguments> for ConnectionUpdateSecret {
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, new_secret) = LongStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionUpdateSecret.new_secret"))?;
+        let (buffer, reason) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ConnectionUpdateSecret.reason"))?;
+        Ok((buffer, Arguments::ConnectionUpdateSecret(ConnectionUpdateSecret { new_secret, reason })))
+    }
+}
+
+/// RabbitMQ extension, confirm the secret was replaced
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
+pub struct ConnectionUpdateSecretOk;
+
+impl Encode for ConnectionUpdateSecretOk {
+    #[inline]
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
+    }
+}
+
+impl Decode<Arguments> for ConnectionUpdateSecretOk {
+    #[inline]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        Ok((buffer, Arguments::ConnectionUpdateSecretOk(ConnectionUpdateSecretOk)))
+    }
+}
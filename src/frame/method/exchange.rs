@@ -1,10 +1,14 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
+use std::hash::{Hash, Hasher};
 use property::Property;
-use bytes::{BytesMut, BufMut};
-use crate::{ShortStr, FieldTable};
-use crate::frame::base::{Arguments, Decode, Encode};
-use crate::error::FrameDecodeErr;
+use bytes::{BufMut, BytesMut};
+use crate::error::{EncodeError, FrameDecodeErr};
+use crate::frame::base::{Arguments, Decode, Encode, ShortStr, FieldTable, hash_field_table};
 
-#[derive(Property, Default)]
+/// Verify exchange exists, create if needed
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ExchangeDeclare {
     ticket: u16,
@@ -15,71 +19,101 @@ pub struct ExchangeDeclare {
     auto_delete: bool,
     internal: bool,
     no_wait: bool,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     args: FieldTable
 }
 
+impl Hash for ExchangeDeclare {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticket.hash(state);
+        self.exchange_name.hash(state);
+        self.exchange_type.hash(state);
+        self.passive.hash(state);
+        self.durable.hash(state);
+        self.auto_delete.hash(state);
+        self.internal.hash(state);
+        self.no_wait.hash(state);
+        hash_field_table(&self.args, state);
+    }
+}
+
 impl Encode for ExchangeDeclare {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.exchange_name.encode(buffer);
         self.exchange_type.encode(buffer);
-        let mut flag = 0u8;
-        flag |= if self.passive { 1 } else { 0 };
-        flag |= if self.durable { 1 << 1 } else { 0 };
-        flag |= if self.auto_delete { 1 << 2 } else { 0 };
-        flag |= if self.internal { 1 << 3 } else { 0 };
-        flag |= if self.no_wait { 1 << 4 } else { 0 };
-        buffer.put_u8(flag);
+        let mut flags = 0u8;
+        flags |= self.passive as u8;
+        flags |= (self.durable as u8) << 1;
+        flags |= (self.auto_delete as u8) << 2;
+        flags |= (self.internal as u8) << 3;
+        flags |= (self.no_wait as u8) << 4;
+        buffer.put_u8(flags);
         self.args.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.exchange_name.encoded_len() + self.exchange_type.encoded_len() + 1 + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.exchange_name.try_encode(buffer)?;
+        self.exchange_type.try_encode(buffer)?;
+        let mut flags = 0u8;
+        flags |= self.passive as u8;
+        flags |= (self.durable as u8) << 1;
+        flags |= (self.auto_delete as u8) << 2;
+        flags |= (self.internal as u8) << 3;
+        flags |= (self.no_wait as u8) << 4;
+        buffer.put_u8(flags);
+        self.args.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ExchangeDeclare {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDeclare ticket -> {}", e)))
-        };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDeclare exchange_name -> {}", e)))
-        };
-        let (buffer, exchange_type) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDeclare exchange_type -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDeclare flags -> {}", e)))
-        };
-        let passive = if flags & (1 << 0) != 0 { true } else { false };
-        let durable = if flags & (1 << 1) != 0 { true } else { false };
-        let auto_delete = if flags & (1 << 2) != 0 { true } else { false };
-        let internal = if flags & (1 << 3) != 0 { true } else { false };
-        let no_wait = if flags & (1 << 4) != 0 { true } else { false };
-        let (buffer, args) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDeclare args -> {}", e)))
-        };
-        Ok((buffer, Arguments::ExchangeDeclare(ExchangeDeclare { ticket, exchange_name, exchange_type, passive, durable, auto_delete, internal, no_wait, args})))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDeclare.ticket"))?;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDeclare.exchange_name"))?;
+        let (buffer, exchange_type) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDeclare.exchange_type"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDeclare.flags"))?;
+        let passive = flags & 1 != 0;
+        let durable = flags & (1 << 1) != 0;
+        let auto_delete = flags & (1 << 2) != 0;
+        let internal = flags & (1 << 3) != 0;
+        let no_wait = flags & (1 << 4) != 0;
+        let (buffer, args) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDeclare.args"))?;
+        Ok((buffer, Arguments::ExchangeDeclare(ExchangeDeclare { ticket, exchange_name, exchange_type, passive, durable, auto_delete, internal, no_wait, args })))
     }
 }
 
+/// Confirm exchange declaration
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ExchangeDeclareOk;
 
 impl Encode for ExchangeDeclareOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for ExchangeDeclareOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    #[inline]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::ExchangeDeclareOk(ExchangeDeclareOk)))
     }
 }
 
-#[derive(Property, Default)]
+/// Delete an exchange
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ExchangeDelete {
     ticket: u16,
@@ -92,48 +126,64 @@ impl Encode for ExchangeDelete {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.exchange_name.encode(buffer);
-        let mut flag = 0u8;
-        flag |= if self.if_unused { 1 } else { 0 };
-        flag |= if self.no_wait { 1 << 1 } else { 0};
-        buffer.put_u8(flag);
+        let mut flags = 0u8;
+        flags |= self.if_unused as u8;
+        flags |= (self.no_wait as u8) << 1;
+        buffer.put_u8(flags);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.exchange_name.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.exchange_name.try_encode(buffer)?;
+        let mut flags = 0u8;
+        flags |= self.if_unused as u8;
+        flags |= (self.no_wait as u8) << 1;
+        buffer.put_u8(flags);
+        Ok(())
     }
 }
 
 impl Decode<Arguments> for ExchangeDelete {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDelete ticket -> {}", e)))
-        };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDelete exchange_name -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeDelete flags -> {}", e)))
-        };
-        let if_unused = if flags & (1 << 0) != 0 { true } else { false };
-        let no_wait = if flags & (1 << 1) != 0 { true } else { false };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDelete.ticket"))?;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDelete.exchange_name"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ExchangeDelete.flags"))?;
+        let if_unused = flags & 1 != 0;
+        let no_wait = flags & (1 << 1) != 0;
         Ok((buffer, Arguments::ExchangeDelete(ExchangeDelete { ticket, exchange_name, if_unused, no_wait })))
     }
 }
 
+/// Confirm deletion of an exchange
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ExchangeDeleteOk;
 
 impl Encode for ExchangeDeleteOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for ExchangeDeleteOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    #[inline]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::ExchangeDeleteOk(ExchangeDeleteOk)))
     }
 }
 
-#[derive(Property, Default)]
+/// RabbitMQ extension, bind exchange to an exchange
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ExchangeBind {
     ticket: u16,
@@ -141,67 +191,85 @@ pub struct ExchangeBind {
     source: ShortStr,
     routing_key: ShortStr,
     no_wait: bool,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     args: FieldTable
 }
 
+impl Hash for ExchangeBind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticket.hash(state);
+        self.destination.hash(state);
+        self.source.hash(state);
+        self.routing_key.hash(state);
+        self.no_wait.hash(state);
+        hash_field_table(&self.args, state);
+    }
+}
+
 impl Encode for ExchangeBind {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.destination.encode(buffer);
         self.source.encode(buffer);
         self.routing_key.encode(buffer);
-        buffer.put_u8(if self.no_wait { 1 } else { 0});
+        buffer.put_u8(self.no_wait as u8);
         self.args.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.destination.encoded_len() + self.source.encoded_len() + self.routing_key.encoded_len() + 1 + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.destination.try_encode(buffer)?;
+        self.source.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        buffer.put_u8(self.no_wait as u8);
+        self.args.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ExchangeBind {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeBind ticket -> {}", e)))
-        };
-        let (buffer, destination) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeBind destination -> {}", e)))
-        };
-        let (buffer, source) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeBind source -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeBind routing_key -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeBind flags -> {}", e)))
-        };
-        let no_wait = if flags & (1 << 0) != 0 { true } else { false };
-        let (buffer, args) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeBind args -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "ExchangeBind.ticket"))?;
+        let (buffer, destination) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeBind.destination"))?;
+        let (buffer, source) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeBind.source"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeBind.routing_key"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ExchangeBind.flags"))?;
+        let no_wait = flags & 1 != 0;
+        let (buffer, args) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "ExchangeBind.args"))?;
         Ok((buffer, Arguments::ExchangeBind(ExchangeBind { ticket, destination, source, routing_key, no_wait, args })))
     }
 }
 
+/// Confirm bind successful
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ExchangeBindOk;
 
 impl Encode for ExchangeBindOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for ExchangeBindOk {
     #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::ExchangeBindOk(ExchangeBindOk)))
     }
 }
 
-#[derive(Property, Default)]
+/// RabbitMQ extension, unbind an exchange from an exchange
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct ExchangeUnbind {
     ticket: u16,
@@ -209,61 +277,78 @@ pub struct ExchangeUnbind {
     source: ShortStr,
     routing_key: ShortStr,
     no_wait: bool,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     args: FieldTable
 }
 
+impl Hash for ExchangeUnbind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticket.hash(state);
+        self.destination.hash(state);
+        self.source.hash(state);
+        self.routing_key.hash(state);
+        self.no_wait.hash(state);
+        hash_field_table(&self.args, state);
+    }
+}
+
 impl Encode for ExchangeUnbind {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.destination.encode(buffer);
         self.source.encode(buffer);
         self.routing_key.encode(buffer);
-        buffer.put_u8(if self.no_wait { 1 } else { 0});
+        buffer.put_u8(self.no_wait as u8);
         self.args.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.destination.encoded_len() + self.source.encoded_len() + self.routing_key.encoded_len() + 1 + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.destination.try_encode(buffer)?;
+        self.source.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        buffer.put_u8(self.no_wait as u8);
+        self.args.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for ExchangeUnbind {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeUnbind ticket -> {}", e)))
-        };
-        let (buffer, destination) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeUnbind destination -> {}", e)))
-        };
-        let (buffer, source) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeUnbind source -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeUnbind routing_key -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeUnbind flags -> {}", e)))
-        };
-        let no_wait = if flags & (1 << 0) != 0 { true } else { false };
-        let (buffer, args) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode ExchangeUnbind args -> {}", e)))
-        };
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "ExchangeUnbind.ticket"))?;
+        let (buffer, destination) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeUnbind.destination"))?;
+        let (buffer, source) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeUnbind.source"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "ExchangeUnbind.routing_key"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "ExchangeUnbind.flags"))?;
+        let no_wait = flags & 1 != 0;
+        let (buffer, args) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "ExchangeUnbind.args"))?;
         Ok((buffer, Arguments::ExchangeUnbind(ExchangeUnbind { ticket, destination, source, routing_key, no_wait, args })))
     }
 }
 
+/// Confirm unbind successful
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ExchangeUnbindOk;
 
 impl Encode for ExchangeUnbindOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for ExchangeUnbindOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    #[inline]
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::ExchangeUnbindOk(ExchangeUnbindOk)))
     }
 }
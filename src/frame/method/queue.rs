@@ -1,10 +1,14 @@
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+
+use std::hash::{Hash, Hasher};
 use property::Property;
-use bytes::{BytesMut, BufMut};
-use crate::{ShortStr, FieldTable};
-use crate::frame::base::{Encode, Arguments, Decode};
-use crate::error::FrameDecodeErr;
+use bytes::{BufMut, BytesMut};
+use crate::error::{EncodeError, FrameDecodeErr};
+use crate::frame::base::{Arguments, Decode, Encode, ShortStr, FieldTable, hash_field_table};
 
-#[derive(Property, Default)]
+/// Declare queue, create if needed
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct QueueDeclare {
     ticket: u16,
@@ -14,52 +18,75 @@ pub struct QueueDeclare {
     exclusive: bool,
     auto_delete: bool,
     no_wait: bool,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     args: FieldTable
 }
 
+impl Hash for QueueDeclare {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticket.hash(state);
+        self.queue_name.hash(state);
+        self.passive.hash(state);
+        self.durable.hash(state);
+        self.exclusive.hash(state);
+        self.auto_delete.hash(state);
+        self.no_wait.hash(state);
+        hash_field_table(&self.args, state);
+    }
+}
+
 impl Encode for QueueDeclare {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.queue_name.encode(buffer);
-        let mut flag = 0u8;
-        flag |= if self.passive { 1 } else { 0 };
-        flag |= if self.durable { 1 << 1 } else { 0};
-        flag |= if self.exclusive { 1 << 2 } else { 0 };
-        flag |= if self.auto_delete { 1 << 3 } else { 0 };
-        flag |= if self.no_wait { 1 << 4 } else { 0 };
-        buffer.put_u8(flag);
+        let mut flags = 0u8;
+        flags |= self.passive as u8;
+        flags |= (self.durable as u8) << 1;
+        flags |= (self.exclusive as u8) << 2;
+        flags |= (self.auto_delete as u8) << 3;
+        flags |= (self.no_wait as u8) << 4;
+        buffer.put_u8(flags);
         self.args.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.queue_name.encoded_len() + 1 + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.queue_name.try_encode(buffer)?;
+        let mut flags = 0u8;
+        flags |= self.passive as u8;
+        flags |= (self.durable as u8) << 1;
+        flags |= (self.exclusive as u8) << 2;
+        flags |= (self.auto_delete as u8) << 3;
+        flags |= (self.no_wait as u8) << 4;
+        buffer.put_u8(flags);
+        self.args.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for QueueDeclare {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeclare ticket -> {}", e)))
-        };
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeclare queue_name -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeclare flags -> {}", e)))
-        };
-        let (buffer, args) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeclare args -> {}", e)))
-        };
-        let passive = if flags & (1 << 0) != 0 { true } else { false };
-        let durable = if flags & (1 << 1) != 0 { true } else { false };
-        let exclusive = if flags & (1 << 2) != 0 { true } else { false };
-        let auto_delete = if flags & (1 << 3) != 0 { true } else { false };
-        let no_wait = if flags & (1 << 4) != 0 { true } else { false };
-        Ok((buffer, Arguments::QueueDeclare(QueueDeclare { ticket, queue_name, passive, durable, exclusive, auto_delete, no_wait, args})))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "QueueDeclare.ticket"))?;
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueDeclare.queue_name"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "QueueDeclare.flags"))?;
+        let passive = flags & 1 != 0;
+        let durable = flags & (1 << 1) != 0;
+        let exclusive = flags & (1 << 2) != 0;
+        let auto_delete = flags & (1 << 3) != 0;
+        let no_wait = flags & (1 << 4) != 0;
+        let (buffer, args) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "QueueDeclare.args"))?;
+        Ok((buffer, Arguments::QueueDeclare(QueueDeclare { ticket, queue_name, passive, durable, exclusive, auto_delete, no_wait, args })))
     }
 }
 
-#[derive(Property, Default)]
+/// Confirms a queue definition
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct QueueDeclareOk {
     queue_name: ShortStr,
@@ -73,27 +100,32 @@ impl Encode for QueueDeclareOk {
         buffer.put_u32(self.message_count);
         buffer.put_u32(self.consumer_count);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.queue_name.encoded_len() + 8
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        self.queue_name.try_encode(buffer)?;
+        buffer.put_u32(self.message_count);
+        buffer.put_u32(self.consumer_count);
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for QueueDeclareOk {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeclareOk queue_name -> {}", e)))
-        };
-        let (buffer, message_count) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeclareOk message_count -> {}", e)))
-        };
-        let (buffer, consumer_count) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeclareOk consumer_count -> {}", e)))
-        };
-        Ok((buffer, Arguments::QueueDeclareOk(QueueDeclareOk {queue_name, message_count, consumer_count})))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueDeclareOk.queue_name"))?;
+        let (buffer, message_count) = u32::decode(buffer).map_err(|e| e.field(buffer, "QueueDeclareOk.message_count"))?;
+        let (buffer, consumer_count) = u32::decode(buffer).map_err(|e| e.field(buffer, "QueueDeclareOk.consumer_count"))?;
+        Ok((buffer, Arguments::QueueDeclareOk(QueueDeclareOk { queue_name, message_count, consumer_count })))
     }
 }
 
-#[derive(Property, Default)]
+/// Bind queue to an exchange
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
 pub struct QueueBind {
     ticket: u16,
@@ -101,250 +133,302 @@ pub struct QueueBind {
     exchange_name: ShortStr,
     routing_key: ShortStr,
     no_wait: bool,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
     args: FieldTable
 }
 
+impl Hash for QueueBind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticket.hash(state);
+        self.queue_name.hash(state);
+        self.exchange_name.hash(state);
+        self.routing_key.hash(state);
+        self.no_wait.hash(state);
+        hash_field_table(&self.args, state);
+    }
+}
+
 impl Encode for QueueBind {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.queue_name.encode(buffer);
         self.exchange_name.encode(buffer);
         self.routing_key.encode(buffer);
-        buffer.put_u8(if self.no_wait { 1 } else { 0 });
+        buffer.put_u8(self.no_wait as u8);
         self.args.encode(buffer);
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.queue_name.encoded_len() + self.exchange_name.encoded_len() + self.routing_key.encoded_len() + 1 + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.queue_name.try_encode(buffer)?;
+        self.exchange_name.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        buffer.put_u8(self.no_wait as u8);
+        self.args.try_encode(buffer)?;
+        Ok(())
+    }
 }
 
 impl Decode<Arguments> for QueueBind {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueBind ticket -> {}", e)))
-        };
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueBind queue_name -> {}", e)))
-        };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueBind exchange_name -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueBind routing_key -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueBind flags -> {}", e)))
-        };
-        let (buffer, args) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueBind args -> {}", e)))
-        };
-        let no_wait = if flags & (1 << 0) != 0 { true } else { false };
-        Ok((buffer, Arguments::QueueBind(QueueBind {ticket, queue_name, exchange_name, routing_key, no_wait, args})))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "QueueBind.ticket"))?;
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueBind.queue_name"))?;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueBind.exchange_name"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueBind.routing_key"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "QueueBind.flags"))?;
+        let no_wait = flags & 1 != 0;
+        let (buffer, args) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "QueueBind.args"))?;
+        Ok((buffer, Arguments::QueueBind(QueueBind { ticket, queue_name, exchange_name, routing_key, no_wait, args })))
     }
 }
 
+/// Confirm bind successful
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct QueueBindOk;
 
 impl Encode for QueueBindOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
 impl Decode<Arguments> for QueueBindOk {
     #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
         Ok((buffer, Arguments::QueueBindOk(QueueBindOk)))
     }
 }
 
-#[derive(Property, Default)]
+/// Unbind a queue from an exchange
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
-pub struct QueuePurge {
+pub struct QueueUnbind {
     ticket: u16,
     queue_name: ShortStr,
-    no_wait: bool
+    exchange_name: ShortStr,
+    routing_key: ShortStr,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::frame::arbitrary::field_table()"))]
+    args: FieldTable
 }
 
-impl Encode for QueuePurge {
-    #[inline]
+impl Hash for QueueUnbind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ticket.hash(state);
+        self.queue_name.hash(state);
+        self.exchange_name.hash(state);
+        self.routing_key.hash(state);
+        hash_field_table(&self.args, state);
+    }
+}
+
+impl Encode for QueueUnbind {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.queue_name.encode(buffer);
-        buffer.put_u8(if self.no_wait { 1 } else { 0 });
+        self.exchange_name.encode(buffer);
+        self.routing_key.encode(buffer);
+        self.args.encode(buffer);
     }
-}
 
-impl Decode<Arguments> for QueuePurge {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueuePurge ticket -> {}", e)))
-        };
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueuePurge queue_name -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueuePurge flags -> {}", e)))
-        };
-        let no_wait = if flags & (1 << 0) != 0 { true } else { false };
-        Ok((buffer, Arguments::QueuePurge(QueuePurge { ticket, queue_name, no_wait})))
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.queue_name.encoded_len() + self.exchange_name.encoded_len() + self.routing_key.encoded_len() + self.args.encoded_len()
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.queue_name.try_encode(buffer)?;
+        self.exchange_name.try_encode(buffer)?;
+        self.routing_key.try_encode(buffer)?;
+        self.args.try_encode(buffer)?;
+        Ok(())
     }
 }
 
-#[derive(Property, Default)]
-#[property(get(public), set(public))]
-pub struct QueuePurgeOk {
-    message_count: u32
+impl Decode<Arguments> for QueueUnbind {
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "QueueUnbind.ticket"))?;
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueUnbind.queue_name"))?;
+        let (buffer, exchange_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueUnbind.exchange_name"))?;
+        let (buffer, routing_key) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueUnbind.routing_key"))?;
+        let (buffer, args) = FieldTable::decode(buffer).map_err(|e| e.field(buffer, "QueueUnbind.args"))?;
+        Ok((buffer, Arguments::QueueUnbind(QueueUnbind { ticket, queue_name, exchange_name, routing_key, args })))
+    }
 }
 
-impl Encode for QueuePurgeOk {
+/// Confirm unbind successful
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
+pub struct QueueUnbindOk;
+
+impl Encode for QueueUnbindOk {
     #[inline]
-    fn encode(&self, buffer: &mut BytesMut) {
-        buffer.put_u32(self.message_count);
+    fn encode(&self, _: &mut BytesMut) {}
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
     }
 }
 
-impl Decode<Arguments> for QueuePurgeOk {
+impl Decode<Arguments> for QueueUnbindOk {
     #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, message_count) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueuePurgeOk message_count -> {}", e)))
-        };
-        Ok((buffer, Arguments::QueuePurgeOk(QueuePurgeOk { message_count })))
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        Ok((buffer, Arguments::QueueUnbindOk(QueueUnbindOk)))
     }
 }
 
-#[derive(Property, Default)]
+/// Purge a queue
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
-pub struct QueueDelete {
+pub struct QueuePurge {
     ticket: u16,
     queue_name: ShortStr,
-    if_unused: bool,
-    if_empty: bool,
     no_wait: bool
 }
 
-impl Encode for QueueDelete {
+impl Encode for QueuePurge {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.queue_name.encode(buffer);
-        let mut flag = 0u8;
-        flag |= if self.if_unused { 1 } else { 0};
-        flag |= if self.if_empty { 1 << 1 } else { 0 };
-        flag |= if self.no_wait { 1 << 2 } else { 0 };
-        buffer.put_u8(flag);
+        buffer.put_u8(self.no_wait as u8);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.queue_name.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.queue_name.try_encode(buffer)?;
+        buffer.put_u8(self.no_wait as u8);
+        Ok(())
     }
 }
 
-impl Decode<Arguments> for QueueDelete {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDelete ticket -> {}", e)))
-        };
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDelete queue_name -> {}", e)))
-        };
-        let (buffer, flags) = match u8::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDelete flags -> {}", e)))
-        };
-        let if_unused = if flags & (1 << 0) != 0 { true } else { false };
-        let if_empty = if flags & (1 << 1) != 0 { true } else { false };
-        let no_wait = if flags & (1 << 2) != 0 { true } else { false };
-        Ok((buffer, Arguments::QueueDelete(QueueDelete { ticket, queue_name, if_unused, if_empty, no_wait})))
+impl Decode<Arguments> for QueuePurge {
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "QueuePurge.ticket"))?;
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueuePurge.queue_name"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "QueuePurge.flags"))?;
+        let no_wait = flags & 1 != 0;
+        Ok((buffer, Arguments::QueuePurge(QueuePurge { ticket, queue_name, no_wait })))
     }
 }
 
-#[derive(Property, Default)]
+/// Confirms a queue purge
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
-pub struct QueueDeleteOk {
+pub struct QueuePurgeOk {
     message_count: u32
 }
 
-impl Encode for QueueDeleteOk {
-    #[inline]
+impl Encode for QueuePurgeOk {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u32(self.message_count);
     }
-}
 
-impl Decode<Arguments> for QueueDeleteOk {
     #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, message_count) = match u32::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueDeleteOk message_count -> {}", e)))
-        };
-        Ok((buffer, Arguments::QueueDeleteOk(QueueDeleteOk { message_count })))
+    fn encoded_len(&self) -> usize {
+        4
+    }
+}
+
+impl Decode<Arguments> for QueuePurgeOk {
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, message_count) = u32::decode(buffer).map_err(|e| e.field(buffer, "QueuePurgeOk.message_count"))?;
+        Ok((buffer, Arguments::QueuePurgeOk(QueuePurgeOk { message_count })))
     }
 }
 
-#[derive(Property, Default)]
+/// Delete a queue
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[property(get(public), set(public))]
-pub struct QueueUnbind {
+pub struct QueueDelete {
     ticket: u16,
     queue_name: ShortStr,
-    exchange_name: ShortStr,
-    routing_key: ShortStr,
-    args: FieldTable
+    if_unused: bool,
+    if_empty: bool,
+    no_wait: bool
 }
 
-impl Encode for QueueUnbind {
+impl Encode for QueueDelete {
     fn encode(&self, buffer: &mut BytesMut) {
         buffer.put_u16(self.ticket);
         self.queue_name.encode(buffer);
-        self.exchange_name.encode(buffer);
-        self.routing_key.encode(buffer);
-        self.args.encode(buffer);
+        let mut flags = 0u8;
+        flags |= self.if_unused as u8;
+        flags |= (self.if_empty as u8) << 1;
+        flags |= (self.no_wait as u8) << 2;
+        buffer.put_u8(flags);
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        2 + self.queue_name.encoded_len() + 1
+    }
+
+    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {
+        buffer.put_u16(self.ticket);
+        self.queue_name.try_encode(buffer)?;
+        let mut flags = 0u8;
+        flags |= self.if_unused as u8;
+        flags |= (self.if_empty as u8) << 1;
+        flags |= (self.no_wait as u8) << 2;
+        buffer.put_u8(flags);
+        Ok(())
     }
 }
 
-impl Decode<Arguments> for QueueUnbind {
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        let (buffer, ticket) = match u16::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueUnbind ticket -> {}", e)))
-        };
-        let (buffer, queue_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueUnbind queue_name -> {}", e)))
-        };
-        let (buffer, exchange_name) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueUnbind exchange_name -> {}", e)))
-        };
-        let (buffer, routing_key) = match ShortStr::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueUnbind routing_key -> {}", e)))
-        };
-        let (buffer, args) = match FieldTable::decode(buffer) {
-            Ok(ret) => ret,
-            Err(e) => return Err(FrameDecodeErr::DecodeError(format!("decode QueueUnbind args -> {}", e)))
-        };
-        Ok((buffer, Arguments::QueueUnbind(QueueUnbind { ticket, queue_name, exchange_name, routing_key, args })))
+impl Decode<Arguments> for QueueDelete {
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, ticket) = u16::decode(buffer).map_err(|e| e.field(buffer, "QueueDelete.ticket"))?;
+        let (buffer, queue_name) = ShortStr::decode(buffer).map_err(|e| e.field(buffer, "QueueDelete.queue_name"))?;
+        let (buffer, flags) = u8::decode(buffer).map_err(|e| e.field(buffer, "QueueDelete.flags"))?;
+        let if_unused = flags & 1 != 0;
+        let if_empty = flags & (1 << 1) != 0;
+        let no_wait = flags & (1 << 2) != 0;
+        Ok((buffer, Arguments::QueueDelete(QueueDelete { ticket, queue_name, if_unused, if_empty, no_wait })))
     }
 }
 
-pub struct QueueUnbindOk;
+/// Confirm deletion of a queue
+#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
+#[property(get(public), set(public))]
+pub struct QueueDeleteOk {
+    message_count: u32
+}
+
+impl Encode for QueueDeleteOk {
+    fn encode(&self, buffer: &mut BytesMut) {
+        buffer.put_u32(self.message_count);
+    }
 
-impl Encode for QueueUnbindOk {
     #[inline]
-    fn encode(&self, _: &mut BytesMut) {
+    fn encoded_len(&self) -> usize {
+        4
     }
 }
 
-impl Decode<Arguments> for QueueUnbindOk {
-    #[inline]
-    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr>{
-        Ok((buffer, Arguments::QueueUnbindOk(QueueUnbindOk)))
+impl Decode<Arguments> for QueueDeleteOk {
+    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {
+        let (buffer, message_count) = u32::decode(buffer).map_err(|e| e.field(buffer, "QueueDeleteOk.message_count"))?;
+        Ok((buffer, Arguments::QueueDeleteOk(QueueDeleteOk { message_count })))
     }
 }
@@ -1,4 +1,14 @@
 pub mod frame_codec;
+pub mod blocking;
+pub mod vectored;
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
 pub mod base;
+mod arguments;
 pub mod header;
-pub mod method;
\ No newline at end of file
+pub mod method;
+pub mod borrowed;
+pub mod constants;
+mod trace;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
@@ -0,0 +1,391 @@
+//! Human readable rendering of frames, in the style of the RabbitMQ firehose tracer:
+//! `ch=1 basic.publish(exchange="x", routing_key="rk", mandatory=true, immediate=false)`.
+//!
+//! Long strings and content bodies are cut after 128 bytes, the precision sets another limit
+//! (`{:.16}`) and the alternate flag (`{:#}`) renders everything.
+
+use std::fmt::{self, Display, Formatter, Write};
+use crate::{ShortStr, LongStr, Decimal, FieldValue, FieldArray, FieldTable};
+use crate::frame::base::{Frame, Payload, Property, Arguments};
+use crate::frame::header::basic::BasicProperties;
+use crate::frame::method::connection::*;
+use crate::frame::method::channel::*;
+use crate::frame::method::access::*;
+use crate::frame::method::exchange::*;
+use crate::frame::method::queue::*;
+use crate::frame::method::basic::*;
+use crate::frame::method::tx::*;
+use crate::frame::method::confirm::*;
+
+const DEFAULT_LIMIT: usize = 128;
+
+// max bytes of a long string or content body, None renders all of them
+type Limit = Option<usize>;
+
+fn limit(f: &Formatter<'_>) -> Limit {
+    if f.alternate() {
+        None
+    } else {
+        Some(f.precision().unwrap_or(DEFAULT_LIMIT))
+    }
+}
+
+trait Trace {
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result;
+}
+
+impl<T: Trace + ?Sized> Trace for &T {
+    #[inline]
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        (**self).trace(f, limit)
+    }
+}
+
+macro_rules! trace_display {
+    ($($t:ty),+) => {
+        $(
+            impl Trace for $t {
+                #[inline]
+                fn trace(&self, f: &mut Formatter<'_>, _: Limit) -> fmt::Result {
+                    write!(f, "{}", self)
+                }
+            }
+        )+
+    }
+}
+
+trace_display!(bool, u8, u16, u32, u64, Decimal);
+
+// quoted and escaped, cut at a char boundary
+fn write_str(f: &mut Formatter<'_>, value: &str, limit: Limit) -> fmt::Result {
+    let end = match limit {
+        Some(limit) if value.len() > limit => (0..=limit).rev().find(|i| value.is_char_boundary(*i)).unwrap_or(0),
+        _ => value.len()
+    };
+    f.write_char('"')?;
+    for c in value[..end].chars() {
+        write!(f, "{}", c.escape_debug())?;
+    }
+    f.write_char('"')?;
+    if end < value.len() {
+        f.write_str("...")?;
+    }
+    Ok(())
+}
+
+fn write_bytes(f: &mut Formatter<'_>, value: &[u8], limit: Limit) -> fmt::Result {
+    let end = match limit {
+        Some(limit) => value.len().min(limit),
+        None => value.len()
+    };
+    write!(f, "\"{}\"", value[..end].escape_ascii())?;
+    if end < value.len() {
+        f.write_str("...")?;
+    }
+    Ok(())
+}
+
+impl Trace for ShortStr {
+    #[inline]
+    fn trace(&self, f: &mut Formatter<'_>, _: Limit) -> fmt::Result {
+        write_str(f, self.as_str(), None)
+    }
+}
+
+impl Trace for LongStr {
+    #[inline]
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        match self.to_str() {
+            Some(value) => write_str(f, value, limit),
+            None => write_bytes(f, self.as_bytes(), limit)
+        }
+    }
+}
+
+/// Credentials are rendered by their size only, a trace must not leak them
+struct Redacted<'a>(&'a LongStr);
+
+impl Trace for Redacted<'_> {
+    #[inline]
+    fn trace(&self, f: &mut Formatter<'_>, _: Limit) -> fmt::Result {
+        write!(f, "<{} bytes>", self.0.as_bytes().len())
+    }
+}
+
+impl Trace for FieldValue {
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        match self {
+            FieldValue::Boolean(v) => v.trace(f, limit),
+            FieldValue::U8(v) => write!(f, "{}", v),
+            FieldValue::I8(v) => write!(f, "{}", v),
+            FieldValue::U16(v) => write!(f, "{}", v),
+            FieldValue::I16(v) => write!(f, "{}", v),
+            FieldValue::U32(v) => write!(f, "{}", v),
+            FieldValue::I32(v) => write!(f, "{}", v),
+            FieldValue::U64(v) => write!(f, "{}", v),
+            FieldValue::I64(v) => write!(f, "{}", v),
+            FieldValue::F32(v) => write!(f, "{}", v),
+            FieldValue::F64(v) => write!(f, "{}", v),
+            FieldValue::Timestamp(v) => write!(f, "{}", v),
+            FieldValue::Decimal(v) => v.trace(f, limit),
+            FieldValue::LongStr(v) => v.trace(f, limit),
+            FieldValue::FieldArray(v) => v.trace(f, limit),
+            FieldValue::FieldTable(v) => v.trace(f, limit),
+            FieldValue::BytesArray(v) => v.trace(f, limit),
+            FieldValue::Void => f.write_str("void")
+        }
+    }
+}
+
+impl Trace for FieldArray {
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, value) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            value.trace(f, limit)?;
+        }
+        f.write_char(']')
+    }
+}
+
+impl Trace for FieldTable {
+    /// Entries are sorted by name, a table renders the same every time
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        f.write_char('{')?;
+        for (i, (name, value)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}=", name)?;
+            value.trace(f, limit)?;
+        }
+        f.write_char('}')
+    }
+}
+
+// the class and method a close reports are rendered as ids, like on the wire
+/// Writes `(name=value, name=value)`, nothing if there are no fields
+struct Fields<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    limit: Limit,
+    empty: bool
+}
+
+impl<'a, 'b> Fields<'a, 'b> {
+    fn new(f: &'a mut Formatter<'b>, limit: Limit) -> Self {
+        Fields { f, limit, empty: true }
+    }
+
+    fn field(&mut self, name: &str, value: impl Trace) -> fmt::Result {
+        self.f.write_str(if self.empty { "(" } else { ", " })?;
+        self.empty = false;
+        write!(self.f, "{}=", name)?;
+        value.trace(self.f, self.limit)
+    }
+
+    fn finish(self) -> fmt::Result {
+        if self.empty {
+            Ok(())
+        } else {
+            self.f.write_char(')')
+        }
+    }
+}
+
+macro_rules! field_name {
+    ($getter:ident) => { stringify!($getter) };
+    ($getter:ident $name:literal) => { $name };
+}
+
+macro_rules! field_value {
+    ($value:expr) => { $value };
+    ($value:expr, $wrap:ident) => { $wrap($value) };
+}
+
+// Field names are the spec names, reserved fields are left out, `=> Redacted` hides the value
+macro_rules! trace_arguments {
+    ($($args:ident { $($getter:ident $(as $name:literal)? $(=> $wrap:ident)?),* }),+) => {
+        $(
+            impl Trace for $args {
+                #[allow(unused_mut)]
+                fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+                    let mut fields = Fields::new(f, limit);
+                    $(fields.field(field_name!($getter $($name)?), field_value!(self.$getter() $(, $wrap)?))?;)*
+                    fields.finish()
+                }
+            }
+        )+
+
+        impl Trace for Arguments {
+            fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+                if let Arguments::Unknown { class_id, method_id, raw } = self {
+                    write!(f, "unknown(class_id={}, method_id={}, raw=", class_id, method_id)?;
+                    write_bytes(f, raw, limit)?;
+                    return f.write_char(')');
+                }
+                let (class, method) = self.class_method();
+                write!(f, "{}.{}", class.name(), method.name().replace('-', "_"))?;
+                match self {
+                    $(Arguments::$args(args) => args.trace(f, limit),)+
+                    Arguments::Unknown { .. } => Ok(())
+                }
+            }
+        }
+    }
+}
+
+trace_arguments! {
+    ConnectionStart { version_major, version_minor, server_properties, mechanisms, locales },
+    ConnectionStartOk { client_properties, mechanism, response => Redacted, locale },
+    ConnectionSecure { challenge },
+    ConnectionSecureOk { response => Redacted },
+    ConnectionTune { channel_max, frame_max, heartbeat },
+    ConnectionTuneOk { channel_max, frame_max, heartbeat },
+    ConnectionOpen { vhost as "virtual_host" },
+    ConnectionOpenOk {},
+    ConnectionClose { reply_code, reply_text, class_id, method_id },
+    ConnectionCloseOk {},
+    ConnectionBlocked { reason },
+    ConnectionUnblocked {},
+    ConnectionUpdateSecret { new_secret => Redacted, reason },
+    ConnectionUpdateSecretOk {},
+
+    ChannelOpen {},
+    ChannelOpenOk {},
+    ChannelFlow { active },
+    ChannelFlowOk { active },
+    ChannelClose { reply_code, reply_text, class_id, method_id },
+    ChannelCloseOk {},
+
+    AccessRequest { realm, exclusive, passive, active, write, read },
+    AccessRequestOk { ticket },
+
+    ExchangeDeclare { exchange_name as "exchange", exchange_type as "type", passive, durable, auto_delete, internal, no_wait, args as "arguments" },
+    ExchangeDeclareOk {},
+    ExchangeDelete { exchange_name as "exchange", if_unused, no_wait },
+    ExchangeDeleteOk {},
+    ExchangeBind { destination, source, routing_key, no_wait, args as "arguments" },
+    ExchangeBindOk {},
+    ExchangeUnbind { destination, source, routing_key, no_wait, args as "arguments" },
+    ExchangeUnbindOk {},
+
+    QueueDeclare { queue_name as "queue", passive, durable, exclusive, auto_delete, no_wait, args as "arguments" },
+    QueueDeclareOk { queue_name as "queue", message_count, consumer_count },
+    QueueBind { queue_name as "queue", exchange_name as "exchange", routing_key, no_wait, args as "arguments" },
+    QueueBindOk {},
+    QueueUnbind { queue_name as "queue", exchange_name as "exchange", routing_key, args as "arguments" },
+    QueueUnbindOk {},
+    QueuePurge { queue_name as "queue", no_wait },
+    QueuePurgeOk { message_count },
+    QueueDelete { queue_name as "queue", if_unused, if_empty, no_wait },
+    QueueDeleteOk { message_count },
+
+    BasicQos { prefetch_size, prefetch_count, global },
+    BasicQosOk {},
+    BasicConsume { queue_name as "queue", consumer_tag, no_local, no_ack, exclusive, no_wait, args as "arguments" },
+    BasicConsumeOk { consumer_tag },
+    BasicCancel { consumer_tag, no_wait },
+    BasicCancelOk { consumer_tag },
+    BasicPublish { exchange_name as "exchange", routing_key, mandatory, immediate },
+    BasicReturn { reply_code, reply_text, exchange_name as "exchange", routing_key },
+    BasicDeliver { consumer_tag, delivery_tag, redelivered, exchange_name as "exchange", routing_key },
+    BasicGet { queue_name as "queue", no_ack },
+    BasicGetOk { delivery_tag, redelivered, exchange_name as "exchange", routing_key, message_count },
+    BasicGetEmpty {},
+    BasicAck { delivery_tag, multiple },
+    BasicReject { delivery_tag, requeue },
+    BasicRecoverAsync { requeue },
+    BasicRecover { requeue },
+    BasicRecoverOk {},
+    BasicNack { delivery_tag, multiple, requeue },
+
+    TxSelect {},
+    TxSelectOk {},
+    TxCommit {},
+    TxCommitOk {},
+    TxRollback {},
+    TxRollbackOk {},
+
+    ConfirmSelect { no_wait as "nowait" },
+    ConfirmSelectOk {}
+}
+
+impl Trace for BasicProperties {
+    /// Only the properties which are present
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        let flags = self.flags();
+        let mut empty = true;
+        let mut property = |f: &mut Formatter<'_>, flag: u16, name: &str, value: &dyn Trace| -> fmt::Result {
+            if flags & flag == 0 {
+                return Ok(());
+            }
+            f.write_str(if empty { "" } else { ", " })?;
+            empty = false;
+            write!(f, "{}=", name)?;
+            value.trace(f, limit)
+        };
+        property(f, BasicProperties::CONTENT_TYPE_FLAG, "content_type", self.content_type())?;
+        property(f, BasicProperties::CONTENT_ENCODING_FLAG, "content_encoding", self.content_encoding())?;
+        property(f, BasicProperties::HEADERS_FLAG, "headers", self.headers())?;
+        property(f, BasicProperties::DELIVERY_FLAG, "delivery_mode", &self.delivery_mode())?;
+        property(f, BasicProperties::PRIORITY_FLAG, "priority", &self.priority())?;
+        property(f, BasicProperties::CORRELATION_ID_FLAG, "correlation_id", self.correlation_id())?;
+        property(f, BasicProperties::REPLY_TO_FLAG, "reply_to", self.reply_to())?;
+        property(f, BasicProperties::EXPIRATION_FLAG, "expiration", self.expiration())?;
+        property(f, BasicProperties::MESSAGE_ID_FLAG, "message_id", self.message_id())?;
+        property(f, BasicProperties::TIMESTAMP_FLAG, "timestamp", &self.timestamp())?;
+        property(f, BasicProperties::BASIC_TYPE_FLAG, "type", self.basic_type())?;
+        property(f, BasicProperties::USER_ID_FLAG, "user_id", self.user_id())?;
+        property(f, BasicProperties::APP_ID_FLAG, "app_id", self.app_id())?;
+        property(f, BasicProperties::CLUSTER_ID_FLAG, "cluster_id", self.cluster_id())
+    }
+}
+
+impl Trace for Property {
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        f.write_char('{')?;
+        // the other classes have no properties
+        if let Property::Basic(properties) = self {
+            properties.trace(f, limit)?;
+        }
+        f.write_char('}')
+    }
+}
+
+impl Trace for Frame {
+    fn trace(&self, f: &mut Formatter<'_>, limit: Limit) -> fmt::Result {
+        write!(f, "ch={} ", self.channel())?;
+        match self.payload() {
+            Payload::Heartbeat(_) => f.write_str("heartbeat"),
+            Payload::Method(method) => method.args().trace(f, limit),
+            Payload::ContentHeader(header) => {
+                write!(f, "content-header body_size={} props", header.body_size())?;
+                header.properties().trace(f, limit)
+            }
+            Payload::ContentBody(body) => {
+                write!(f, "content-body size={} ", body.len())?;
+                write_bytes(f, body, limit)
+            }
+        }
+    }
+}
+
+macro_rules! impl_display {
+    ($($t:ty),+) => {
+        $(
+            impl Display for $t {
+                #[inline]
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    let limit = limit(f);
+                    self.trace(f, limit)
+                }
+            }
+        )+
+    }
+}
+
+impl_display!(FieldValue, Arguments, Property, Frame);
@@ -0,0 +1,184 @@
+use std::collections::VecDeque;
+use std::io;
+use std::io::{IoSlice, Write};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use crate::error::EncodeError;
+use crate::frame::base::{Encode, Frame, Payload};
+use crate::frame::constants::{FRAME_BODY, FRAME_END};
+
+// bodies shorter than this are copied, a slice of their own costs more than the copy
+const INLINE_BODY_SIZE: usize = 256;
+
+enum Segment {
+    // start and end in the encoded bytes
+    Encoded(usize, usize),
+    Body(Bytes),
+}
+
+/// Encodes a batch of frames for `write_vectored`, content bodies are referenced instead of copied
+///
+/// Everything but the bodies is encoded into one buffer, `io_slices` puts the bodies back between
+/// their frame header and frame end in the order the frames were pushed.
+#[derive(Default)]
+pub struct VectoredEncoder {
+    encoded: BytesMut,
+    segments: VecDeque<Segment>,
+    len: usize,
+    frame_max: u32,
+}
+
+impl VectoredEncoder {
+    pub fn new() -> Self {
+        VectoredEncoder::default()
+    }
+
+    /// Bytes left to write
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn frame_max(&self) -> u32 {
+        self.frame_max
+    }
+
+    /// Frames larger than the negotiated `frame_max` fail to push, 0 is no limit
+    #[inline]
+    pub fn set_frame_max(&mut self, frame_max: u32) -> &mut Self {
+        self.frame_max = frame_max;
+        self
+    }
+
+    pub fn clear(&mut self) {
+        self.encoded.clear();
+        self.segments.clear();
+        self.len = 0;
+    }
+
+    /// Encode a frame, the body of a content body frame is moved into the batch
+    ///
+    /// A frame larger than `frame_max` is an error and leaves the batch as it was.
+    pub fn push(&mut self, frame: Frame) -> Result<(), EncodeError> {
+        if self.frame_max != 0 {
+            let size = frame.encoded_len();
+            if size > self.frame_max as usize {
+                return Err(EncodeError::FrameTooLarge { size, frame_max: self.frame_max });
+            }
+        }
+        let inline = match frame.payload() {
+            Payload::ContentBody(body) => body.len() < INLINE_BODY_SIZE,
+            _ => true
+        };
+        if !inline {
+            let channel = frame.channel();
+            if let Payload::ContentBody(body) = frame.into_payload() {
+                return self.push_body(channel, Bytes::from(body));
+            }
+            unreachable!();
+        }
+        let start = self.encoded.len();
+        frame.try_encode(&mut self.encoded)?;
+        self.push_encoded(start);
+        Ok(())
+    }
+
+    /// Encode a content body frame of a body which is already `Bytes`, such as a slice of a larger buffer
+    pub fn push_body(&mut self, channel: u16, body: Bytes) -> Result<(), EncodeError> {
+        if body.len() > u32::MAX as usize {
+            return Err(EncodeError::FrameTooLarge { size: body.len() + 8, frame_max: u32::MAX });
+        }
+        if self.frame_max != 0 && body.len() + 8 > self.frame_max as usize {
+            return Err(EncodeError::FrameTooLarge { size: body.len() + 8, frame_max: self.frame_max });
+        }
+        let start = self.encoded.len();
+        self.encoded.put_u8(FRAME_BODY);
+        self.encoded.put_u16(channel);
+        self.encoded.put_u32(body.len() as u32);
+        if body.len() < INLINE_BODY_SIZE {
+            self.encoded.extend_from_slice(&body);
+            self.encoded.put_u8(FRAME_END);
+            self.push_encoded(start);
+            return Ok(());
+        }
+        self.push_encoded(start);
+        self.len += body.len();
+        self.segments.push_back(Segment::Body(body));
+        let start = self.encoded.len();
+        self.encoded.put_u8(FRAME_END);
+        self.push_encoded(start);
+        Ok(())
+    }
+
+    /// The bytes left to write in order, for `write_vectored`
+    pub fn io_slices(&self) -> Vec<IoSlice<'_>> {
+        self.segments.iter().map(|segment| match segment {
+            Segment::Encoded(start, end) => IoSlice::new(&self.encoded[*start..*end]),
+            Segment::Body(body) => IoSlice::new(body)
+        }).collect()
+    }
+
+    /// Drop `count` bytes from the front after they were written
+    ///
+    /// # Panics
+    /// If `count` is larger than `len`
+    pub fn advance(&mut self, mut count: usize) {
+        assert!(count <= self.len, "advance past the end of the batch");
+        self.len -= count;
+        while count > 0 {
+            let done = match self.segments.front_mut() {
+                Some(Segment::Encoded(start, end)) => {
+                    let taken = count.min(*end - *start);
+                    *start += taken;
+                    count -= taken;
+                    start == end
+                }
+                Some(Segment::Body(body)) => {
+                    let taken = count.min(body.len());
+                    body.advance(taken);
+                    count -= taken;
+                    body.is_empty()
+                }
+                None => break
+            };
+            if done {
+                self.segments.pop_front();
+            }
+        }
+        if self.segments.is_empty() {
+            self.encoded.clear();
+        }
+    }
+
+    /// Write the whole batch with `write_vectored`, the batch is empty afterwards unless it fails
+    pub fn write_to<W: Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        while !self.is_empty() {
+            let written = match writer.write_vectored(&self.io_slices()) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write frames")),
+                Ok(written) => written,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            };
+            self.advance(written);
+        }
+        Ok(())
+    }
+
+    // the encoded bytes from start to the end of the buffer follow the last segment
+    fn push_encoded(&mut self, start: usize) {
+        let end = self.encoded.len();
+        if start == end {
+            return;
+        }
+        self.len += end - start;
+        match self.segments.back_mut() {
+            Some(Segment::Encoded(_, last_end)) if *last_end == start => *last_end = end,
+            _ => self.segments.push_back(Segment::Encoded(start, end))
+        }
+    }
+}
@@ -5,12 +5,37 @@ mod method;
 mod frame;
 mod error;
 
+/// SASL mechanisms used by connection authentication
+pub mod sasl;
+
+/// Tokio based client, enabled by the `client` feature
+#[cfg(feature = "client")]
+pub mod client;
+
+/// Proxy between a client and a broker which rewrites frames, enabled by the `proxy` feature
+#[cfg(feature = "proxy")]
+pub mod proxy;
+
+/// Route the frames of one connection to per channel queues and interleave their outbound frames,
+/// enabled by the `mux` feature
+#[cfg(feature = "mux")]
+pub mod mux;
+
 /// Complex amqp types
 pub use frame::base::{Timestamp, ShortStr, LongStr, Decimal, FieldName, FieldValue, FieldArray, FieldTable, BytesArray};
 
 /// Method type and id definitions
 pub use method::{AccessMethod, BasicMethod, ChannelMethod, ConnectionMethod, ConfirmMethod, ExchangeMethod, QueueMethod, TxMethod, Method, MethodId};
 
+/// Match synchronous method replies with their requests
+pub use method::{RpcQueue, UnexpectedReply};
+
+/// Allocate channel ids up to channel_max, reused once the channel is closed
+pub use method::ChannelIds;
+
+/// Track the transaction of a channel and keep tx and confirm mode apart
+pub use method::{ChannelMode, TxAck, TxOutcome, TxTracker};
+
 /// Class type and id definitions
 pub use class::Class;
 
@@ -38,21 +63,45 @@ pub mod arguments {
     pub use crate::frame::method::tx;
 }
 
-/// Decode and Encode frame, also has an tokio frame codec.
+/// Decode and Encode frame, also has frame codecs for tokio, `futures::io` and blocking `std::io`.
 pub mod codec {
-    pub use crate::frame::frame_codec::{DecodedFrame, FrameCodec};
-    pub use crate::frame::base::{ContentHeaderPayload, HeartbeatPayload, MethodPayload, Payload, Frame, ProtocolHeader, Decode, Encode};
+    pub use crate::frame::frame_codec::{DecodedFrame, FrameCodec, Role};
+    pub use crate::frame::blocking::{FrameReader, FrameWriter};
+    pub use crate::frame::vectored::VectoredEncoder;
+    #[cfg(feature = "futures-io")]
+    pub use crate::frame::futures_io::FuturesFramed;
+    pub use crate::frame::base::{ContentHeaderPayload, HeartbeatPayload, MethodPayload, Payload, Frame, FrameType, Arguments, Property, ProtocolHeader, Decode, Encode};
+    pub use crate::frame::constants::{FRAME_METHOD, FRAME_HEADER, FRAME_BODY, FRAME_HEARTBEAT, FRAME_MIN_SIZE, FRAME_END};
+}
+
+/// Zero copy decoding, the decoded values borrow from the input buffer
+pub mod borrowed {
+    pub use crate::frame::borrowed::{DecodeRef, ShortStrRef, LongStrRef, FieldValueRef, FieldArrayRef, FieldArrayIter, FieldTableRef, FieldTableIter};
+    pub use crate::frame::borrowed::{BasicPublishRef, BasicReturnRef, BasicDeliverRef, MethodPayloadRef, FrameRef};
+}
+
+/// Proptest generators of realistic amqp values, enabled by the `arbitrary` feature
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary {
+    pub use crate::frame::arbitrary::{short_str, long_str, field_name, decimal, field_value, field_array, field_table};
+    pub use crate::frame::arbitrary::{basic_properties, arguments, frame};
 }
 
 /// Frame decode error and amqp protocol error definitions.
 pub mod err {
-    pub use crate::error::FrameDecodeErr;
+    pub use crate::error::{FrameDecodeErr, FrameReadErr, EncodeError, DecodeError, FieldPath};
     pub use crate::error::amqp::{AmqpError, AmqpErrorKind};
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{LongStr, FieldValue, FieldTable, FieldName};
+    use crate::{ShortStr, LongStr, FieldValue, FieldTable, FieldName, Method, BasicMethod, QueueMethod, RpcQueue};
+    use crate::frame::base::Arguments;
+    use crate::frame::method::queue::QueueDeclare;
+    use crate::frame::header::basic::BasicProperties;
+    use crate::codec::{Frame, FrameType, Property, Encode};
+    use crate::err::{AmqpErrorKind, FrameDecodeErr};
+    use crate::Class;
     use crate::frame::method::connection::ConnectionStart;
     use crate::codec::{Decode};
     use bytes::{BytesMut, BufMut};
@@ -95,4 +144,412 @@ mod tests {
             panic!("Expected FieldTable value");
         }
     }
+
+    #[test]
+    fn test_rpc_queue() {
+        assert!(Arguments::QueueDeclare(QueueDeclare::default()).expects_reply());
+        let mut declare = QueueDeclare::default();
+        declare.set_no_wait(true);
+        assert!(!Arguments::QueueDeclare(declare).expects_reply());
+        assert!(!Method::Basic(BasicMethod::Publish).is_synchronous());
+
+        let mut queue = RpcQueue::new();
+        queue.push(Method::Basic(BasicMethod::Get), 1);
+        queue.push(Method::Queue(QueueMethod::Declare), 2);
+        assert!(queue.complete(Method::Queue(QueueMethod::DeclareOk)).is_err());
+        assert!(matches!(queue.complete(Method::Basic(BasicMethod::GetEmpty)), Ok((_, 1))));
+        assert!(matches!(queue.complete(Method::Queue(QueueMethod::DeclareOk)), Ok((_, 2))));
+        assert!(queue.complete(Method::Basic(BasicMethod::QosOk)).is_err());
+    }
+
+    #[test]
+    fn test_spec_ids() {
+        assert_eq!(BasicMethod::from(120), BasicMethod::Nack);
+        assert!(Method::Basic(BasicMethod::Deliver).has_content());
+
+        let mut buffer = BytesMut::with_capacity(8);
+        Frame::heartbeat_frame().encode(&mut buffer);
+        assert_eq!(&buffer[..], Frame::heartbeat_frame_bytes());
+        let (_, frame) = Frame::decode(&buffer).unwrap();
+        assert_eq!(frame.frame_type(), &FrameType::HEARTBEAT);
+    }
+
+    #[test]
+    fn test_decode_error_path() {
+        let mut headers = FieldTable::new();
+        let items = vec![FieldValue::from_i32(0x11111111), FieldValue::from_i32(0x22222222), FieldValue::from_i32(0x33333333)];
+        headers.insert(FieldName::with_bytes(b"x-foo").unwrap(), FieldValue::from_field_array(items));
+        let mut properties = BasicProperties::default();
+        properties.set_headers(headers);
+        let mut buffer = BytesMut::with_capacity(128);
+        Frame::content_header_frame(3, Class::Basic, 0, Property::Basic(properties)).encode(&mut buffer);
+
+        // break the type tag of the third array item
+        let offset = buffer.windows(4).position(|w| w == [0x33u8; 4]).unwrap() - 1;
+        buffer[offset] = b'!';
+        match Frame::decode(&buffer) {
+            Err(FrameDecodeErr::DecodeError(e)) => {
+                assert_eq!(e.path_string(), "BasicProperties.headers[\"x-foo\"][2]");
+                assert_eq!(e.offset(), Some(offset));
+                assert_eq!(e.channel(), Some(3));
+                assert_eq!(e.class_id(), Some(60));
+                assert_eq!(e.kind(), AmqpErrorKind::SyntaxError);
+            }
+            _ => panic!("Expected decode error")
+        }
+    }
+
+    #[test]
+    fn test_field_value_eq_hash() {
+        use std::collections::HashSet;
+
+        // floats compare by their bits
+        assert_eq!(FieldValue::from_f64(f64::NAN), FieldValue::from_f64(f64::NAN));
+        assert_ne!(FieldValue::from_f32(0.0), FieldValue::from_f32(-0.0));
+
+        // tables are equal and hash the same whatever the insertion order
+        let names = [b"a", b"b", b"c", b"d"];
+        let mut forward = FieldTable::new();
+        let mut backward = FieldTable::new();
+        for (i, name) in names.iter().enumerate() {
+            forward.insert(FieldName::with_bytes(*name).unwrap(), FieldValue::from_f32(i as f32));
+        }
+        for (i, name) in names.iter().enumerate().rev() {
+            backward.insert(FieldName::with_bytes(*name).unwrap(), FieldValue::from_f32(i as f32));
+        }
+        let mut set = HashSet::new();
+        set.insert(FieldValue::from_field_table(forward.clone()));
+        assert!(set.contains(&FieldValue::from_field_table(backward.clone())));
+
+        // a decoded frame equals and hashes the same as the frame it was encoded from, whose length is not set
+        let mut args = QueueDeclare::default();
+        args.set_queue_name(ShortStr::with_bytes(b"q").unwrap()).set_durable(true).set_args(forward);
+        let frame = Frame::method_frame(1, Arguments::QueueDeclare(args.clone()));
+        let mut buffer = BytesMut::new();
+        frame.clone().encode(&mut buffer);
+        let decoded = Frame::decode(&buffer).unwrap().1;
+        assert_eq!(decoded, frame);
+        let mut frames = HashSet::new();
+        frames.insert(frame);
+        assert!(frames.contains(&decoded));
+        args.set_args(backward);
+        assert!(frames.contains(&Frame::method_frame(1, Arguments::QueueDeclare(args.clone()))));
+        args.set_durable(false);
+        assert!(!frames.contains(&Frame::method_frame(1, Arguments::QueueDeclare(args))));
+    }
+
+    #[test]
+    fn test_frame_display() {
+        use crate::frame::method::basic::BasicPublish;
+
+        let mut args = BasicPublish::default();
+        args.set_exchange_name(ShortStr::with_bytes(b"x").unwrap())
+            .set_routing_key(ShortStr::with_bytes(b"rk").unwrap())
+            .set_mandatory(true);
+        let frame = Frame::method_frame(1, Arguments::BasicPublish(args));
+        assert_eq!(frame.to_string(), r#"ch=1 basic.publish(exchange="x", routing_key="rk", mandatory=true, immediate=false)"#);
+        assert_eq!(format!("{:.1}", frame), r#"ch=1 basic.publish(exchange="x", routing_key="rk", mandatory=true, immediate=false)"#);
+        let frame = Frame::method_frame(1, Arguments::QueueDeclare(QueueDeclare::default()));
+        assert_eq!(frame.to_string(), r#"ch=1 queue.declare(queue="", passive=false, durable=false, exclusive=false, auto_delete=false, no_wait=false, arguments={})"#);
+
+        let mut headers = FieldTable::new();
+        headers.insert(FieldName::with_bytes(b"b").unwrap(), FieldValue::from_field_array(vec![FieldValue::from_u8(1), FieldValue::from_void()]));
+        headers.insert(FieldName::with_bytes(b"a").unwrap(), FieldValue::from_long_string(LongStr::with_bytes(b"hello").unwrap()));
+        let mut properties = BasicProperties::default();
+        properties.set_content_type(ShortStr::with_bytes(b"application/json").unwrap());
+        properties.set_headers(headers);
+        let frame = Frame::content_header_frame(1, Class::Basic, 512, Property::Basic(properties));
+        assert_eq!(frame.to_string(), r#"ch=1 content-header body_size=512 props{content_type="application/json", headers={a="hello", b=[1, void]}}"#);
+
+        // bodies are cut after 128 bytes unless a precision or `#` says otherwise
+        let frame = Frame::content_body_frame(1, vec![b'a'; 200]);
+        assert_eq!(frame.to_string(), format!("ch=1 content-body size=200 \"{}\"...", "a".repeat(128)));
+        assert_eq!(format!("{:.4}", frame), "ch=1 content-body size=200 \"aaaa\"...");
+        assert_eq!(format!("{:#}", frame), format!("ch=1 content-body size=200 \"{}\"", "a".repeat(200)));
+        assert_eq!(Frame::content_body_frame(2, b"\x00\n".to_vec()).to_string(), r#"ch=2 content-body size=2 "\x00\n""#);
+        assert_eq!(Frame::heartbeat_frame().to_string(), "ch=0 heartbeat");
+
+        // sasl responses carry credentials, only their size is shown
+        use crate::frame::method::connection::{ConnectionStartOk, ConnectionSecureOk};
+        let mut start_ok = ConnectionStartOk::default();
+        start_ok.set_mechanism(ShortStr::with_bytes(b"PLAIN").unwrap())
+            .set_response(LongStr::with_bytes(b"\x00guest\x00s3cret").unwrap());
+        let frame = Frame::method_frame(0, Arguments::ConnectionStartOk(start_ok));
+        for trace in &[frame.to_string(), format!("{:#}", frame)] {
+            assert!(!trace.contains("s3cret"));
+            assert!(trace.contains("response=<13 bytes>"));
+        }
+        let mut secure_ok = ConnectionSecureOk::default();
+        secure_ok.set_response(LongStr::with_bytes(b"c=biws,r=nonce,p=proof").unwrap());
+        let frame = Frame::method_frame(0, Arguments::ConnectionSecureOk(secure_ok));
+        assert_eq!(format!("{:#}", frame), "ch=0 connection.secure_ok(response=<22 bytes>)");
+        use crate::frame::method::connection::ConnectionUpdateSecret;
+        let mut update_secret = ConnectionUpdateSecret::default();
+        update_secret.set_new_secret(LongStr::with_bytes(b"eyJhbGciOiJIUzI1NiJ9.token").unwrap())
+            .set_reason(ShortStr::with_bytes(b"token refresh").unwrap());
+        let frame = Frame::method_frame(0, Arguments::ConnectionUpdateSecret(update_secret));
+        assert_eq!(format!("{:#}", frame), r#"ch=0 connection.update_secret(new_secret=<26 bytes>, reason="token refresh")"#);
+    }
+
+    #[test]
+    fn test_blocking_reader_writer() {
+        use std::io::{self, Read};
+        use crate::codec::{DecodedFrame, FrameReader, FrameWriter, ProtocolHeader};
+        use crate::err::{EncodeError, FrameReadErr};
+
+        // hands out a few bytes per read like a slow socket
+        struct Trickle(io::Cursor<Vec<u8>>);
+
+        impl Read for Trickle {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = buf.len().min(3);
+                self.0.read(&mut buf[..len])
+            }
+        }
+
+        let publish = Frame::method_frame(1, Arguments::BasicPublish(Default::default()));
+        let body = Frame::content_body_frame(1, b"hello".to_vec());
+        let mut writer = FrameWriter::with_batch_size(Vec::new(), 64);
+        writer.write_protocol_header(&ProtocolHeader::default()).unwrap();
+        writer.write_frame(&publish).unwrap();
+        assert!(writer.get_ref().is_empty());
+        writer.write_frame(&body).unwrap();
+        writer.write_frame(&Frame::heartbeat_frame()).unwrap();
+        assert_ne!(writer.buffered(), 0);
+        let bytes = writer.into_inner().unwrap();
+
+        let mut reader = FrameReader::new(Trickle(io::Cursor::new(bytes.clone())));
+        assert_eq!(reader.read_frame().unwrap(), Some(DecodedFrame::ProtocolHeader(ProtocolHeader::default())));
+        let frames = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(frames, vec![DecodedFrame::AmqpFrame(publish.clone()), DecodedFrame::AmqpFrame(body.clone()), DecodedFrame::AmqpFrame(Frame::heartbeat_frame())]);
+
+        // the stream ends inside the last frame
+        let mut reader = FrameReader::new(&bytes[..bytes.len() - 1]);
+        assert_eq!(reader.by_ref().take(3).count(), 3);
+        match reader.read_frame() {
+            Err(FrameReadErr::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            _ => panic!("Expected unexpected eof")
+        }
+
+        // a server which does not support the version answers with its own protocol header
+        let mut reader = FrameReader::client(&bytes[..]);
+        assert_eq!(reader.read_frame().unwrap(), Some(DecodedFrame::VersionMismatch(ProtocolHeader::default())));
+        let mut reader = FrameReader::client(&bytes[8..]);
+        assert_eq!(reader.read_frame().unwrap(), Some(DecodedFrame::AmqpFrame(Frame::method_frame(1, Arguments::BasicPublish(Default::default())))));
+
+        // takes a few bytes, then fails once
+        struct Flaky { written: Vec<u8>, fail: bool }
+
+        impl io::Write for Flaky {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if std::mem::take(&mut self.fail) {
+                    return Err(io::Error::new(io::ErrorKind::WouldBlock, "full"));
+                }
+                self.fail = true;
+                let len = buf.len().min(5);
+                self.written.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        // the bytes written before an error are not written again
+        let mut writer = FrameWriter::new(Flaky { written: Vec::new(), fail: false });
+        assert!(writer.write_frame(&body).is_err());
+        assert_eq!(writer.buffered(), body.encoded_len() - 5);
+        while writer.flush().is_err() {}
+        assert_eq!(writer.get_ref().written, bytes[8 + publish.encoded_len()..8 + publish.encoded_len() + body.encoded_len()].to_vec());
+
+        // frame_max applies to the written frames
+        let mut writer = FrameWriter::new(Vec::new());
+        writer.codec_mut().set_frame_max(4096);
+        assert!(matches!(writer.write_frame(&Frame::content_body_frame(1, vec![0; 4096])), Err(EncodeError::FrameTooLarge { .. })));
+        assert_eq!(writer.buffered(), 0);
+        writer.write_frame(&body).unwrap();
+    }
+
+    #[test]
+    fn test_try_encode() {
+        use crate::codec::FrameCodec;
+        use crate::err::EncodeError;
+
+        // invalid UTF-8 grows when it is replaced
+        let queue_name = ShortStr::with_bytes(&[0xff; 200]).unwrap();
+        let mut buffer = BytesMut::new();
+        assert!(matches!(queue_name.try_encode(&mut buffer), Err(EncodeError::ShortStrTooLong(600))));
+        let mut declare = QueueDeclare::default();
+        declare.set_queue_name(queue_name);
+        buffer.put_u8(1);
+        let frame = Frame::method_frame(1, Arguments::QueueDeclare(declare));
+        assert!(matches!(frame.try_encode(&mut buffer), Err(EncodeError::ShortStrTooLong(600))));
+        assert_eq!(&buffer[..], &[1]);
+
+        let name = FieldName::with_bytes(&[&b"a"[..], &[0xff; 50]].concat()).unwrap();
+        let mut headers = FieldTable::new();
+        headers.insert(name, FieldValue::from_void());
+        assert!(matches!(headers.try_encode(&mut buffer), Err(EncodeError::InvalidFieldName(_))));
+
+        let mut codec = FrameCodec::without_protocol_header();
+        codec.set_frame_max(64);
+        let mut buffer = BytesMut::new();
+        codec.encode_frame(&Frame::content_body_frame(1, vec![0; 56]), &mut buffer).unwrap();
+        assert_eq!(buffer.len(), 64);
+        match codec.encode_frame(&Frame::content_body_frame(1, vec![0; 57]), &mut buffer) {
+            Err(EncodeError::FrameTooLarge { size, frame_max }) => assert_eq!((size, frame_max), (65, 64)),
+            _ => panic!("Expected frame too large")
+        }
+        assert_eq!(buffer.len(), 64);
+
+        // the limit applies to decoding, a frame declaring 4 GiB fails on its header
+        assert!(matches!(codec.decode_frame(&mut buffer), Ok(Some(_))));
+        let mut buffer = BytesMut::from(&[3u8, 0, 1, 0xff, 0xff, 0xff, 0xf7][..]);
+        match codec.decode_frame(&mut buffer) {
+            Err(e) => {
+                assert_eq!(e.kind(), AmqpErrorKind::FrameError);
+                assert_eq!(e.to_string(), "Decode error: frame size 4294967295 is larger than frame_max 64 (501, Frame error)");
+            }
+            _ => panic!("Expected frame too large")
+        }
+    }
+
+    #[test]
+    fn test_vectored_encoder() {
+        use std::io::{self, Write};
+        use bytes::Bytes;
+        use crate::codec::VectoredEncoder;
+        use crate::err::EncodeError;
+
+        // takes a few bytes of the first slice per write like a full socket
+        struct Trickle(Vec<u8>);
+
+        impl Write for Trickle {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let len = buf.len().min(5);
+                self.0.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let body = Bytes::from(vec![b'x'; 1000]);
+        let frames = [
+            Frame::method_frame(1, Arguments::BasicPublish(Default::default())),
+            Frame::content_header_frame(1, Class::Basic, 1300, Property::Basic(BasicProperties::default())),
+            Frame::content_body_frame(1, vec![b'y'; 300]),
+            Frame::content_body_frame(1, body.to_vec()),
+            Frame::heartbeat_frame()
+        ];
+        let mut expected = BytesMut::new();
+        frames.iter().for_each(|frame| frame.encode(&mut expected));
+
+        let mut encoder = VectoredEncoder::new();
+        for frame in frames.iter().take(3).cloned() {
+            encoder.push(frame).unwrap();
+        }
+        encoder.push_body(1, body.clone()).unwrap();
+        encoder.push(Frame::heartbeat_frame()).unwrap();
+        assert_eq!(encoder.len(), expected.len());
+        // the method, header and the first body's frame header, its body, its frame end and the second body's
+        // frame header, the second body, the frame ends and the heartbeat
+        let slices = encoder.io_slices();
+        assert_eq!(slices.len(), 5);
+        assert_eq!(slices[3].as_ptr(), body.as_ptr());
+        assert_eq!(slices.iter().flat_map(|slice| slice.iter().copied()).collect::<Vec<_>>(), &expected[..]);
+
+        let mut writer = Trickle(Vec::new());
+        encoder.write_to(&mut writer).unwrap();
+        assert!(encoder.is_empty() && encoder.io_slices().is_empty());
+        assert_eq!(writer.0, &expected[..]);
+
+        // frames larger than frame_max are refused and leave the batch empty
+        encoder.set_frame_max(1008);
+        encoder.push_body(1, body.clone()).unwrap();
+        encoder.clear();
+        assert!(matches!(encoder.push_body(1, Bytes::from(vec![b'x'; 1001])), Err(EncodeError::FrameTooLarge { size: 1009, frame_max: 1008 })));
+        assert!(matches!(encoder.push(Frame::content_body_frame(1, vec![b'x'; 1001])), Err(EncodeError::FrameTooLarge { size: 1009, frame_max: 1008 })));
+        assert!(encoder.is_empty());
+    }
+
+    #[test]
+    fn test_frame_codec_roles() {
+        use crate::codec::{DecodedFrame, FrameCodec, ProtocolHeader, Role};
+        use crate::arguments::connection::ConnectionStart;
+
+        let start = Frame::method_frame(0, Arguments::ConnectionStart(ConnectionStart::default()));
+        let mut bytes = BytesMut::new();
+        start.encode(&mut bytes);
+        let mut codec = FrameCodec::client();
+        assert_eq!(codec.role(), Role::Client);
+        assert_eq!(codec.decode_frame(&mut bytes.clone()).unwrap(), Some(DecodedFrame::AmqpFrame(start)));
+
+        // a broker which only speaks 0-8 answers the 0-9-1 header with its own
+        let mut old = ProtocolHeader::default();
+        old.set_major_version(8u8).set_minor_version(0u8);
+        let mut bytes = BytesMut::new();
+        old.encode(&mut bytes);
+        assert_eq!(FrameCodec::client().decode_frame(&mut bytes.clone()).unwrap(), Some(DecodedFrame::VersionMismatch(old.clone())));
+        assert_eq!(FrameCodec::server().decode_frame(&mut bytes.clone()).unwrap(), Some(DecodedFrame::ProtocolHeader(old)));
+        assert!(FrameCodec::client().decode_frame(&mut BytesMut::from(&b"AMQ"[..])).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tx_tracker() {
+        use crate::{ChannelMode, TxAck, TxOutcome, TxTracker};
+        use crate::arguments::basic::BasicAck;
+
+        let mut confirm = TxTracker::<u32>::new();
+        confirm.confirm_select().unwrap();
+        assert_eq!(confirm.select().unwrap_err().kind(), Some(AmqpErrorKind::PreconditionFailed));
+        assert_eq!(confirm.publish(1), Some(1));
+        assert_eq!(confirm.commit().unwrap_err().kind(), Some(AmqpErrorKind::PreconditionFailed));
+
+        let mut tx = TxTracker::new();
+        // checking does not change the mode, a client selects once the server replied
+        tx.check_select().unwrap();
+        assert_eq!(tx.mode(), ChannelMode::Normal);
+        tx.select().unwrap();
+        assert_eq!(tx.mode(), ChannelMode::Transactional);
+        assert_eq!(tx.confirm_select().unwrap_err().kind(), Some(AmqpErrorKind::PreconditionFailed));
+        let mut ack = BasicAck::default();
+        ack.set_delivery_tag(7u64);
+        assert_eq!(tx.publish(1), None);
+        assert_eq!(tx.ack(TxAck::Ack(ack.clone())), None);
+        assert_eq!(tx.pending_publishes(), &[1]);
+        assert_eq!(tx.commit().unwrap(), TxOutcome::Committed { publishes: vec![1], acks: vec![TxAck::Ack(ack)] });
+        tx.publish(2);
+        let rolled_back = tx.rollback().unwrap();
+        assert!(!rolled_back.is_committed());
+        assert_eq!(rolled_back.publishes(), &[2]);
+        assert!(tx.pending_publishes().is_empty() && tx.pending_acks().is_empty());
+        // an empty transaction
+        assert!(tx.commit().unwrap().publishes().is_empty());
+    }
+
+    #[cfg(feature = "futures-io")]
+    #[test]
+    fn test_futures_framed() {
+        use futures::{executor, SinkExt, StreamExt};
+        use futures::io::Cursor;
+        use crate::codec::{DecodedFrame, FrameCodec, FuturesFramed};
+
+        let publish = Frame::method_frame(1, Arguments::BasicPublish(Default::default()));
+        let mut framed = FuturesFramed::new(Cursor::new(Vec::new()), FrameCodec::without_protocol_header());
+        executor::block_on(framed.send(publish.clone())).unwrap();
+        executor::block_on(framed.send(Frame::heartbeat_frame())).unwrap();
+        let mut bytes = framed.into_inner().into_inner();
+        bytes.extend_from_slice(&[1, 0]);
+
+        let framed = FuturesFramed::new(Cursor::new(bytes), FrameCodec::without_protocol_header());
+        let frames = executor::block_on(framed.collect::<Vec<_>>());
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].as_ref().unwrap(), &DecodedFrame::AmqpFrame(publish));
+        assert_eq!(frames[1].as_ref().unwrap(), &DecodedFrame::AmqpFrame(Frame::heartbeat_frame()));
+        assert!(frames[2].is_err());
+    }
 }
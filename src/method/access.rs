@@ -1,12 +1,52 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum AccessMethod {
     Request,
     RequestOk,
     Unknown
 }
 
+impl AccessMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        false
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            AccessMethod::Request => &[Method::Access(AccessMethod::RequestOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, AccessMethod::RequestOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        false
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessMethod::Request => "request",
+            AccessMethod::RequestOk => "request-ok",
+            AccessMethod::Unknown => "unknown"
+        }
+    }
+}
+
 impl MethodId for AccessMethod {
     #[inline]
     fn method_id(&self) -> u16 {
@@ -34,4 +74,4 @@ impl From<u16> for AccessMethod {
             _  => AccessMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
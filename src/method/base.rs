@@ -13,7 +13,7 @@ pub trait MethodId {
     fn method_id(&self) -> u16;
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Method {
     Connection(ConnectionMethod),
     Channel(ChannelMethod),
@@ -46,6 +46,97 @@ impl Default for Method {
     }
 }
 
+impl Method {
+    pub fn class(&self) -> Class {
+        match self {
+            Method::Connection(_) => Class::Connection,
+            Method::Channel(_) => Class::Channel,
+            Method::Access(_) => Class::Access,
+            Method::Exchange(_) => Class::Exchange,
+            Method::Queue(_) => Class::Queue,
+            Method::Basic(_) => Class::Basic,
+            Method::Confirm(_) => Class::Confirm,
+            Method::Tx(_) => Class::Tx
+        }
+    }
+
+    /// Name of the method in the spec, without the class
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::Connection(method) => method.name(),
+            Method::Channel(method) => method.name(),
+            Method::Access(method) => method.name(),
+            Method::Exchange(method) => method.name(),
+            Method::Queue(method) => method.name(),
+            Method::Basic(method) => method.name(),
+            Method::Confirm(method) => method.name(),
+            Method::Tx(method) => method.name()
+        }
+    }
+
+    /// The method is followed by a content header frame and content body frames
+    pub fn has_content(&self) -> bool {
+        match self {
+            Method::Connection(method) => method.has_content(),
+            Method::Channel(method) => method.has_content(),
+            Method::Access(method) => method.has_content(),
+            Method::Exchange(method) => method.has_content(),
+            Method::Queue(method) => method.has_content(),
+            Method::Basic(method) => method.has_content(),
+            Method::Confirm(method) => method.has_content(),
+            Method::Tx(method) => method.has_content()
+        }
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            Method::Connection(method) => method.responses(),
+            Method::Channel(method) => method.responses(),
+            Method::Access(method) => method.responses(),
+            Method::Exchange(method) => method.responses(),
+            Method::Queue(method) => method.responses(),
+            Method::Basic(method) => method.responses(),
+            Method::Confirm(method) => method.responses(),
+            Method::Tx(method) => method.responses()
+        }
+    }
+
+    /// The peer must reply to this method, unless `no_wait` is set on methods which carry the flag
+    #[inline]
+    pub fn is_synchronous(&self) -> bool {
+        !self.responses().is_empty()
+    }
+
+    /// This method is the reply of a synchronous method
+    pub fn is_response(&self) -> bool {
+        match self {
+            Method::Connection(method) => method.is_response(),
+            Method::Channel(method) => method.is_response(),
+            Method::Access(method) => method.is_response(),
+            Method::Exchange(method) => method.is_response(),
+            Method::Queue(method) => method.is_response(),
+            Method::Basic(method) => method.is_response(),
+            Method::Confirm(method) => method.is_response(),
+            Method::Tx(method) => method.is_response()
+        }
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    pub fn has_no_wait(&self) -> bool {
+        match self {
+            Method::Connection(method) => method.has_no_wait(),
+            Method::Channel(method) => method.has_no_wait(),
+            Method::Access(method) => method.has_no_wait(),
+            Method::Exchange(method) => method.has_no_wait(),
+            Method::Queue(method) => method.has_no_wait(),
+            Method::Basic(method) => method.has_no_wait(),
+            Method::Confirm(method) => method.has_no_wait(),
+            Method::Tx(method) => method.has_no_wait()
+        }
+    }
+}
+
 pub(crate) fn get_method_type(class: Class, method_id: u16) -> Result<Method, FrameDecodeErr> {
     match class {
         Class::Connection => {
@@ -1,6 +1,7 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum BasicMethod {
     Qos,
     QosOk,
@@ -23,7 +24,67 @@ pub enum BasicMethod {
     Unknown
 }
 
+impl BasicMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        matches!(self, BasicMethod::Publish | BasicMethod::Return | BasicMethod::Deliver | BasicMethod::GetOk)
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            BasicMethod::Qos => &[Method::Basic(BasicMethod::QosOk)],
+            BasicMethod::Consume => &[Method::Basic(BasicMethod::ConsumeOk)],
+            BasicMethod::Cancel => &[Method::Basic(BasicMethod::CancelOk)],
+            BasicMethod::Get => &[Method::Basic(BasicMethod::GetOk), Method::Basic(BasicMethod::GetEmpty)],
+            BasicMethod::Recover => &[Method::Basic(BasicMethod::RecoverOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, BasicMethod::QosOk | BasicMethod::ConsumeOk | BasicMethod::CancelOk | BasicMethod::GetOk | BasicMethod::GetEmpty | BasicMethod::RecoverOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        matches!(self, BasicMethod::Consume | BasicMethod::Cancel)
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            BasicMethod::Qos => "qos",
+            BasicMethod::QosOk => "qos-ok",
+            BasicMethod::Consume => "consume",
+            BasicMethod::ConsumeOk => "consume-ok",
+            BasicMethod::Cancel => "cancel",
+            BasicMethod::CancelOk => "cancel-ok",
+            BasicMethod::Publish => "publish",
+            BasicMethod::Return => "return",
+            BasicMethod::Deliver => "deliver",
+            BasicMethod::Get => "get",
+            BasicMethod::GetOk => "get-ok",
+            BasicMethod::GetEmpty => "get-empty",
+            BasicMethod::Ack => "ack",
+            BasicMethod::Reject => "reject",
+            BasicMethod::RecoverAsync => "recover-async",
+            BasicMethod::Recover => "recover",
+            BasicMethod::RecoverOk => "recover-ok",
+            BasicMethod::Nack => "nack",
+            BasicMethod::Unknown => "unknown"
+        }
+    }
+}
+
 impl MethodId for BasicMethod {
+    #[inline]
     fn method_id(&self) -> u16 {
         match self {
             BasicMethod::Qos => 10,
@@ -50,12 +111,14 @@ impl MethodId for BasicMethod {
 }
 
 impl Default for BasicMethod {
+    #[inline]
     fn default() -> Self {
         BasicMethod::Unknown
     }
 }
 
 impl From<u16> for BasicMethod {
+    #[inline]
     fn from(method_id: u16) -> Self {
         match method_id {
             10 => BasicMethod::Qos,
@@ -75,7 +138,8 @@ impl From<u16> for BasicMethod {
             100 => BasicMethod::RecoverAsync,
             110 => BasicMethod::Recover,
             111 => BasicMethod::RecoverOk,
+            120 => BasicMethod::Nack,
             _  => BasicMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
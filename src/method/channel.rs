@@ -1,6 +1,7 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ChannelMethod {
     Open,
     OpenOk,
@@ -11,7 +12,53 @@ pub enum ChannelMethod {
     Unknown
 }
 
+impl ChannelMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        false
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            ChannelMethod::Open => &[Method::Channel(ChannelMethod::OpenOk)],
+            ChannelMethod::Flow => &[Method::Channel(ChannelMethod::FlowOk)],
+            ChannelMethod::Close => &[Method::Channel(ChannelMethod::CloseOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, ChannelMethod::OpenOk | ChannelMethod::FlowOk | ChannelMethod::CloseOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        false
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChannelMethod::Open => "open",
+            ChannelMethod::OpenOk => "open-ok",
+            ChannelMethod::Flow => "flow",
+            ChannelMethod::FlowOk => "flow-ok",
+            ChannelMethod::Close => "close",
+            ChannelMethod::CloseOk => "close-ok",
+            ChannelMethod::Unknown => "unknown"
+        }
+    }
+}
+
 impl MethodId for ChannelMethod {
+    #[inline]
     fn method_id(&self) -> u16 {
         match self {
             ChannelMethod::Open => 10,
@@ -26,12 +73,14 @@ impl MethodId for ChannelMethod {
 }
 
 impl Default for ChannelMethod {
-    fn default() -> ChannelMethod {
+    #[inline]
+    fn default() -> Self {
         ChannelMethod::Unknown
     }
 }
 
 impl From<u16> for ChannelMethod {
+    #[inline]
     fn from(method_id: u16) -> Self {
         match method_id {
             10 => ChannelMethod::Open,
@@ -43,4 +92,4 @@ impl From<u16> for ChannelMethod {
             _  => ChannelMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
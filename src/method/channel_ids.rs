@@ -0,0 +1,39 @@
+use std::collections::HashSet;
+
+/// Channel ids of a connection, an id is in use from its channel.open until the channel.close-ok
+pub struct ChannelIds {
+    channel_max: u16,
+    used: HashSet<u16>
+}
+
+impl ChannelIds {
+    /// `channel_max` as negotiated by connection.tune-ok, 0 allows every id
+    #[inline]
+    pub fn new(channel_max: u16) -> Self {
+        ChannelIds { channel_max, used: HashSet::new() }
+    }
+
+    #[inline]
+    pub fn channel_max(&self) -> u16 {
+        self.channel_max
+    }
+
+    /// The lowest free id, None if `channel_max` ids are in use
+    pub fn allocate(&mut self) -> Option<u16> {
+        let channel_max = if self.channel_max == 0 { u16::MAX } else { self.channel_max };
+        let id = (1..=channel_max).find(|id| !self.used.contains(id))?;
+        self.used.insert(id);
+        Some(id)
+    }
+
+    /// Free the id once the channel is closed
+    #[inline]
+    pub fn release(&mut self, id: u16) {
+        self.used.remove(&id);
+    }
+
+    #[inline]
+    pub fn is_used(&self, id: u16) -> bool {
+        self.used.contains(&id)
+    }
+}
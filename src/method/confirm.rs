@@ -1,13 +1,54 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ConfirmMethod {
     Select,
     SelectOk,
     Unknown
 }
 
+impl ConfirmMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        false
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            ConfirmMethod::Select => &[Method::Confirm(ConfirmMethod::SelectOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, ConfirmMethod::SelectOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        matches!(self, ConfirmMethod::Select)
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfirmMethod::Select => "select",
+            ConfirmMethod::SelectOk => "select-ok",
+            ConfirmMethod::Unknown => "unknown"
+        }
+    }
+}
+
 impl MethodId for ConfirmMethod {
+    #[inline]
     fn method_id(&self) -> u16 {
         match self {
             ConfirmMethod::Select => 10,
@@ -18,17 +59,19 @@ impl MethodId for ConfirmMethod {
 }
 
 impl Default for ConfirmMethod {
+    #[inline]
     fn default() -> Self {
         ConfirmMethod::Unknown
     }
 }
 
 impl From<u16> for ConfirmMethod {
+    #[inline]
     fn from(method_id: u16) -> Self {
         match method_id {
             10 => ConfirmMethod::Select,
             11 => ConfirmMethod::SelectOk,
-            _ => ConfirmMethod::Unknown
+            _  => ConfirmMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
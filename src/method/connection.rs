@@ -1,6 +1,7 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ConnectionMethod {
     Start,
     StartOk,
@@ -12,10 +13,71 @@ pub enum ConnectionMethod {
     OpenOk,
     Close,
     CloseOk,
-    Unknown,
+    Blocked,
+    Unblocked,
+    UpdateSecret,
+    UpdateSecretOk,
+    Unknown
+}
+
+impl ConnectionMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        false
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            ConnectionMethod::Start => &[Method::Connection(ConnectionMethod::StartOk)],
+            ConnectionMethod::Secure => &[Method::Connection(ConnectionMethod::SecureOk)],
+            ConnectionMethod::Tune => &[Method::Connection(ConnectionMethod::TuneOk)],
+            ConnectionMethod::Open => &[Method::Connection(ConnectionMethod::OpenOk)],
+            ConnectionMethod::Close => &[Method::Connection(ConnectionMethod::CloseOk)],
+            ConnectionMethod::UpdateSecret => &[Method::Connection(ConnectionMethod::UpdateSecretOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, ConnectionMethod::StartOk | ConnectionMethod::SecureOk | ConnectionMethod::TuneOk | ConnectionMethod::OpenOk | ConnectionMethod::CloseOk | ConnectionMethod::UpdateSecretOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        false
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectionMethod::Start => "start",
+            ConnectionMethod::StartOk => "start-ok",
+            ConnectionMethod::Secure => "secure",
+            ConnectionMethod::SecureOk => "secure-ok",
+            ConnectionMethod::Tune => "tune",
+            ConnectionMethod::TuneOk => "tune-ok",
+            ConnectionMethod::Open => "open",
+            ConnectionMethod::OpenOk => "open-ok",
+            ConnectionMethod::Close => "close",
+            ConnectionMethod::CloseOk => "close-ok",
+            ConnectionMethod::Blocked => "blocked",
+            ConnectionMethod::Unblocked => "unblocked",
+            ConnectionMethod::UpdateSecret => "update-secret",
+            ConnectionMethod::UpdateSecretOk => "update-secret-ok",
+            ConnectionMethod::Unknown => "unknown"
+        }
+    }
 }
 
 impl MethodId for ConnectionMethod {
+    #[inline]
     fn method_id(&self) -> u16 {
         match self {
             ConnectionMethod::Start => 10,
@@ -28,18 +90,24 @@ impl MethodId for ConnectionMethod {
             ConnectionMethod::OpenOk => 41,
             ConnectionMethod::Close => 50,
             ConnectionMethod::CloseOk => 51,
+            ConnectionMethod::Blocked => 60,
+            ConnectionMethod::Unblocked => 61,
+            ConnectionMethod::UpdateSecret => 70,
+            ConnectionMethod::UpdateSecretOk => 71,
             ConnectionMethod::Unknown => 0xffff
         }
     }
 }
 
 impl Default for ConnectionMethod {
+    #[inline]
     fn default() -> Self {
         ConnectionMethod::Unknown
     }
 }
 
 impl From<u16> for ConnectionMethod {
+    #[inline]
     fn from(method_id: u16) -> Self {
         match method_id {
             10 => ConnectionMethod::Start,
@@ -52,7 +120,11 @@ impl From<u16> for ConnectionMethod {
             41 => ConnectionMethod::OpenOk,
             50 => ConnectionMethod::Close,
             51 => ConnectionMethod::CloseOk,
+            60 => ConnectionMethod::Blocked,
+            61 => ConnectionMethod::Unblocked,
+            70 => ConnectionMethod::UpdateSecret,
+            71 => ConnectionMethod::UpdateSecretOk,
             _  => ConnectionMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
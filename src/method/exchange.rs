@@ -1,6 +1,7 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ExchangeMethod {
     Declare,
     DeclareOk,
@@ -13,7 +14,56 @@ pub enum ExchangeMethod {
     Unknown
 }
 
+impl ExchangeMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        false
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            ExchangeMethod::Declare => &[Method::Exchange(ExchangeMethod::DeclareOk)],
+            ExchangeMethod::Delete => &[Method::Exchange(ExchangeMethod::DeleteOk)],
+            ExchangeMethod::Bind => &[Method::Exchange(ExchangeMethod::BindOk)],
+            ExchangeMethod::Unbind => &[Method::Exchange(ExchangeMethod::UnbindOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, ExchangeMethod::DeclareOk | ExchangeMethod::DeleteOk | ExchangeMethod::BindOk | ExchangeMethod::UnbindOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        matches!(self, ExchangeMethod::Declare | ExchangeMethod::Delete | ExchangeMethod::Bind | ExchangeMethod::Unbind)
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExchangeMethod::Declare => "declare",
+            ExchangeMethod::DeclareOk => "declare-ok",
+            ExchangeMethod::Delete => "delete",
+            ExchangeMethod::DeleteOk => "delete-ok",
+            ExchangeMethod::Bind => "bind",
+            ExchangeMethod::BindOk => "bind-ok",
+            ExchangeMethod::Unbind => "unbind",
+            ExchangeMethod::UnbindOk => "unbind-ok",
+            ExchangeMethod::Unknown => "unknown"
+        }
+    }
+}
+
 impl MethodId for ExchangeMethod {
+    #[inline]
     fn method_id(&self) -> u16 {
         match self {
             ExchangeMethod::Declare => 10,
@@ -30,12 +80,14 @@ impl MethodId for ExchangeMethod {
 }
 
 impl Default for ExchangeMethod {
+    #[inline]
     fn default() -> Self {
         ExchangeMethod::Unknown
     }
 }
 
 impl From<u16> for ExchangeMethod {
+    #[inline]
     fn from(method_id: u16) -> Self {
         match method_id {
             10 => ExchangeMethod::Declare,
@@ -49,4 +101,4 @@ impl From<u16> for ExchangeMethod {
             _  => ExchangeMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
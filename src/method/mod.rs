@@ -7,9 +7,15 @@ mod connection;
 mod exchange;
 mod queue;
 mod tx;
+mod rpc;
+mod channel_ids;
+mod transaction;
 
 pub use base::{Method, MethodId};
 pub(crate) use base::get_method_type;
+pub use rpc::{RpcQueue, UnexpectedReply};
+pub use channel_ids::ChannelIds;
+pub use transaction::{ChannelMode, TxAck, TxOutcome, TxTracker};
 
 pub use access::AccessMethod;
 pub use basic::BasicMethod;
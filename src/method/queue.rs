@@ -1,6 +1,7 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum QueueMethod {
     Declare,
     DeclareOk,
@@ -15,7 +16,59 @@ pub enum QueueMethod {
     Unknown
 }
 
+impl QueueMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        false
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            QueueMethod::Declare => &[Method::Queue(QueueMethod::DeclareOk)],
+            QueueMethod::Bind => &[Method::Queue(QueueMethod::BindOk)],
+            QueueMethod::Unbind => &[Method::Queue(QueueMethod::UnbindOk)],
+            QueueMethod::Purge => &[Method::Queue(QueueMethod::PurgeOk)],
+            QueueMethod::Delete => &[Method::Queue(QueueMethod::DeleteOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, QueueMethod::DeclareOk | QueueMethod::BindOk | QueueMethod::UnbindOk | QueueMethod::PurgeOk | QueueMethod::DeleteOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        matches!(self, QueueMethod::Declare | QueueMethod::Bind | QueueMethod::Purge | QueueMethod::Delete)
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            QueueMethod::Declare => "declare",
+            QueueMethod::DeclareOk => "declare-ok",
+            QueueMethod::Bind => "bind",
+            QueueMethod::BindOk => "bind-ok",
+            QueueMethod::Unbind => "unbind",
+            QueueMethod::UnbindOk => "unbind-ok",
+            QueueMethod::Purge => "purge",
+            QueueMethod::PurgeOk => "purge-ok",
+            QueueMethod::Delete => "delete",
+            QueueMethod::DeleteOk => "delete-ok",
+            QueueMethod::Unknown => "unknown"
+        }
+    }
+}
+
 impl MethodId for QueueMethod {
+    #[inline]
     fn method_id(&self) -> u16 {
         match self {
             QueueMethod::Declare => 10,
@@ -34,12 +87,14 @@ impl MethodId for QueueMethod {
 }
 
 impl Default for QueueMethod {
+    #[inline]
     fn default() -> Self {
         QueueMethod::Unknown
     }
 }
 
 impl From<u16> for QueueMethod {
+    #[inline]
     fn from(method_id: u16) -> Self {
         match method_id {
             10 => QueueMethod::Declare,
@@ -55,4 +110,4 @@ impl From<u16> for QueueMethod {
             _  => QueueMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
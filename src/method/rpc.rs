@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use property::Property;
+use crate::method::base::{Method, MethodId};
+
+/// An inbound reply which does not answer the oldest outstanding request
+#[derive(Property, Debug)]
+#[property(get(public), set(disable))]
+pub struct UnexpectedReply {
+    // None if no request is outstanding
+    expected: Option<Method>,
+    received: Method
+}
+
+impl Display for UnexpectedReply {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let received = self.received;
+        match self.expected {
+            Some(expected) => write!(f, "unexpected reply {}.{} to request {}.{}", received.class().class_id(), received.method_id(),
+                expected.class().class_id(), expected.method_id()),
+            None => write!(f, "unexpected reply {}.{} without request", received.class().class_id(), received.method_id())
+        }
+    }
+}
+
+impl std::error::Error for UnexpectedReply {}
+
+/// Outstanding synchronous requests of one channel, the peer answers them in order
+///
+/// Each request carries a value of `T`, such as the waiter of the reply, which is handed back when the reply arrives.
+pub struct RpcQueue<T> {
+    pending: VecDeque<(Method, T)>
+}
+
+impl<T> Default for RpcQueue<T> {
+    #[inline]
+    fn default() -> Self {
+        RpcQueue { pending: VecDeque::new() }
+    }
+}
+
+impl<T> RpcQueue<T> {
+    #[inline]
+    pub fn new() -> Self {
+        RpcQueue::default()
+    }
+
+    /// Record an outbound request, the caller checks it expects a reply with `Arguments::expects_reply`
+    #[inline]
+    pub fn push(&mut self, request: Method, value: T) {
+        self.pending.push_back((request, value));
+    }
+
+    /// Match an inbound reply with the oldest request, the request is removed only if the reply is valid for it
+    pub fn complete(&mut self, reply: Method) -> Result<(Method, T), UnexpectedReply> {
+        match self.pending.front() {
+            Some((request, _)) if request.responses().contains(&reply) => Ok(self.pending.pop_front().unwrap()),
+            Some((request, _)) => Err(UnexpectedReply { expected: Some(*request), received: reply }),
+            None => Err(UnexpectedReply { expected: None, received: reply })
+        }
+    }
+
+    /// The oldest outstanding request
+    #[inline]
+    pub fn front(&self) -> Option<Method> {
+        self.pending.front().map(|(request, _)| *request)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Remove every outstanding request, used when the channel is closed
+    #[inline]
+    pub fn drain(&mut self) -> impl Iterator<Item = (Method, T)> + '_ {
+        self.pending.drain(..)
+    }
+}
@@ -0,0 +1,170 @@
+use crate::error::amqp::{AmqpError, AmqpErrorKind};
+use crate::frame::method::basic::{BasicAck, BasicNack, BasicReject};
+use crate::method::base::Method;
+use crate::method::confirm::ConfirmMethod;
+use crate::method::tx::TxMethod;
+
+/// Mode a channel is switched to by tx.select or confirm.select, a channel can not be in both
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelMode {
+    Normal,
+    Transactional,
+    Confirm
+}
+
+/// An acknowledgement of a delivery, held back on a transactional channel until the commit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxAck {
+    Ack(BasicAck),
+    Nack(BasicNack),
+    Reject(BasicReject)
+}
+
+/// What an ended transaction did with the publishes and acknowledgements made in it
+#[derive(Debug, PartialEq, Eq)]
+pub enum TxOutcome<P> {
+    Committed { publishes: Vec<P>, acks: Vec<TxAck> },
+    RolledBack { publishes: Vec<P>, acks: Vec<TxAck> }
+}
+
+impl<P> TxOutcome<P> {
+    #[inline]
+    pub fn is_committed(&self) -> bool {
+        matches!(self, TxOutcome::Committed { .. })
+    }
+
+    #[inline]
+    pub fn publishes(&self) -> &[P] {
+        match self {
+            TxOutcome::Committed { publishes, .. } | TxOutcome::RolledBack { publishes, .. } => publishes
+        }
+    }
+
+    #[inline]
+    pub fn acks(&self) -> &[TxAck] {
+        match self {
+            TxOutcome::Committed { acks, .. } | TxOutcome::RolledBack { acks, .. } => acks
+        }
+    }
+}
+
+/// Transaction state of one channel, fed with its methods in the order they are sent or received
+///
+/// Both peers see the same methods, a client tracks what it sends and a broker what it receives.
+/// Each publish carries a value of `P`, such as the message a broker routes once it is committed.
+pub struct TxTracker<P> {
+    mode: ChannelMode,
+    publishes: Vec<P>,
+    acks: Vec<TxAck>
+}
+
+impl<P> Default for TxTracker<P> {
+    #[inline]
+    fn default() -> Self {
+        TxTracker { mode: ChannelMode::Normal, publishes: Vec::new(), acks: Vec::new() }
+    }
+}
+
+impl<P> TxTracker<P> {
+    #[inline]
+    pub fn new() -> Self {
+        TxTracker::default()
+    }
+
+    #[inline]
+    pub fn mode(&self) -> ChannelMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn is_transactional(&self) -> bool {
+        self.mode == ChannelMode::Transactional
+    }
+
+    /// The error of a tx.select on a channel in confirm mode, the mode does not change
+    pub fn check_select(&self) -> Result<(), AmqpError> {
+        if self.mode == ChannelMode::Confirm {
+            return Err(AmqpError::with_method(AmqpErrorKind::PreconditionFailed,
+                "PRECONDITION_FAILED - cannot switch from confirm to tx mode", Method::Tx(TxMethod::Select)));
+        }
+        Ok(())
+    }
+
+    /// tx.select, a channel in confirm mode can not be transactional
+    ///
+    /// A client checks with `check_select` before it sends tx.select and selects once tx.select-ok arrives.
+    pub fn select(&mut self) -> Result<(), AmqpError> {
+        self.check_select()?;
+        self.mode = ChannelMode::Transactional;
+        Ok(())
+    }
+
+    /// The error of a confirm.select on a transactional channel, the mode does not change
+    pub fn check_confirm_select(&self) -> Result<(), AmqpError> {
+        if self.mode == ChannelMode::Transactional {
+            return Err(AmqpError::with_method(AmqpErrorKind::PreconditionFailed,
+                "PRECONDITION_FAILED - cannot switch from tx to confirm mode", Method::Confirm(ConfirmMethod::Select)));
+        }
+        Ok(())
+    }
+
+    /// confirm.select, a transactional channel can not be put in confirm mode
+    pub fn confirm_select(&mut self) -> Result<(), AmqpError> {
+        self.check_confirm_select()?;
+        self.mode = ChannelMode::Confirm;
+        Ok(())
+    }
+
+    /// basic.publish, the publish is handed back if the channel is not transactional and takes effect now
+    pub fn publish(&mut self, publish: P) -> Option<P> {
+        if !self.is_transactional() {
+            return Some(publish);
+        }
+        self.publishes.push(publish);
+        None
+    }
+
+    /// basic.ack, basic.nack or basic.reject, handed back if the channel is not transactional
+    pub fn ack(&mut self, ack: TxAck) -> Option<TxAck> {
+        if !self.is_transactional() {
+            return Some(ack);
+        }
+        self.acks.push(ack);
+        None
+    }
+
+    /// Publishes of the current transaction
+    #[inline]
+    pub fn pending_publishes(&self) -> &[P] {
+        &self.publishes
+    }
+
+    /// Acknowledgements of the current transaction
+    #[inline]
+    pub fn pending_acks(&self) -> &[TxAck] {
+        &self.acks
+    }
+
+    /// tx.commit, ends the current transaction and the next one starts
+    ///
+    /// The outcome belongs to the caller: a client holds it until tx.commit-ok and drops it if the reply
+    /// never comes, a broker applies it before it replies.
+    pub fn commit(&mut self) -> Result<TxOutcome<P>, AmqpError> {
+        let (publishes, acks) = self.end(TxMethod::Commit)?;
+        Ok(TxOutcome::Committed { publishes, acks })
+    }
+
+    /// tx.rollback, ends the current transaction and the next one starts
+    pub fn rollback(&mut self) -> Result<TxOutcome<P>, AmqpError> {
+        let (publishes, acks) = self.end(TxMethod::Rollback)?;
+        Ok(TxOutcome::RolledBack { publishes, acks })
+    }
+
+    fn end(&mut self, method: TxMethod) -> Result<(Vec<P>, Vec<TxAck>), AmqpError> {
+        if !self.is_transactional() {
+            return Err(AmqpError::with_method(AmqpErrorKind::PreconditionFailed,
+                "PRECONDITION_FAILED - channel is not transactional", Method::Tx(method)));
+        }
+        Ok((std::mem::take(&mut self.publishes), std::mem::take(&mut self.acks)))
+    }
+}
@@ -1,6 +1,7 @@
-use crate::method::base::MethodId;
+// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.
+use crate::method::base::{Method, MethodId};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum TxMethod {
     Select,
     SelectOk,
@@ -11,7 +12,53 @@ pub enum TxMethod {
     Unknown
 }
 
+impl TxMethod {
+    /// The method is followed by a content header frame and content body frames
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        false
+    }
+
+    /// Methods the peer may reply with, empty if the method is asynchronous
+    #[inline]
+    pub fn responses(&self) -> &'static [Method] {
+        match self {
+            TxMethod::Select => &[Method::Tx(TxMethod::SelectOk)],
+            TxMethod::Commit => &[Method::Tx(TxMethod::CommitOk)],
+            TxMethod::Rollback => &[Method::Tx(TxMethod::RollbackOk)],
+            _ => &[]
+        }
+    }
+
+    /// This method is the reply of a synchronous method
+    #[inline]
+    pub fn is_response(&self) -> bool {
+        matches!(self, TxMethod::SelectOk | TxMethod::CommitOk | TxMethod::RollbackOk)
+    }
+
+    /// The method carries a `no_wait` flag, the reply is suppressed when it is set
+    #[inline]
+    pub fn has_no_wait(&self) -> bool {
+        false
+    }
+
+    /// Name of the method in the spec
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        match self {
+            TxMethod::Select => "select",
+            TxMethod::SelectOk => "select-ok",
+            TxMethod::Commit => "commit",
+            TxMethod::CommitOk => "commit-ok",
+            TxMethod::Rollback => "rollback",
+            TxMethod::RollbackOk => "rollback-ok",
+            TxMethod::Unknown => "unknown"
+        }
+    }
+}
+
 impl MethodId for TxMethod {
+    #[inline]
     fn method_id(&self) -> u16 {
         match self {
             TxMethod::Select => 10,
@@ -26,12 +73,14 @@ impl MethodId for TxMethod {
 }
 
 impl Default for TxMethod {
+    #[inline]
     fn default() -> Self {
         TxMethod::Unknown
     }
 }
 
 impl From<u16> for TxMethod {
+    #[inline]
     fn from(method_id: u16) -> Self {
         match method_id {
             10 => TxMethod::Select,
@@ -43,4 +92,4 @@ impl From<u16> for TxMethod {
             _  => TxMethod::Unknown
         }
     }
-}
\ No newline at end of file
+}
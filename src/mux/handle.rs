@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, Notify};
+use crate::error::mux::MuxError;
+use crate::frame::base::Frame;
+use crate::method::ChannelIds;
+use crate::mux::inbound::Demux;
+use crate::mux::outbound::Mux;
+
+pub(crate) type Opened = (u16, mpsc::Receiver<Vec<Frame>>);
+
+/// An inbound queue holds at most this many times its capacity, the frames which arrive after `Routed::Full`
+/// before the peer pauses the channel
+pub const LIMIT_FACTOR: usize = 4;
+
+// inbound frames of one channel, the demux never waits on it
+pub(crate) struct Inbox {
+    state: Mutex<InboxState>,
+    notify: Notify
+}
+
+#[derive(Default)]
+struct InboxState {
+    frames: VecDeque<Frame>,
+    // reached the capacity and not drained yet
+    full: bool,
+    closed: bool
+}
+
+impl Inbox {
+    fn new() -> Arc<Self> {
+        Arc::new(Inbox { state: Mutex::new(InboxState::default()), notify: Notify::new() })
+    }
+
+    // queue a frame, true if the queue just reached `capacity`, the frame is dropped past `limit`
+    pub(crate) fn push(&self, frame: Frame, capacity: Option<usize>, limit: usize) -> Result<bool, MuxError> {
+        let mut state = self.state.lock().unwrap();
+        if state.frames.len() >= limit {
+            return Err(MuxError::Overflow(frame.channel()));
+        }
+        state.frames.push_back(frame);
+        let full = match capacity {
+            Some(capacity) => !state.full && state.frames.len() >= capacity,
+            None => false
+        };
+        state.full |= full;
+        drop(state);
+        self.notify.notify();
+        Ok(full)
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.notify.notify();
+    }
+}
+
+// channels of the connection, shared by the opener and both halves
+pub(crate) struct Shared {
+    ids: ChannelIds,
+    pub(crate) inbound: HashMap<u16, Arc<Inbox>>,
+    // channels the peer sent channel.close on
+    pub(crate) closed_by_peer: HashSet<u16>,
+    opened: mpsc::UnboundedSender<Opened>,
+    drained: mpsc::UnboundedSender<u16>,
+    pub(crate) capacity: usize
+}
+
+impl Shared {
+    // the channel is closed, its id can be allocated again
+    pub(crate) fn release(&mut self, id: u16) {
+        if let Some(inbox) = self.inbound.remove(&id) {
+            inbox.close();
+        }
+        self.closed_by_peer.remove(&id);
+        self.ids.release(id);
+    }
+}
+
+/// Multiplex a connection of `channel_max` channels, each queue holds up to `capacity` commands outbound and
+/// `capacity` frames inbound before `Demux::route` asks to pause the channel. An inbound queue, channel 0
+/// included, never holds more than `LIMIT_FACTOR * capacity` frames.
+///
+/// Returns the opener of channels, the handle of channel 0 which carries the connection methods and heartbeats,
+/// the inbound half fed by the frame codec and the outbound half which feeds it.
+pub fn multiplex(channel_max: u16, capacity: usize) -> (Opener, ChannelHandle, Demux, Mux) {
+    let capacity = capacity.max(1);
+    let inbound = Inbox::new();
+    let (outbound, outbound_receiver) = mpsc::channel(capacity);
+    let (opened, opened_receiver) = mpsc::unbounded_channel();
+    let (drained, drained_receiver) = mpsc::unbounded_channel();
+    let mut channels = HashMap::new();
+    channels.insert(0, inbound.clone());
+    let connection = ChannelHandle { id: 0, inbound, outbound, drained: drained.clone(), capacity };
+    let shared = Arc::new(Mutex::new(Shared { ids: ChannelIds::new(channel_max), inbound: channels, closed_by_peer: HashSet::new(), opened, drained, capacity }));
+    let demux = Demux::new(shared.clone(), drained_receiver);
+    let mux = Mux::new(shared.clone(), outbound_receiver, opened_receiver);
+    (Opener { shared }, connection, demux, mux)
+}
+
+/// Allocates the channels of a multiplexed connection
+#[derive(Clone)]
+pub struct Opener {
+    shared: Arc<Mutex<Shared>>
+}
+
+impl Opener {
+    /// Allocate the lowest free channel id and its queues, the caller sends channel.open on it.
+    /// None if `channel_max` channels are in use or the outbound half is dropped.
+    pub fn open(&self) -> Option<ChannelHandle> {
+        let mut shared = self.shared.lock().unwrap();
+        let id = shared.ids.allocate()?;
+        let (outbound, outbound_receiver) = mpsc::channel(shared.capacity);
+        if shared.opened.send((id, outbound_receiver)).is_err() {
+            shared.ids.release(id);
+            return None;
+        }
+        let inbound = Inbox::new();
+        shared.inbound.insert(id, inbound.clone());
+        Some(ChannelHandle { id, inbound, outbound, drained: shared.drained.clone(), capacity: shared.capacity })
+    }
+}
+
+/// Both queues of one channel
+pub struct ChannelHandle {
+    id: u16,
+    inbound: Arc<Inbox>,
+    outbound: mpsc::Sender<Vec<Frame>>,
+    drained: mpsc::UnboundedSender<u16>,
+    capacity: usize
+}
+
+impl ChannelHandle {
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Next inbound frame of the channel, None once its channel.close-ok is routed.
+    /// A full queue is drained once half of it is taken.
+    pub async fn recv(&mut self) -> Option<Frame> {
+        loop {
+            {
+                let mut state = self.inbound.state.lock().unwrap();
+                if let Some(frame) = state.frames.pop_front() {
+                    if state.full && state.frames.len() <= self.capacity / 2 {
+                        state.full = false;
+                        let _ = self.drained.send(self.id);
+                    }
+                    return Some(frame);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.inbound.notify.notified().await;
+        }
+    }
+
+    /// Queue a method, with its content header and body frames if it has content. They are sent together,
+    /// waits while the queue of the channel is full.
+    pub async fn send(&mut self, frames: Vec<Frame>) -> Result<(), MuxError> {
+        if let Some(frame) = frames.iter().find(|frame| frame.channel() != self.id) {
+            return Err(MuxError::WrongChannel(frame.channel()));
+        }
+        self.outbound.send(frames).await.map_err(|_| MuxError::Closed)
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use crate::error::mux::MuxError;
+use crate::frame::base::Frame;
+use crate::mux::handle::{Shared, LIMIT_FACTOR};
+use crate::mux::{is_close, is_close_ok};
+
+/// State of the queue of a channel after `Demux::route`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Routed {
+    Queued,
+    /// The queue of the channel reached its capacity. Frames are still queued, the transport pauses the deliveries
+    /// of the channel, with channel.flow or basic.qos, until `Demux::drained` returns it.
+    Full(u16)
+}
+
+/// Routes inbound frames to the queue of their channel
+pub struct Demux {
+    shared: Arc<Mutex<Shared>>,
+    drained: mpsc::UnboundedReceiver<u16>
+}
+
+impl Demux {
+    pub(crate) fn new(shared: Arc<Mutex<Shared>>, drained: mpsc::UnboundedReceiver<u16>) -> Self {
+        Demux { shared, drained }
+    }
+
+    /// Queue a frame read from the codec on its channel. Never waits, so a slow channel does not hold up the
+    /// others, heartbeats or channel 0, which has no capacity. A channel.close-ok frees the channel id after it
+    /// is queued, a channel.close is answered by the outbound half if the handle is dropped.
+    ///
+    /// A queue which reaches `LIMIT_FACTOR` times its capacity drops the frame and returns `MuxError::Overflow`,
+    /// the peer ignored the pause and the transport closes the connection.
+    pub fn route(&mut self, frame: Frame) -> Result<Routed, MuxError> {
+        let channel = frame.channel();
+        let close = channel != 0 && is_close(&frame);
+        let close_ok = channel != 0 && is_close_ok(&frame);
+        let mut shared = self.shared.lock().unwrap();
+        let capacity = if channel == 0 { None } else { Some(shared.capacity) };
+        let limit = shared.capacity.saturating_mul(LIMIT_FACTOR);
+        let inbox = shared.inbound.get(&channel).ok_or(MuxError::ChannelNotOpen(channel))?;
+        let full = inbox.push(frame, capacity, limit)?;
+        if close {
+            shared.closed_by_peer.insert(channel);
+        }
+        if close_ok {
+            shared.release(channel);
+            return Ok(Routed::Queued);
+        }
+        Ok(if full { Routed::Full(channel) } else { Routed::Queued })
+    }
+
+    /// Next channel whose full queue was drained to half its capacity, its deliveries can resume
+    pub async fn drained(&mut self) -> Option<u16> {
+        self.drained.recv().await
+    }
+}
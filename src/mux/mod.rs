@@ -0,0 +1,147 @@
+mod handle;
+mod inbound;
+mod outbound;
+
+pub use handle::{multiplex, Opener, ChannelHandle, LIMIT_FACTOR};
+pub use inbound::{Demux, Routed};
+pub use outbound::Mux;
+pub use crate::error::mux::MuxError;
+
+use crate::frame::base::{Arguments, Frame, Payload};
+
+// the channel is closed once its channel.close-ok is sent or received
+fn is_close_ok(frame: &Frame) -> bool {
+    match frame.payload() {
+        Payload::Method(payload) => matches!(payload.args(), Arguments::ChannelCloseOk(_)),
+        _ => false
+    }
+}
+
+fn is_close(frame: &Frame) -> bool {
+    match frame.payload() {
+        Payload::Method(payload) => matches!(payload.args(), Arguments::ChannelClose(_)),
+        _ => false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+    use crate::class::Class;
+    use crate::codec::{Arguments, Frame, Payload, Property};
+    use crate::properties::basic::BasicProperties;
+    use crate::arguments::channel::{ChannelClose, ChannelCloseOk};
+    use crate::arguments::basic::{BasicAck, BasicPublish};
+    use crate::mux::{multiplex, MuxError, Routed, LIMIT_FACTOR};
+
+    fn ack(channel: u16, delivery_tag: u64) -> Frame {
+        let mut ack = BasicAck::default();
+        ack.set_delivery_tag(delivery_tag);
+        Frame::method_frame(channel, Arguments::BasicAck(ack))
+    }
+
+    fn delivery_tag(frame: &Frame) -> u64 {
+        match frame.payload() {
+            Payload::Method(payload) => match payload.args() {
+                Arguments::BasicAck(ack) => ack.delivery_tag(),
+                _ => panic!("Expected basic.ack")
+            },
+            _ => panic!("Expected method frame")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multiplex() {
+        let (opener, mut connection, mut demux, mut mux) = multiplex(2, 2);
+        let mut one = opener.open().unwrap();
+        let mut two = opener.open().unwrap();
+        assert_eq!((one.id(), two.id()), (1, 2));
+        assert!(opener.open().is_none());
+
+        // inbound frames go to their channel, a full queue asks to pause the channel but holds up nothing
+        assert_eq!(demux.route(Frame::heartbeat_frame()), Ok(Routed::Queued));
+        assert_eq!(demux.route(ack(2, 1)), Ok(Routed::Queued));
+        assert_eq!(demux.route(ack(2, 2)), Ok(Routed::Full(2)));
+        assert_eq!(demux.route(ack(2, 3)), Ok(Routed::Queued));
+        for _ in 0..3 {
+            assert_eq!(demux.route(Frame::heartbeat_frame()), Ok(Routed::Queued));
+        }
+        assert_eq!(demux.route(ack(7, 1)), Err(MuxError::ChannelNotOpen(7)));
+        assert_eq!(connection.recv().await, Some(Frame::heartbeat_frame()));
+        assert_eq!(delivery_tag(&two.recv().await.unwrap()), 1);
+        assert!(demux.drained().now_or_never().is_none());
+        assert_eq!(delivery_tag(&two.recv().await.unwrap()), 2);
+        assert_eq!(demux.drained().await, Some(2));
+        assert_eq!(delivery_tag(&two.recv().await.unwrap()), 3);
+
+        // outbound commands are taken from the channels in turn, a message is never split
+        let publish = vec![
+            Frame::method_frame(1, Arguments::BasicPublish(BasicPublish::default())),
+            Frame::content_header_frame(1, Class::Basic, 4, Property::Basic(BasicProperties::default())),
+            Frame::content_body_frame(1, b"he".to_vec()),
+            Frame::content_body_frame(1, b"llo".to_vec())
+        ];
+        assert_eq!(one.send(vec![ack(2, 1)]).await, Err(MuxError::WrongChannel(2)));
+        one.send(publish.clone()).await.unwrap();
+        one.send(vec![ack(1, 5)]).await.unwrap();
+        two.send(vec![ack(2, 6)]).await.unwrap();
+        connection.send(vec![Frame::heartbeat_frame()]).await.unwrap();
+        assert_eq!(mux.next().await.unwrap(), vec![Frame::heartbeat_frame()]);
+        assert_eq!(mux.next().await.unwrap(), publish);
+        assert_eq!(mux.next().await.unwrap(), vec![ack(2, 6)]);
+        assert_eq!(mux.next().await.unwrap(), vec![ack(1, 5)]);
+
+        // ids are reused after channel.close-ok in either direction
+        two.send(vec![Frame::method_frame(2, Arguments::ChannelCloseOk(ChannelCloseOk))]).await.unwrap();
+        mux.next().await.unwrap();
+        let three = opener.open().unwrap();
+        assert_eq!(three.id(), 2);
+        demux.route(Frame::method_frame(1, Arguments::ChannelCloseOk(ChannelCloseOk))).unwrap();
+        assert!(one.recv().await.is_some());
+        assert!(one.recv().await.is_none());
+        let one = opener.open().unwrap();
+        assert_eq!(one.id(), 1);
+
+        // a dropped handle closes its channel, or answers the channel.close of the peer
+        drop(one);
+        let close = mux.next().await.unwrap();
+        assert!(matches!(close[0].payload(), Payload::Method(payload) if matches!(payload.args(), Arguments::ChannelClose(_))));
+        assert!(opener.open().is_none());
+        demux.route(Frame::method_frame(1, Arguments::ChannelCloseOk(ChannelCloseOk))).unwrap();
+        let reopened = opener.open().unwrap();
+        assert_eq!(reopened.id(), 1);
+        demux.route(Frame::method_frame(1, Arguments::ChannelClose(ChannelClose::default()))).unwrap();
+        drop(reopened);
+        assert_eq!(mux.next().await.unwrap(), vec![Frame::method_frame(1, Arguments::ChannelCloseOk(ChannelCloseOk))]);
+        assert_eq!(opener.open().unwrap().id(), 1);
+
+        drop(connection);
+        assert!(mux.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_limit() {
+        let (opener, mut connection, mut demux, _mux) = multiplex(1, 2);
+        let mut one = opener.open().unwrap();
+        let limit = 2 * LIMIT_FACTOR;
+
+        // a channel which is not drained stops at its limit, the others go on
+        for tag in 1..=limit as u64 {
+            assert!(demux.route(ack(1, tag)).is_ok());
+        }
+        assert_eq!(demux.route(ack(1, 100)), Err(MuxError::Overflow(1)));
+        assert_eq!(demux.route(Frame::heartbeat_frame()), Ok(Routed::Queued));
+        for tag in 1..=limit as u64 {
+            assert_eq!(delivery_tag(&one.recv().await.unwrap()), tag);
+        }
+        assert!(one.recv().now_or_never().is_none());
+
+        // channel 0 has no capacity but the same limit
+        for _ in 1..limit {
+            assert_eq!(demux.route(Frame::heartbeat_frame()), Ok(Routed::Queued));
+        }
+        assert_eq!(demux.route(Frame::heartbeat_frame()), Err(MuxError::Overflow(0)));
+        assert_eq!(connection.recv().await, Some(Frame::heartbeat_frame()));
+        assert_eq!(demux.route(Frame::heartbeat_frame()), Ok(Routed::Queued));
+    }
+}
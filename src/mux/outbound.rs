@@ -0,0 +1,89 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use futures::future::poll_fn;
+use tokio::sync::mpsc;
+use crate::error::amqp::{AmqpError, AmqpErrorKind};
+use crate::frame::base::{Arguments, Frame};
+use crate::frame::method::channel::ChannelCloseOk;
+use crate::mux::handle::{Opened, Shared};
+use crate::mux::{is_close, is_close_ok};
+
+/// Takes the outbound commands of every channel in turn
+///
+/// A command is the frames of one `ChannelHandle::send`, a method with its content header and body frames,
+/// and is never interleaved with frames of other channels. Channel 0 goes first so heartbeats and
+/// connection methods are not held up by busy channels. A channel whose handle is dropped is closed, or its
+/// channel.close from the peer is answered, and its id is freed once it is closed.
+pub struct Mux {
+    shared: Arc<Mutex<Shared>>,
+    connection: mpsc::Receiver<Vec<Frame>>,
+    opened: mpsc::UnboundedReceiver<Opened>,
+    channels: HashMap<u16, mpsc::Receiver<Vec<Frame>>>,
+    // channels in the order they are served
+    order: VecDeque<u16>,
+    // channels which sent channel.close
+    closing: HashSet<u16>
+}
+
+impl Mux {
+    pub(crate) fn new(shared: Arc<Mutex<Shared>>, connection: mpsc::Receiver<Vec<Frame>>, opened: mpsc::UnboundedReceiver<Opened>) -> Self {
+        Mux { shared, connection, opened, channels: HashMap::new(), order: VecDeque::new(), closing: HashSet::new() }
+    }
+
+    /// Frames of the next command to write, None once the handle of channel 0 is dropped.
+    /// A channel.close-ok frees the channel id.
+    pub async fn next(&mut self) -> Option<Vec<Frame>> {
+        let frames = poll_fn(|cx| self.poll_next(cx)).await?;
+        if let Some(frame) = frames.iter().find(|frame| frame.channel() != 0 && is_close(frame)) {
+            self.closing.insert(frame.channel());
+        }
+        if let Some(frame) = frames.iter().find(|frame| frame.channel() != 0 && is_close_ok(frame)) {
+            let channel = frame.channel();
+            self.channels.remove(&channel);
+            self.order.retain(|id| *id != channel);
+            self.shared.lock().unwrap().release(channel);
+        }
+        Some(frames)
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Vec<Frame>>> {
+        // a reused id replaces the queue of its closed channel
+        while let Poll::Ready(Some((id, receiver))) = self.opened.poll_recv(cx) {
+            self.closing.remove(&id);
+            if self.channels.insert(id, receiver).is_none() {
+                self.order.push_back(id);
+            }
+        }
+        if let Poll::Ready(frames) = self.connection.poll_recv(cx) {
+            return Poll::Ready(frames);
+        }
+        for _ in 0..self.order.len() {
+            let id = match self.order.pop_front() {
+                Some(id) => id,
+                None => break
+            };
+            match self.channels.get_mut(&id).map(|receiver| receiver.poll_recv(cx)) {
+                Some(Poll::Ready(Some(frames))) => {
+                    self.order.push_back(id);
+                    return Poll::Ready(Some(frames));
+                }
+                Some(Poll::Pending) => self.order.push_back(id),
+                // the handle is dropped, close the channel unless it is closing already
+                _ => {
+                    self.channels.remove(&id);
+                    if self.closing.contains(&id) {
+                        continue;
+                    }
+                    let frame = if self.shared.lock().unwrap().closed_by_peer.contains(&id) {
+                        Frame::method_frame(id, Arguments::ChannelCloseOk(ChannelCloseOk))
+                    } else {
+                        AmqpError::new(AmqpErrorKind::ReplySuccess, "channel handle dropped").to_close_frame(id)
+                    };
+                    return Poll::Ready(Some(vec![frame]));
+                }
+            }
+        }
+        Poll::Pending
+    }
+}
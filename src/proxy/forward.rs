@@ -0,0 +1,81 @@
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::Framed;
+use crate::error::proxy::ProxyError;
+use crate::frame::base::{Encode, Frame};
+use crate::frame::frame_codec::{FrameCodec, DecodedFrame, PROTOCOL_HEADER_SIZE};
+use crate::proxy::pipeline::Pipeline;
+use crate::proxy::rewriter::{Direction, FrameRewriter};
+
+/// Forward a client connection to the broker through the rewriter until either side closes its stream
+///
+/// The client's protocol header is forwarded as it is, every frame after it goes through a `Pipeline`.
+/// A broker which rejects the client's version sends its protocol header back, it is forwarded and the proxy stops.
+/// The `frame_max` of connection.tune-ok applies to the frames read from both sides afterwards.
+pub async fn run<C, S, R>(client: C, server: S, rewriter: R) -> Result<(), ProxyError>
+    where C: AsyncRead + AsyncWrite + Unpin, S: AsyncRead + AsyncWrite + Unpin, R: FrameRewriter
+{
+    run_pipeline(client, server, Pipeline::new(rewriter)).await
+}
+
+/// `run` with a pipeline configured by the caller, such as its `max_content_size`
+pub async fn run_pipeline<C, S, R>(client: C, server: S, mut pipeline: Pipeline<R>) -> Result<(), ProxyError>
+    where C: AsyncRead + AsyncWrite + Unpin, S: AsyncRead + AsyncWrite + Unpin, R: FrameRewriter
+{
+    let mut client = Framed::new(client, FrameCodec::default());
+    let mut server = Framed::new(server, FrameCodec::client());
+    match client.next().await {
+        Some(Ok(DecodedFrame::ProtocolHeader(header))) => {
+            let mut buffer = BytesMut::with_capacity(PROTOCOL_HEADER_SIZE);
+            header.encode(&mut buffer);
+            server.get_mut().write_all(&buffer).await?;
+        }
+        Some(Ok(DecodedFrame::AmqpFrame(_))) | Some(Ok(DecodedFrame::VersionMismatch(_))) => {
+            return Err(ProxyError::UnexpectedFrame("expected protocol header"))
+        }
+        Some(Err(e)) => return Err(ProxyError::Decode(e)),
+        None => return Ok(())
+    }
+
+    loop {
+        let (direction, frame) = tokio::select! {
+            frame = client.next() => (Direction::ToServer, frame),
+            frame = server.next() => (Direction::ToClient, frame)
+        };
+        let frame = match frame {
+            Some(Ok(DecodedFrame::AmqpFrame(frame))) => frame,
+            Some(Ok(DecodedFrame::ProtocolHeader(_))) => return Err(ProxyError::UnexpectedFrame("protocol header")),
+            Some(Ok(DecodedFrame::VersionMismatch(header))) => {
+                let mut buffer = BytesMut::with_capacity(PROTOCOL_HEADER_SIZE);
+                header.encode(&mut buffer);
+                client.get_mut().write_all(&buffer).await?;
+                return Ok(());
+            }
+            Some(Err(e)) => return Err(ProxyError::Decode(e)),
+            None => return Ok(())
+        };
+        let frames = pipeline.process(direction, frame)?;
+        if pipeline.frame_max() != client.codec().frame_max() {
+            client.codec_mut().set_frame_max(pipeline.frame_max());
+            server.codec_mut().set_frame_max(pipeline.frame_max());
+        }
+        match direction {
+            Direction::ToServer => send(&mut server, frames).await?,
+            Direction::ToClient => send(&mut client, frames).await?
+        }
+    }
+}
+
+async fn send<IO>(framed: &mut Framed<IO, FrameCodec>, frames: Vec<Frame>) -> Result<(), ProxyError>
+    where IO: AsyncRead + AsyncWrite + Unpin
+{
+    if frames.is_empty() {
+        return Ok(());
+    }
+    for frame in frames {
+        framed.feed(frame).await?;
+    }
+    framed.flush().await?;
+    Ok(())
+}
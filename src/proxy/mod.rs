@@ -0,0 +1,224 @@
+mod forward;
+mod pipeline;
+mod rewriter;
+
+pub use forward::{run, run_pipeline};
+pub use pipeline::Pipeline;
+pub use rewriter::{Direction, FrameRewriter, Output, Passthrough};
+pub use crate::error::proxy::ProxyError;
+
+#[cfg(test)]
+mod tests {
+    use futures::{SinkExt, StreamExt};
+    use tokio::io::AsyncWriteExt;
+    use tokio_util::codec::Framed;
+    use crate::{ShortStr, FieldName, FieldValue, FieldTable};
+    use crate::class::Class;
+    use crate::codec::{Arguments, Frame, FrameCodec, DecodedFrame, Payload, Property};
+    use crate::properties::basic::BasicProperties;
+    use crate::arguments::connection::{ConnectionTuneOk, ConnectionOpen};
+    use crate::arguments::channel::ChannelOpen;
+    use crate::arguments::queue::{QueueDeclare, QueueDeclareOk};
+    use crate::arguments::basic::BasicPublish;
+    use crate::err::AmqpErrorKind;
+    use crate::proxy::{run, Direction, FrameRewriter, Output, Passthrough, Pipeline, ProxyError};
+
+    // puts the client in a tenant's vhost and queues, headers are not passed to the broker
+    struct Tenant;
+
+    impl FrameRewriter for Tenant {
+        fn rewrite(&mut self, direction: Direction, frame: Frame, output: &mut Output) {
+            let channel = frame.channel();
+            let frame = match (direction, frame.into_payload()) {
+                (Direction::ToServer, Payload::Method(payload)) => match payload.into_args() {
+                    Arguments::ConnectionOpen(mut open) => {
+                        open.set_vhost(ShortStr::with_bytes(b"tenant-a").unwrap());
+                        Frame::method_frame(channel, Arguments::ConnectionOpen(open))
+                    }
+                    Arguments::QueueDeclare(mut declare) => {
+                        let queue_name = format!("tenant-a.{}", declare.queue_name());
+                        declare.set_queue_name(ShortStr::with_bytes(queue_name.as_bytes()).unwrap());
+                        Frame::method_frame(channel, Arguments::QueueDeclare(declare))
+                    }
+                    args => Frame::method_frame(channel, args)
+                },
+                (Direction::ToServer, Payload::ContentHeader(header)) => {
+                    let mut properties = BasicProperties::default();
+                    if let Property::Basic(received) = header.properties() {
+                        properties.set_content_type(received.content_type().clone());
+                    }
+                    Frame::content_header_frame(channel, Class::Basic, header.body_size(), Property::Basic(properties))
+                }
+                // the body grows, its header and frames have to follow
+                (Direction::ToServer, Payload::ContentBody(body)) => Frame::content_body_frame(channel, body.repeat(1000)),
+                (Direction::ToClient, Payload::Method(payload)) => match payload.into_args() {
+                    Arguments::QueueDeclareOk(mut declare_ok) => {
+                        let queue_name = declare_ok.queue_name().to_string().trim_start_matches("tenant-a.").to_string();
+                        declare_ok.set_queue_name(ShortStr::with_bytes(queue_name.as_bytes()).unwrap());
+                        Frame::method_frame(channel, Arguments::QueueDeclareOk(declare_ok))
+                    }
+                    args => Frame::method_frame(channel, args)
+                },
+                (_, Payload::Heartbeat(_)) => Frame::heartbeat_frame(),
+                (_, payload) => panic!("Unexpected payload {:?}", payload)
+            };
+            output.push(frame);
+        }
+    }
+
+    fn method(frame: Option<Result<DecodedFrame, crate::err::FrameDecodeErr>>) -> (u16, Arguments) {
+        match frame {
+            Some(Ok(DecodedFrame::AmqpFrame(frame))) => {
+                let channel = frame.channel();
+                match frame.into_payload() {
+                    Payload::Method(payload) => (channel, payload.into_args()),
+                    _ => panic!("Expected method frame")
+                }
+            }
+            _ => panic!("Expected frame")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_through_proxy() {
+        let (client_io, proxy_client_io) = tokio::io::duplex(64 * 1024);
+        let (proxy_server_io, server_io) = tokio::io::duplex(64 * 1024);
+        let proxy = tokio::spawn(run(proxy_client_io, proxy_server_io, Tenant));
+        let mut client = Framed::new(client_io, FrameCodec::without_protocol_header());
+        let mut server = Framed::new(server_io, FrameCodec::default());
+
+        client.get_mut().write_all(b"AMQP\x00\x00\x09\x01").await.unwrap();
+        assert!(matches!(server.next().await, Some(Ok(DecodedFrame::ProtocolHeader(_)))));
+        let mut tune_ok = ConnectionTuneOk::default();
+        tune_ok.set_channel_max(16u16).set_frame_max(4096u32);
+        client.send(Frame::method_frame(0, Arguments::ConnectionTuneOk(tune_ok))).await.unwrap();
+        assert!(matches!(method(server.next().await), (0, Arguments::ConnectionTuneOk(_))));
+        client.send(Frame::method_frame(0, Arguments::ConnectionOpen(ConnectionOpen::default()))).await.unwrap();
+        match method(server.next().await) {
+            (0, Arguments::ConnectionOpen(open)) => assert_eq!(open.vhost().to_string(), "tenant-a"),
+            _ => panic!("Expected connection.open")
+        }
+        client.send(Frame::method_frame(1, Arguments::ChannelOpen(ChannelOpen::default()))).await.unwrap();
+        assert!(matches!(method(server.next().await), (1, Arguments::ChannelOpen(_))));
+
+        let mut declare = QueueDeclare::default();
+        declare.set_queue_name(ShortStr::with_bytes(b"tasks").unwrap());
+        client.send(Frame::method_frame(1, Arguments::QueueDeclare(declare))).await.unwrap();
+        match method(server.next().await) {
+            (1, Arguments::QueueDeclare(declare)) => assert_eq!(declare.queue_name().to_string(), "tenant-a.tasks"),
+            _ => panic!("Expected queue.declare")
+        }
+        let mut declare_ok = QueueDeclareOk::default();
+        declare_ok.set_queue_name(ShortStr::with_bytes(b"tenant-a.tasks").unwrap());
+        server.send(Frame::method_frame(1, Arguments::QueueDeclareOk(declare_ok))).await.unwrap();
+        match method(client.next().await) {
+            (1, Arguments::QueueDeclareOk(declare_ok)) => assert_eq!(declare_ok.queue_name().to_string(), "tasks"),
+            _ => panic!("Expected queue.declare-ok")
+        }
+
+        let mut properties = BasicProperties::default();
+        let mut headers = FieldTable::new();
+        headers.insert(FieldName::with_bytes(b"x-tenant-secret").unwrap(), FieldValue::from_bool(true));
+        properties.set_content_type(ShortStr::with_bytes(b"text/plain").unwrap());
+        properties.set_headers(headers);
+        client.send(Frame::method_frame(1, Arguments::BasicPublish(BasicPublish::default()))).await.unwrap();
+        client.send(Frame::content_header_frame(1, Class::Basic, 5, Property::Basic(properties))).await.unwrap();
+        client.send(Frame::content_body_frame(1, b"hel".to_vec())).await.unwrap();
+        client.send(Frame::content_body_frame(1, b"lo".to_vec())).await.unwrap();
+        assert!(matches!(method(server.next().await), (1, Arguments::BasicPublish(_))));
+        match server.next().await {
+            Some(Ok(DecodedFrame::AmqpFrame(frame))) => match frame.into_payload() {
+                Payload::ContentHeader(header) => {
+                    assert_eq!(header.body_size(), 5000);
+                    match header.properties() {
+                        Property::Basic(properties) => assert_eq!(properties.flags(), BasicProperties::CONTENT_TYPE_FLAG),
+                        _ => panic!("Expected basic properties")
+                    }
+                }
+                _ => panic!("Expected content header")
+            },
+            _ => panic!("Expected frame")
+        }
+        let mut body = Vec::new();
+        for size in &[4088, 912] {
+            match server.next().await {
+                Some(Ok(DecodedFrame::AmqpFrame(frame))) => match frame.into_payload() {
+                    Payload::ContentBody(chunk) => {
+                        assert_eq!(chunk.len(), *size);
+                        body.extend_from_slice(&chunk);
+                    }
+                    _ => panic!("Expected content body")
+                },
+                _ => panic!("Expected frame")
+            }
+        }
+        assert_eq!(body, [b"hel".repeat(1000), b"lo".repeat(1000)].concat());
+
+        drop(client);
+        proxy.await.unwrap().unwrap();
+    }
+
+    // opens a channel of its own
+    struct Inject;
+
+    impl FrameRewriter for Inject {
+        fn rewrite(&mut self, _: Direction, frame: Frame, output: &mut Output) {
+            output.push(frame);
+            output.push(Frame::method_frame(9, Arguments::QueueDeclare(QueueDeclare::default())));
+        }
+    }
+
+    #[test]
+    fn test_channel_not_open() {
+        let mut pipeline = Pipeline::new(Inject);
+        let frame = Frame::method_frame(0, Arguments::ConnectionOpen(ConnectionOpen::default()));
+        assert!(matches!(pipeline.process(Direction::ToServer, frame), Err(ProxyError::ChannelNotOpen(9))));
+    }
+
+    #[test]
+    fn test_max_content_size() {
+        let publish = || Frame::method_frame(1, Arguments::BasicPublish(BasicPublish::default()));
+        let header = |size| Frame::content_header_frame(1, Class::Basic, size, Property::Basic(BasicProperties::default()));
+
+        // announced by the header
+        let mut pipeline = Pipeline::new(Passthrough);
+        pipeline.set_max_content_size(1000);
+        pipeline.process(Direction::ToServer, Frame::method_frame(1, Arguments::ChannelOpen(ChannelOpen::default()))).unwrap();
+        pipeline.process(Direction::ToServer, publish()).unwrap();
+        assert!(matches!(pipeline.process(Direction::ToServer, header(1001)), Err(ProxyError::ContentTooLarge { channel: 1, size: 1001, max: 1000 })));
+
+        // passed by the rewriter, which grows the body
+        let mut pipeline = Pipeline::new(Tenant);
+        pipeline.set_max_content_size(1000);
+        pipeline.process(Direction::ToServer, Frame::method_frame(1, Arguments::ChannelOpen(ChannelOpen::default()))).unwrap();
+        pipeline.process(Direction::ToServer, publish()).unwrap();
+        pipeline.process(Direction::ToServer, header(5)).unwrap();
+        let body = Frame::content_body_frame(1, b"hel".to_vec());
+        assert!(matches!(pipeline.process(Direction::ToServer, body), Err(ProxyError::ContentTooLarge { channel: 1, size: 3000, max: 1000 })));
+    }
+
+    #[tokio::test]
+    async fn test_frame_max_through_proxy() {
+        let (client_io, proxy_client_io) = tokio::io::duplex(64 * 1024);
+        let (proxy_server_io, server_io) = tokio::io::duplex(64 * 1024);
+        let proxy = tokio::spawn(run(proxy_client_io, proxy_server_io, Passthrough));
+        let mut client = Framed::new(client_io, FrameCodec::without_protocol_header());
+        let mut server = Framed::new(server_io, FrameCodec::default());
+
+        client.get_mut().write_all(b"AMQP\x00\x00\x09\x01").await.unwrap();
+        assert!(matches!(server.next().await, Some(Ok(DecodedFrame::ProtocolHeader(_)))));
+        let mut tune_ok = ConnectionTuneOk::default();
+        tune_ok.set_frame_max(4096u32);
+        client.send(Frame::method_frame(0, Arguments::ConnectionTuneOk(tune_ok))).await.unwrap();
+        assert!(matches!(method(server.next().await), (0, Arguments::ConnectionTuneOk(_))));
+
+        // the frame is refused by its header, before its payload is buffered
+        let mut frame = vec![3u8, 0, 1];
+        frame.extend_from_slice(&u32::MAX.to_be_bytes());
+        client.get_mut().write_all(&frame).await.unwrap();
+        match proxy.await.unwrap() {
+            Err(ProxyError::Decode(e)) => assert_eq!(e.kind(), AmqpErrorKind::FrameError),
+            _ => panic!("Expected frame too large")
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+use crate::class::Class;
+use crate::error::proxy::ProxyError;
+use crate::frame::base::{Arguments, Frame, Payload, Property};
+use crate::frame::header::basic::BasicProperties;
+use crate::proxy::rewriter::{Direction, FrameRewriter, Output};
+
+// RabbitMQ's default max_message_size
+const DEFAULT_MAX_CONTENT_SIZE: u64 = 128 * 1024 * 1024;
+
+// a message whose content is still arriving, the rewritten frames of its channel wait for the last body frame
+#[derive(Default)]
+struct Content {
+    // body bytes still expected, None until the content header arrives
+    remaining: Option<u64>,
+    held: Vec<Frame>,
+    // body bytes of the held frames
+    size: u64
+}
+
+enum Part {
+    ContentMethod,
+    Header,
+    Body,
+    Other
+}
+
+/// Runs the rewriter over the frames of both directions and keeps what it forwards well formed
+///
+/// The content of a message is framed again once its last body frame arrived: the header's `body_size` is
+/// the size of the body frames the rewriter passed and the body is split to the negotiated `frame_max`.
+/// Frames the rewriter forwards on a channel the client has not opened are an error, so is a content larger
+/// than `max_content_size`, announced by its header or passed by the rewriter.
+pub struct Pipeline<R> {
+    rewriter: R,
+    output: Output,
+    // set by connection.tune-ok, 0 if there is no limit
+    frame_max: u32,
+    max_content_size: u64,
+    channels: HashSet<u16>,
+    to_server: HashMap<u16, Content>,
+    to_client: HashMap<u16, Content>
+}
+
+impl<R: FrameRewriter> Pipeline<R> {
+    pub fn new(rewriter: R) -> Self {
+        Pipeline {
+            rewriter,
+            output: Output::default(),
+            frame_max: 0,
+            max_content_size: DEFAULT_MAX_CONTENT_SIZE,
+            channels: HashSet::new(),
+            to_server: HashMap::new(),
+            to_client: HashMap::new()
+        }
+    }
+
+    #[inline]
+    pub fn rewriter(&self) -> &R {
+        &self.rewriter
+    }
+
+    #[inline]
+    pub fn rewriter_mut(&mut self) -> &mut R {
+        &mut self.rewriter
+    }
+
+    #[inline]
+    pub fn frame_max(&self) -> u32 {
+        self.frame_max
+    }
+
+    #[inline]
+    pub fn max_content_size(&self) -> u64 {
+        self.max_content_size
+    }
+
+    /// Largest message body buffered until its last frame, 128 MiB by default, 0 is no limit
+    #[inline]
+    pub fn set_max_content_size(&mut self, max_content_size: u64) -> &mut Self {
+        self.max_content_size = max_content_size;
+        self
+    }
+
+    /// Rewrite a frame, returns the frames to forward in its direction now
+    pub fn process(&mut self, direction: Direction, frame: Frame) -> Result<Vec<Frame>, ProxyError> {
+        let channel = frame.channel();
+        let completed = self.receive(direction, &frame)?;
+        self.rewriter.rewrite(direction, frame, &mut self.output);
+        let max = self.max_content_size;
+        let mut forward = Vec::new();
+        for frame in self.output.take() {
+            self.observe(direction, &frame)?;
+            match self.contents(direction).get_mut(&frame.channel()) {
+                Some(content) => {
+                    if let Payload::ContentBody(body) = frame.payload() {
+                        content.size += body.len() as u64;
+                        if max != 0 && content.size > max {
+                            return Err(ProxyError::ContentTooLarge { channel: frame.channel(), size: content.size, max });
+                        }
+                    }
+                    content.held.push(frame)
+                }
+                None => forward.push(frame)
+            }
+        }
+        if completed {
+            if let Some(content) = self.contents(direction).remove(&channel) {
+                frame_content(content.held, self.frame_max, &mut forward);
+            }
+        }
+        Ok(forward)
+    }
+
+    #[inline]
+    fn contents(&mut self, direction: Direction) -> &mut HashMap<u16, Content> {
+        match direction {
+            Direction::ToServer => &mut self.to_server,
+            Direction::ToClient => &mut self.to_client
+        }
+    }
+
+    // track the content of a received frame, returns true if it is the last frame of a message
+    fn receive(&mut self, direction: Direction, frame: &Frame) -> Result<bool, ProxyError> {
+        let max = self.max_content_size;
+        let contents = self.contents(direction);
+        let completed = match frame.payload() {
+            Payload::Method(payload) if payload.method().has_content() => {
+                contents.insert(frame.channel(), Content::default());
+                false
+            }
+            Payload::ContentHeader(header) => match contents.get_mut(&frame.channel()) {
+                Some(_) if max != 0 && header.body_size() > max => {
+                    return Err(ProxyError::ContentTooLarge { channel: frame.channel(), size: header.body_size(), max });
+                }
+                Some(content) if content.remaining.is_none() => {
+                    content.remaining = Some(header.body_size());
+                    header.body_size() == 0
+                }
+                _ => false
+            },
+            Payload::ContentBody(body) => match contents.get_mut(&frame.channel()) {
+                Some(Content { remaining: Some(remaining), .. }) => {
+                    *remaining = remaining.saturating_sub(body.len() as u64);
+                    *remaining == 0
+                }
+                _ => false
+            },
+            _ => false
+        };
+        Ok(completed)
+    }
+
+    // follow the handshake and the channels of a frame which is forwarded
+    fn observe(&mut self, direction: Direction, frame: &Frame) -> Result<(), ProxyError> {
+        let channel = frame.channel();
+        if let Payload::Method(payload) = frame.payload() {
+            match (direction, payload.args()) {
+                (Direction::ToServer, Arguments::ConnectionTuneOk(tune_ok)) => self.frame_max = tune_ok.frame_max(),
+                (Direction::ToServer, Arguments::ChannelOpen(_)) => {
+                    self.channels.insert(channel);
+                }
+                (_, Arguments::ChannelCloseOk(_)) => {
+                    self.channels.remove(&channel);
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        if channel != 0 && !self.channels.contains(&channel) {
+            return Err(ProxyError::ChannelNotOpen(channel));
+        }
+        Ok(())
+    }
+}
+
+// frames held before the content method, the method with its header and body, then the other held frames
+fn frame_content(held: Vec<Frame>, frame_max: u32, forward: &mut Vec<Frame>) {
+    let mut method = None;
+    let mut header = None;
+    let mut body = Vec::new();
+    let mut after = Vec::new();
+    for frame in held {
+        let part = match frame.payload() {
+            Payload::Method(payload) if payload.method().has_content() => Part::ContentMethod,
+            Payload::ContentHeader(_) => Part::Header,
+            Payload::ContentBody(_) => Part::Body,
+            _ => Part::Other
+        };
+        match part {
+            Part::ContentMethod if method.is_none() => method = Some(frame),
+            Part::Header => header = header.or(Some(frame)),
+            Part::Body => if let Payload::ContentBody(bytes) = frame.into_payload() {
+                body.extend_from_slice(&bytes);
+            },
+            _ if method.is_none() => forward.push(frame),
+            _ => after.push(frame)
+        }
+    }
+    // content without its method is dropped with it
+    if let Some(method) = method {
+        let channel = method.channel();
+        forward.push(method);
+        let (class, properties) = match header.map(Frame::into_payload) {
+            Some(Payload::ContentHeader(header)) => (*header.class(), header.into_properties()),
+            _ => (Class::Basic, Property::Basic(BasicProperties::default()))
+        };
+        forward.push(Frame::content_header_frame(channel, class, body.len() as u64, properties));
+        let max = if frame_max == 0 { body.len().max(1) } else { (frame_max as usize).saturating_sub(8).max(1) };
+        forward.extend(body.chunks(max).map(|chunk| Frame::content_body_frame(channel, chunk.to_vec())));
+    }
+    forward.extend(after);
+}
@@ -0,0 +1,52 @@
+use crate::frame::base::Frame;
+
+/// Which way a frame travels through the proxy
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// From the client to the broker
+    ToServer,
+    /// From the broker to the client
+    ToClient
+}
+
+/// Frames the rewriter forwards in place of the one it was given, in the order they are pushed
+#[derive(Default, Debug)]
+pub struct Output {
+    frames: Vec<Frame>
+}
+
+impl Output {
+    #[inline]
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn take(&mut self) -> Vec<Frame> {
+        std::mem::take(&mut self.frames)
+    }
+}
+
+/// Sees every frame the proxy decodes, the handshake included
+///
+/// Push the frame to `output` to pass it, push a changed frame to modify it, push nothing to drop it
+/// and push more frames to inject them. The frames are forwarded in the same direction.
+pub trait FrameRewriter: Send {
+    fn rewrite(&mut self, direction: Direction, frame: Frame, output: &mut Output);
+}
+
+/// Forwards every frame as it is
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Passthrough;
+
+impl FrameRewriter for Passthrough {
+    #[inline]
+    fn rewrite(&mut self, _: Direction, frame: Frame, output: &mut Output) {
+        output.push(frame);
+    }
+}
@@ -0,0 +1,71 @@
+use bytes::{BytesMut, BufMut};
+use crate::{LongStr, FieldName, FieldValue, FieldTable};
+use crate::frame::base::{Encode, Decode};
+use crate::sasl::{SaslMechanism, SaslVerifier, SaslStep, SaslError};
+
+/// AMQPLAIN mechanism, the response is a field table with LOGIN and PASSWORD, without the table length
+pub struct AmqPlain {
+    username: String,
+    password: String
+}
+
+impl AmqPlain {
+    pub fn new(username: &str, password: &str) -> Self {
+        AmqPlain { username: username.to_string(), password: password.to_string() }
+    }
+}
+
+impl SaslMechanism for AmqPlain {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "AMQPLAIN"
+    }
+
+    fn initial_response(&mut self) -> Result<Vec<u8>, SaslError> {
+        let mut table = FieldTable::new();
+        table.insert(FieldName::with_bytes(b"LOGIN")?, FieldValue::from_long_string(LongStr::with_bytes(self.username.as_bytes())?));
+        table.insert(FieldName::with_bytes(b"PASSWORD")?, FieldValue::from_long_string(LongStr::with_bytes(self.password.as_bytes())?));
+        let mut buffer = BytesMut::with_capacity(64);
+        table.encode(&mut buffer);
+        // skip the table length
+        Ok(buffer[std::mem::size_of::<u32>()..].to_vec())
+    }
+}
+
+/// Verify AMQPLAIN responses, the check function is called with username and password
+pub struct AmqPlainVerifier<F> {
+    check: F
+}
+
+impl<F> AmqPlainVerifier<F> where F: Fn(&str, &str) -> bool {
+    pub fn new(check: F) -> Self {
+        AmqPlainVerifier { check }
+    }
+}
+
+impl<F> SaslVerifier for AmqPlainVerifier<F> where F: Fn(&str, &str) -> bool {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "AMQPLAIN"
+    }
+
+    fn verify(&mut self, response: &[u8]) -> Result<SaslStep, SaslError> {
+        let mut buffer = BytesMut::with_capacity(response.len() + std::mem::size_of::<u32>());
+        buffer.put_u32(response.len() as u32);
+        buffer.put_slice(response);
+        let (_, table) = FieldTable::decode(&buffer)?;
+        let username = match table.get(&FieldName::with_bytes(b"LOGIN")?) {
+            Some(FieldValue::LongStr(username)) => username.to_str().ok_or(SaslError::Malformed("AMQPLAIN LOGIN is not utf8"))?,
+            _ => return Err(SaslError::Malformed("AMQPLAIN response without LOGIN"))
+        };
+        let password = match table.get(&FieldName::with_bytes(b"PASSWORD")?) {
+            Some(FieldValue::LongStr(password)) => password.to_str().ok_or(SaslError::Malformed("AMQPLAIN PASSWORD is not utf8"))?,
+            _ => return Err(SaslError::Malformed("AMQPLAIN response without PASSWORD"))
+        };
+        if (self.check)(username, password) {
+            Ok(SaslStep::Success(username.to_string()))
+        } else {
+            Err(SaslError::AuthenticationFailed)
+        }
+    }
+}
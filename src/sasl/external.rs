@@ -0,0 +1,63 @@
+use crate::sasl::{SaslMechanism, SaslVerifier, SaslStep, SaslError};
+
+/// EXTERNAL mechanism, the identity is established outside of AMQP, usually by the TLS client certificate
+#[derive(Default)]
+pub struct External {
+    authzid: String
+}
+
+impl External {
+    #[inline]
+    pub fn new() -> Self {
+        External::default()
+    }
+
+    /// Ask to act as another identity than the one of the transport
+    #[inline]
+    pub fn with_authzid(authzid: &str) -> Self {
+        External { authzid: authzid.to_string() }
+    }
+}
+
+impl SaslMechanism for External {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "EXTERNAL"
+    }
+
+    #[inline]
+    fn initial_response(&mut self) -> Result<Vec<u8>, SaslError> {
+        Ok(self.authzid.as_bytes().to_vec())
+    }
+}
+
+/// Verify EXTERNAL responses against the identity of the transport, None if the transport is not authenticated
+pub struct ExternalVerifier {
+    identity: Option<String>
+}
+
+impl ExternalVerifier {
+    #[inline]
+    pub fn new(identity: Option<String>) -> Self {
+        ExternalVerifier { identity }
+    }
+}
+
+impl SaslVerifier for ExternalVerifier {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "EXTERNAL"
+    }
+
+    fn verify(&mut self, response: &[u8]) -> Result<SaslStep, SaslError> {
+        let identity = match self.identity.take() {
+            Some(identity) => identity,
+            None => return Err(SaslError::AuthenticationFailed)
+        };
+        // an authzid other than the transport identity is not allowed
+        if !response.is_empty() && response != identity.as_bytes() {
+            return Err(SaslError::AuthenticationFailed);
+        }
+        Ok(SaslStep::Success(identity))
+    }
+}
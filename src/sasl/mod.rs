@@ -0,0 +1,251 @@
+mod plain;
+mod amqplain;
+mod external;
+#[cfg(feature = "scram")]
+mod scram;
+
+pub use plain::{Plain, PlainVerifier};
+pub use amqplain::{AmqPlain, AmqPlainVerifier};
+pub use external::{External, ExternalVerifier};
+#[cfg(feature = "scram")]
+pub use scram::{ScramSha256, ScramSha256Verifier, ScramCredentials};
+pub use crate::error::sasl::SaslError;
+
+use crate::{ShortStr, LongStr, FieldTable};
+use crate::frame::method::connection::{ConnectionStartOk, ConnectionSecure, ConnectionSecureOk};
+
+/// Client side of a SASL mechanism
+///
+/// The initial response is carried by `ConnectionStartOk`, every `ConnectionSecure` challenge is answered by a `ConnectionSecureOk`.
+pub trait SaslMechanism {
+    /// Mechanism name as listed in `ConnectionStart::mechanisms`
+    fn name(&self) -> &'static str;
+
+    fn initial_response(&mut self) -> Result<Vec<u8>, SaslError>;
+
+    /// Answer a challenge, single round mechanisms do not expect any
+    fn challenge(&mut self, _challenge: &[u8]) -> Result<Vec<u8>, SaslError> {
+        Err(SaslError::UnexpectedChallenge)
+    }
+}
+
+/// Result of a server side verification step
+pub enum SaslStep {
+    /// Send the challenge in a `ConnectionSecure` and wait for another response
+    Challenge(Vec<u8>),
+    /// The client is authenticated as the identity
+    Success(String)
+}
+
+/// Server side of a SASL mechanism, verifies the responses of a client
+pub trait SaslVerifier {
+    /// Mechanism name as listed in `ConnectionStart::mechanisms`
+    fn name(&self) -> &'static str;
+
+    /// Verify the response of `ConnectionStartOk` or `ConnectionSecureOk`
+    fn verify(&mut self, response: &[u8]) -> Result<SaslStep, SaslError>;
+}
+
+/// Pick the first candidate the server offers, candidates are ordered by client preference
+///
+/// # Examples
+///
+/// ```
+/// use amqp_proto::sasl::{select_mechanism, SaslMechanism, Plain, AmqPlain};
+///
+/// let candidates: Vec<Box<dyn SaslMechanism + Send>> = vec![Box::new(AmqPlain::new("guest", "guest")), Box::new(Plain::new("guest", "guest"))];
+/// let mechanism = select_mechanism("PLAIN AMQPLAIN", candidates).unwrap();
+///
+/// assert_eq!(mechanism.name(), "AMQPLAIN");
+/// ```
+pub fn select_mechanism(mechanisms: &str, candidates: Vec<Box<dyn SaslMechanism + Send>>) -> Result<Box<dyn SaslMechanism + Send>, SaslError> {
+    candidates.into_iter()
+        .find(|candidate| mechanisms.split(' ').any(|mechanism| mechanism == candidate.name()))
+        .ok_or(SaslError::NoMechanism)
+}
+
+/// Build the `ConnectionStartOk` with the initial response of the mechanism
+pub fn start_ok(mechanism: &mut dyn SaslMechanism, client_properties: FieldTable, locale: &str) -> Result<ConnectionStartOk, SaslError> {
+    let response = mechanism.initial_response()?;
+    let mut start_ok = ConnectionStartOk::default();
+    start_ok.set_client_properties(client_properties)
+        .set_mechanism(ShortStr::with_bytes(mechanism.name().as_bytes())?)
+        .set_response(LongStr::with_bytes(&response)?)
+        .set_locale(ShortStr::with_bytes(locale.as_bytes())?);
+    Ok(start_ok)
+}
+
+/// Build the `ConnectionSecureOk` which answers a challenge
+pub fn secure_ok(mechanism: &mut dyn SaslMechanism, secure: &ConnectionSecure) -> Result<ConnectionSecureOk, SaslError> {
+    let response = mechanism.challenge(secure.challenge().as_bytes())?;
+    let mut secure_ok = ConnectionSecureOk::default();
+    secure_ok.set_response(LongStr::with_bytes(&response)?);
+    Ok(secure_ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, BytesMut};
+    use crate::{FieldTable, LongStr};
+    use crate::codec::{Arguments, Decode, Encode, Frame, Payload};
+    use crate::frame::method::connection::ConnectionSecure;
+    use crate::sasl::{SaslMechanism, SaslVerifier, SaslStep, SaslError, AmqPlain, AmqPlainVerifier, Plain, PlainVerifier, External, ExternalVerifier};
+    use crate::sasl::{start_ok, secure_ok};
+
+    // echoes the challenge with its bytes inverted
+    struct Echo;
+
+    impl SaslMechanism for Echo {
+        fn name(&self) -> &'static str {
+            "ECHO"
+        }
+
+        fn initial_response(&mut self) -> Result<Vec<u8>, SaslError> {
+            Ok(Vec::new())
+        }
+
+        fn challenge(&mut self, challenge: &[u8]) -> Result<Vec<u8>, SaslError> {
+            Ok(challenge.iter().map(|b| !b).collect())
+        }
+    }
+
+    // encode and decode the arguments like they go over the wire
+    fn round_trip(args: Arguments) -> Arguments {
+        let mut buffer = BytesMut::new();
+        Frame::method_frame(0, args).encode(&mut buffer);
+        match Frame::decode(&buffer).unwrap().1.into_payload() {
+            Payload::Method(payload) => payload.into_args(),
+            _ => panic!("Expected method frame")
+        }
+    }
+
+    // the AMQPLAIN response is a field table without its length
+    fn amqplain_table(response: &[u8]) -> FieldTable {
+        let mut buffer = BytesMut::new();
+        buffer.put_u32(response.len() as u32);
+        buffer.put_slice(response);
+        FieldTable::decode(&buffer).unwrap().1
+    }
+
+    #[test]
+    fn test_plain() {
+        let mut verifier = PlainVerifier::new(|username, password| username == "guest" && password == "secret");
+        let response = Plain::new("guest", "secret").initial_response().unwrap();
+        assert!(matches!(verifier.verify(&response), Ok(SaslStep::Success(identity)) if identity == "guest"));
+        let response = Plain::new("guest", "guest").initial_response().unwrap();
+        assert!(matches!(verifier.verify(&response), Err(SaslError::AuthenticationFailed)));
+        assert!(matches!(verifier.verify(b"\0guest"), Err(SaslError::Malformed(_))));
+        assert!(matches!(verifier.verify(b"\0guest\0secret\0more"), Err(SaslError::Malformed(_))));
+        assert!(matches!(verifier.verify(b"\0gu\xffest\0secret"), Err(SaslError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_external() {
+        let mut verifier = ExternalVerifier::new(Some("CN=client".to_string()));
+        let response = External::new().initial_response().unwrap();
+        assert!(matches!(verifier.verify(&response), Ok(SaslStep::Success(identity)) if identity == "CN=client"));
+        // the identity is used once
+        assert!(matches!(verifier.verify(&response), Err(SaslError::AuthenticationFailed)));
+
+        let mut verifier = ExternalVerifier::new(Some("CN=client".to_string()));
+        let response = External::with_authzid("CN=client").initial_response().unwrap();
+        assert!(matches!(verifier.verify(&response), Ok(SaslStep::Success(identity)) if identity == "CN=client"));
+        let mut verifier = ExternalVerifier::new(Some("CN=client".to_string()));
+        let response = External::with_authzid("CN=admin").initial_response().unwrap();
+        assert!(matches!(verifier.verify(&response), Err(SaslError::AuthenticationFailed)));
+        let mut verifier = ExternalVerifier::new(None);
+        assert!(matches!(verifier.verify(b""), Err(SaslError::AuthenticationFailed)));
+    }
+
+    #[test]
+    fn test_amqplain() {
+        let mut verifier = AmqPlainVerifier::new(|username, password| username == "guest" && password == "secret");
+        let response = AmqPlain::new("guest", "secret").initial_response().unwrap();
+        assert!(matches!(verifier.verify(&response), Ok(SaslStep::Success(identity)) if identity == "guest"));
+        let response = AmqPlain::new("guest", "guest").initial_response().unwrap();
+        assert!(verifier.verify(&response).is_err());
+
+        // the length of a long password is not valid utf8, the response is sent as it is
+        let password = "p".repeat(200);
+        let mut verifier = AmqPlainVerifier::new(|username, checked| username == "guest" && checked == password);
+        let mut mechanism = AmqPlain::new("guest", &password);
+        let expected = AmqPlain::new("guest", &password).initial_response().unwrap();
+        let start_ok = match round_trip(Arguments::ConnectionStartOk(start_ok(&mut mechanism, FieldTable::new(), "en_US").unwrap())) {
+            Arguments::ConnectionStartOk(start_ok) => start_ok,
+            _ => panic!("Expected connection.start-ok")
+        };
+        // the order of the table entries is not fixed, compare the tables
+        assert_eq!(amqplain_table(start_ok.response().as_bytes()), amqplain_table(&expected));
+        assert!(matches!(verifier.verify(start_ok.response().as_bytes()), Ok(SaslStep::Success(identity)) if identity == "guest"));
+    }
+
+    #[test]
+    fn test_binary_challenge() {
+        let challenge = [0x00u8, 0x80, 0xff, 0xc3, 0x28];
+        let mut secure = ConnectionSecure::default();
+        secure.set_challenge(LongStr::with_bytes(&challenge).unwrap());
+        let secure = match round_trip(Arguments::ConnectionSecure(secure)) {
+            Arguments::ConnectionSecure(secure) => secure,
+            _ => panic!("Expected connection.secure")
+        };
+        let secure_ok = match round_trip(Arguments::ConnectionSecureOk(secure_ok(&mut Echo, &secure).unwrap())) {
+            Arguments::ConnectionSecureOk(secure_ok) => secure_ok,
+            _ => panic!("Expected connection.secure-ok")
+        };
+        assert_eq!(secure_ok.response().as_bytes(), &[0xffu8, 0x7f, 0x00, 0x3c, 0xd7][..]);
+        assert!(secure_ok.response().to_str().is_none());
+    }
+
+    #[cfg(feature = "scram")]
+    #[test]
+    fn test_scram_sha256() {
+        use crate::sasl::{ScramSha256, ScramSha256Verifier, ScramCredentials};
+
+        let credentials = ScramCredentials::new("pencil", b"salt", 4096);
+        let mut verifier = ScramSha256Verifier::new(|username| if username == "u,ser" { Some(credentials.clone()) } else { None });
+        let mut client = ScramSha256::new("u,ser", "pencil");
+
+        let mut response = client.initial_response().unwrap();
+        loop {
+            match verifier.verify(&response).unwrap() {
+                SaslStep::Challenge(challenge) => response = client.challenge(&challenge).unwrap(),
+                SaslStep::Success(identity) => {
+                    assert_eq!(identity, "u,ser");
+                    break;
+                }
+            }
+        }
+
+        let mut verifier = ScramSha256Verifier::new(|_: &str| Some(credentials.clone()));
+        let mut client = ScramSha256::new("user", "wrong");
+        let challenge = match verifier.verify(&client.initial_response().unwrap()).unwrap() {
+            SaslStep::Challenge(challenge) => challenge,
+            SaslStep::Success(_) => panic!("Expected server first message")
+        };
+        assert!(verifier.verify(&client.challenge(&challenge).unwrap()).is_err());
+
+        // an unknown user gets a salt of its own and fails like a wrong password
+        let first = |username: &str| {
+            let mut verifier = ScramSha256Verifier::new(|_: &str| None).fake_iterations(10000);
+            let mut client = ScramSha256::new(username, "pencil");
+            let challenge = match verifier.verify(&client.initial_response().unwrap()).unwrap() {
+                SaslStep::Challenge(challenge) => challenge,
+                SaslStep::Success(_) => panic!("Expected server first message")
+            };
+            let challenge = String::from_utf8(challenge).unwrap();
+            let response = client.challenge(challenge.as_bytes()).unwrap();
+            assert!(matches!(verifier.verify(&response), Err(SaslError::AuthenticationFailed)));
+            challenge[challenge.find(",s=").unwrap()..].to_string()
+        };
+        assert!(first("nobody").ends_with(",i=10000"));
+        assert_eq!(first("nobody"), first("nobody"));
+        assert_ne!(first("nobody"), first("somebody"));
+
+        // the client refuses to spend too many iterations
+        let mut client = ScramSha256::new("user", "pencil");
+        let response = String::from_utf8(client.initial_response().unwrap()).unwrap();
+        let nonce = &response[response.find(",r=").unwrap() + 3..];
+        let challenge = format!("r={}server,s=c2FsdA==,i=2000000", nonce);
+        assert!(matches!(client.challenge(challenge.as_bytes()), Err(SaslError::TooManyIterations(2000000))));
+    }
+}
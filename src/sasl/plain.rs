@@ -0,0 +1,72 @@
+use crate::sasl::{SaslMechanism, SaslVerifier, SaslStep, SaslError};
+
+/// PLAIN mechanism, the response is `authzid \0 username \0 password`
+pub struct Plain {
+    username: String,
+    password: String
+}
+
+impl Plain {
+    pub fn new(username: &str, password: &str) -> Self {
+        Plain { username: username.to_string(), password: password.to_string() }
+    }
+}
+
+impl SaslMechanism for Plain {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "PLAIN"
+    }
+
+    /// # Examples
+    ///
+    /// ```
+    /// use amqp_proto::sasl::{SaslMechanism, Plain};
+    ///
+    /// let mut plain = Plain::new("guest", "guest");
+    ///
+    /// assert_eq!(plain.initial_response().unwrap(), b"\0guest\0guest".to_vec());
+    /// ```
+    fn initial_response(&mut self) -> Result<Vec<u8>, SaslError> {
+        Ok(format!("\0{}\0{}", self.username, self.password).into_bytes())
+    }
+}
+
+/// Verify PLAIN responses, the check function is called with username and password
+pub struct PlainVerifier<F> {
+    check: F
+}
+
+impl<F> PlainVerifier<F> where F: Fn(&str, &str) -> bool {
+    pub fn new(check: F) -> Self {
+        PlainVerifier { check }
+    }
+}
+
+impl<F> SaslVerifier for PlainVerifier<F> where F: Fn(&str, &str) -> bool {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "PLAIN"
+    }
+
+    fn verify(&mut self, response: &[u8]) -> Result<SaslStep, SaslError> {
+        let mut parts = response.split(|c| *c == 0);
+        let (username, password) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(_), Some(username), Some(password), None) => (username, password),
+            _ => return Err(SaslError::Malformed("PLAIN response should have 3 parts"))
+        };
+        let username = match std::str::from_utf8(username) {
+            Ok(username) => username,
+            Err(_) => return Err(SaslError::Malformed("PLAIN username is not utf8"))
+        };
+        let password = match std::str::from_utf8(password) {
+            Ok(password) => password,
+            Err(_) => return Err(SaslError::Malformed("PLAIN password is not utf8"))
+        };
+        if (self.check)(username, password) {
+            Ok(SaslStep::Success(username.to_string()))
+        } else {
+            Err(SaslError::AuthenticationFailed)
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::sync::OnceLock;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use crate::sasl::{SaslMechanism, SaslVerifier, SaslStep, SaslError};
+
+// base64 of the gs2 header `n,,`, channel binding is not supported
+const CHANNEL_BINDING: &str = "biws";
+const NONCE_LEN: usize = 18;
+// iterations a server may ask of the client, each one is an hmac of the password
+const MAX_ITERATIONS: u32 = 1_000_000;
+// iterations announced for unknown users, RabbitMQ's default
+const FAKE_ITERATIONS: u32 = 4096;
+const FAKE_SALT_LEN: usize = 16;
+
+fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+fn nonce() -> Result<String, SaslError> {
+    let mut bytes = [0u8; NONCE_LEN];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        return Err(SaslError::Nonce);
+    }
+    Ok(STANDARD.encode(bytes))
+}
+
+// `=` and `,` are escaped in a saslname
+fn escape(username: &str) -> String {
+    username.replace('=', "=3D").replace(',', "=2C")
+}
+
+fn unescape(username: &str) -> String {
+    username.replace("=2C", ",").replace("=3D", "=")
+}
+
+// value of the attribute in a `k=v,k=v` message
+fn attribute(message: &str, name: char) -> Option<&str> {
+    message.split(',').find_map(|part| {
+        let mut chars = part.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), Some('=')) if c == name => Some(&part[2..]),
+            _ => None
+        }
+    })
+}
+
+// key of the fake salts of unknown users, fixed for the process so a username always gets the same salt
+fn fake_key() -> Result<&'static [u8; 32], SaslError> {
+    static KEY: OnceLock<[u8; 32]> = OnceLock::new();
+    if let Some(key) = KEY.get() {
+        return Ok(key);
+    }
+    let mut key = [0u8; 32];
+    if getrandom::getrandom(&mut key).is_err() {
+        return Err(SaslError::Nonce);
+    }
+    Ok(KEY.get_or_init(|| key))
+}
+
+#[inline]
+fn equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Keys derived from the password, the server stores them instead of the password
+#[derive(Clone)]
+pub struct ScramCredentials {
+    salt: Vec<u8>,
+    iterations: u32,
+    stored_key: [u8; 32],
+    server_key: [u8; 32]
+}
+
+impl ScramCredentials {
+    pub fn new(password: &str, salt: &[u8], iterations: u32) -> Self {
+        let salted_password = salted_password(password, salt, iterations);
+        let client_key = hmac(&salted_password, b"Client Key");
+        ScramCredentials {
+            salt: salt.to_vec(),
+            iterations,
+            stored_key: Sha256::digest(client_key).into(),
+            server_key: hmac(&salted_password, b"Server Key")
+        }
+    }
+}
+
+impl ScramCredentials {
+    // credentials of an unknown user, no proof matches them
+    fn fake(username: &str, iterations: u32) -> Result<Self, SaslError> {
+        let key = fake_key()?;
+        Ok(ScramCredentials {
+            salt: hmac(key, username.as_bytes())[..FAKE_SALT_LEN].to_vec(),
+            iterations,
+            stored_key: hmac(key, b"Stored Key"),
+            server_key: hmac(key, b"Server Key")
+        })
+    }
+}
+
+fn salted_password(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut salted_password = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut salted_password);
+    salted_password
+}
+
+enum ClientState {
+    Initial,
+    // client first message without gs2 header, and client nonce
+    First { client_first_bare: String, nonce: String },
+    // expected server signature
+    Final { server_signature: [u8; 32] },
+    Done
+}
+
+/// SCRAM-SHA-256 mechanism, runs two challenges with `ConnectionSecure`, the second one proves the server knows the password.
+/// A server asking for more than 1,000,000 iterations is refused.
+pub struct ScramSha256 {
+    username: String,
+    password: String,
+    state: ClientState
+}
+
+impl ScramSha256 {
+    pub fn new(username: &str, password: &str) -> Self {
+        ScramSha256 { username: username.to_string(), password: password.to_string(), state: ClientState::Initial }
+    }
+
+    fn client_final(&self, client_first_bare: &str, nonce: &str, server_first: &str) -> Result<(String, [u8; 32]), SaslError> {
+        let server_nonce = attribute(server_first, 'r').ok_or(SaslError::Malformed("SCRAM server first message without nonce"))?;
+        if !server_nonce.starts_with(nonce) {
+            return Err(SaslError::Malformed("SCRAM server nonce does not extend the client nonce"));
+        }
+        let salt = attribute(server_first, 's').ok_or(SaslError::Malformed("SCRAM server first message without salt"))?;
+        let salt = STANDARD.decode(salt).map_err(|_| SaslError::Malformed("SCRAM salt is not base64"))?;
+        let iterations = attribute(server_first, 'i').ok_or(SaslError::Malformed("SCRAM server first message without iterations"))?;
+        let iterations = iterations.parse::<u32>().map_err(|_| SaslError::Malformed("SCRAM iterations is not a number"))?;
+        if iterations > MAX_ITERATIONS {
+            return Err(SaslError::TooManyIterations(iterations));
+        }
+
+        let salted_password = salted_password(&self.password, &salt, iterations);
+        let client_key = hmac(&salted_password, b"Client Key");
+        let stored_key = Sha256::digest(client_key);
+        let without_proof = format!("c={},r={}", CHANNEL_BINDING, server_nonce);
+        let auth_message = format!("{},{},{}", client_first_bare, server_first, without_proof);
+        let client_signature = hmac(&stored_key, auth_message.as_bytes());
+        let proof: Vec<u8> = client_key.iter().zip(client_signature.iter()).map(|(k, s)| k ^ s).collect();
+        let server_signature = hmac(&hmac(&salted_password, b"Server Key"), auth_message.as_bytes());
+        Ok((format!("{},p={}", without_proof, STANDARD.encode(proof)), server_signature))
+    }
+}
+
+impl SaslMechanism for ScramSha256 {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "SCRAM-SHA-256"
+    }
+
+    fn initial_response(&mut self) -> Result<Vec<u8>, SaslError> {
+        let nonce = nonce()?;
+        let client_first_bare = format!("n={},r={}", escape(&self.username), nonce);
+        let response = format!("n,,{}", client_first_bare);
+        self.state = ClientState::First { client_first_bare, nonce };
+        Ok(response.into_bytes())
+    }
+
+    fn challenge(&mut self, challenge: &[u8]) -> Result<Vec<u8>, SaslError> {
+        let challenge = std::str::from_utf8(challenge).map_err(|_| SaslError::Malformed("SCRAM challenge is not utf8"))?;
+        match std::mem::replace(&mut self.state, ClientState::Done) {
+            ClientState::First { client_first_bare, nonce } => {
+                let (client_final, server_signature) = self.client_final(&client_first_bare, &nonce, challenge)?;
+                self.state = ClientState::Final { server_signature };
+                Ok(client_final.into_bytes())
+            }
+            ClientState::Final { server_signature } => {
+                if attribute(challenge, 'e').is_some() {
+                    return Err(SaslError::AuthenticationFailed);
+                }
+                let verifier = attribute(challenge, 'v').ok_or(SaslError::Malformed("SCRAM server final message without verifier"))?;
+                let verifier = STANDARD.decode(verifier).map_err(|_| SaslError::Malformed("SCRAM verifier is not base64"))?;
+                if !equal(&verifier, &server_signature) {
+                    return Err(SaslError::ServerSignatureMismatch);
+                }
+                Ok(Vec::new())
+            }
+            ClientState::Initial | ClientState::Done => Err(SaslError::UnexpectedChallenge)
+        }
+    }
+}
+
+enum ServerState {
+    Initial,
+    // `known` is false for the fake credentials of an unknown user
+    First { username: String, credentials: ScramCredentials, known: bool, client_first_bare: String, server_first: String, nonce: String },
+    Final { username: String },
+    Done
+}
+
+/// Verify SCRAM-SHA-256 exchanges, the lookup function returns the credentials of a username
+///
+/// An unknown user gets a salt derived from the username and the exchange fails at the client final message,
+/// like a wrong password, so usernames can not be enumerated.
+pub struct ScramSha256Verifier<F> {
+    lookup: F,
+    fake_iterations: u32,
+    state: ServerState
+}
+
+impl<F> ScramSha256Verifier<F> where F: Fn(&str) -> Option<ScramCredentials> {
+    pub fn new(lookup: F) -> Self {
+        ScramSha256Verifier { lookup, fake_iterations: FAKE_ITERATIONS, state: ServerState::Initial }
+    }
+
+    /// Iterations announced for unknown users, 4096 by default, should be the iterations of the known users
+    pub fn fake_iterations(mut self, iterations: u32) -> Self {
+        self.fake_iterations = iterations;
+        self
+    }
+}
+
+impl<F> SaslVerifier for ScramSha256Verifier<F> where F: Fn(&str) -> Option<ScramCredentials> {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "SCRAM-SHA-256"
+    }
+
+    fn verify(&mut self, response: &[u8]) -> Result<SaslStep, SaslError> {
+        let response = std::str::from_utf8(response).map_err(|_| SaslError::Malformed("SCRAM response is not utf8"))?;
+        match std::mem::replace(&mut self.state, ServerState::Done) {
+            ServerState::Initial => {
+                let client_first_bare = match response.strip_prefix("n,,") {
+                    Some(bare) => bare,
+                    None => return Err(SaslError::Malformed("SCRAM channel binding is not supported"))
+                };
+                let username = attribute(client_first_bare, 'n').ok_or(SaslError::Malformed("SCRAM client first message without username"))?;
+                let username = unescape(username);
+                let client_nonce = attribute(client_first_bare, 'r').ok_or(SaslError::Malformed("SCRAM client first message without nonce"))?;
+                let (credentials, known) = match (self.lookup)(&username) {
+                    Some(credentials) => (credentials, true),
+                    None => (ScramCredentials::fake(&username, self.fake_iterations)?, false)
+                };
+                let nonce = format!("{}{}", client_nonce, nonce()?);
+                let server_first = format!("r={},s={},i={}", nonce, STANDARD.encode(&credentials.salt), credentials.iterations);
+                self.state = ServerState::First {
+                    username, credentials, known, client_first_bare: client_first_bare.to_string(), server_first: server_first.clone(), nonce
+                };
+                Ok(SaslStep::Challenge(server_first.into_bytes()))
+            }
+            ServerState::First { username, credentials, known, client_first_bare, server_first, nonce } => {
+                if attribute(response, 'c') != Some(CHANNEL_BINDING) {
+                    return Err(SaslError::Malformed("SCRAM channel binding is not supported"));
+                }
+                if attribute(response, 'r') != Some(nonce.as_str()) {
+                    return Err(SaslError::AuthenticationFailed);
+                }
+                let proof = attribute(response, 'p').ok_or(SaslError::Malformed("SCRAM client final message without proof"))?;
+                let proof = STANDARD.decode(proof).map_err(|_| SaslError::Malformed("SCRAM proof is not base64"))?;
+                let without_proof = match response.rfind(",p=") {
+                    Some(index) => &response[..index],
+                    None => return Err(SaslError::Malformed("SCRAM client final message without proof"))
+                };
+                let auth_message = format!("{},{},{}", client_first_bare, server_first, without_proof);
+                let client_signature = hmac(&credentials.stored_key, auth_message.as_bytes());
+                let client_key: Vec<u8> = proof.iter().zip(client_signature.iter()).map(|(p, s)| p ^ s).collect();
+                let valid = proof.len() == client_signature.len() && equal(&Sha256::digest(&client_key), &credentials.stored_key);
+                if !valid || !known {
+                    return Err(SaslError::AuthenticationFailed);
+                }
+                let server_signature = hmac(&credentials.server_key, auth_message.as_bytes());
+                self.state = ServerState::Final { username };
+                Ok(SaslStep::Challenge(format!("v={}", STANDARD.encode(server_signature)).into_bytes()))
+            }
+            // the client has checked the server signature
+            ServerState::Final { username } => Ok(SaslStep::Success(username)),
+            ServerState::Done => Err(SaslError::UnexpectedChallenge)
+        }
+    }
+}
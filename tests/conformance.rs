@@ -0,0 +1,415 @@
+//! Spec conformance vectors
+//!
+//! Every vector is assembled from the field layout of the AMQP 0-9-1 spec and the RabbitMQ
+//! extensions, the same bytes a broker or client puts on the wire. None of them is a capture of
+//! broker or client traffic yet, a captured vector names its source next to it. Each one is checked
+//! in both directions: the value built through the public API must encode to the vector, and the
+//! vector must decode to the same frame, which encodes back to it.
+
+use bytes::{Bytes, BytesMut};
+use amqp_proto::{Class, Method, QueueMethod, BasicMethod, ShortStr, LongStr, FieldName, FieldValue, FieldTable, Decimal};
+use amqp_proto::codec::{Arguments, Decode, Encode, Frame, Property};
+use amqp_proto::properties::basic::BasicProperties;
+use amqp_proto::arguments::connection::*;
+use amqp_proto::arguments::channel::*;
+use amqp_proto::arguments::access::*;
+use amqp_proto::arguments::exchange::*;
+use amqp_proto::arguments::queue::*;
+use amqp_proto::arguments::basic::*;
+use amqp_proto::arguments::tx::*;
+use amqp_proto::arguments::confirm::*;
+
+fn hex(vector: &str) -> Vec<u8> {
+    let digits: Vec<u8> = vector.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits.chunks(2).map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap()).collect()
+}
+
+fn short(value: &str) -> ShortStr {
+    ShortStr::with_bytes(value.as_bytes()).unwrap()
+}
+
+fn long(value: &[u8]) -> LongStr {
+    LongStr::with_bytes(value).unwrap()
+}
+
+fn table(name: &str, value: FieldValue) -> FieldTable {
+    let mut table = FieldTable::new();
+    table.insert(FieldName::with_bytes(name.as_bytes()).unwrap(), value);
+    table
+}
+
+fn encode(frame: &Frame) -> Vec<u8> {
+    let mut buffer = BytesMut::with_capacity(256);
+    frame.encode(&mut buffer);
+    buffer.to_vec()
+}
+
+/// The encoded frame and the vector decoded and encoded again are both the vector, the decoded frame is the frame
+fn assert_frame(name: &str, frame: Frame, expected: &[u8]) {
+    assert_eq!(encode(&frame), expected, "{}: encode", name);
+    let (rest, decoded) = match Frame::decode(expected) {
+        Ok(ret) => ret,
+        Err(e) => panic!("{}: decode: {}", name, e)
+    };
+    assert!(rest.is_empty(), "{}: trailing bytes", name);
+    assert_eq!(decoded, frame, "{}: decoded frame", name);
+    assert_eq!(encode(&decoded), expected, "{}: decode", name);
+}
+
+/// Wrap a method payload vector in a method frame on channel 1
+fn assert_method(name: &str, args: Arguments, payload: &str) {
+    let payload = hex(payload);
+    let mut expected = vec![0x01, 0x00, 0x01];
+    expected.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    expected.extend_from_slice(&payload);
+    expected.push(0xce);
+    assert_frame(name, Frame::method_frame(1, args), &expected);
+}
+
+#[test]
+fn test_frames() {
+    assert_frame("heartbeat", Frame::heartbeat_frame(), &hex("08 0000 00000000 ce"));
+    assert_frame("content body", Frame::content_body_frame(1, b"hello".to_vec()), &hex("03 0001 00000005 68656c6c6f ce"));
+
+    let mut properties = BasicProperties::default();
+    properties.set_content_type(short("text/plain"));
+    properties.set_delivery_mode(2);
+    assert_frame("content header", Frame::content_header_frame(1, Class::Basic, 5, Property::Basic(properties)),
+        &hex("02 0001 0000001a 003c 0000 0000000000000005 9000 0a746578742f706c61696e 02 ce"));
+}
+
+#[test]
+fn test_connection_methods() {
+    let mut start = ConnectionStart::default();
+    start.set_version_major(0u8).set_version_minor(9u8)
+        .set_server_properties(table("product", FieldValue::from_long_string(long(b"RabbitMQ"))))
+        .set_mechanisms(long(b"PLAIN AMQPLAIN"))
+        .set_locales(long(b"en_US"));
+    assert_method("connection.start", Arguments::ConnectionStart(start),
+        "000a000a 00090000 00150770 726f6475 63745300 00000852 61626269 744d5100 00000e50 4c41494e 20414d51 504c4149 4e000000 05656e5f 5553");
+
+    let mut start_ok = ConnectionStartOk::default();
+    start_ok.set_client_properties(table("product", FieldValue::from_long_string(long(b"amqp-proto"))))
+        .set_mechanism(short("PLAIN"))
+        .set_response(long(b"\0guest\0guest"))
+        .set_locale(short("en_US"));
+    assert_method("connection.start-ok", Arguments::ConnectionStartOk(start_ok),
+        "000a000b 00000017 0770726f 64756374 53000000 0a616d71 702d7072 6f746f05 504c4149 4e000000 0c006775 65737400 67756573 7405656e 5f5553");
+
+    let mut secure = ConnectionSecure::default();
+    secure.set_challenge(long(b"challenge"));
+    assert_method("connection.secure", Arguments::ConnectionSecure(secure), "000a0014 00000009 6368616c 6c656e67 65");
+
+    let mut secure_ok = ConnectionSecureOk::default();
+    secure_ok.set_response(long(b"response"));
+    assert_method("connection.secure-ok", Arguments::ConnectionSecureOk(secure_ok), "000a0015 00000008 72657370 6f6e7365");
+
+    let mut tune = ConnectionTune::default();
+    tune.set_channel_max(2047u16).set_frame_max(131072u32).set_heartbeat(60u16);
+    assert_method("connection.tune", Arguments::ConnectionTune(tune), "000a001e 07ff0002 0000003c");
+
+    let mut tune_ok = ConnectionTuneOk::default();
+    tune_ok.set_channel_max(2047u16).set_frame_max(131072u32).set_heartbeat(60u16);
+    assert_method("connection.tune-ok", Arguments::ConnectionTuneOk(tune_ok), "000a001f 07ff0002 0000003c");
+
+    let mut open = ConnectionOpen::default();
+    open.set_vhost(short("/"));
+    assert_method("connection.open", Arguments::ConnectionOpen(open), "000a0028 012f0000");
+
+    assert_method("connection.open-ok", Arguments::ConnectionOpenOk(ConnectionOpenOk::default()), "000a0029 00");
+
+    let mut close = ConnectionClose::default();
+    close.set_reply_code(320u16).set_reply_text(short("CONNECTION_FORCED"));
+    assert_method("connection.close", Arguments::ConnectionClose(close),
+        "000a0032 01401143 4f4e4e45 4354494f 4e5f464f 52434544 00000000");
+
+    let mut close = ConnectionClose::default();
+    close.set_reply_code(503u16).set_reply_text(short("COMMAND_INVALID"))
+        .set_method(Method::Queue(QueueMethod::Declare));
+    assert_method("connection.close with method", Arguments::ConnectionClose(close),
+        "000a0032 01f70f43 4f4d4d41 4e445f49 4e56414c 49440032 000a");
+
+    assert_method("connection.close-ok", Arguments::ConnectionCloseOk(ConnectionCloseOk), "000a0033");
+
+    let mut blocked = ConnectionBlocked::default();
+    blocked.set_reason(short("low on memory"));
+    assert_method("connection.blocked", Arguments::ConnectionBlocked(blocked), "000a003c 0d6c6f77 206f6e20 6d656d6f 7279");
+    assert_method("connection.unblocked", Arguments::ConnectionUnblocked(ConnectionUnblocked), "000a003d");
+
+    let mut update_secret = ConnectionUpdateSecret::default();
+    update_secret.set_new_secret(long(b"tok")).set_reason(short("refresh"));
+    assert_method("connection.update-secret", Arguments::ConnectionUpdateSecret(update_secret),
+        "000a0046 00000003 746f6b07 72656672 657368");
+    assert_method("connection.update-secret-ok", Arguments::ConnectionUpdateSecretOk(ConnectionUpdateSecretOk), "000a0047");
+}
+
+#[test]
+fn test_channel_methods() {
+    assert_method("channel.open", Arguments::ChannelOpen(ChannelOpen::default()), "0014000a 00");
+    assert_method("channel.open-ok", Arguments::ChannelOpenOk(ChannelOpenOk::default()), "0014000b 00000000");
+
+    let mut flow = ChannelFlow::default();
+    flow.set_active(true);
+    assert_method("channel.flow", Arguments::ChannelFlow(flow), "00140014 01");
+    assert_method("channel.flow-ok", Arguments::ChannelFlowOk(ChannelFlowOk::default()), "00140015 00");
+
+    let mut close = ChannelClose::default();
+    close.set_reply_code(404u16).set_reply_text(short("NOT_FOUND - no queue 'q'"))
+        .set_method(Method::Queue(QueueMethod::Declare));
+    assert_method("channel.close", Arguments::ChannelClose(close),
+        "00140028 0194184e 4f545f46 4f554e44 202d206e 6f207175 65756520 27712700 32000a");
+
+    let mut close = ChannelClose::default();
+    close.set_reply_code(540u16).set_reply_text(short("NOT_IMPLEMENTED")).set_class_id(60u16).set_method_id(250u16);
+    assert_method("channel.close with vendor method", Arguments::ChannelClose(close),
+        "00140028 021c0f4e 4f545f49 4d504c45 4d454e54 4544003c 00fa");
+    assert_method("channel.close-ok", Arguments::ChannelCloseOk(ChannelCloseOk), "00140029");
+}
+
+#[test]
+fn test_access_methods() {
+    let mut request = AccessRequest::default();
+    request.set_realm(short("/data")).set_passive(true).set_active(true).set_write(true).set_read(true);
+    assert_method("access.request", Arguments::AccessRequest(request), "001e000a 052f6461 74611e");
+
+    let mut request_ok = AccessRequestOk::default();
+    request_ok.set_ticket(1u16);
+    assert_method("access.request-ok", Arguments::AccessRequestOk(request_ok), "001e000b 0001");
+}
+
+#[test]
+fn test_exchange_methods() {
+    let mut declare = ExchangeDeclare::default();
+    declare.set_exchange_name(short("logs")).set_exchange_type(short("fanout")).set_durable(true)
+        .set_args(table("alternate-exchange", FieldValue::from_long_string(long(b"ae"))));
+    assert_method("exchange.declare", Arguments::ExchangeDeclare(declare),
+        "0028000a 0000046c 6f677306 66616e6f 75740200 00001a12 616c7465 726e6174 652d6578 6368616e 67655300 00000261 65");
+    assert_method("exchange.declare-ok", Arguments::ExchangeDeclareOk(ExchangeDeclareOk), "0028000b");
+
+    let mut delete = ExchangeDelete::default();
+    delete.set_exchange_name(short("logs")).set_if_unused(true);
+    assert_method("exchange.delete", Arguments::ExchangeDelete(delete), "00280014 0000046c 6f677301");
+    assert_method("exchange.delete-ok", Arguments::ExchangeDeleteOk(ExchangeDeleteOk), "00280015");
+
+    let mut bind = ExchangeBind::default();
+    bind.set_destination(short("dest")).set_source(short("src")).set_routing_key(short("key"));
+    assert_method("exchange.bind", Arguments::ExchangeBind(bind), "0028001e 00000464 65737403 73726303 6b657900 00000000");
+    assert_method("exchange.bind-ok", Arguments::ExchangeBindOk(ExchangeBindOk), "0028001f");
+
+    let mut unbind = ExchangeUnbind::default();
+    unbind.set_destination(short("dest")).set_source(short("src")).set_routing_key(short("key")).set_no_wait(true);
+    assert_method("exchange.unbind", Arguments::ExchangeUnbind(unbind), "00280028 00000464 65737403 73726303 6b657901 00000000");
+    assert_method("exchange.unbind-ok", Arguments::ExchangeUnbindOk(ExchangeUnbindOk), "00280033");
+}
+
+#[test]
+fn test_queue_methods() {
+    let mut declare = QueueDeclare::default();
+    declare.set_queue_name(short("tasks")).set_durable(true).set_args(table("x-max-length", FieldValue::from_i32(1000)));
+    assert_method("queue.declare", Arguments::QueueDeclare(declare),
+        "0032000a 00000574 61736b73 02000000 120c782d 6d61782d 6c656e67 74684900 0003e8");
+
+    let mut declare_ok = QueueDeclareOk::default();
+    declare_ok.set_queue_name(short("tasks")).set_message_count(5u32).set_consumer_count(1u32);
+    assert_method("queue.declare-ok", Arguments::QueueDeclareOk(declare_ok), "0032000b 05746173 6b730000 00050000 0001");
+
+    let mut bind = QueueBind::default();
+    bind.set_queue_name(short("tasks")).set_exchange_name(short("amq.direct")).set_routing_key(short("tasks"));
+    assert_method("queue.bind", Arguments::QueueBind(bind),
+        "00320014 00000574 61736b73 0a616d71 2e646972 65637405 7461736b 73000000 0000");
+    assert_method("queue.bind-ok", Arguments::QueueBindOk(QueueBindOk), "00320015");
+
+    let mut unbind = QueueUnbind::default();
+    unbind.set_queue_name(short("tasks")).set_exchange_name(short("amq.direct")).set_routing_key(short("tasks"));
+    assert_method("queue.unbind", Arguments::QueueUnbind(unbind),
+        "00320032 00000574 61736b73 0a616d71 2e646972 65637405 7461736b 73000000 00");
+    assert_method("queue.unbind-ok", Arguments::QueueUnbindOk(QueueUnbindOk), "00320033");
+
+    let mut purge = QueuePurge::default();
+    purge.set_queue_name(short("tasks"));
+    assert_method("queue.purge", Arguments::QueuePurge(purge), "0032001e 00000574 61736b73 00");
+
+    let mut purge_ok = QueuePurgeOk::default();
+    purge_ok.set_message_count(42u32);
+    assert_method("queue.purge-ok", Arguments::QueuePurgeOk(purge_ok), "0032001f 0000002a");
+
+    let mut delete = QueueDelete::default();
+    delete.set_queue_name(short("tasks")).set_if_unused(true).set_if_empty(true);
+    assert_method("queue.delete", Arguments::QueueDelete(delete), "00320028 00000574 61736b73 03");
+
+    let mut delete_ok = QueueDeleteOk::default();
+    delete_ok.set_message_count(7u32);
+    assert_method("queue.delete-ok", Arguments::QueueDeleteOk(delete_ok), "00320029 00000007");
+}
+
+#[test]
+fn test_basic_methods() {
+    let mut qos = BasicQos::default();
+    qos.set_prefetch_count(10u16);
+    assert_method("basic.qos", Arguments::BasicQos(qos), "003c000a 00000000 000a00");
+    assert_method("basic.qos-ok", Arguments::BasicQosOk(BasicQosOk), "003c000b");
+
+    let mut consume = BasicConsume::default();
+    consume.set_queue_name(short("tasks")).set_consumer_tag(short("ctag")).set_no_ack(true).set_exclusive(true);
+    assert_method("basic.consume", Arguments::BasicConsume(consume), "003c0014 00000574 61736b73 04637461 67060000 0000");
+
+    let mut consume_ok = BasicConsumeOk::default();
+    consume_ok.set_consumer_tag(short("amq.ctag-1"));
+    assert_method("basic.consume-ok", Arguments::BasicConsumeOk(consume_ok), "003c0015 0a616d71 2e637461 672d31");
+
+    let mut cancel = BasicCancel::default();
+    cancel.set_consumer_tag(short("ctag"));
+    assert_method("basic.cancel", Arguments::BasicCancel(cancel), "003c001e 04637461 6700");
+
+    let mut cancel_ok = BasicCancelOk::default();
+    cancel_ok.set_consumer_tag(short("ctag"));
+    assert_method("basic.cancel-ok", Arguments::BasicCancelOk(cancel_ok), "003c001f 04637461 67");
+
+    let mut publish = BasicPublish::default();
+    publish.set_routing_key(short("tasks")).set_mandatory(true);
+    assert_method("basic.publish", Arguments::BasicPublish(publish), "003c0028 00000005 7461736b 7301");
+
+    let mut basic_return = BasicReturn::default();
+    basic_return.set_reply_code(312u16).set_reply_text(short("NO_ROUTE")).set_routing_key(short("tasks"));
+    assert_method("basic.return", Arguments::BasicReturn(basic_return), "003c0032 0138084e 4f5f524f 55544500 05746173 6b73");
+
+    let mut deliver = BasicDeliver::default();
+    deliver.set_consumer_tag(short("ctag")).set_delivery_tag(1u64).set_redelivered(true).set_routing_key(short("tasks"));
+    assert_method("basic.deliver", Arguments::BasicDeliver(deliver), "003c003c 04637461 67000000 00000000 01010005 7461736b 73");
+
+    let mut get = BasicGet::default();
+    get.set_queue_name(short("tasks")).set_no_ack(true);
+    assert_method("basic.get", Arguments::BasicGet(get), "003c0046 00000574 61736b73 01");
+
+    let mut get_ok = BasicGetOk::default();
+    get_ok.set_delivery_tag(2u64).set_routing_key(short("tasks")).set_message_count(3u32);
+    assert_method("basic.get-ok", Arguments::BasicGetOk(get_ok), "003c0047 00000000 00000002 00000574 61736b73 00000003");
+    assert_method("basic.get-empty", Arguments::BasicGetEmpty(BasicGetEmpty::default()), "003c0048 00");
+
+    let mut ack = BasicAck::default();
+    ack.set_delivery_tag(3u64).set_multiple(true);
+    assert_method("basic.ack", Arguments::BasicAck(ack), "003c0050 00000000 00000003 01");
+
+    let mut reject = BasicReject::default();
+    reject.set_delivery_tag(4u64).set_requeue(true);
+    assert_method("basic.reject", Arguments::BasicReject(reject), "003c005a 00000000 00000004 01");
+
+    let mut recover_async = BasicRecoverAsync::default();
+    recover_async.set_requeue(true);
+    assert_method("basic.recover-async", Arguments::BasicRecoverAsync(recover_async), "003c0064 01");
+
+    let mut recover = BasicRecover::default();
+    recover.set_requeue(true);
+    assert_method("basic.recover", Arguments::BasicRecover(recover), "003c006e 01");
+    assert_method("basic.recover-ok", Arguments::BasicRecoverOk(BasicRecoverOk), "003c006f");
+
+    let mut nack = BasicNack::default();
+    nack.set_delivery_tag(5u64).set_multiple(true).set_requeue(true);
+    assert_method("basic.nack", Arguments::BasicNack(nack), "003c0078 00000000 00000005 03");
+}
+
+#[test]
+fn test_tx_and_confirm_methods() {
+    assert_method("tx.select", Arguments::TxSelect(TxSelect), "005a000a");
+    assert_method("tx.select-ok", Arguments::TxSelectOk(TxSelectOk), "005a000b");
+    assert_method("tx.commit", Arguments::TxCommit(TxCommit), "005a0014");
+    assert_method("tx.commit-ok", Arguments::TxCommitOk(TxCommitOk), "005a0015");
+    assert_method("tx.rollback", Arguments::TxRollback(TxRollback), "005a001e");
+    assert_method("tx.rollback-ok", Arguments::TxRollbackOk(TxRollbackOk), "005a001f");
+    assert_method("confirm.select", Arguments::ConfirmSelect(ConfirmSelect::default()), "0055000a 00");
+    assert_method("confirm.select-ok", Arguments::ConfirmSelectOk(ConfirmSelectOk), "0055000b");
+}
+
+#[test]
+fn test_unknown_classes_and_methods() {
+    // vendor extensions are kept as they are received
+    let args = Arguments::Unknown { class_id: 60, method_id: 200, raw: Bytes::from_static(&[1, 2, 3]) };
+    assert_eq!(args.class_method(), (Class::Basic, Method::Basic(BasicMethod::Unknown)));
+    assert_method("basic method 200", args, "003c00c8 010203");
+    assert_method("class 1000", Arguments::Unknown { class_id: 1000, method_id: 10, raw: Bytes::new() }, "03e8000a");
+    let properties = Property::Unknown { class_id: 1000, raw: Bytes::from_static(&[0x80, 0x00, 0x07]) };
+    assert_frame("class 1000 content header", Frame::content_header_frame(1, Class::Unknown, 5, properties),
+        &hex("02 0001 0000000f 03e8 0000 0000000000000005 800007 ce"));
+}
+
+type PropertyVector = (u16, fn(&mut BasicProperties), &'static str);
+
+#[test]
+fn test_basic_properties_flags() {
+    // property flag bit, setter and encoded value, in wire order
+    let fields: [PropertyVector; 14] = [
+        (1 << 15, |p| p.set_content_type(short("text/plain")), "0a746578742f706c61696e"),
+        (1 << 14, |p| p.set_content_encoding(short("gzip")), "04677a6970"),
+        (1 << 13, |p| p.set_headers(table("x-retry", FieldValue::from_u8(3))), "0000000a 07782d7265747279 42 03"),
+        (1 << 12, |p| p.set_delivery_mode(2), "02"),
+        (1 << 11, |p| p.set_priority(9), "09"),
+        (1 << 10, |p| p.set_correlation_id(short("c1")), "026331"),
+        (1 << 9, |p| p.set_reply_to(short("rq")), "027271"),
+        (1 << 8, |p| p.set_expiration(short("60000")), "053630303030"),
+        (1 << 7, |p| p.set_message_id(short("m1")), "026d31"),
+        (1 << 6, |p| p.set_timestamp(1_600_000_000), "000000005f5e1000"),
+        (1 << 5, |p| p.set_basic_type(short("t")), "0174"),
+        (1 << 4, |p| p.set_user_id(short("guest")), "056775657374"),
+        (1 << 3, |p| p.set_app_id(short("app")), "03617070"),
+        (1 << 2, |p| p.set_cluster_id(short("")), "00")
+    ];
+    for combination in 0u32..(1 << fields.len()) {
+        let mut properties = BasicProperties::default();
+        let mut flags = 0u16;
+        let mut values = Vec::new();
+        for (index, (flag, set, value)) in fields.iter().enumerate() {
+            if combination & (1 << index) != 0 {
+                set(&mut properties);
+                flags |= flag;
+                values.extend_from_slice(&hex(value));
+            }
+        }
+        let mut expected = vec![0x02, 0x00, 0x01];
+        expected.extend_from_slice(&(14 + values.len() as u32).to_be_bytes());
+        expected.extend_from_slice(&hex("003c 0000 0000000000000000"));
+        expected.extend_from_slice(&flags.to_be_bytes());
+        expected.extend_from_slice(&values);
+        expected.push(0xce);
+        assert_frame(&format!("basic properties {:#06x}", flags), Frame::content_header_frame(1, Class::Basic, 0, Property::Basic(properties)), &expected);
+    }
+}
+
+#[test]
+fn test_field_value_tags() {
+    let array = vec![FieldValue::from_i8(1), FieldValue::from_void()];
+    let vectors = vec![
+        ("t", FieldValue::from_bool(true), "74 01"),
+        ("b", FieldValue::from_i8(-1), "62 ff"),
+        ("B", FieldValue::from_u8(0x7f), "42 7f"),
+        ("s", FieldValue::from_i16(-32768), "73 8000"),
+        ("u", FieldValue::from_u16(0xffff), "75 ffff"),
+        ("I", FieldValue::from_i32(-1), "49 ffffffff"),
+        ("i", FieldValue::from_u32(0x8000_0000), "69 80000000"),
+        ("l", FieldValue::from_i64(i64::MIN), "6c 8000000000000000"),
+        ("L", FieldValue::from_u64(u64::MAX), "4c ffffffffffffffff"),
+        ("f", FieldValue::from_f32(1.5), "66 3fc00000"),
+        ("d", FieldValue::from_f64(1.5), "64 3ff8000000000000"),
+        ("T", FieldValue::from_timestamp(1_600_000_000), "54 000000005f5e1000"),
+        ("D", FieldValue::from_decimal(Decimal::new(2, 1234)), "44 02 000004d2"),
+        ("S", FieldValue::from_long_string(long(b"hi")), "53 00000002 6869"),
+        ("A", FieldValue::from_field_array(array), "41 00000003 6201 56"),
+        ("F", FieldValue::from_field_table(table("a", FieldValue::from_bool(true))), "46 00000004 0161 7401"),
+        ("x", FieldValue::from_bytes_array(long(&[1, 2, 3])), "78 00000003 010203"),
+        ("V", FieldValue::from_void(), "56")
+    ];
+    for (tag, value, vector) in vectors {
+        let expected = hex(vector);
+        let mut buffer = BytesMut::with_capacity(32);
+        value.encode(&mut buffer);
+        assert_eq!(&buffer[..], &expected[..], "field value {}: encode", tag);
+
+        let (rest, decoded) = match FieldValue::decode(&expected) {
+            Ok(ret) => ret,
+            Err(e) => panic!("field value {}: decode: {}", tag, e)
+        };
+        assert!(rest.is_empty(), "field value {}: trailing bytes", tag);
+        let mut buffer = BytesMut::with_capacity(32);
+        decoded.encode(&mut buffer);
+        assert_eq!(&buffer[..], &expected[..], "field value {}: decode", tag);
+    }
+}
@@ -0,0 +1,65 @@
+//! Inputs found by the fuzz targets in fuzz/, each of them used to panic or overflow the stack.
+//! They must fail to decode with an error.
+
+use bytes::BytesMut;
+use amqp_proto::{FieldValue, FieldTable};
+use amqp_proto::borrowed::{DecodeRef, FieldValueRef};
+use amqp_proto::codec::{ContentHeaderPayload, Decode, Frame, FrameCodec, MethodPayload};
+use amqp_proto::err::AmqpErrorKind;
+
+fn hex(vector: &str) -> Vec<u8> {
+    let digits: Vec<u8> = vector.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits.chunks(2).map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap()).collect()
+}
+
+// a field array holding a field array, `depth` times
+fn nested_arrays(depth: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(depth * 5);
+    for level in 0..depth {
+        bytes.push(b'A');
+        bytes.extend_from_slice(&(((depth - level - 1) * 5) as u32).to_be_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn test_empty_field_name() {
+    // table of one entry with a zero length name
+    assert!(FieldTable::decode(&hex("00000001 00")).is_err());
+    // connection.start with that table as server properties
+    assert!(MethodPayload::decode(&hex("000a 000a 00 09 00000001 00")).is_err());
+    // basic content header with that table as headers
+    assert!(ContentHeaderPayload::decode(&hex("003c 0000 0000000000000000 2000 00000001 00")).is_err());
+    assert!(Frame::decode(&hex("01 0000 0000000b 000a000a 0009 0000000100 ce")).is_err());
+}
+
+#[test]
+fn test_nesting_too_deep() {
+    let bytes = nested_arrays(100_000);
+    let err = FieldValue::decode(&bytes).unwrap_err();
+    assert_eq!(err.kind(), AmqpErrorKind::SyntaxError);
+
+    let (_, value) = FieldValueRef::decode_ref(&bytes).unwrap();
+    assert!(value.into_owned().is_err());
+
+    // nesting which real peers use still decodes
+    assert!(FieldValue::decode(&nested_arrays(32)).is_ok());
+}
+
+#[test]
+fn test_codec_nesting_too_deep() {
+    let array = nested_arrays(100_000);
+    let mut payload = hex("000a 000a 00 09");
+    payload.extend_from_slice(&(array.len() as u32 + 2).to_be_bytes());
+    payload.extend_from_slice(b"\x01a");
+    payload.extend_from_slice(&array);
+
+    let mut buffer = BytesMut::new();
+    buffer.extend_from_slice(&hex("01 0000"));
+    buffer.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(&payload);
+    buffer.extend_from_slice(&[0xce]);
+    let mut codec = FrameCodec::default();
+    assert!(codec.decode_frame(&mut BytesMut::from(&b"AMQP\x00\x00\x09\x01"[..])).unwrap().is_some());
+    assert!(codec.decode_frame(&mut buffer).is_err());
+}
@@ -0,0 +1,73 @@
+//! Round-trip properties over the generators of the `arbitrary` feature, every generated value
+//! must decode from its own encoding to an equal value with no bytes left over, and the encoding
+//! must be as long as its `encoded_len` and the same as its `try_encode`.
+#![cfg(feature = "arbitrary")]
+
+use bytes::BytesMut;
+use proptest::prelude::*;
+use amqp_proto::{FieldValue, FieldArray, FieldTable};
+use amqp_proto::arbitrary::{field_value, field_array, field_table, basic_properties, arguments, frame};
+use amqp_proto::codec::{Decode, Encode, Frame, Property};
+use amqp_proto::properties::basic::BasicProperties;
+
+fn encode<T: Encode>(value: &T) -> BytesMut {
+    let mut buffer = BytesMut::new();
+    value.encode(&mut buffer);
+    assert_eq!(buffer.len(), value.encoded_len());
+    let mut tried = BytesMut::new();
+    value.try_encode(&mut tried).unwrap();
+    assert_eq!(tried, buffer);
+    buffer
+}
+
+proptest! {
+    #[test]
+    fn test_field_value_round_trip(value in field_value()) {
+        let bytes = encode(&value);
+        let (rest, decoded) = FieldValue::decode(&bytes).unwrap();
+        prop_assert!(rest.is_empty());
+        prop_assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_field_array_round_trip(array in field_array()) {
+        let bytes = encode(&array);
+        let (rest, decoded) = FieldArray::decode(&bytes).unwrap();
+        prop_assert!(rest.is_empty());
+        prop_assert_eq!(decoded, array);
+    }
+
+    #[test]
+    fn test_field_table_round_trip(table in field_table()) {
+        let bytes = encode(&table);
+        let (rest, decoded) = FieldTable::decode(&bytes).unwrap();
+        prop_assert!(rest.is_empty());
+        prop_assert_eq!(decoded, table);
+    }
+
+    #[test]
+    fn test_basic_properties_round_trip(properties in basic_properties()) {
+        let bytes = encode(&properties);
+        let (rest, decoded) = BasicProperties::decode(&bytes).unwrap();
+        prop_assert!(rest.is_empty());
+        prop_assert_eq!(decoded, Property::Basic(properties));
+    }
+
+    #[test]
+    fn test_arguments_round_trip(args in arguments()) {
+        let frame = Frame::method_frame(1, args);
+        let bytes = encode(&frame);
+        let (rest, decoded) = Frame::decode(&bytes).unwrap();
+        prop_assert!(rest.is_empty());
+        prop_assert_eq!(decoded, frame);
+    }
+
+    #[test]
+    fn test_frame_round_trip(frame in frame()) {
+        let bytes = encode(&frame);
+        let (rest, decoded) = Frame::decode(&bytes).unwrap();
+        prop_assert!(rest.is_empty());
+        prop_assert_eq!(decoded.decoded_frame_len(), bytes.len());
+        prop_assert_eq!(decoded, frame);
+    }
+}
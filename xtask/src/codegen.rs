@@ -0,0 +1,652 @@
+use std::fmt::Write;
+use crate::spec::{Spec, Class, Method};
+
+const HEADER: &str = "// Generated by `cargo xtask codegen` from docs/amqp0-9-1, do not edit.\n";
+
+// frame constants, with the type of the rust constant
+const FRAME_CONSTANTS: [(&str, &str); 6] = [
+    ("frame-method", "u8"),
+    ("frame-header", "u8"),
+    ("frame-body", "u8"),
+    ("frame-heartbeat", "u8"),
+    ("frame-min-size", "u32"),
+    ("frame-end", "u8")
+];
+
+// rust names of the fields which are reserved, a keyword or kept from amqp 0-9, by `class.method.field` or `field`
+const FIELD_NAMES: [(&str, &str); 15] = [
+    ("connection.open.virtual-host", "vhost"),
+    ("connection.open.reserved-1", "capabilities"),
+    ("connection.open.reserved-2", "insist"),
+    ("connection.open-ok.reserved-1", "known_hosts"),
+    ("channel.open.reserved-1", "out_of_band"),
+    ("channel.open-ok.reserved-1", "channel_id"),
+    ("exchange.declare.type", "exchange_type"),
+    ("exchange.declare.reserved-2", "auto_delete"),
+    ("exchange.declare.reserved-3", "internal"),
+    ("basic.get-empty.reserved-1", "cluster_id"),
+    ("reserved-1", "ticket"),
+    ("exchange", "exchange_name"),
+    ("queue", "queue_name"),
+    ("arguments", "args"),
+    ("nowait", "no_wait")
+];
+
+/// `recover-async` -> `RecoverAsync`
+fn camel_case(name: &str) -> String {
+    name.split('-').map(|part| {
+        let mut chars = part.chars();
+        match chars.next() {
+            Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            None => String::new()
+        }
+    }).collect()
+}
+
+/// `recover-async` -> `recover_async`
+fn snake_case(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// First letter in upper case, for doc comments from labels
+fn sentence(label: &str) -> String {
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Kind {
+    Bit,
+    Octet,
+    Short,
+    Long,
+    LongLong,
+    ShortStr,
+    LongStr,
+    Table,
+    // a class-id and method-id pair, kept as they are on the wire so the ids of unknown methods survive
+    ClassMethod
+}
+
+impl Kind {
+    fn rust_type(self) -> &'static str {
+        match self {
+            Kind::Bit => "bool",
+            Kind::Octet => "u8",
+            Kind::Short => "u16",
+            Kind::Long => "u32",
+            Kind::LongLong => "u64",
+            Kind::ShortStr => "ShortStr",
+            Kind::LongStr => "LongStr",
+            Kind::Table => "FieldTable",
+            Kind::ClassMethod => unreachable!("class and method are two fields")
+        }
+    }
+
+    // size on the wire, None if it depends on the value
+    fn fixed_len(self) -> Option<usize> {
+        match self {
+            Kind::Bit | Kind::Octet => Some(1),
+            Kind::Short => Some(2),
+            Kind::Long => Some(4),
+            Kind::LongLong => Some(8),
+            Kind::ClassMethod => Some(4),
+            Kind::ShortStr | Kind::LongStr | Kind::Table => None
+        }
+    }
+
+    fn put(self) -> &'static str {
+        match self {
+            Kind::Octet => "put_u8",
+            Kind::Short => "put_u16",
+            Kind::Long => "put_u32",
+            Kind::LongLong => "put_u64",
+            _ => unreachable!("only numbers are put")
+        }
+    }
+}
+
+struct Field {
+    name: String,
+    kind: Kind
+}
+
+/// Fields of the arguments of a method with their rust names
+fn fields(spec: &Spec, class: &Class, method: &Method) -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+    let mut spec_fields = method.fields.iter();
+    while let Some(field) = spec_fields.next() {
+        let key = format!("{}.{}.{}", class.name, method.name, field.name);
+        if field.domain == "class-id" {
+            match spec_fields.next() {
+                Some(next) if next.domain == "method-id" => {
+                    fields.push(Field { name: "class_id".to_string(), kind: Kind::ClassMethod });
+                    continue;
+                }
+                _ => return Err(format!("class-id {} is not followed by a method-id", key))
+            }
+        }
+        let name = match FIELD_NAMES.iter().find(|(spec_name, _)| *spec_name == key || *spec_name == field.name) {
+            Some((_, name)) => name.to_string(),
+            None if field.reserved => return Err(format!("reserved field {} has no rust name", key)),
+            None => snake_case(&field.name)
+        };
+        let kind = match spec.domain_type(&field.domain)? {
+            "bit" => Kind::Bit,
+            "octet" => Kind::Octet,
+            "short" => Kind::Short,
+            "long" => Kind::Long,
+            "longlong" | "timestamp" => Kind::LongLong,
+            "shortstr" => Kind::ShortStr,
+            "longstr" => Kind::LongStr,
+            "table" => Kind::Table,
+            other => return Err(format!("field {} has unknown type {}", key, other))
+        };
+        fields.push(Field { name, kind });
+    }
+    Ok(fields)
+}
+
+// runs of consecutive fields, bits of a run share an octet of up to 8 bits
+fn runs(fields: &[Field]) -> Vec<&[Field]> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    while start < fields.len() {
+        let mut end = start + 1;
+        if fields[start].kind == Kind::Bit {
+            while end < fields.len() && end - start < 8 && fields[end].kind == Kind::Bit {
+                end += 1;
+            }
+        }
+        runs.push(&fields[start..end]);
+        start = end;
+    }
+    runs
+}
+
+// statements which write the fields, `try_encode` fails on values which do not fit
+fn encode_body(out: &mut String, fields: &[Field], fallible: bool) {
+    for run in runs(fields) {
+        let field = &run[0];
+        match field.kind {
+            Kind::Bit if run.len() == 1 => writeln!(out, "        buffer.put_u8(self.{} as u8);", field.name).unwrap(),
+            Kind::Bit => {
+                out.push_str("        let mut flags = 0u8;\n");
+                for (bit, field) in run.iter().enumerate() {
+                    match bit {
+                        0 => writeln!(out, "        flags |= self.{} as u8;", field.name).unwrap(),
+                        _ => writeln!(out, "        flags |= (self.{} as u8) << {};", field.name, bit).unwrap()
+                    }
+                }
+                out.push_str("        buffer.put_u8(flags);\n");
+            }
+            Kind::ShortStr | Kind::LongStr | Kind::Table if fallible => writeln!(out, "        self.{}.try_encode(buffer)?;", field.name).unwrap(),
+            Kind::ShortStr | Kind::LongStr | Kind::Table => writeln!(out, "        self.{}.encode(buffer);", field.name).unwrap(),
+            Kind::ClassMethod => out.push_str("        buffer.put_u16(self.class_id);\n        buffer.put_u16(self.method_id);\n"),
+            kind => writeln!(out, "        buffer.{}(self.{});", kind.put(), field.name).unwrap()
+        }
+    }
+}
+
+// expression of the encoded length, adjacent fixed sizes are added up
+fn encoded_len(fields: &[Field]) -> String {
+    let mut terms = Vec::new();
+    let mut fixed = 0;
+    for run in runs(fields) {
+        let field = &run[0];
+        match field.kind.fixed_len() {
+            Some(len) => fixed += len,
+            None => {
+                if fixed > 0 {
+                    terms.push(fixed.to_string());
+                    fixed = 0;
+                }
+                terms.push(format!("self.{}.encoded_len()", field.name));
+            }
+        }
+    }
+    if fixed > 0 || terms.is_empty() {
+        terms.push(fixed.to_string());
+    }
+    terms.join(" + ")
+}
+
+fn arguments_struct(out: &mut String, name: &str, method: &Method, fields: &[Field]) {
+    out.push('\n');
+    if let Some(label) = &method.label {
+        writeln!(out, "/// {}", sentence(label)).unwrap();
+    }
+    if fields.is_empty() {
+        out.push_str("#[derive(Clone, Debug, PartialEq, Eq, Hash)]\n#[cfg_attr(feature = \"arbitrary\", derive(proptest_derive::Arbitrary))]\n");
+        writeln!(out, "pub struct {};\n", name).unwrap();
+        writeln!(out, "impl Encode for {} {{", name).unwrap();
+        out.push_str("    #[inline]\n    fn encode(&self, _: &mut BytesMut) {}\n\n");
+        out.push_str("    #[inline]\n    fn encoded_len(&self) -> usize {\n        0\n    }\n}\n\n");
+        writeln!(out, "impl Decode<Arguments> for {} {{", name).unwrap();
+        out.push_str("    #[inline]\n    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {\n");
+        writeln!(out, "        Ok((buffer, Arguments::{}({})))\n    }}\n}}", name, name).unwrap();
+        return;
+    }
+
+    let class_method = fields.iter().any(|field| field.kind == Kind::ClassMethod);
+    // a `FieldTable` is a `HashMap` which does not implement `Hash`, the struct hashes its fields by hand
+    let table = fields.iter().any(|field| field.kind == Kind::Table);
+    if table {
+        out.push_str("#[derive(Property, Default, Clone, Debug, PartialEq, Eq)]\n");
+    } else {
+        out.push_str("#[derive(Property, Default, Clone, Debug, PartialEq, Eq, Hash)]\n");
+    }
+    out.push_str("#[cfg_attr(feature = \"arbitrary\", derive(proptest_derive::Arbitrary))]\n");
+    out.push_str("#[property(get(public), set(public))]\n");
+    writeln!(out, "pub struct {} {{", name).unwrap();
+    let mut members = Vec::new();
+    for field in fields {
+        match field.kind {
+            Kind::ClassMethod => {
+                members.push("    class_id: u16".to_string());
+                members.push("    method_id: u16".to_string());
+            }
+            Kind::Table => members.push(format!(
+                "    #[cfg_attr(feature = \"arbitrary\", proptest(strategy = \"crate::frame::arbitrary::field_table()\"))]\n    {}: FieldTable",
+                field.name
+            )),
+            kind => members.push(format!("    {}: {}", field.name, kind.rust_type()))
+        }
+    }
+    writeln!(out, "{}\n}}\n", members.join(",\n")).unwrap();
+
+    if class_method {
+        writeln!(out, "impl {} {{", name).unwrap();
+        out.push_str(concat!(
+            "    /// The method which caused the close, None if the ids are zero or not in the spec\n",
+            "    pub fn method(&self) -> Option<Method> {\n",
+            "        get_method_type(Class::from(self.class_id), self.method_id).ok()\n",
+            "    }\n\n",
+            "    /// Set the class and method ids to those of the method which caused the close\n",
+            "    pub fn set_method(&mut self, method: Method) -> &mut Self {\n",
+            "        self.class_id = method.class().class_id();\n",
+            "        self.method_id = method.method_id();\n",
+            "        self\n",
+            "    }\n",
+            "}\n\n"
+        ));
+    }
+
+    if table {
+        writeln!(out, "impl Hash for {} {{", name).unwrap();
+        out.push_str("    fn hash<H: Hasher>(&self, state: &mut H) {\n");
+        for field in fields {
+            match field.kind {
+                Kind::ClassMethod => out.push_str("        self.class_id.hash(state);\n        self.method_id.hash(state);\n"),
+                Kind::Table => writeln!(out, "        hash_field_table(&self.{}, state);", field.name).unwrap(),
+                _ => writeln!(out, "        self.{}.hash(state);", field.name).unwrap()
+            }
+        }
+        out.push_str("    }\n}\n\n");
+    }
+
+    let fallible = fields.iter().any(|field| field.kind.fixed_len().is_none());
+    writeln!(out, "impl Encode for {} {{", name).unwrap();
+    out.push_str("    fn encode(&self, buffer: &mut BytesMut) {\n");
+    encode_body(out, fields, false);
+    out.push_str("    }\n\n    #[inline]\n    fn encoded_len(&self) -> usize {\n");
+    writeln!(out, "        {}\n    }}", encoded_len(fields)).unwrap();
+    if fallible {
+        out.push_str("\n    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {\n");
+        encode_body(out, fields, true);
+        out.push_str("        Ok(())\n    }\n");
+    }
+    out.push_str("}\n\n");
+
+    writeln!(out, "impl Decode<Arguments> for {} {{", name).unwrap();
+    out.push_str("    fn decode(buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {\n");
+    let mut names = Vec::new();
+    for run in runs(fields) {
+        let field = &run[0];
+        let decode = |out: &mut String, var: &str, ty: &str, path: &str| {
+            writeln!(out, "        let (buffer, {}) = {}::decode(buffer).map_err(|e| e.field(buffer, \"{}.{}\"))?;", var, ty, name, path).unwrap();
+        };
+        match field.kind {
+            Kind::Bit => {
+                decode(out, "flags", "u8", "flags");
+                for (bit, field) in run.iter().enumerate() {
+                    match bit {
+                        0 => writeln!(out, "        let {} = flags & 1 != 0;", field.name).unwrap(),
+                        _ => writeln!(out, "        let {} = flags & (1 << {}) != 0;", field.name, bit).unwrap()
+                    }
+                    names.push(field.name.clone());
+                }
+            }
+            Kind::ClassMethod => {
+                decode(out, "class_id", "u16", "class_id");
+                decode(out, "method_id", "u16", "method_id");
+                names.push("class_id".to_string());
+                names.push("method_id".to_string());
+            }
+            kind => {
+                decode(out, &field.name, kind.rust_type(), &field.name);
+                names.push(field.name.clone());
+            }
+        }
+    }
+    writeln!(out, "        Ok((buffer, Arguments::{}({} {{ {} }})))\n    }}\n}}", name, name, names.join(", ")).unwrap();
+}
+
+fn arguments_file(spec: &Spec, class: &Class) -> Result<String, String> {
+    let mut body = String::new();
+    let mut all = Vec::new();
+    for method in &class.methods {
+        let fields = fields(spec, class, method)?;
+        arguments_struct(&mut body, &format!("{}{}", camel_case(&class.name), camel_case(&method.name)), method, &fields);
+        all.extend(fields);
+    }
+    let has = |kind: Kind| all.iter().any(|field| field.kind == kind);
+
+    let mut out = String::from(HEADER);
+    out.push('\n');
+    if has(Kind::Table) {
+        out.push_str("use std::hash::{Hash, Hasher};\n");
+    }
+    if !all.is_empty() {
+        out.push_str("use property::Property;\nuse bytes::{BufMut, BytesMut};\n");
+    } else {
+        out.push_str("use bytes::BytesMut;\n");
+    }
+    if has(Kind::ClassMethod) {
+        out.push_str("use crate::class::Class;\n");
+    }
+    if all.iter().any(|field| field.kind.fixed_len().is_none()) {
+        out.push_str("use crate::error::{EncodeError, FrameDecodeErr};\n");
+    } else {
+        out.push_str("use crate::error::FrameDecodeErr;\n");
+    }
+    let mut base = vec!["Arguments", "Decode", "Encode"];
+    for (kind, name) in [(Kind::ShortStr, "ShortStr"), (Kind::LongStr, "LongStr"), (Kind::Table, "FieldTable")].iter() {
+        if has(*kind) {
+            base.push(name);
+        }
+    }
+    if has(Kind::Table) {
+        base.push("hash_field_table");
+    }
+    writeln!(out, "use crate::frame::base::{{{}}};", base.join(", ")).unwrap();
+    if has(Kind::ClassMethod) {
+        out.push_str("use crate::method::{get_method_type, Method, MethodId};\n");
+    }
+    out.push_str(&body);
+    Ok(out)
+}
+
+// `match self` over the arguments of every method, `arm` is the expression of a variant bound to `args`
+fn match_arguments(out: &mut String, spec: &Spec, arm: &str, unknown: &str) {
+    out.push_str("        match self {\n");
+    for class in &spec.classes {
+        for method in &class.methods {
+            writeln!(out, "            Arguments::{}{}(args) => {},", camel_case(&class.name), camel_case(&method.name), arm).unwrap();
+        }
+        out.push('\n');
+    }
+    writeln!(out, "            Arguments::Unknown {{ raw, .. }} => {}\n        }}", unknown).unwrap();
+}
+
+fn arguments_enum_file(spec: &Spec) -> Result<String, String> {
+    let mut out = String::from(HEADER);
+    out.push_str("\nuse bytes::{Bytes, BytesMut};\nuse crate::class::Class;\nuse crate::error::{EncodeError, FrameDecodeErr};\nuse crate::frame::base::{Decode, Encode};\n");
+    for class in &spec.classes {
+        let names: Vec<String> = class.methods.iter().map(|method| format!("{}{}", camel_case(&class.name), camel_case(&method.name))).collect();
+        writeln!(out, "use crate::frame::method::{}::{{{}}};", class.name, names.join(", ")).unwrap();
+    }
+    let methods: Vec<String> = spec.classes.iter().map(|class| format!("{}Method", camel_case(&class.name))).collect();
+    writeln!(out, "use crate::method::{{Method, {}}};\n", methods.join(", ")).unwrap();
+
+    out.push_str("/// Arguments of a method frame, one variant per method of the spec\n#[derive(Clone, Debug, PartialEq, Eq, Hash)]\npub enum Arguments {\n");
+    for class in &spec.classes {
+        for method in &class.methods {
+            let name = format!("{}{}", camel_case(&class.name), camel_case(&method.name));
+            writeln!(out, "    {}({}),", name, name).unwrap();
+        }
+        out.push('\n');
+    }
+    out.push_str("    /// A class or method which is not in the spec, such as a vendor extension, `raw` is the arguments as received\n");
+    out.push_str("    Unknown { class_id: u16, method_id: u16, raw: Bytes }\n}\n\n");
+
+    out.push_str("impl Encode for Arguments {\n    fn encode(&self, buffer: &mut BytesMut) {\n");
+    match_arguments(&mut out, spec, "args.encode(buffer)", "buffer.extend_from_slice(raw)");
+    out.push_str("    }\n\n    fn encoded_len(&self) -> usize {\n");
+    match_arguments(&mut out, spec, "args.encoded_len()", "raw.len()");
+    out.push_str("    }\n\n    fn try_encode(&self, buffer: &mut BytesMut) -> Result<(), EncodeError> {\n");
+    match_arguments(&mut out, spec, "args.try_encode(buffer)", "{\n                buffer.extend_from_slice(raw);\n                Ok(())\n            }");
+    out.push_str("    }\n}\n\nimpl Arguments {\n");
+
+    out.push_str("    /// Class and method of the arguments, `Unknown` arguments have the unknown method of their class,\n");
+    out.push_str("    /// or `Class::Unknown` and the default method if the class is unknown as well\n");
+    out.push_str("    pub fn class_method(&self) -> (Class, Method) {\n        match self {\n");
+    for class in &spec.classes {
+        let class_name = camel_case(&class.name);
+        for method in &class.methods {
+            let method_name = camel_case(&method.name);
+            writeln!(out, "            Arguments::{0}{1}(_) => (Class::{0}, Method::{0}({0}Method::{1})),", class_name, method_name).unwrap();
+        }
+        out.push('\n');
+    }
+    out.push_str("            Arguments::Unknown { class_id, .. } => {\n                let class = Class::from(*class_id);\n                let method = match class {\n");
+    for class in &spec.classes {
+        writeln!(out, "                    Class::{0} => Method::{0}({0}Method::Unknown),", camel_case(&class.name)).unwrap();
+    }
+    out.push_str("                    Class::Unknown => Method::default()\n                };\n                (class, method)\n            }\n        }\n    }\n\n");
+
+    out.push_str("    /// Value of the `no_wait` flag, false if the method does not carry it\n    pub fn no_wait(&self) -> bool {\n        match self {\n");
+    for class in &spec.classes {
+        for method in &class.methods {
+            if has_no_wait(spec, class, method)? {
+                writeln!(out, "            Arguments::{}{}(args) => args.no_wait(),", camel_case(&class.name), camel_case(&method.name)).unwrap();
+            }
+        }
+    }
+    out.push_str("            _ => false\n        }\n    }\n\n");
+
+    out.push_str("    // decode the arguments of a method of the spec, unknown methods are kept raw by the caller\n");
+    out.push_str("    pub(crate) fn decode_method(method: Method, buffer: &[u8]) -> Result<(&[u8], Arguments), FrameDecodeErr> {\n        match method {\n");
+    for class in &spec.classes {
+        let class_name = camel_case(&class.name);
+        for method in &class.methods {
+            let method_name = camel_case(&method.name);
+            writeln!(out, "            Method::{0}({0}Method::{1}) => {0}{1}::decode(buffer),", class_name, method_name).unwrap();
+        }
+    }
+    out.push_str("            _ => Err(FrameDecodeErr::SyntaxError(\"decode MethodPayload unknown method\"))\n        }\n    }\n}\n");
+    Ok(out)
+}
+
+fn class_file(spec: &Spec) -> String {
+    let mut classes: Vec<&Class> = spec.classes.iter().collect();
+    classes.sort_by_key(|class| class.index);
+
+    let mut out = String::from(HEADER);
+    out.push_str("\n#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]\npub enum Class {\n");
+    for class in &classes {
+        writeln!(out, "    {},", camel_case(&class.name)).unwrap();
+    }
+    out.push_str("    Unknown\n}\n\nimpl Class {\n    pub fn class_id(&self) -> u16 {\n        match self {\n");
+    for class in &classes {
+        writeln!(out, "            Class::{} => {},", camel_case(&class.name), class.index).unwrap();
+    }
+    out.push_str("            Class::Unknown => 0xffff\n        }\n    }\n\n");
+    out.push_str("    /// Name of the class in the spec\n    pub fn name(&self) -> &'static str {\n        match self {\n");
+    for class in &classes {
+        writeln!(out, "            Class::{} => \"{}\",", camel_case(&class.name), class.name).unwrap();
+    }
+    out.push_str("            Class::Unknown => \"unknown\"\n        }\n    }\n}\n\n");
+    out.push_str("impl From<u16> for Class {\n    fn from(class_id: u16) -> Self {\n        match class_id {\n");
+    for class in &classes {
+        writeln!(out, "            {} => Class::{},", class.index, camel_case(&class.name)).unwrap();
+    }
+    out.push_str("            _  => Class::Unknown\n        }\n    }\n}\n\n");
+    out.push_str("impl Default for Class {\n    fn default() -> Self {\n        Class::Unknown\n    }\n}\n");
+    out
+}
+
+// the method carries the `no_wait` flag
+fn has_no_wait(spec: &Spec, class: &Class, method: &Method) -> Result<bool, String> {
+    Ok(fields(spec, class, method)?.iter().any(|field| field.name == "no_wait"))
+}
+
+fn method_file(spec: &Spec, class: &Class) -> Result<String, String> {
+    let class_name = camel_case(&class.name);
+    let name = format!("{}Method", class_name);
+    let variants: Vec<(String, &crate::spec::Method)> = class.methods.iter().map(|method| (camel_case(&method.name), method)).collect();
+    // the responses of each method, and every method which is one
+    let mut responses = Vec::new();
+    let mut replies = Vec::new();
+    for (variant, method) in &variants {
+        let mut arms = Vec::new();
+        for response in &method.responses {
+            let reply = class.methods.iter().find(|m| &m.name == response)
+                .ok_or_else(|| format!("response {} of {}.{} is not a method of the class", response, class.name, method.name))?;
+            let reply = format!("{}::{}", name, camel_case(&reply.name));
+            arms.push(format!("Method::{}({})", class_name, reply));
+            if !replies.contains(&reply) {
+                replies.push(reply);
+            }
+        }
+        if !arms.is_empty() {
+            responses.push(format!("            {}::{} => &[{}],", name, variant, arms.join(", ")));
+        }
+    }
+    let mut no_wait = Vec::new();
+    for (variant, method) in &variants {
+        if has_no_wait(spec, class, method)? {
+            no_wait.push(format!("{}::{}", name, variant));
+        }
+    }
+
+    let mut out = String::from(HEADER);
+    out.push_str("use crate::method::base::{Method, MethodId};\n\n#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]\n");
+    writeln!(out, "pub enum {} {{", name).unwrap();
+    for (variant, _) in &variants {
+        writeln!(out, "    {},", variant).unwrap();
+    }
+    out.push_str("    Unknown\n}\n\n");
+
+    writeln!(out, "impl {} {{", name).unwrap();
+    out.push_str("    /// The method is followed by a content header frame and content body frames\n");
+    out.push_str("    #[inline]\n    pub fn has_content(&self) -> bool {\n");
+    let content: Vec<String> = variants.iter().filter(|(_, method)| method.content).map(|(variant, _)| format!("{}::{}", name, variant)).collect();
+    matches_or_false(&mut out, &content);
+    out.push_str("    }\n\n");
+    out.push_str("    /// Methods the peer may reply with, empty if the method is asynchronous\n");
+    out.push_str("    #[inline]\n    pub fn responses(&self) -> &'static [Method] {\n");
+    if responses.is_empty() {
+        out.push_str("        &[]\n");
+    } else {
+        out.push_str("        match self {\n");
+        for arm in &responses {
+            writeln!(out, "{}", arm).unwrap();
+        }
+        out.push_str("            _ => &[]\n        }\n");
+    }
+    out.push_str("    }\n\n");
+    out.push_str("    /// This method is the reply of a synchronous method\n");
+    out.push_str("    #[inline]\n    pub fn is_response(&self) -> bool {\n");
+    matches_or_false(&mut out, &replies);
+    out.push_str("    }\n\n");
+    out.push_str("    /// The method carries a `no_wait` flag, the reply is suppressed when it is set\n");
+    out.push_str("    #[inline]\n    pub fn has_no_wait(&self) -> bool {\n");
+    matches_or_false(&mut out, &no_wait);
+    out.push_str("    }\n\n");
+    out.push_str("    /// Name of the method in the spec\n    #[inline]\n    pub fn name(&self) -> &'static str {\n        match self {\n");
+    for (variant, method) in &variants {
+        writeln!(out, "            {}::{} => \"{}\",", name, variant, method.name).unwrap();
+    }
+    writeln!(out, "            {}::Unknown => \"unknown\"\n        }}\n    }}\n}}\n", name).unwrap();
+
+    writeln!(out, "impl MethodId for {} {{", name).unwrap();
+    out.push_str("    #[inline]\n    fn method_id(&self) -> u16 {\n        match self {\n");
+    for (variant, method) in &variants {
+        writeln!(out, "            {}::{} => {},", name, variant, method.index).unwrap();
+    }
+    writeln!(out, "            {}::Unknown => 0xffff\n        }}\n    }}\n}}\n", name).unwrap();
+
+    writeln!(out, "impl Default for {} {{", name).unwrap();
+    writeln!(out, "    #[inline]\n    fn default() -> Self {{\n        {}::Unknown\n    }}\n}}\n", name).unwrap();
+
+    writeln!(out, "impl From<u16> for {} {{", name).unwrap();
+    out.push_str("    #[inline]\n    fn from(method_id: u16) -> Self {\n        match method_id {\n");
+    for (variant, method) in &variants {
+        writeln!(out, "            {} => {}::{},", method.index, name, variant).unwrap();
+    }
+    writeln!(out, "            _  => {}::Unknown\n        }}\n    }}\n}}", name).unwrap();
+    Ok(out)
+}
+
+// body of a predicate which is true for the given variants
+fn matches_or_false(out: &mut String, variants: &[String]) {
+    if variants.is_empty() {
+        out.push_str("        false\n");
+    } else {
+        writeln!(out, "        matches!(self, {})", variants.join(" | ")).unwrap();
+    }
+}
+
+fn constants_file(spec: &Spec) -> Result<String, String> {
+    let mut out = String::from(HEADER);
+    out.push('\n');
+    for (name, ty) in FRAME_CONSTANTS.iter() {
+        let value = spec.constant(name)?;
+        writeln!(out, "pub const {}: {} = {};", name.replace('-', "_").to_uppercase(), ty, value).unwrap();
+    }
+    Ok(out)
+}
+
+/// Generated files, paths are relative to the repository root
+pub fn generate(spec: &Spec) -> Result<Vec<(String, String)>, String> {
+    let mut files = vec![("src/class/class.rs".to_string(), class_file(spec))];
+    for class in &spec.classes {
+        files.push((format!("src/method/{}.rs", class.name), method_file(spec, class)?));
+    }
+    for class in &spec.classes {
+        files.push((format!("src/frame/method/{}.rs", class.name), arguments_file(spec, class)?));
+    }
+    files.push(("src/frame/arguments.rs".to_string(), arguments_enum_file(spec)?));
+    files.push(("src/frame/constants.rs".to_string(), constants_file(spec)?));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spec::{Spec, Class, Method, Field};
+    use super::{fields, runs, encoded_len, Kind};
+
+    fn field(name: &str, domain: &str) -> Field {
+        Field { name: name.to_string(), domain: domain.to_string(), reserved: false }
+    }
+
+    #[test]
+    fn test_fields() {
+        let mut spec = Spec::default();
+        spec.domains.push(("no-wait".to_string(), "bit".to_string()));
+        spec.domains.push(("bit".to_string(), "bit".to_string()));
+        let mut method = Method { name: "close".to_string(), index: 50, content: false, label: None, fields: vec![
+            field("reply-text", "shortstr"), field("class-id", "class-id"), field("method-id", "method-id")
+        ], responses: Vec::new()};
+        for index in 0..9 {
+            method.fields.push(field(&format!("flag-{}", index), "bit"));
+        }
+        method.fields.push(field("nowait", "no-wait"));
+        let class = Class { name: "test".to_string(), index: 1, methods: Vec::new() };
+
+        let args = fields(&spec, &class, &method).unwrap();
+        assert_eq!((args[1].name.as_str(), args[1].kind), ("class_id", Kind::ClassMethod));
+        assert_eq!(args.last().unwrap().name, "no_wait");
+        // ten bits are packed in two octets
+        let runs: Vec<usize> = runs(&args).iter().map(|run| run.len()).collect();
+        assert_eq!(runs, [1, 1, 8, 2]);
+        assert_eq!(encoded_len(&args), "self.reply_text.encoded_len() + 6");
+
+        method.fields.push(Field { name: "reserved-2".to_string(), domain: "bit".to_string(), reserved: true });
+        assert!(fields(&spec, &class, &method).is_err());
+    }
+}
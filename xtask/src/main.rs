@@ -0,0 +1,78 @@
+//! Development tasks of amqp-proto
+//!
+//! `cargo xtask codegen` regenerates the class and method ids, the responses of the methods, the frame
+//! constants, the method arguments with their encoders and decoders and the `Arguments` enum from the AMQP
+//! 0-9-1 spec and the RabbitMQ extensions in docs/amqp0-9-1, `cargo xtask check` fails if the generated
+//! files are out of date.
+
+mod spec;
+mod codegen;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use spec::Spec;
+
+const SPECS: [&str; 2] = ["docs/amqp0-9-1/amqp0-9-1.xml", "docs/amqp0-9-1/rabbitmq-extensions.xml"];
+
+fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().expect("xtask is in the workspace").to_path_buf()
+}
+
+fn generate(root: &Path) -> Result<Vec<(String, String)>, String> {
+    let mut spec = Spec::default();
+    for path in SPECS.iter() {
+        spec.merge(&root.join(path))?;
+    }
+    codegen::generate(&spec)
+}
+
+/// Paths of the generated files which differ from the files in the tree
+fn stale(root: &Path) -> Result<Vec<String>, String> {
+    let files = generate(root)?;
+    Ok(files.into_iter()
+        .filter(|(path, content)| fs::read_to_string(root.join(path)).ok().as_deref() != Some(content.as_str()))
+        .map(|(path, _)| path)
+        .collect())
+}
+
+fn run(task: &str) -> Result<(), String> {
+    let root = root();
+    match task {
+        "codegen" => {
+            for (path, content) in generate(&root)? {
+                fs::write(root.join(&path), content).map_err(|e| format!("write {}: {}", path, e))?;
+                println!("generated {}", path);
+            }
+            Ok(())
+        }
+        "check" => {
+            let stale = stale(&root)?;
+            if stale.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("out of date, run `cargo xtask codegen`: {}", stale.join(", ")))
+            }
+        }
+        _ => Err(format!("unknown task `{}`, expected `codegen` or `check`", task))
+    }
+}
+
+fn main() {
+    let task = std::env::args().nth(1).unwrap_or_default();
+    if let Err(e) = run(&task) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{root, stale};
+
+    #[test]
+    fn test_generated_files_up_to_date() {
+        let stale = stale(&root()).unwrap();
+        assert!(stale.is_empty(), "out of date, run `cargo xtask codegen`: {}", stale.join(", "));
+    }
+}
@@ -0,0 +1,263 @@
+use std::fs;
+use std::path::Path;
+
+/// Start or end tag of the spec xml, text and comments are skipped
+struct Tag {
+    name: String,
+    attrs: Vec<(String, String)>,
+    closing: bool,
+    self_closing: bool
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, String> {
+        self.attr(name).ok_or_else(|| format!("<{}> without {}", self.name, name))
+    }
+
+    fn index(&self) -> Result<u16, String> {
+        let index = self.required("index")?;
+        index.parse().map_err(|_| format!("<{}> index is not a number: {}", self.name, index))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.attr(name) == Some("1")
+    }
+}
+
+fn parse_attrs(mut input: &str) -> Result<Vec<(String, String)>, String> {
+    let mut attrs = Vec::new();
+    loop {
+        input = input.trim_start();
+        if input.is_empty() {
+            return Ok(attrs);
+        }
+        let eq = input.find('=').ok_or_else(|| format!("attribute without value: {}", input))?;
+        let name = input[..eq].trim().to_string();
+        let rest = input[eq + 1..].trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("attribute {} is not quoted", name))?;
+        let end = rest[1..].find(quote).ok_or_else(|| format!("attribute {} is not closed", name))?;
+        attrs.push((name, rest[1..end + 1].to_string()));
+        input = &rest[end + 2..];
+    }
+}
+
+fn parse_tags(xml: &str) -> Result<Vec<Tag>, String> {
+    let mut tags = Vec::new();
+    let mut input = xml;
+    while let Some(start) = input.find('<') {
+        input = &input[start..];
+        if input.starts_with("<!--") {
+            let end = input.find("-->").ok_or("comment is not closed")?;
+            input = &input[end + 3..];
+            continue;
+        }
+        let end = input.find('>').ok_or("tag is not closed")?;
+        let tag = &input[1..end];
+        input = &input[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        let closing = tag.starts_with('/');
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_start_matches('/').trim_end_matches('/');
+        let (name, attrs) = match tag.find(char::is_whitespace) {
+            Some(index) => (&tag[..index], &tag[index..]),
+            None => (tag, "")
+        };
+        tags.push(Tag { name: name.to_string(), attrs: parse_attrs(attrs)?, closing, self_closing });
+    }
+    Ok(tags)
+}
+
+pub struct Constant {
+    pub name: String,
+    pub value: u32
+}
+
+pub struct Field {
+    pub name: String,
+    // name of the domain, or of the type for reserved fields
+    pub domain: String,
+    pub reserved: bool
+}
+
+pub struct Method {
+    pub name: String,
+    pub index: u16,
+    // the method is followed by a content header and body frames
+    pub content: bool,
+    pub label: Option<String>,
+    pub fields: Vec<Field>,
+    // names of the methods of the class the peer replies with
+    pub responses: Vec<String>
+}
+
+pub struct Class {
+    pub name: String,
+    pub index: u16,
+    pub methods: Vec<Method>
+}
+
+/// Constants, domains, classes and methods of the protocol
+#[derive(Default)]
+pub struct Spec {
+    pub constants: Vec<Constant>,
+    // domain name and the domain or type it is defined as
+    pub domains: Vec<(String, String)>,
+    pub classes: Vec<Class>
+}
+
+impl Spec {
+    /// Read a spec, classes which are already known get the new methods appended. A method which is already
+    /// known, with the same index, can only get responses added.
+    pub fn merge(&mut self, path: &Path) -> Result<(), String> {
+        let xml = fs::read_to_string(path).map_err(|e| format!("read {}: {}", path.display(), e))?;
+        let mut class: Option<usize> = None;
+        // fields outside of a method are content properties
+        let mut in_method: Option<usize> = None;
+        // the method was defined by an earlier spec
+        let mut amending = false;
+        for tag in parse_tags(&xml)? {
+            match (tag.name.as_str(), tag.closing) {
+                ("constant", false) => {
+                    let value = tag.required("value")?;
+                    let value = value.parse().map_err(|_| format!("constant value is not a number: {}", value))?;
+                    self.constants.push(Constant { name: tag.required("name")?.to_string(), value });
+                }
+                ("domain", false) => {
+                    self.domains.push((tag.required("name")?.to_string(), tag.required("type")?.to_string()));
+                }
+                ("class", false) => {
+                    let name = tag.required("name")?;
+                    let index = tag.index()?;
+                    let position = match self.classes.iter().position(|class| class.name == name) {
+                        Some(position) if self.classes[position].index == index => position,
+                        Some(_) => return Err(format!("class {} redefined with another index", name)),
+                        None => {
+                            self.classes.push(Class { name: name.to_string(), index, methods: Vec::new() });
+                            self.classes.len() - 1
+                        }
+                    };
+                    class = if tag.self_closing { None } else { Some(position) };
+                }
+                ("class", true) => class = None,
+                ("method", true) => in_method = None,
+                ("method", false) => {
+                    let class = match class {
+                        Some(class) => &mut self.classes[class],
+                        None => return Err(format!("method {} outside of a class", tag.required("name")?))
+                    };
+                    let method = Method {
+                        name: tag.required("name")?.to_string(),
+                        index: tag.index()?,
+                        content: tag.flag("content"),
+                        label: tag.attr("label").map(str::to_string),
+                        fields: Vec::new(),
+                        responses: Vec::new()
+                    };
+                    let position = match class.methods.iter().position(|m| m.name == method.name || m.index == method.index) {
+                        Some(position) if class.methods[position].name == method.name && class.methods[position].index == method.index => {
+                            amending = true;
+                            position
+                        }
+                        Some(_) => return Err(format!("method {}.{} is defined twice", class.name, method.name)),
+                        None => {
+                            amending = false;
+                            class.methods.push(method);
+                            class.methods.len() - 1
+                        }
+                    };
+                    in_method = if tag.self_closing { None } else { Some(position) };
+                }
+                ("response", false) => {
+                    if let (Some(class), Some(method)) = (class, in_method) {
+                        self.classes[class].methods[method].responses.push(tag.required("name")?.to_string());
+                    }
+                }
+                ("field", false) => {
+                    if let (Some(class), Some(method)) = (class, in_method) {
+                        if amending {
+                            let class = &self.classes[class];
+                            return Err(format!("method {}.{} is amended with fields", class.name, class.methods[method].name));
+                        }
+                        let domain = match tag.attr("domain") {
+                            Some(domain) => domain,
+                            None => tag.required("type")?
+                        };
+                        self.classes[class].methods[method].fields.push(Field {
+                            name: tag.required("name")?.to_string(),
+                            domain: domain.to_string(),
+                            reserved: tag.flag("reserved")
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Type of a domain, domains can be defined by other domains
+    pub fn domain_type<'a>(&'a self, mut domain: &'a str) -> Result<&'a str, String> {
+        for _ in 0..self.domains.len() {
+            match self.domains.iter().find(|(name, _)| name == domain) {
+                Some((name, ty)) if name == ty => return Ok(ty),
+                Some((_, ty)) => domain = ty,
+                None => return Ok(domain)
+            }
+        }
+        Err(format!("domain {} is defined by itself", domain))
+    }
+
+    pub fn constant(&self, name: &str) -> Result<u32, String> {
+        self.constants.iter().find(|c| c.name == name).map(|c| c.value).ok_or_else(|| format!("constant {} is missing", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::{parse_tags, Spec};
+
+    #[test]
+    fn test_parse_tags() {
+        let tags = parse_tags("<?xml version = \"1.0\"?><!-- <class> --><class name = \"tx\" index='90'><doc>a > b</doc><method name=\"select\" index=\"10\"/></class>").unwrap();
+        let names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(names, ["class", "doc", "doc", "method", "class"]);
+        assert_eq!(tags[0].attr("index"), Some("90"));
+        assert!(tags[3].self_closing);
+        assert!(tags[4].closing);
+    }
+
+    #[test]
+    fn test_merge_responses() {
+        let dir = std::env::temp_dir().join(format!("xtask-spec-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, xml: &str| {
+            let path = dir.join(name);
+            fs::write(&path, xml).unwrap();
+            path
+        };
+        let spec = write("spec.xml", "<class name=\"basic\" index=\"60\"><method name=\"get\" index=\"70\"><response name=\"get-ok\"/><response name=\"get-empty\"/></method><method name=\"recover\" index=\"110\"/></class>");
+        let amend = write("amend.xml", "<class name=\"basic\" index=\"60\"><method name=\"recover\" index=\"110\"><response name=\"recover-ok\"/></method></class>");
+        let fields = write("fields.xml", "<class name=\"basic\" index=\"60\"><method name=\"recover\" index=\"110\"><field name=\"requeue\" domain=\"bit\"/></method></class>");
+        let index = write("index.xml", "<class name=\"basic\" index=\"60\"><method name=\"recover\" index=\"111\"/></class>");
+
+        let mut merged = Spec::default();
+        merged.merge(&spec).unwrap();
+        merged.merge(&amend).unwrap();
+        let methods = &merged.classes[0].methods;
+        assert_eq!(methods.len(), 2);
+        assert_eq!(methods[0].responses, ["get-ok", "get-empty"]);
+        assert_eq!(methods[1].responses, ["recover-ok"]);
+        // a known method only gets responses
+        assert!(merged.merge(&fields).is_err());
+        assert!(merged.merge(&index).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}